@@ -0,0 +1,179 @@
+//! Autoloader optimization recommendations
+//! Sizes the classmap Composer would generate for production and checks what stands
+//! in the way of `--classmap-authoritative`, based on the project's actual code
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::namespace::detect_namespaces_raw;
+use super::psr4::analyze_psr4_autoloading_raw;
+use crate::composer::{get_psr4_mappings, read_composer_json, read_composer_lock};
+use crate::types::AutoloadConfig;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AutoloadOptimizationResult {
+    /// Classes, interfaces and traits under production PSR-4 prefixes
+    #[serde(rename = "classmapSize")]
+    pub classmap_size: usize,
+    /// Namespaced class-likes no production or dev PSR-4 prefix or classmap path covers
+    #[serde(rename = "classesOutsideAutoload")]
+    pub classes_outside_autoload: usize,
+    #[serde(rename = "filesWithoutNamespace")]
+    pub files_without_namespace: usize,
+    /// Files whose namespace doesn't match their PSR-4 path; Composer leaves them out of the classmap
+    #[serde(rename = "psr4Violations")]
+    pub psr4_violations: usize,
+    /// `autoload.files` entries, which are included on every request
+    #[serde(rename = "filesAutoloads")]
+    pub files_autoloads: Vec<FilesAutoload>,
+    #[serde(rename = "currentConfig")]
+    pub current_config: AutoloaderConfig,
+    /// "classmap-authoritative" or "optimize-autoloader"
+    pub recommendation: String,
+    pub command: String,
+    pub reasons: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FilesAutoload {
+    /// "root" or the package declaring the files
+    pub source: String,
+    pub files: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AutoloaderConfig {
+    #[serde(rename = "optimizeAutoloader")]
+    pub optimize_autoloader: bool,
+    #[serde(rename = "classmapAuthoritative")]
+    pub classmap_authoritative: bool,
+    #[serde(rename = "apcuAutoloader")]
+    pub apcu_autoloader: bool,
+}
+
+/// Recommend production autoloader settings from the shape of the codebase
+pub fn analyze_autoload_optimization<P: AsRef<Path> + Sync>(repo_path: P) -> Result<String> {
+    let composer = read_composer_json(&repo_path)?;
+    let namespaces = detect_namespaces_raw(&repo_path, false)?;
+    let mappings = get_psr4_mappings(&composer);
+    let psr4_violations = analyze_psr4_autoloading_raw(&repo_path)?.stats.violation_count;
+
+    let prefix_matches = |namespace: &str, prefix: &str| {
+        let prefix = prefix.trim_end_matches('\\');
+        namespace == prefix || namespace.starts_with(&format!("{}\\", prefix))
+    };
+    let classmap_paths: Vec<String> = [&composer.autoload, &composer.autoload_dev]
+        .into_iter()
+        .flatten()
+        .flat_map(|a| a.classmap.clone().unwrap_or_default())
+        .map(|p| p.trim_start_matches("./").trim_end_matches('/').to_string())
+        .collect();
+
+    let mut classmap_size = 0;
+    let mut classes_outside_autoload = 0;
+    for ns in &namespaces.namespaces {
+        let count = ns.classes.len() + ns.interfaces.len() + ns.traits.len();
+        let production = mappings.iter().any(|m| !m.is_dev && prefix_matches(&ns.namespace, &m.namespace));
+        let covered = production
+            || mappings.iter().any(|m| prefix_matches(&ns.namespace, &m.namespace))
+            || ns.files.iter().any(|f| classmap_paths.iter().any(|p| f.starts_with(p.as_str())));
+        if production {
+            classmap_size += count;
+        }
+        if !covered {
+            classes_outside_autoload += count;
+        }
+    }
+
+    let mut files_autoloads = Vec::new();
+    if let Some(files) = files_of(&composer.autoload) {
+        files_autoloads.push(FilesAutoload { source: "root".to_string(), files });
+    }
+    if let Ok(lock) = read_composer_lock(&repo_path) {
+        for pkg in &lock.packages {
+            if let Some(files) = files_of(&pkg.autoload) {
+                files_autoloads.push(FilesAutoload { source: pkg.name.clone(), files });
+            }
+        }
+    }
+
+    let config_flag = |key: &str| {
+        composer
+            .config
+            .as_ref()
+            .and_then(|c| c.get(key))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+    };
+    let current_config = AutoloaderConfig {
+        optimize_autoloader: config_flag("optimize-autoloader"),
+        classmap_authoritative: config_flag("classmap-authoritative"),
+        apcu_autoloader: config_flag("apcu-autoloader"),
+    };
+
+    let files_without_namespace = namespaces
+        .files_without_namespace_by_directory
+        .iter()
+        .map(|d| d.count)
+        .sum();
+
+    let mut reasons = vec![format!(
+        "An optimized classmap would hold {} root classes, interfaces and traits (plus vendor classes)",
+        classmap_size
+    )];
+    if !files_autoloads.is_empty() {
+        let count: usize = files_autoloads.iter().map(|f| f.files.len()).sum();
+        reasons.push(format!(
+            "{} `files` autoload entries from {} source(s) are included eagerly on every request and keep full authoritative mode off the table",
+            count,
+            files_autoloads.len()
+        ));
+    }
+    if classes_outside_autoload > 0 {
+        reasons.push(format!(
+            "{} classes are outside every PSR-4 prefix and classmap path; an authoritative classmap would never find them",
+            classes_outside_autoload
+        ));
+    }
+    if psr4_violations > 0 {
+        reasons.push(format!(
+            "{} files don't match their PSR-4 path and are skipped when the classmap is generated",
+            psr4_violations
+        ));
+    }
+
+    let authoritative = files_autoloads.is_empty() && classes_outside_autoload == 0 && psr4_violations == 0;
+    let (recommendation, command) = if authoritative {
+        reasons.push(
+            "All classes are known at build time, so the autoloader can skip filesystem lookups entirely".to_string(),
+        );
+        ("classmap-authoritative", "composer dump-autoload --no-dev --classmap-authoritative")
+    } else {
+        ("optimize-autoloader", "composer dump-autoload --no-dev --optimize")
+    };
+    if current_config.classmap_authoritative && !authoritative {
+        reasons.push("classmap-authoritative is enabled in composer.json despite the blockers above".to_string());
+    }
+
+    let result = AutoloadOptimizationResult {
+        classmap_size,
+        classes_outside_autoload,
+        files_without_namespace,
+        psr4_violations,
+        files_autoloads,
+        current_config,
+        recommendation: recommendation.to_string(),
+        command: command.to_string(),
+        reasons,
+    };
+
+    Ok(serde_json::to_string_pretty(&result)?)
+}
+
+fn files_of(autoload: &Option<AutoloadConfig>) -> Option<Vec<String>> {
+    autoload
+        .as_ref()
+        .and_then(|a| a.files.clone())
+        .filter(|files| !files.is_empty())
+}
@@ -0,0 +1,198 @@
+//! Autoload config suggestions for unconfigured codebases
+//! Infers PSR-4 prefixes from where each namespace's files live (`src/Foo/Bar.php` declaring
+//! `App\Foo` means `App\` -> `src/`) and proposes an `autoload` block to paste into composer.json
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+use super::namespace::detect_namespaces_raw;
+use crate::composer::{get_psr4_mappings, read_composer_json};
+
+/// Top-level directories whose mappings belong in `autoload-dev`
+const DEV_DIRECTORIES: &[&str] = &["tests", "test", "spec"];
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AutoloadSuggestion {
+    /// Proposed `autoload` block for composer.json
+    pub autoload: Value,
+    /// Proposed `autoload-dev` block, for mappings under test directories
+    #[serde(rename = "autoloadDev", skip_serializing_if = "Value::is_null")]
+    pub autoload_dev: Value,
+    /// How each suggested prefix was inferred
+    pub mappings: Vec<SuggestedMapping>,
+    /// Files without a namespace, which only a classmap can autoload
+    #[serde(rename = "filesWithoutNamespace")]
+    pub files_without_namespace: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SuggestedMapping {
+    pub namespace: String,
+    /// Most files first
+    pub paths: Vec<String>,
+    /// Files the mapping covers
+    pub files: usize,
+    pub dev: bool,
+    /// composer.json already maps this prefix
+    #[serde(rename = "alreadyConfigured")]
+    pub already_configured: bool,
+}
+
+/// Propose `autoload.psr-4` (and `autoload-dev.psr-4`) from detected namespaces, plus a
+/// classmap for the directories below the root that hold files without a namespace
+pub fn suggest_autoload_config<P: AsRef<Path> + Sync>(repo_path: P) -> Result<String> {
+    let detected = detect_namespaces_raw(&repo_path, true)?;
+    let configured: HashSet<String> = read_composer_json(&repo_path)
+        .map(|c| get_psr4_mappings(&c).into_iter().map(|m| m.namespace).collect())
+        .unwrap_or_default();
+
+    // prefix -> base directory -> files
+    let mut votes: BTreeMap<String, BTreeMap<String, usize>> = BTreeMap::new();
+    for ns in &detected.namespaces {
+        for file in &ns.files {
+            let (prefix, base) = infer_mapping(&ns.namespace, file);
+            *votes.entry(prefix).or_default().entry(base).or_default() += 1;
+        }
+    }
+
+    let mappings: Vec<SuggestedMapping> = votes
+        .into_iter()
+        .map(|(namespace, bases)| {
+            let files = bases.values().sum();
+            let mut bases: Vec<(String, usize)> = bases.into_iter().collect();
+            bases.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            let dev = bases.iter().all(|(base, _)| is_dev_directory(base));
+            SuggestedMapping {
+                already_configured: configured.contains(&namespace),
+                paths: bases.into_iter().map(|(base, _)| base).collect(),
+                namespace,
+                files,
+                dev,
+            }
+        })
+        .collect();
+
+    // Un-namespaced files at the root are usually entry scripts, not classes
+    let (classmap_dev, classmap): (Vec<String>, Vec<String>) = detected
+        .files_without_namespace_by_directory
+        .iter()
+        .filter(|d| d.directory != ".")
+        .map(|d| format!("{}/", d.directory))
+        .partition(|directory| is_dev_directory(directory));
+
+    let result = AutoloadSuggestion {
+        autoload: autoload_block(mappings.iter().filter(|m| !m.dev), &classmap),
+        autoload_dev: match autoload_block(mappings.iter().filter(|m| m.dev), &classmap_dev) {
+            Value::Object(block) if block.is_empty() => Value::Null,
+            block => block,
+        },
+        files_without_namespace: detected.files_without_namespace.as_ref().map_or(0, Vec::len),
+        mappings,
+    };
+
+    Ok(serde_json::to_string_pretty(&result)?)
+}
+
+/// Strip the trailing namespace segments that match the file's directories, so
+/// `App\Foo` in `src/Foo/Bar.php` gives (`App\`, `src/`). At least one segment stays in
+/// the prefix, and a namespace that matches no directory maps straight to its own.
+fn infer_mapping(namespace: &str, file: &str) -> (String, String) {
+    let segments: Vec<&str> = namespace.split('\\').filter(|s| !s.is_empty()).collect();
+    let directories: Vec<String> = Path::new(file)
+        .parent()
+        .map(|p| p.components().map(|c| c.as_os_str().to_string_lossy().into_owned()).collect())
+        .unwrap_or_default();
+
+    let matched = segments
+        .iter()
+        .rev()
+        .zip(directories.iter().rev())
+        .take(segments.len().saturating_sub(1))
+        .take_while(|(segment, directory)| *segment == directory)
+        .count();
+
+    let prefix = format!("{}\\", segments[..segments.len() - matched].join("\\"));
+    let base = &directories[..directories.len() - matched];
+    let base = if base.is_empty() { String::new() } else { format!("{}/", base.join("/")) };
+    (prefix, base)
+}
+
+fn is_dev_directory(base: &str) -> bool {
+    let top = base.split('/').next().unwrap_or_default();
+    DEV_DIRECTORIES.iter().any(|d| top.eq_ignore_ascii_case(d))
+}
+
+/// `{"psr-4": {...}, "classmap": [...]}`, using a plain string for single-path prefixes
+fn autoload_block<'a>(mappings: impl Iterator<Item = &'a SuggestedMapping>, classmap: &[String]) -> Value {
+    let psr4: Map<String, Value> = mappings
+        .map(|m| {
+            let paths = match m.paths.as_slice() {
+                [single] => json!(single),
+                paths => json!(paths),
+            };
+            (m.namespace.clone(), paths)
+        })
+        .collect();
+
+    let mut block = Map::new();
+    if !psr4.is_empty() {
+        block.insert("psr-4".to_string(), Value::Object(psr4));
+    }
+    if !classmap.is_empty() {
+        block.insert("classmap".to_string(), json!(classmap));
+    }
+    Value::Object(block)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_infer_mapping_strips_matching_directories() {
+        let cases = [
+            ("App\\Foo", "src/Foo/Bar.php", "App\\", "src/"),
+            ("App", "src/Bar.php", "App\\", "src/"),
+            ("App", "Bar.php", "App\\", ""),
+            ("App\\Tests\\Unit", "tests/Unit/BarTest.php", "App\\Tests\\", "tests/"),
+            ("App\\Models", "lib/Entity/User.php", "App\\Models\\", "lib/Entity/"),
+            // The vendor segment is never stripped, even when a directory matches it
+            ("Foo\\Bar", "Foo/Bar/Baz.php", "Foo\\", "Foo/"),
+            ("App\\Http\\Controllers", "Http/Controllers/Home.php", "App\\", ""),
+        ];
+        for (namespace, file, prefix, base) in cases {
+            assert_eq!(infer_mapping(namespace, file), (prefix.to_string(), base.to_string()), "{} in {}", namespace, file);
+        }
+    }
+
+    #[test]
+    fn test_suggestion_splits_dev_mappings_and_classmap() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        for sub in ["src/Http", "tests/Unit", "legacy"] {
+            std::fs::create_dir_all(dir.join(sub)).unwrap();
+        }
+        let files = [
+            ("src/Kernel.php", "<?php\nnamespace App;\nclass Kernel {}\n"),
+            ("src/Http/Home.php", "<?php\nnamespace App\\Http;\nclass Home {}\n"),
+            ("tests/Unit/HomeTest.php", "<?php\nnamespace App\\Tests\\Unit;\nclass HomeTest {}\n"),
+            ("legacy/helpers.php", "<?php\nfunction helper() {}\n"),
+            ("index.php", "<?php\nrequire 'vendor/autoload.php';\n"),
+        ];
+        for (file, body) in files {
+            std::fs::write(dir.join(file), body).unwrap();
+        }
+        std::fs::write(dir.join("composer.json"), r#"{"autoload": {"psr-4": {"App\\": "src/"}}}"#).unwrap();
+
+        let result: AutoloadSuggestion = serde_json::from_str(&suggest_autoload_config(dir).unwrap()).unwrap();
+
+        assert_eq!(result.autoload, json!({"psr-4": {"App\\": "src/"}, "classmap": ["legacy/"]}));
+        assert_eq!(result.autoload_dev, json!({"psr-4": {"App\\Tests\\": "tests/"}}));
+        let app = result.mappings.iter().find(|m| m.namespace == "App\\").unwrap();
+        assert_eq!((app.files, app.dev, app.already_configured), (2, false, true));
+        assert_eq!(result.files_without_namespace, 2);
+    }
+}
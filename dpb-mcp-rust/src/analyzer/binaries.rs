@@ -0,0 +1,174 @@
+//! Installed binaries inventory
+//! Lists the executables `composer install` links into the bin dir and which package
+//! ships each one, for supply-chain review of what lands on the path
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::composer::{read_composer_json, read_composer_lock};
+
+#[derive(Debug, Serialize)]
+pub struct BinariesResult {
+    /// Where Composer links the binaries, from `config.bin-dir`
+    #[serde(rename = "binDir")]
+    pub bin_dir: String,
+    #[serde(rename = "binaryCount")]
+    pub binary_count: usize,
+    #[serde(rename = "packageCount")]
+    pub package_count: usize,
+    pub binaries: Vec<InstalledBinary>,
+    /// Binary names shipped by more than one package; only one of them ends up linked
+    pub collisions: Vec<BinaryCollision>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InstalledBinary {
+    /// File name the binary is linked under
+    pub name: String,
+    /// Path of the binary inside its package, as declared in `bin`
+    pub source: String,
+    #[serde(rename = "linkPath")]
+    pub link_path: String,
+    pub package: String,
+    pub version: String,
+    #[serde(rename = "isDev")]
+    pub is_dev: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BinaryCollision {
+    pub name: String,
+    pub packages: Vec<String>,
+}
+
+/// List every binary declared by a locked package; `include_dev: false` skips
+/// packages only installed for development
+pub fn list_installed_binaries<P: AsRef<Path>>(repo_path: P, include_dev: bool) -> Result<String> {
+    let lock = read_composer_lock(&repo_path)?;
+    let bin_dir = read_composer_json(&repo_path)
+        .ok()
+        .and_then(|c| c.config)
+        .and_then(|config| config.get("bin-dir").and_then(|v| v.as_str()).map(|s| s.to_string()))
+        .unwrap_or_else(|| "vendor/bin".to_string());
+    let bin_dir = bin_dir.trim_end_matches('/').to_string();
+
+    let dev_packages = if include_dev { lock.packages_dev.as_deref().unwrap_or_default() } else { &[] };
+    let bin_dir_ref = bin_dir.as_str();
+    let mut binaries: Vec<InstalledBinary> = lock
+        .packages
+        .iter()
+        .map(|p| (p, false))
+        .chain(dev_packages.iter().map(|p| (p, true)))
+        .flat_map(|(pkg, is_dev)| {
+            pkg.bin.iter().flatten().map(move |source| {
+                let name = source.rsplit(['/', '\\']).next().unwrap_or(source).to_string();
+                InstalledBinary {
+                    link_path: format!("{}/{}", bin_dir_ref, name),
+                    name,
+                    source: source.clone(),
+                    package: pkg.name.clone(),
+                    version: pkg.version.clone(),
+                    is_dev,
+                }
+            })
+        })
+        .collect();
+    binaries.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.package.cmp(&b.package)));
+
+    let mut by_name: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for binary in &binaries {
+        by_name.entry(&binary.name).or_default().push(binary.package.clone());
+    }
+    let collisions = by_name
+        .into_iter()
+        .filter(|(_, packages)| packages.len() > 1)
+        .map(|(name, packages)| BinaryCollision { name: name.to_string(), packages })
+        .collect();
+
+    let mut packages: Vec<&str> = binaries.iter().map(|b| b.package.as_str()).collect();
+    packages.sort();
+    packages.dedup();
+
+    let result = BinariesResult {
+        bin_dir,
+        binary_count: binaries.len(),
+        package_count: packages.len(),
+        binaries,
+        collisions,
+    };
+
+    Ok(serde_json::to_string_pretty(&result)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn write_repo(dir: &Path) {
+        let composer = json!({"config": {"bin-dir": "bin/"}});
+        std::fs::write(dir.join("composer.json"), composer.to_string()).unwrap();
+        let lock = json!({
+            "packages": [
+                {"name": "acme/cli", "version": "1.0.0", "bin": ["bin/acme", "bin/tool"]},
+                {"name": "other/cli", "version": "2.0.0", "bin": ["scripts\\tool"]},
+                {"name": "acme/lib", "version": "1.0.0"}
+            ],
+            "packages-dev": [{"name": "phpunit/phpunit", "version": "10.5.0", "bin": ["phpunit"]}]
+        });
+        std::fs::write(dir.join("composer.lock"), lock.to_string()).unwrap();
+    }
+
+    #[test]
+    fn test_binaries_link_into_configured_bin_dir() {
+        let temp = crate::test_support::temp_dir();
+        write_repo(temp.path());
+
+        let result: Value = serde_json::from_str(&list_installed_binaries(temp.path(), true).unwrap()).unwrap();
+
+        assert_eq!(result["binDir"], "bin");
+        assert_eq!(result["binaryCount"], 4);
+        assert_eq!(result["packageCount"], 3);
+        let linked: Vec<(&str, &str)> = result["binaries"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|b| (b["linkPath"].as_str().unwrap(), b["package"].as_str().unwrap()))
+            .collect();
+        assert_eq!(
+            linked,
+            [
+                ("bin/acme", "acme/cli"),
+                ("bin/phpunit", "phpunit/phpunit"),
+                ("bin/tool", "acme/cli"),
+                ("bin/tool", "other/cli"),
+            ]
+        );
+        assert_eq!(result["binaries"][1]["isDev"], true);
+        assert_eq!(result["collisions"], json!([{"name": "tool", "packages": ["acme/cli", "other/cli"]}]));
+    }
+
+    #[test]
+    fn test_dev_binaries_are_skipped_on_request() {
+        let temp = crate::test_support::temp_dir();
+        write_repo(temp.path());
+
+        let result: Value = serde_json::from_str(&list_installed_binaries(temp.path(), false).unwrap()).unwrap();
+
+        assert_eq!(result["binaryCount"], 3);
+        assert!(result["binaries"].as_array().unwrap().iter().all(|b| b["package"] != "phpunit/phpunit"));
+    }
+
+    #[test]
+    fn test_default_bin_dir_without_composer_json() {
+        let temp = crate::test_support::temp_dir();
+        let lock = json!({"packages": [{"name": "acme/cli", "version": "1.0.0", "bin": ["bin/acme"]}]});
+        std::fs::write(temp.path().join("composer.lock"), lock.to_string()).unwrap();
+
+        let result: Value = serde_json::from_str(&list_installed_binaries(temp.path(), true).unwrap()).unwrap();
+
+        assert_eq!(result["binaries"][0]["linkPath"], "vendor/bin/acme");
+    }
+}
@@ -0,0 +1,291 @@
+//! License compatibility against the root project's own license
+//! Only combinations that are actually incompatible are reported, unlike the
+//! generic "restrictive license" check in `check_compliance`
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::security::spdx_ids;
+use crate::composer::{get_licenses, read_composer_json, read_composer_lock};
+
+/// License families that behave the same for compatibility purposes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LicenseKind {
+    Permissive,
+    /// Permissive, but its patent clause is incompatible with GPL-2.0-only
+    Apache2,
+    /// LGPL / MPL: copyleft limited to the library itself
+    WeakCopyleft,
+    /// EPL: weak copyleft that is GPL-incompatible
+    Epl,
+    Gpl2Only,
+    Gpl2OrLater,
+    Gpl3,
+    Agpl3,
+    Proprietary,
+    Unknown,
+}
+
+impl LicenseKind {
+    fn classify(id: &str) -> Self {
+        let id = id.trim().to_uppercase();
+        let or_later = id.ends_with('+') || id.ends_with("-OR-LATER");
+        let base = id.trim_end_matches('+').trim_end_matches("-OR-LATER").trim_end_matches("-ONLY");
+
+        match base {
+            "MIT" | "ISC" | "ZLIB" | "UNLICENSE" | "0BSD" | "CC0-1.0" | "WTFPL" | "PHP-3.0" | "PHP-3.01"
+            | "POSTGRESQL" | "BSL-1.0" | "ARTISTIC-2.0" => Self::Permissive,
+            b if b.starts_with("BSD-") => Self::Permissive,
+            "APACHE-2.0" => Self::Apache2,
+            b if b.starts_with("LGPL-") || b.starts_with("MPL-") => Self::WeakCopyleft,
+            b if b.starts_with("EPL-") => Self::Epl,
+            "GPL-2.0" if or_later => Self::Gpl2OrLater,
+            "GPL-2.0" => Self::Gpl2Only,
+            "GPL-3.0" => Self::Gpl3,
+            "AGPL-3.0" => Self::Agpl3,
+            "PROPRIETARY" => Self::Proprietary,
+            _ => Self::Unknown,
+        }
+    }
+
+    fn is_strong_copyleft(self) -> bool {
+        matches!(self, Self::Gpl2Only | Self::Gpl2OrLater | Self::Gpl3 | Self::Agpl3)
+    }
+
+    /// Whether a dependency under `self` may be combined into a project under `project`
+    fn compatible_with(self, project: LicenseKind) -> bool {
+        use LicenseKind::*;
+        match self {
+            Permissive | WeakCopyleft => true,
+            Apache2 => project != Gpl2Only,
+            Epl => !project.is_strong_copyleft(),
+            Gpl2Only => matches!(project, Gpl2Only | Gpl2OrLater),
+            Gpl2OrLater => project.is_strong_copyleft(),
+            Gpl3 => matches!(project, Gpl2OrLater | Gpl3 | Agpl3),
+            Agpl3 => matches!(project, Gpl2OrLater | Gpl3 | Agpl3),
+            // Can't be judged from the license id alone
+            Proprietary | Unknown => true,
+        }
+    }
+
+    fn is_known(self) -> bool {
+        !matches!(self, Self::Proprietary | Self::Unknown)
+    }
+}
+
+/// Whether an SPDX id is one the compatibility matrix knows about
+pub fn is_recognized_license(id: &str) -> bool {
+    LicenseKind::classify(id) != LicenseKind::Unknown
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LicenseCompatibilityResult {
+    #[serde(rename = "projectLicenses")]
+    pub project_licenses: Vec<String>,
+    /// composer.json declares no license, so the project is treated as proprietary
+    #[serde(rename = "assumedProprietary")]
+    pub assumed_proprietary: bool,
+    pub incompatible: Vec<LicenseIncompatibility>,
+    /// Packages whose license could not be classified and need a manual look
+    #[serde(rename = "needsReview")]
+    pub needs_review: Vec<String>,
+    #[serde(rename = "checkedPackages")]
+    pub checked_packages: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LicenseIncompatibility {
+    pub package: String,
+    pub version: String,
+    pub licenses: Vec<String>,
+    #[serde(rename = "projectLicense")]
+    pub project_license: String,
+    pub reason: String,
+}
+
+/// Evaluate each locked package's license against the root project's license.
+/// A dual-licensed dependency (`OR`, or several entries in `license`) is compatible if any
+/// of its options is, and an `A AND B` option only if both terms are; every license
+/// the project itself offers has to be satisfiable. Dev packages are not distributed,
+/// so they are skipped unless `include_dev` is set.
+pub fn check_license_compatibility<P: AsRef<Path>>(repo_path: P, include_dev: bool) -> Result<String> {
+    let composer = read_composer_json(&repo_path)?;
+    let lock = read_composer_lock(&repo_path)?;
+
+    let project_licenses = get_licenses(&composer);
+    let assumed_proprietary = project_licenses.is_empty();
+    let project: Vec<(String, LicenseKind)> = if assumed_proprietary {
+        vec![("proprietary".to_string(), LicenseKind::Proprietary)]
+    } else {
+        // A custom project license gets the same treatment as a proprietary one
+        project_licenses
+            .iter()
+            .flat_map(|l| spdx_ids(l).map(|id| id.to_string()).collect::<Vec<_>>())
+            .map(|id| match LicenseKind::classify(&id) {
+                LicenseKind::Unknown => (id, LicenseKind::Proprietary),
+                kind => (id, kind),
+            })
+            .collect()
+    };
+
+    let packages: Vec<_> = lock
+        .packages
+        .iter()
+        .chain(lock.packages_dev.iter().flatten().filter(|_| include_dev))
+        .collect();
+
+    let mut incompatible = Vec::new();
+    let mut needs_review = Vec::new();
+
+    for pkg in &packages {
+        let licenses = pkg.license.clone().unwrap_or_default();
+        let options = license_options(&licenses);
+
+        // Options with an unclassifiable term can't be judged; with none left, ask a human
+        if !options.iter().any(|terms| terms.iter().all(|(_, kind)| kind.is_known())) {
+            needs_review.push(pkg.name.clone());
+            continue;
+        }
+
+        for (project_id, project_kind) in &project {
+            if options_compatible(&options, *project_kind) {
+                continue;
+            }
+            let described: Vec<String> = options
+                .iter()
+                .map(|terms| terms.iter().map(|(id, _)| id.as_str()).collect::<Vec<_>>().join(" AND "))
+                .collect();
+            incompatible.push(LicenseIncompatibility {
+                package: pkg.name.clone(),
+                version: pkg.version.clone(),
+                licenses: licenses.clone(),
+                project_license: project_id.clone(),
+                reason: format!(
+                    "{} cannot be combined into a project distributed under {}",
+                    described.join(" / "),
+                    project_id
+                ),
+            });
+        }
+    }
+
+    incompatible.sort_by(|a, b| a.package.cmp(&b.package));
+    needs_review.sort();
+
+    let result = LicenseCompatibilityResult {
+        project_licenses,
+        assumed_proprietary,
+        incompatible,
+        needs_review,
+        checked_packages: packages.len(),
+    };
+
+    Ok(serde_json::to_string_pretty(&result)?)
+}
+
+/// A package's licenses as alternatives (OR), each a set of terms that all apply (AND).
+/// Entries of composer's `license` array are alternatives too; `WITH` exceptions are dropped.
+fn license_options(licenses: &[String]) -> Vec<Vec<(String, LicenseKind)>> {
+    licenses
+        .iter()
+        .flat_map(|expression| {
+            let tokens: Vec<String> = expression
+                .replace('(', " ( ")
+                .replace(')', " ) ")
+                .split_whitespace()
+                .map(str::to_string)
+                .collect();
+            parse_or(&tokens, &mut 0)
+        })
+        .map(|terms| terms.iter().map(|id| (id.clone(), LicenseKind::classify(id))).collect())
+        .collect()
+}
+
+/// `a OR b OR ...`, as alternatives of AND-ed ids
+fn parse_or(tokens: &[String], pos: &mut usize) -> Vec<Vec<String>> {
+    let mut options = parse_and(tokens, pos);
+    while tokens.get(*pos).is_some_and(|t| t.eq_ignore_ascii_case("or")) {
+        *pos += 1;
+        options.extend(parse_and(tokens, pos));
+    }
+    options
+}
+
+/// `a AND b AND ...`: every combination of the operands' alternatives
+fn parse_and(tokens: &[String], pos: &mut usize) -> Vec<Vec<String>> {
+    let mut options = parse_term(tokens, pos);
+    while tokens.get(*pos).is_some_and(|t| t.eq_ignore_ascii_case("and")) {
+        *pos += 1;
+        let right = parse_term(tokens, pos);
+        options = options
+            .iter()
+            .flat_map(|left| right.iter().map(move |r| left.iter().chain(r).cloned().collect()))
+            .collect();
+    }
+    options
+}
+
+fn parse_term(tokens: &[String], pos: &mut usize) -> Vec<Vec<String>> {
+    let Some(token) = tokens.get(*pos) else {
+        return vec![Vec::new()];
+    };
+    *pos += 1;
+    let options = if token == "(" {
+        let inner = parse_or(tokens, pos);
+        if tokens.get(*pos).is_some_and(|t| t == ")") {
+            *pos += 1;
+        }
+        inner
+    } else {
+        vec![vec![token.clone()]]
+    };
+    // `GPL-2.0-only WITH Classpath-exception-2.0` is judged by the license itself
+    if tokens.get(*pos).is_some_and(|t| t.eq_ignore_ascii_case("with")) {
+        *pos += 2;
+    }
+    options
+}
+
+/// Whether some alternative has every term compatible with the project license
+fn options_compatible(options: &[Vec<(String, LicenseKind)>], project: LicenseKind) -> bool {
+    options
+        .iter()
+        .filter(|terms| terms.iter().all(|(_, kind)| kind.is_known()))
+        .any(|terms| terms.iter().all(|(_, kind)| kind.compatible_with(project)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_license_expressions_against_project_license() {
+        use LicenseKind::*;
+        // (package licenses, project license, compatible, needs review)
+        let cases: &[(&[&str], LicenseKind, bool, bool)] = &[
+            (&["MIT"], Proprietary, true, false),
+            (&["GPL-3.0-only"], Proprietary, false, false),
+            (&["MIT OR GPL-3.0-only"], Proprietary, true, false),
+            (&["MIT", "GPL-3.0-only"], Proprietary, true, false),
+            (&["MIT AND GPL-3.0-only"], Proprietary, false, false),
+            (&["(MIT OR Apache-2.0) AND GPL-3.0-only"], Gpl3, true, false),
+            (&["(MIT OR Apache-2.0) AND GPL-3.0-only"], Proprietary, false, false),
+            (&["Apache-2.0 AND MIT"], Gpl2Only, false, false),
+            (&["GPL-2.0-only WITH Classpath-exception-2.0"], Gpl2Only, true, false),
+            (&["Custom-License"], Proprietary, true, true),
+            (&["MIT AND Custom-License"], Proprietary, true, true),
+            (&["GPL-3.0-only OR Custom-License"], Proprietary, false, false),
+        ];
+
+        for (licenses, project, compatible, needs_review) in cases {
+            let licenses: Vec<String> = licenses.iter().map(|l| l.to_string()).collect();
+            let options = license_options(&licenses);
+            let review = !options.iter().any(|terms| terms.iter().all(|(_, kind)| kind.is_known()));
+            assert_eq!(review, *needs_review, "needs review: {:?}", licenses);
+            if !review {
+                assert_eq!(options_compatible(&options, *project), *compatible, "{:?} vs {:?}", licenses, project);
+            }
+        }
+    }
+}
@@ -0,0 +1,313 @@
+//! Declared-constraint comparison
+//! Diffs the require/require-dev constraints of two composer.json files (two repos or two
+//! git refs) and classifies each change as loosened or tightened, not just "different"
+
+use anyhow::{anyhow, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use crate::composer::constraint::{Constraint, Stability, Version};
+use crate::composer::{parse_composer_json, read_composer_json};
+use crate::types::ComposerJson;
+use super::mkdocs::{git_output, resolve_commit};
+
+lazy_static! {
+    static ref VERSION_LITERAL_RE: Regex = Regex::new(r"\d+(?:\.\d+){0,3}").unwrap();
+    static ref BRANCH_RE: Regex = Regex::new(r"dev-[\w./-]+").unwrap();
+}
+
+/// How many example versions a change lists in each direction
+const EXAMPLE_VERSIONS: usize = 3;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConstraintComparisonResult {
+    #[serde(rename = "repoA")]
+    pub repo_a: ConstraintSource,
+    #[serde(rename = "repoB")]
+    pub repo_b: ConstraintSource,
+    pub added: Vec<DeclaredConstraint>,
+    pub removed: Vec<DeclaredConstraint>,
+    /// B accepts every version A did, and more
+    pub loosened: Vec<ConstraintChange>,
+    /// A accepts every version B does, and more
+    pub tightened: Vec<ConstraintChange>,
+    /// Each side accepts versions the other does not, e.g. `^1.0` -> `^2.0`
+    pub shifted: Vec<ConstraintChange>,
+    /// Written differently but accepting the same versions, e.g. `^1.2` -> `>=1.2 <2.0`
+    pub equivalent: Vec<ConstraintChange>,
+    /// Changed, but at least one side could not be parsed
+    pub unclassified: Vec<ConstraintChange>,
+    pub summary: ConstraintComparisonSummary,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConstraintSource {
+    pub path: String,
+    #[serde(rename = "ref", skip_serializing_if = "Option::is_none")]
+    pub git_ref: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeclaredConstraint {
+    pub name: String,
+    pub constraint: String,
+    pub dev: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConstraintChange {
+    pub name: String,
+    #[serde(rename = "constraintA")]
+    pub constraint_a: String,
+    #[serde(rename = "constraintB")]
+    pub constraint_b: String,
+    /// Declared in require-dev in B (or in A, if B moved it back to require)
+    pub dev: bool,
+    /// Sample versions B accepts that A did not
+    #[serde(rename = "newlyAccepted", skip_serializing_if = "Vec::is_empty", default)]
+    pub newly_accepted: Vec<String>,
+    /// Sample versions A accepted that B does not
+    #[serde(rename = "noLongerAccepted", skip_serializing_if = "Vec::is_empty", default)]
+    pub no_longer_accepted: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConstraintComparisonSummary {
+    pub added: usize,
+    pub removed: usize,
+    pub loosened: usize,
+    pub tightened: usize,
+    pub shifted: usize,
+    pub equivalent: usize,
+    pub unclassified: usize,
+    pub unchanged: usize,
+}
+
+/// Compare the constraints declared in two composer.json files. With a ref, a side's
+/// composer.json is read from git (`git show <ref>:composer.json`) instead of the working
+/// tree, so passing one repo twice with two refs diffs its history.
+pub fn compare_constraints<P: AsRef<Path>>(
+    repo_a: P,
+    repo_b: P,
+    ref_a: Option<&str>,
+    ref_b: Option<&str>,
+) -> Result<String> {
+    let declared_a = declared_constraints(&read_composer_at(&repo_a, ref_a)?);
+    let declared_b = declared_constraints(&read_composer_at(&repo_b, ref_b)?);
+
+    let mut added = Vec::new();
+    let mut removed = Vec::new();
+    let mut loosened = Vec::new();
+    let mut tightened = Vec::new();
+    let mut shifted = Vec::new();
+    let mut equivalent = Vec::new();
+    let mut unclassified = Vec::new();
+    let mut unchanged = 0;
+
+    for (name, (constraint_a, dev_a)) in &declared_a {
+        let Some((constraint_b, dev_b)) = declared_b.get(name) else {
+            removed.push(DeclaredConstraint { name: name.clone(), constraint: constraint_a.clone(), dev: *dev_a });
+            continue;
+        };
+        if normalize(constraint_a) == normalize(constraint_b) {
+            unchanged += 1;
+            continue;
+        }
+
+        let mut change = ConstraintChange {
+            name: name.clone(),
+            constraint_a: constraint_a.clone(),
+            constraint_b: constraint_b.clone(),
+            dev: *dev_b || *dev_a,
+            newly_accepted: Vec::new(),
+            no_longer_accepted: Vec::new(),
+        };
+        let (Some(parsed_a), Some(parsed_b)) = (Constraint::parse(constraint_a), Constraint::parse(constraint_b)) else {
+            unclassified.push(change);
+            continue;
+        };
+
+        for version in sample_versions(&[constraint_a, constraint_b]) {
+            let (in_a, in_b) = (parsed_a.matches(&version), parsed_b.matches(&version));
+            if in_b && !in_a {
+                change.newly_accepted.push(format_version(&version));
+            } else if in_a && !in_b {
+                change.no_longer_accepted.push(format_version(&version));
+            }
+        }
+        let bucket = match (change.newly_accepted.is_empty(), change.no_longer_accepted.is_empty()) {
+            (true, true) => &mut equivalent,
+            (false, true) => &mut loosened,
+            (true, false) => &mut tightened,
+            (false, false) => &mut shifted,
+        };
+        change.newly_accepted.truncate(EXAMPLE_VERSIONS);
+        change.no_longer_accepted.truncate(EXAMPLE_VERSIONS);
+        bucket.push(change);
+    }
+
+    for (name, (constraint, dev)) in &declared_b {
+        if !declared_a.contains_key(name) {
+            added.push(DeclaredConstraint { name: name.clone(), constraint: constraint.clone(), dev: *dev });
+        }
+    }
+
+    let result = ConstraintComparisonResult {
+        repo_a: ConstraintSource {
+            path: repo_a.as_ref().display().to_string(),
+            git_ref: ref_a.map(str::to_string),
+        },
+        repo_b: ConstraintSource {
+            path: repo_b.as_ref().display().to_string(),
+            git_ref: ref_b.map(str::to_string),
+        },
+        summary: ConstraintComparisonSummary {
+            added: added.len(),
+            removed: removed.len(),
+            loosened: loosened.len(),
+            tightened: tightened.len(),
+            shifted: shifted.len(),
+            equivalent: equivalent.len(),
+            unclassified: unclassified.len(),
+            unchanged,
+        },
+        added,
+        removed,
+        loosened,
+        tightened,
+        shifted,
+        equivalent,
+        unclassified,
+    };
+
+    Ok(serde_json::to_string_pretty(&result)?)
+}
+
+/// composer.json from the working tree, or from `git_ref` when given
+fn read_composer_at<P: AsRef<Path>>(repo_path: P, git_ref: Option<&str>) -> Result<ComposerJson> {
+    let Some(git_ref) = git_ref else {
+        return read_composer_json(&repo_path);
+    };
+    let path = repo_path.as_ref().to_string_lossy();
+    let commit = resolve_commit(&path, git_ref)?;
+    let contents = git_output(&path, &["show", &format!("{}:./composer.json", commit)])
+        .map_err(|e| anyhow!("composer.json not found at {} in {}: {}", git_ref, path, e))?;
+    parse_composer_json(&contents)
+}
+
+/// Package name -> (constraint, declared in require-dev), sorted by name
+fn declared_constraints(composer: &ComposerJson) -> BTreeMap<String, (String, bool)> {
+    let dev = composer.require_dev.iter().flatten().map(|(n, c)| (n.clone(), (c.clone(), true)));
+    // require wins when a package is listed in both sections, as it does for Composer
+    dev.chain(composer.require.iter().flatten().map(|(n, c)| (n.clone(), (c.clone(), false))))
+        .collect()
+}
+
+/// Whitespace-insensitive form, so `^1.0|^2.0` and `^1.0 | ^2.0` count as unchanged
+fn normalize(constraint: &str) -> String {
+    constraint.split_whitespace().collect::<Vec<_>>().join(" ").replace(" |", "|").replace("| ", "|")
+}
+
+/// Versions on both sides of every bound either constraint mentions: each literal and its
+/// neighbours one major, minor and patch away, plus very low and very high versions to
+/// catch open-ended ranges. Branch names written in a constraint are sampled too.
+fn sample_versions(constraints: &[&String]) -> BTreeSet<Version> {
+    let stable = |parts: [u64; 4]| Version { parts, stability: Stability::Stable, pre_number: 0, branch: None };
+
+    let mut samples: BTreeSet<Version> =
+        [[0, 0, 0, 0], [0, 0, 1, 0], [0, 1, 0, 0], [999_999, 0, 0, 0]].into_iter().map(stable).collect();
+    for constraint in constraints {
+        for literal in VERSION_LITERAL_RE.find_iter(constraint) {
+            let Some(v) = Version::parse(literal.as_str()) else { continue };
+            for i in 0..3 {
+                let mut next = v.parts;
+                next[i] += 1;
+                next[i + 1..].fill(0);
+                samples.insert(stable(next));
+                if v.parts[i] > 0 {
+                    let mut previous = v.parts;
+                    previous[i] -= 1;
+                    samples.insert(stable(previous));
+                }
+            }
+            samples.insert(stable(v.parts));
+        }
+        for branch in BRANCH_RE.find_iter(constraint) {
+            samples.extend(Version::parse(branch.as_str()));
+        }
+    }
+    samples
+}
+
+fn format_version(version: &Version) -> String {
+    if let Some(branch) = &version.branch {
+        return format!("dev-{}", branch);
+    }
+    let [major, minor, patch, build] = version.parts;
+    if build > 0 {
+        format!("{}.{}.{}.{}", major, minor, patch, build)
+    } else {
+        format!("{}.{}.{}", major, minor, patch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changes_are_classified_by_accepted_versions() {
+        // (package, constraint in A, constraint in B, expected bucket)
+        let cases = [
+            ("a/loosened", "^1.2", "^1.0", "loosened"),
+            ("a/loosened-or", "^1.0", "^1.0 || ^2.0", "loosened"),
+            ("a/loosened-open", "~1.2.0", ">=1.2", "loosened"),
+            ("a/tightened", ">=1.0", "^1.5", "tightened"),
+            ("a/tightened-tilde", "^1.0", "~1.2.0", "tightened"),
+            ("a/shifted", "^1.0", "^2.0", "shifted"),
+            ("a/shifted-overlap", "^1.0 || ^2.0", "^2.0 || ^3.0", "shifted"),
+            ("a/equivalent", "^1.2", ">=1.2 <2.0", "equivalent"),
+            ("a/unclassified", "^1.0", "not a constraint", "unclassified"),
+            ("a/unchanged", "^1.0|^2.0", "^1.0 | ^2.0", "unchanged"),
+        ];
+
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        for side in ["a", "b"] {
+            let require: serde_json::Map<String, serde_json::Value> = cases
+                .iter()
+                .map(|&(name, a, b, _)| (name.to_string(), (if side == "a" { a } else { b }).into()))
+                .collect();
+            std::fs::create_dir_all(dir.join(side)).unwrap();
+            std::fs::write(dir.join(side).join("composer.json"), serde_json::json!({ "require": require }).to_string())
+                .unwrap();
+        }
+        let result = compare_constraints(dir.join("a"), dir.join("b"), None, None);
+        let result: ConstraintComparisonResult = serde_json::from_str(&result.unwrap()).unwrap();
+
+        let buckets = [
+            ("loosened", &result.loosened),
+            ("tightened", &result.tightened),
+            ("shifted", &result.shifted),
+            ("equivalent", &result.equivalent),
+            ("unclassified", &result.unclassified),
+        ];
+        for (name, a, b, expected) in cases {
+            let bucket = buckets
+                .iter()
+                .find(|(_, changes)| changes.iter().any(|c| c.name == name))
+                .map_or("unchanged", |(bucket, _)| *bucket);
+            assert_eq!(bucket, expected, "{}: {} -> {}", name, a, b);
+        }
+        assert_eq!(result.summary.unchanged, 1);
+    }
+
+    #[test]
+    fn test_option_like_refs_are_rejected() {
+        let error = read_composer_at(".", Some("--output=composer.json")).unwrap_err();
+        assert!(error.to_string().starts_with("Invalid git ref"));
+    }
+}
@@ -0,0 +1,68 @@
+//! Analysis of composer files passed as strings
+//! Lets a server running without the repository mounted (e.g. in a container) analyze client files
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use super::dependency::{analyze_parsed_dependencies, DependencyAnalysisResult, DependencyFilter, DependencyScope};
+use super::security::{analyze_lock_licenses, audit_lock, LicenseAnalysisResult, SecurityAuditResult, SecurityConfig};
+use crate::composer::{parse_composer_json, parse_composer_lock};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ContentsAnalysisResult {
+    pub dependencies: DependencyAnalysisResult,
+    /// Security and license analysis need composer.lock
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub security: Option<SecurityAuditResult>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub licenses: Option<LicenseAnalysisResult>,
+}
+
+/// Run dependency, security and license analysis on in-memory composer.json / composer.lock.
+/// Security findings below `severity_floor` are dropped.
+pub fn analyze_composer_contents(
+    composer_json: Option<&str>,
+    composer_lock: Option<&str>,
+    include_dev: bool,
+    severity_floor: Option<u8>,
+    config: &SecurityConfig,
+) -> Result<String> {
+    if composer_json.is_none() && composer_lock.is_none() {
+        return Err(anyhow!("composer_json or composer_lock content required"));
+    }
+
+    let composer = composer_json.map(parse_composer_json).transpose()?;
+    let lock = composer_lock.map(parse_composer_lock).transpose()?;
+
+    let scope = if include_dev { DependencyScope::All } else { DependencyScope::Production };
+    let security = lock.as_ref().map(|l| audit_lock(l, None, severity_floor, config, include_dev));
+    let licenses = lock.as_ref().map(|l| analyze_lock_licenses(l, include_dev));
+
+    let result = ContentsAnalysisResult {
+        dependencies: analyze_parsed_dependencies(composer, lock, &DependencyFilter::with_scope(scope))?,
+        security,
+        licenses,
+    };
+
+    Ok(serde_json::to_string_pretty(&result)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::security::severity_rank;
+
+    #[test]
+    fn test_severity_floor_applies_to_inline_lock() {
+        let lock = r#"{"packages": [{"name": "a/pre", "version": "0.3.0"}, {"name": "a/dev", "version": "1.0.x-dev"}]}"#;
+        let audit = |floor| {
+            let result = analyze_composer_contents(None, Some(lock), true, floor, &SecurityConfig::default()).unwrap();
+            let result: ContentsAnalysisResult = serde_json::from_str(&result).unwrap();
+            let security = result.security.unwrap();
+            security.vulnerabilities.into_iter().map(|v| (v.package, v.severity)).collect::<Vec<_>>()
+        };
+
+        assert_eq!(audit(None).len(), 2);
+        assert_eq!(audit(Some(severity_rank("medium"))), [("a/dev".to_string(), "medium".to_string())]);
+    }
+}
@@ -1,22 +1,61 @@
-use anyhow::Result;
+use anyhow::{anyhow, Context, Result};
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::sync::{Arc, Mutex};
 
-use crate::composer::{filter_php_dependencies, read_composer_json, read_composer_lock};
-use crate::types::{ComposerLock, DependencyNode};
+use crate::composer::{
+    cache, filter_php_dependencies, get_inline_packages, is_platform_package, package_name_matches,
+    read_composer_json, read_composer_lock,
+};
+use crate::types::{ComposerJson, ComposerLock, PackageInfo, DependencyNode, InlinePackage};
+use super::verdict::Verdict;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DependencyAnalysisResult {
     pub production: HashMap<String, String>,
     pub development: HashMap<String, String>,
+    /// Packages served by inline `"type": "package"` repositories (local sources)
+    #[serde(rename = "inlinePackages", default)]
+    pub inline_packages: Vec<InlinePackage>,
     pub tree: Vec<DependencyNode>,
+    #[serde(rename = "nestedTree", skip_serializing_if = "Option::is_none", default)]
+    pub nested_tree: Option<Vec<NestedDependencyNode>>,
     pub stats: DependencyStats,
+    #[serde(default)]
+    pub warnings: Vec<String>,
+    /// Set when there was no composer.json and the maps were derived from composer.lock
+    #[serde(rename = "composerJsonMissing", skip_serializing_if = "std::ops::Not::not", default)]
+    pub composer_json_missing: bool,
+    #[serde(default)]
+    pub summary: Verdict,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// A node of the nested install tree. The first occurrence of a package is
+/// expanded in place; every later occurrence is a `ref` leaf pointing at its id,
+/// which keeps shared and cyclic subtrees from blowing up the output.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NestedDependencyNode {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub version: Option<String>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none", default)]
+    pub node_type: Option<String>,
+    #[serde(rename = "ref", skip_serializing_if = "std::ops::Not::not", default)]
+    pub is_ref: bool,
+    /// Dependencies left out because the tree reached `MAX_NESTED_DEPTH`
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub truncated: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub dependencies: Vec<NestedDependencyNode>,
+}
+
+/// Deepest level the nested install tree is expanded to, so a pathological chain can't
+/// exhaust the stack
+const MAX_NESTED_DEPTH: usize = 64;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DependencyStats {
     #[serde(rename = "totalProduction")]
     pub total_production: usize,
@@ -27,61 +66,382 @@ pub struct DependencyStats {
     pub up_to_date: usize,
 }
 
+/// Which dependency groups an analysis covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DependencyScope {
+    Production,
+    Development,
+    #[default]
+    All,
+}
+
+impl DependencyScope {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "production" | "prod" => Some(Self::Production),
+            "development" | "dev" => Some(Self::Development),
+            "all" | "both" => Some(Self::All),
+            _ => None,
+        }
+    }
+
+    pub fn includes(&self, is_dev: bool) -> bool {
+        match self {
+            Self::Production => !is_dev,
+            Self::Development => is_dev,
+            Self::All => true,
+        }
+    }
+}
+
+/// Scope and exclusion filter applied to dependency trees and graphs
+#[derive(Debug, Clone, Default)]
+pub struct DependencyFilter {
+    pub scope: DependencyScope,
+    /// Package names or vendor wildcards (e.g. `symfony/*`) to hide
+    pub exclude: Vec<String>,
+}
+
+impl DependencyFilter {
+    pub fn with_scope(scope: DependencyScope) -> Self {
+        Self { scope, exclude: Vec::new() }
+    }
+
+    pub fn is_unrestricted(&self) -> bool {
+        self.scope == DependencyScope::All && self.exclude.is_empty()
+    }
+
+    /// Exclusion patterns match package names case-insensitively, like Packagist
+    pub fn is_excluded(&self, name: &str) -> bool {
+        self.exclude.iter().any(|pattern| package_name_matches(pattern, name))
+    }
+
+    pub fn includes(&self, name: &str, is_dev: bool) -> bool {
+        self.scope.includes(is_dev) && !self.is_excluded(name)
+    }
+}
+
 /// Analyze dependencies and return the raw struct
 pub fn analyze_dependencies_raw<P: AsRef<Path>>(repo_path: P) -> Result<DependencyAnalysisResult> {
-    let composer_json = read_composer_json(&repo_path)?;
+    analyze_dependencies_filtered(repo_path, &DependencyFilter::default())
+}
+
+/// Analyze dependencies, restricting the maps and tree to what the filter includes
+pub fn analyze_dependencies_filtered<P: AsRef<Path>>(
+    repo_path: P,
+    filter: &DependencyFilter,
+) -> Result<DependencyAnalysisResult> {
+    // The unfiltered analysis is what most tools build on, so it is shared between calls
+    if filter.is_unrestricted() {
+        return cache::memoize(repo_path.as_ref(), cache::Kind::Dependencies, || analyze_repo_dependencies(&repo_path, filter));
+    }
+    analyze_repo_dependencies(repo_path, filter)
+}
 
-    let lock = read_composer_lock(&repo_path).ok();
+fn analyze_repo_dependencies<P: AsRef<Path>>(repo_path: P, filter: &DependencyFilter) -> Result<DependencyAnalysisResult> {
+    // A vendored snapshot may ship only composer.lock; anything else wrong with composer.json is an error
+    let composer_json = if repo_path.as_ref().join("composer.json").exists() {
+        Some(read_composer_json(&repo_path)?)
+    } else {
+        None
+    };
+    let lock = match &composer_json {
+        Some(_) => read_composer_lock(&repo_path).ok(),
+        None => Some(read_composer_lock(&repo_path).context(
+            "Neither composer.json nor a readable composer.lock found",
+        )?),
+    };
 
-    let production = composer_json
-        .require
-        .map(|r| filter_php_dependencies(&r))
-        .unwrap_or_default();
+    analyze_parsed_dependencies(composer_json, lock, filter)
+}
 
-    let development = composer_json.require_dev.unwrap_or_default();
+/// Analyze already-parsed composer files; at least one of them must be present
+pub fn analyze_parsed_dependencies(
+    composer_json: Option<ComposerJson>,
+    lock: Option<ComposerLock>,
+    filter: &DependencyFilter,
+) -> Result<DependencyAnalysisResult> {
+    if composer_json.is_none() && lock.is_none() {
+        return Err(anyhow!("Neither composer.json nor composer.lock provided"));
+    }
+    let composer_json_missing = composer_json.is_none();
 
-    let tree = if let Some(lock) = lock {
-        build_dependency_tree(&lock)
+    let inline_packages = composer_json.as_ref().map(get_inline_packages).unwrap_or_default();
+
+    let tree = if let Some(lock) = &lock {
+        build_dependency_tree(lock, composer_json.as_ref(), filter)
     } else {
         Vec::new()
     };
 
+    let (production, development): (HashMap<String, String>, HashMap<String, String>) = match composer_json {
+        Some(composer_json) => (
+            composer_json
+                .require
+                .map(|r| filter_php_dependencies(&r))
+                .unwrap_or_default(),
+            composer_json.require_dev.unwrap_or_default(),
+        ),
+        None => {
+            let lock = lock.as_ref().expect("lock is required without composer.json");
+            let versions = |packages: &[PackageInfo]| -> HashMap<String, String> {
+                packages.iter().map(|p| (p.name.clone(), p.version.clone())).collect()
+            };
+            (
+                versions(&lock.packages),
+                versions(lock.packages_dev.as_deref().unwrap_or_default()),
+            )
+        }
+    };
+
+    // Composer hygiene: a require-dev entry for a package already in require is redundant
+    let mut duplicated: Vec<(&String, &String, &String)> = development
+        .iter()
+        .filter_map(|(name, dev_constraint)| {
+            production
+                .get(name)
+                .map(|constraint| (name, constraint, dev_constraint))
+        })
+        .collect();
+    duplicated.sort();
+    let mut warnings: Vec<String> = duplicated
+        .into_iter()
+        .map(|(name, constraint, dev_constraint)| {
+            format!(
+                "{} is in both require ({}) and require-dev ({}); the require-dev entry is redundant",
+                name, constraint, dev_constraint
+            )
+        })
+        .collect();
+
+    let production: HashMap<String, String> = production
+        .into_iter()
+        .filter(|(name, _)| filter.includes(name, false))
+        .collect();
+
+    let development: HashMap<String, String> = development
+        .into_iter()
+        .filter(|(name, _)| filter.includes(name, true))
+        .collect();
+
+    // A filtered view hides packages on purpose, so only cross-check the full picture
+    if !tree.is_empty() && filter.is_unrestricted() && !composer_json_missing {
+        warnings.extend(check_lock_consistency(&production, &development, &tree));
+    }
+
+    // Hygiene warnings never fail the analysis, but an unlocked project can't be reproduced
+    let lock_missing = lock.is_none();
+    let penalty = warnings.len() * 10 + if lock_missing { 30 } else { 0 };
+    let issues = lock_missing
+        .then(|| "composer.lock not found; installs are not reproducible".to_string())
+        .into_iter()
+        .chain(warnings.iter().cloned());
+    let summary = Verdict::new(
+        100.0 - penalty as f64,
+        if lock_missing || !warnings.is_empty() { "warn" } else { "ok" },
+        issues,
+    );
+
     Ok(DependencyAnalysisResult {
         production: production.clone(),
         development: development.clone(),
+        inline_packages,
         tree,
+        nested_tree: None,
         stats: DependencyStats {
             total_production: production.len(),
             total_development: development.len(),
             outdated: 0,
             up_to_date: 0,
         },
+        warnings,
+        composer_json_missing,
+        summary,
     })
 }
 
+/// Cross-check composer.json requires against the packages actually locked
+fn check_lock_consistency(
+    production: &HashMap<String, String>,
+    development: &HashMap<String, String>,
+    tree: &[DependencyNode],
+) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    let required: HashSet<&str> = production
+        .keys()
+        .chain(development.keys())
+        .map(|s| s.as_str())
+        .filter(|name| !is_platform_package(name))
+        .collect();
+    let locked: HashSet<&str> = tree.iter().map(|n| n.name.as_str()).collect();
+
+    let mut missing: Vec<&&str> = required.difference(&locked).collect();
+    missing.sort();
+    for name in missing {
+        warnings.push(format!(
+            "{} is required in composer.json but missing from composer.lock (never installed; run composer update)",
+            name
+        ));
+    }
+
+    // Top-level lock entries are packages nothing else in the lock depends on
+    let mut orphaned: Vec<&str> = tree
+        .iter()
+        .filter(|n| n.used_by.is_empty() && !required.contains(n.name.as_str()))
+        .map(|n| n.name.as_str())
+        .collect();
+    orphaned.sort();
+    for name in orphaned {
+        warnings.push(format!(
+            "{} is locked at the top level but not required by composer.json (stale lock or manual edit)",
+            name
+        ));
+    }
+
+    warnings
+}
+
 /// Analyze dependencies and return JSON string
-pub fn analyze_dependencies<P: AsRef<Path>>(repo_path: P) -> Result<String> {
-    let result = analyze_dependencies_raw(repo_path)?;
+pub fn analyze_dependencies<P: AsRef<Path>>(
+    repo_path: P,
+    nested: bool,
+    filter: &DependencyFilter,
+) -> Result<String> {
+    let mut result = analyze_dependencies_filtered(repo_path, filter)?;
+    if nested {
+        result.nested_tree = Some(build_nested_tree(&result));
+    }
     Ok(serde_json::to_string_pretty(&result)?)
 }
 
-fn build_dependency_tree(lock: &ComposerLock) -> Vec<DependencyNode> {
+/// Build the nested install tree rooted at the application's direct requires
+pub fn build_nested_tree(result: &DependencyAnalysisResult) -> Vec<NestedDependencyNode> {
+    let nodes: HashMap<&str, &DependencyNode> =
+        result.tree.iter().map(|n| (n.name.as_str(), n)).collect();
+
+    let mut roots: Vec<&String> = result
+        .production
+        .keys()
+        .chain(result.development.keys())
+        .filter(|name| !is_platform_package(name))
+        .collect();
+    roots.sort();
+    roots.dedup();
+
+    let mut expanded = HashSet::new();
+    roots
+        .into_iter()
+        .map(|name| expand_nested_node(name, &nodes, &mut expanded, 1))
+        .collect()
+}
+
+fn expand_nested_node(
+    name: &str,
+    nodes: &HashMap<&str, &DependencyNode>,
+    expanded: &mut HashSet<String>,
+    depth: usize,
+) -> NestedDependencyNode {
+    let node = nodes.get(name);
+
+    if !expanded.insert(name.to_string()) {
+        return NestedDependencyNode {
+            id: name.to_string(),
+            version: None,
+            node_type: None,
+            is_ref: true,
+            truncated: false,
+            dependencies: Vec::new(),
+        };
+    }
+
+    let mut children: Vec<&String> = node.map(|n| n.dependencies.iter().collect()).unwrap_or_default();
+    children.sort();
+    let truncated = depth >= MAX_NESTED_DEPTH && !children.is_empty();
+    if truncated {
+        children.clear();
+    }
+
+    NestedDependencyNode {
+        id: name.to_string(),
+        version: node.map(|n| n.version.clone()),
+        node_type: node.map(|n| n.node_type.clone()),
+        is_ref: false,
+        truncated,
+        dependencies: children
+            .into_iter()
+            .map(|child| expand_nested_node(child, nodes, expanded, depth + 1))
+            .collect(),
+    }
+}
+
+/// Packages reachable from `roots` through the locked `require` graph
+fn reachable_packages<'a>(packages: &[PackageInfo], roots: impl Iterator<Item = &'a String>) -> HashSet<String> {
+    let by_name: HashMap<String, &PackageInfo> =
+        packages.iter().map(|p| (p.name.to_lowercase(), p)).collect();
+
+    let mut reachable = HashSet::new();
+    let mut queue: Vec<String> = roots.map(|n| n.to_lowercase()).collect();
+
+    while let Some(name) = queue.pop() {
+        let Some(pkg) = by_name.get(&name) else {
+            continue;
+        };
+        if !reachable.insert(name) {
+            continue;
+        }
+        if let Some(require) = &pkg.require {
+            queue.extend(
+                require
+                    .keys()
+                    .filter(|dep| !is_platform_package(dep))
+                    .map(|dep| dep.to_lowercase()),
+            );
+        }
+    }
+
+    reachable
+}
+
+/// Build the flat lock tree. With composer.json available, a package is "production" when it is
+/// reachable from `require` and "development" when only `require-dev` reaches it; packages
+/// reachable from neither (or without composer.json) keep the lock section they were listed in.
+fn build_dependency_tree(
+    lock: &ComposerLock,
+    composer_json: Option<&ComposerJson>,
+    filter: &DependencyFilter,
+) -> Vec<DependencyNode> {
     let mut all_packages = lock.packages.clone();
     if let Some(dev_packages) = &lock.packages_dev {
         all_packages.extend(dev_packages.clone());
     }
 
     let production_count = lock.packages.len();
+    let reachable = composer_json.map(|c| {
+        (
+            reachable_packages(&all_packages, c.require.iter().flat_map(|r| r.keys())),
+            reachable_packages(&all_packages, c.require_dev.iter().flat_map(|r| r.keys())),
+        )
+    });
+    let is_dev = |index: usize, pkg: &PackageInfo| {
+        let name = pkg.name.to_lowercase();
+        match &reachable {
+            Some((production, _)) if production.contains(&name) => false,
+            Some((_, development)) if development.contains(&name) => true,
+            _ => index >= production_count,
+        }
+    };
 
     // Build tree in parallel using rayon
     let tree: Vec<DependencyNode> = all_packages
         .par_iter()
         .enumerate()
+        .filter(|(index, pkg)| filter.includes(&pkg.name, is_dev(*index, pkg)))
         .map(|(index, pkg)| {
-            let node_type = if index < production_count {
-                "production"
-            } else {
+            let node_type = if is_dev(index, pkg) {
                 "development"
+            } else {
+                "production"
             };
 
             let deps: Vec<String> = pkg
@@ -89,7 +449,7 @@ fn build_dependency_tree(lock: &ComposerLock) -> Vec<DependencyNode> {
                 .as_ref()
                 .map(|r| {
                     r.keys()
-                        .filter(|name| !name.starts_with("php") && !name.starts_with("ext-"))
+                        .filter(|name| !is_platform_package(name) && !filter.is_excluded(name))
                         .cloned()
                         .collect()
                 })
@@ -120,7 +480,7 @@ fn build_dependency_tree(lock: &ComposerLock) -> Vec<DependencyNode> {
         for dep in &node.dependencies {
             let mut map = used_by_map.lock().unwrap();
             map.entry(dep.clone())
-                .or_insert_with(Vec::new)
+                .or_default()
                 .push(node.name.clone());
         }
     });
@@ -143,7 +503,8 @@ pub struct CircularDependenciesResult {
 
 pub fn find_circular_dependencies<P: AsRef<Path>>(repo_path: P) -> Result<String> {
     let lock = read_composer_lock(&repo_path)?;
-    let tree = build_dependency_tree(&lock);
+    let composer_json = read_composer_json(&repo_path).ok();
+    let tree = build_dependency_tree(&lock, composer_json.as_ref(), &DependencyFilter::default());
 
     let cycles = detect_cycles(&tree);
 
@@ -156,46 +517,83 @@ pub fn find_circular_dependencies<P: AsRef<Path>>(repo_path: P) -> Result<String
 }
 
 fn detect_cycles(tree: &[DependencyNode]) -> Vec<Vec<String>> {
+    let nodes: HashMap<&str, &DependencyNode> = tree.iter().map(|n| (n.name.as_str(), n)).collect();
     let mut cycles = Vec::new();
-    let mut visited = HashMap::new();
-    let mut rec_stack = HashMap::new();
+    let mut visited = HashSet::new();
 
     for node in tree {
-        if !visited.contains_key(&node.name) {
-            dfs(&node.name, tree, &mut visited, &mut rec_stack, &mut Vec::new(), &mut cycles);
+        if !visited.contains(node.name.as_str()) {
+            dfs(&node.name, &nodes, &mut visited, &mut cycles);
         }
     }
 
     cycles
 }
 
-fn dfs(
-    pkg_name: &str,
-    tree: &[DependencyNode],
-    visited: &mut HashMap<String, bool>,
-    rec_stack: &mut HashMap<String, bool>,
-    path: &mut Vec<String>,
+/// Depth-first cycle search using an explicit work stack, so deep or adversarial
+/// dependency chains cannot overflow the call stack
+fn dfs<'a>(
+    start: &'a str,
+    nodes: &HashMap<&str, &'a DependencyNode>,
+    visited: &mut HashSet<&'a str>,
     cycles: &mut Vec<Vec<String>>,
 ) {
-    visited.insert(pkg_name.to_string(), true);
-    rec_stack.insert(pkg_name.to_string(), true);
-    path.push(pkg_name.to_string());
+    // Each frame is a package on the current path and the index of its next dependency to visit
+    let mut stack: Vec<(&'a str, usize)> = vec![(start, 0)];
+    let mut on_path: HashSet<&'a str> = HashSet::from([start]);
+    visited.insert(start);
 
-    if let Some(node) = tree.iter().find(|n| n.name == pkg_name) {
-        for dep in &node.dependencies {
-            if !visited.get(dep).unwrap_or(&false) {
-                dfs(dep, tree, visited, rec_stack, path, cycles);
-            } else if *rec_stack.get(dep).unwrap_or(&false) {
-                // Found a cycle
-                if let Some(start) = path.iter().position(|p| p == dep) {
-                    let mut cycle = path[start..].to_vec();
-                    cycle.push(dep.clone());
-                    cycles.push(cycle);
-                }
+    while let Some(frame) = stack.last_mut() {
+        let (pkg_name, next) = *frame;
+        let deps: &'a [String] = nodes.get(pkg_name).map(|n| n.dependencies.as_slice()).unwrap_or(&[]);
+
+        let Some(dep) = deps.get(next) else {
+            on_path.remove(pkg_name);
+            stack.pop();
+            continue;
+        };
+        frame.1 += 1;
+
+        if !visited.contains(dep.as_str()) {
+            visited.insert(dep);
+            on_path.insert(dep);
+            stack.push((dep, 0));
+        } else if on_path.contains(dep.as_str()) {
+            // Found a cycle
+            if let Some(start) = stack.iter().position(|(p, _)| *p == dep) {
+                let mut cycle: Vec<String> = stack[start..].iter().map(|(p, _)| p.to_string()).collect();
+                cycle.push(dep.clone());
+                cycles.push(cycle);
             }
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    rec_stack.insert(pkg_name.to_string(), false);
-    path.pop();
+    #[test]
+    fn test_nested_tree_stops_at_max_depth() {
+        let chain: Vec<DependencyNode> = (0..MAX_NESTED_DEPTH + 10)
+            .map(|i| DependencyNode {
+                name: format!("v/p{}", i),
+                version: "1.0.0".to_string(),
+                node_type: "production".to_string(),
+                dependencies: vec![format!("v/p{}", i + 1)],
+                used_by: Vec::new(),
+                license: None,
+            })
+            .collect();
+        let nodes: HashMap<&str, &DependencyNode> = chain.iter().map(|n| (n.name.as_str(), n)).collect();
+
+        let mut node = expand_nested_node("v/p0", &nodes, &mut HashSet::new(), 1);
+        let mut depth = 1;
+        while let Some(child) = node.dependencies.pop() {
+            node = child;
+            depth += 1;
+        }
+        assert_eq!(depth, MAX_NESTED_DEPTH);
+        assert!(node.truncated);
+    }
 }
@@ -0,0 +1,231 @@
+//! Composer `extra` sections
+//! Surfaces the framework and plugin settings kept under `extra` (Laravel package discovery,
+//! Symfony Flex, composer-patches, branch aliases) for the project and every locked package
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use super::vendor_integrity::declared_patches;
+use crate::composer::{lock_path, read_composer_json, read_composer_lock};
+
+/// Keys covered by a dedicated field; anything else is listed under `otherKeys`
+const RECOGNIZED_KEYS: &[&str] = &["laravel", "symfony", "patches", "patches-file", "branch-alias"];
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExtraAnalysisResult {
+    /// The project first, then locked packages by name; only those with an `extra` section
+    pub packages: Vec<PackageExtra>,
+    pub summary: ExtraSummary,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PackageExtra {
+    pub name: String,
+    /// Locked version; absent for the project itself
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// The project's own composer.json rather than a dependency
+    pub root: bool,
+    pub dev: bool,
+    /// One line describing the recognized sections
+    pub summary: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub laravel: Option<LaravelExtra>,
+    /// `extra.symfony` as written (Flex settings such as `require` and `allow-contrib`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symfony: Option<Value>,
+    /// Package -> patch descriptions applied by cweagans/composer-patches
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub patches: BTreeMap<String, Vec<String>>,
+    /// Branch -> the version it is aliased as
+    #[serde(rename = "branchAlias", default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub branch_alias: BTreeMap<String, String>,
+    #[serde(rename = "otherKeys", default, skip_serializing_if = "Vec::is_empty")]
+    pub other_keys: Vec<String>,
+}
+
+/// Laravel package discovery settings
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LaravelExtra {
+    pub providers: Vec<String>,
+    /// Facade alias -> class
+    pub aliases: BTreeMap<String, String>,
+    /// Packages excluded from discovery (`*` for all); set by the project
+    #[serde(rename = "dontDiscover", default, skip_serializing_if = "Vec::is_empty")]
+    pub dont_discover: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExtraSummary {
+    #[serde(rename = "packagesWithExtra")]
+    pub packages_with_extra: usize,
+    /// Packages registering Laravel service providers or aliases
+    #[serde(rename = "laravelPackages")]
+    pub laravel_packages: usize,
+    #[serde(rename = "symfonyPackages")]
+    pub symfony_packages: usize,
+    /// Distinct packages that have patches declared against them
+    #[serde(rename = "patchedPackages")]
+    pub patched_packages: usize,
+    /// Branch aliases across all packages
+    #[serde(rename = "branchAliases")]
+    pub branch_aliases: usize,
+}
+
+/// Report the recognized `extra` sections of composer.json and of each package in the lock
+/// (when there is one), with a one-line summary per package
+pub fn analyze_extra<P: AsRef<Path>>(repo_path: P) -> Result<String> {
+    let repo_path = repo_path.as_ref();
+    let composer = read_composer_json(repo_path)?;
+
+    let mut packages = Vec::new();
+    if let Some(extra) = composer.extra.as_ref().filter(|e| !e.is_empty()) {
+        let name = composer.name.clone().unwrap_or_else(|| "(root)".to_string());
+        packages.push(describe(name, None, true, false, extra, Some(repo_path)));
+    }
+
+    if lock_path(repo_path).exists() {
+        let lock = read_composer_lock(repo_path)?;
+        let locked = lock
+            .packages
+            .iter()
+            .map(|pkg| (pkg, false))
+            .chain(lock.packages_dev.iter().flatten().map(|pkg| (pkg, true)));
+        let mut dependencies: Vec<PackageExtra> = locked
+            .filter_map(|(pkg, dev)| {
+                let extra = pkg.extra.as_ref().filter(|e| !e.is_empty())?;
+                Some(describe(pkg.name.clone(), Some(pkg.version.clone()), false, dev, extra, None))
+            })
+            .collect();
+        dependencies.sort_by(|a, b| a.name.cmp(&b.name));
+        packages.extend(dependencies);
+    }
+
+    let mut patched: Vec<&String> = packages.iter().flat_map(|p| p.patches.keys()).collect();
+    patched.sort();
+    patched.dedup();
+
+    let result = ExtraAnalysisResult {
+        summary: ExtraSummary {
+            packages_with_extra: packages.len(),
+            laravel_packages: packages.iter().filter(|p| p.laravel.is_some()).count(),
+            symfony_packages: packages.iter().filter(|p| p.symfony.is_some()).count(),
+            patched_packages: patched.len(),
+            branch_aliases: packages.iter().map(|p| p.branch_alias.len()).sum(),
+        },
+        packages,
+    };
+
+    Ok(serde_json::to_string_pretty(&result)?)
+}
+
+/// `base_dir` is where the root project's `patches-file` is resolved from; `None` for dependencies
+fn describe(
+    name: String,
+    version: Option<String>,
+    root: bool,
+    dev: bool,
+    extra: &HashMap<String, Value>,
+    base_dir: Option<&Path>,
+) -> PackageExtra {
+    let laravel = extra.get("laravel").map(|section| LaravelExtra {
+        providers: strings(section.get("providers")),
+        aliases: string_map(section.get("aliases")),
+        dont_discover: strings(section.get("dont-discover")),
+    });
+    let symfony = extra.get("symfony").cloned();
+    let patches: BTreeMap<String, Vec<String>> = declared_patches(base_dir, Some(extra)).into_iter().collect();
+    let branch_alias = string_map(extra.get("branch-alias"));
+    let mut other_keys: Vec<String> =
+        extra.keys().filter(|k| !RECOGNIZED_KEYS.contains(&k.as_str())).cloned().collect();
+    other_keys.sort();
+
+    let mut parts = Vec::new();
+    if let Some(laravel) = &laravel {
+        parts.push(format!(
+            "Laravel: {} provider(s), {} alias(es){}",
+            laravel.providers.len(),
+            laravel.aliases.len(),
+            if laravel.dont_discover.is_empty() { String::new() } else { format!(", dont-discover {}", laravel.dont_discover.join(", ")) }
+        ));
+    }
+    if let Some(symfony) = &symfony {
+        match symfony.get("require").and_then(Value::as_str) {
+            Some(constraint) => parts.push(format!("Symfony Flex: symfony/* pinned to {}", constraint)),
+            None => parts.push("Symfony Flex settings".to_string()),
+        }
+    }
+    if !patches.is_empty() {
+        let count: usize = patches.values().map(Vec::len).sum();
+        parts.push(format!("{} patch(es) for {} package(s)", count, patches.len()));
+    }
+    if !branch_alias.is_empty() {
+        let aliases: Vec<String> = branch_alias.iter().map(|(branch, alias)| format!("{} as {}", branch, alias)).collect();
+        parts.push(format!("branch alias {}", aliases.join(", ")));
+    }
+    if !other_keys.is_empty() {
+        parts.push(format!("other: {}", other_keys.join(", ")));
+    }
+
+    PackageExtra {
+        name,
+        version,
+        root,
+        dev,
+        summary: parts.join("; "),
+        laravel,
+        symfony,
+        patches,
+        branch_alias,
+        other_keys,
+    }
+}
+
+/// A string list, also accepting a single string
+fn strings(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::String(s)) => vec![s.clone()],
+        Some(Value::Array(items)) => items.iter().filter_map(Value::as_str).map(str::to_string).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn string_map(value: Option<&Value>) -> BTreeMap<String, String> {
+    value
+        .and_then(Value::as_object)
+        .map(|map| map.iter().filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string()))).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::fs;
+
+    #[test]
+    fn test_dependency_patches_file_is_ignored() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        let outside = dir.join("outside.json");
+        fs::write(&outside, json!({"patches": {"acme/lib": {"Leaked": "x.patch"}}}).to_string()).unwrap();
+        fs::write(dir.join("composer.json"), json!({"name": "acme/app", "extra": []}).to_string()).unwrap();
+        let lock = json!({"packages": [
+            {"name": "acme/empty", "version": "1.0.0", "extra": []},
+            {"name": "acme/hostile", "version": "1.0.0", "extra": {"patches-file": outside.to_string_lossy()}},
+            {"name": "acme/aliased", "version": "1.0.0", "extra": {"branch-alias": {"dev-main": "2.x-dev", "dev-next": "3.x-dev"}}}
+        ]});
+        fs::write(dir.join("composer.lock"), lock.to_string()).unwrap();
+
+        let result: ExtraAnalysisResult = serde_json::from_str(&analyze_extra(dir).unwrap()).unwrap();
+
+        let names: Vec<&str> = result.packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["acme/aliased", "acme/hostile"]);
+        assert!(result.packages[1].patches.is_empty());
+        assert_eq!(result.summary.patched_packages, 0);
+        assert_eq!(result.summary.branch_aliases, 2);
+    }
+}
@@ -0,0 +1,365 @@
+//! Security fix planning
+//! Finds the lowest release of a vulnerable package that clears its Packagist advisories
+//! and still fits the project's constraints, and the Composer command that installs it
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::composer::constraint::{Constraint, Stability, Version};
+use crate::composer::{is_platform_package, normalize_package_name, read_composer_json, read_composer_lock};
+use crate::packagist::{fetch_package, fetch_security_advisories, PackagistPackage, PackagistVersion, SecurityAdvisory};
+use crate::types::{ComposerJson, ComposerLock};
+
+#[derive(Debug, Serialize)]
+pub struct FixVersionResult {
+    pub package: String,
+    pub installed: String,
+    #[serde(rename = "isDev")]
+    pub is_dev: bool,
+    pub vulnerable: bool,
+    /// Advisories affecting the installed version
+    pub advisories: Vec<SecurityAdvisory>,
+    /// Constraint in composer.json; absent for transitive dependencies
+    #[serde(rename = "rootConstraint", skip_serializing_if = "Option::is_none")]
+    pub root_constraint: Option<String>,
+    /// Locked packages that require this one, with their constraints
+    #[serde(rename = "requiredBy")]
+    pub required_by: Vec<DependentConstraint>,
+    /// "not-vulnerable", "composer-update", "constraint-change", "blocked" or "no-fix-available"
+    pub resolution: String,
+    #[serde(rename = "targetVersion", skip_serializing_if = "Option::is_none")]
+    pub target_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// Locked packages the target version needs newer releases of
+    #[serde(rename = "alsoUpdates", skip_serializing_if = "Vec::is_empty")]
+    pub also_updates: Vec<String>,
+    /// Dependents whose constraints exclude every fixed release
+    #[serde(rename = "blockedBy", skip_serializing_if = "Vec::is_empty")]
+    pub blocked_by: Vec<DependentConstraint>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DependentConstraint {
+    pub package: String,
+    pub constraint: String,
+}
+
+/// Plan the smallest upgrade that fixes `package`'s known advisories
+pub fn suggest_fix_version<P: AsRef<Path>>(repo_path: P, package: &str) -> Result<String> {
+    let result = suggest_fix_version_raw(repo_path, package)?;
+    Ok(serde_json::to_string_pretty(&result)?)
+}
+
+/// Plan the smallest upgrade that fixes `package`'s known advisories and return the raw struct
+pub fn suggest_fix_version_raw<P: AsRef<Path>>(repo_path: P, package: &str) -> Result<FixVersionResult> {
+    let composer = read_composer_json(&repo_path)?;
+    let lock = read_composer_lock(&repo_path)?;
+    let name = normalize_package_name(package);
+    plan_fix_version(&composer, &lock, &name, fetch_security_advisories, fetch_package)
+}
+
+/// Plan the fix for `name` from the given manifests, looking advisories and releases up
+/// through the supplied fetchers; releases are only fetched when something is vulnerable
+fn plan_fix_version(
+    composer: &ComposerJson,
+    lock: &ComposerLock,
+    name: &str,
+    fetch_advisories: impl FnOnce(&str) -> Result<Arc<Vec<SecurityAdvisory>>>,
+    fetch_releases: impl FnOnce(&str) -> Result<Arc<PackagistPackage>>,
+) -> Result<FixVersionResult> {
+    let name = name.to_string();
+    let (locked, is_dev) = lock
+        .packages
+        .iter()
+        .map(|p| (p, false))
+        .chain(lock.packages_dev.iter().flatten().map(|p| (p, true)))
+        .find(|(p, _)| p.name.eq_ignore_ascii_case(&name))
+        .ok_or_else(|| anyhow!("Package {} not found in composer.lock", name))?;
+    let installed = Version::parse(&locked.version)
+        .ok_or_else(|| anyhow!("Unsupported locked version {} for {}", locked.version, name))?;
+
+    let advisories = fetch_advisories(&name)?;
+    let affecting: Vec<SecurityAdvisory> = advisories
+        .iter()
+        .filter(|a| a.affects(&installed))
+        .cloned()
+        .collect();
+
+    let minimum_stability = composer
+        .minimum_stability
+        .as_deref()
+        .and_then(Stability::parse)
+        .unwrap_or(Stability::Stable);
+
+    let root_constraint = composer
+        .require
+        .iter()
+        .chain(composer.require_dev.iter())
+        .find_map(|r| r.get(&name).cloned());
+
+    let mut required_by: Vec<DependentConstraint> = lock
+        .packages
+        .iter()
+        .chain(lock.packages_dev.iter().flatten())
+        .filter_map(|p| {
+            p.require.as_ref()?.get(&name).map(|c| DependentConstraint {
+                package: p.name.clone(),
+                constraint: c.clone(),
+            })
+        })
+        .collect();
+    required_by.sort_by(|a, b| a.package.cmp(&b.package));
+
+    let mut result = FixVersionResult {
+        package: name.clone(),
+        installed: locked.version.clone(),
+        is_dev,
+        vulnerable: !affecting.is_empty(),
+        advisories: affecting,
+        root_constraint: root_constraint.clone(),
+        required_by: required_by.clone(),
+        resolution: "not-vulnerable".to_string(),
+        target_version: None,
+        command: None,
+        also_updates: Vec::new(),
+        blocked_by: Vec::new(),
+        message: format!("{} {} has no known advisories", name, locked.version),
+    };
+    if !result.vulnerable {
+        return Ok(result);
+    }
+
+    // Newer releases Composer would install that no advisory covers, lowest first
+    let releases = fetch_releases(&name)?;
+    let mut fixed: Vec<(Version, &PackagistVersion)> = releases
+        .parsed_versions()
+        .into_iter()
+        .filter(|(v, _)| !v.is_branch() && *v > installed && v.stability.accepted_by(minimum_stability))
+        .filter(|(v, _)| !advisories.iter().any(|a| a.affects(v)))
+        .collect();
+    fixed.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let root = root_constraint.as_deref().and_then(Constraint::parse);
+    let dependents: Vec<(&DependentConstraint, Constraint)> = required_by
+        .iter()
+        .filter_map(|d| Constraint::parse(&d.constraint).map(|c| (d, c)))
+        .collect();
+    let dependents_allow = |v: &Version| dependents.iter().all(|(_, c)| c.matches(v));
+    let root_allows = |v: &Version| root.as_ref().is_none_or(|c| c.accepts(v, minimum_stability));
+
+    let within_root = fixed.iter().find(|(v, _)| dependents_allow(v) && root_allows(v));
+    let outside_root = fixed.iter().find(|(v, _)| dependents_allow(v));
+
+    let (target, resolution) = match (within_root, outside_root, fixed.first()) {
+        (Some(t), _, _) => (t, "composer-update"),
+        (None, Some(t), _) => (t, "constraint-change"),
+        (None, None, Some(t)) => (t, "blocked"),
+        (None, None, None) => {
+            result.resolution = "no-fix-available".to_string();
+            result.message = format!(
+                "No published release of {} newer than {} is free of the known advisories",
+                name, locked.version
+            );
+            return Ok(result);
+        }
+    };
+    let (target_parsed, target) = target;
+    let target_version = target.version.trim_start_matches(['v', 'V']).to_string();
+
+    // Locked packages the target requires a different version of
+    let locked_versions: HashMap<&str, &str> = lock
+        .packages
+        .iter()
+        .chain(lock.packages_dev.iter().flatten())
+        .map(|p| (p.name.as_str(), p.version.as_str()))
+        .collect();
+    let mut also_updates: Vec<String> = target
+        .require
+        .iter()
+        .flatten()
+        .filter(|(dep, _)| !is_platform_package(dep))
+        .filter(|(dep, constraint)| {
+            let accepted = Constraint::parse(constraint).zip(locked_versions.get(dep.as_str()).and_then(|v| Version::parse(v)));
+            !matches!(accepted, Some((c, v)) if c.matches(&v))
+        })
+        .map(|(dep, _)| dep.clone())
+        .collect();
+    also_updates.sort();
+
+    let command = match resolution {
+        "composer-update" => {
+            let mut command = format!("composer update {} --with {}:{}", name, name, target_version);
+            if !also_updates.is_empty() {
+                command.push_str(" --with-dependencies");
+            }
+            Some(command)
+        }
+        "constraint-change" => {
+            let dev = if composer.require_dev.as_ref().is_some_and(|r| r.contains_key(&name)) { " --dev" } else { "" };
+            let mut command = format!("composer require{} {}:^{}", dev, name, target_version);
+            if !also_updates.is_empty() {
+                command.push_str(" --update-with-dependencies");
+            }
+            Some(command)
+        }
+        _ => None,
+    };
+
+    result.message = match resolution {
+        "composer-update" => format!(
+            "{} clears the advisories affecting {} and is allowed by the current constraints",
+            target_version, locked.version
+        ),
+        "constraint-change" => format!(
+            "{} clears the advisories affecting {} but falls outside the composer.json constraint {}",
+            target_version,
+            locked.version,
+            root_constraint.as_deref().unwrap_or("*")
+        ),
+        _ => format!(
+            "{} is the lowest fixed release, but packages requiring {} don't allow it yet",
+            target_version, name
+        ),
+    };
+    if resolution == "blocked" {
+        result.blocked_by = dependents
+            .iter()
+            .filter(|(_, c)| !c.matches(target_parsed))
+            .map(|(d, _)| (*d).clone())
+            .collect();
+    }
+    result.resolution = resolution.to_string();
+    result.target_version = Some(target_version);
+    result.command = command;
+    result.also_updates = also_updates;
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn lock(packages: Value) -> ComposerLock {
+        serde_json::from_value(json!({ "packages": packages })).unwrap()
+    }
+
+    fn advisory(affected_versions: &str) -> Arc<Vec<SecurityAdvisory>> {
+        let advisory = json!({"title": "RCE", "affectedVersions": affected_versions});
+        Arc::new(vec![serde_json::from_value(advisory).unwrap()])
+    }
+
+    fn releases(versions: Value) -> Arc<PackagistPackage> {
+        Arc::new(serde_json::from_value(json!({"name": "acme/lib", "versions": versions})).unwrap())
+    }
+
+    fn plan(require: Value, packages: Value, affected: &str, versions: Value) -> FixVersionResult {
+        let composer = serde_json::from_value(json!({"require": require})).unwrap();
+        plan_fix_version(&composer, &lock(packages), "acme/lib", |_| Ok(advisory(affected)), |_| Ok(releases(versions)))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_unaffected_package_skips_release_lookup() {
+        let composer = serde_json::from_value(json!({"require": {"acme/lib": "^7.0"}})).unwrap();
+        let lock = lock(json!([{"name": "acme/lib", "version": "7.4.5"}]));
+
+        let result = plan_fix_version(
+            &composer,
+            &lock,
+            "acme/lib",
+            |_| Ok(advisory(">=7.0,<7.4.5")),
+            |_| Err(anyhow!("releases should not be fetched")),
+        )
+        .unwrap();
+
+        assert!(!result.vulnerable);
+        assert_eq!(result.resolution, "not-vulnerable");
+        assert!(result.command.is_none());
+    }
+
+    #[test]
+    fn test_lowest_fix_within_root_constraint() {
+        let result = plan(
+            json!({"acme/lib": "^7.0"}),
+            json!([
+                {"name": "acme/lib", "version": "7.4.0"},
+                {"name": "psr/log", "version": "1.1.4"}
+            ]),
+            ">=7.0,<7.4.5",
+            json!([
+                {"version": "8.0.0"},
+                {"version": "7.4.6", "require": {"php": ">=8.1", "psr/log": "^2.0"}},
+                {"version": "7.4.5"},
+                {"version": "7.4.1"}
+            ]),
+        );
+
+        assert_eq!(result.resolution, "composer-update");
+        assert_eq!(result.target_version.as_deref(), Some("7.4.5"));
+        assert_eq!(result.command.as_deref(), Some("composer update acme/lib --with acme/lib:7.4.5"));
+        assert!(result.also_updates.is_empty());
+    }
+
+    #[test]
+    fn test_fix_outside_root_constraint_needs_require() {
+        let result = plan(
+            json!({"acme/lib": "~7.4.0"}),
+            json!([
+                {"name": "acme/lib", "version": "7.4.0"},
+                {"name": "psr/log", "version": "1.1.4"}
+            ]),
+            "<8.0.1",
+            json!([
+                {"version": "v8.0.1", "require": {"php": ">=8.1", "psr/log": "^2.0"}},
+                {"version": "8.0.0"},
+                {"version": "8.1.0-beta1"}
+            ]),
+        );
+
+        assert_eq!(result.resolution, "constraint-change");
+        assert_eq!(result.target_version.as_deref(), Some("8.0.1"));
+        assert_eq!(result.also_updates, ["psr/log"]);
+        assert_eq!(
+            result.command.as_deref(),
+            Some("composer require acme/lib:^8.0.1 --update-with-dependencies")
+        );
+    }
+
+    #[test]
+    fn test_dependents_block_every_fixed_release() {
+        let result = plan(
+            json!({}),
+            json!([
+                {"name": "acme/lib", "version": "7.4.0"},
+                {"name": "acme/sdk", "version": "1.0.0", "require": {"acme/lib": "^7.0"}}
+            ]),
+            "<8.0.0",
+            json!([{"version": "8.0.0"}]),
+        );
+
+        assert_eq!(result.resolution, "blocked");
+        assert!(result.command.is_none());
+        assert_eq!(result.blocked_by.len(), 1);
+        assert_eq!(result.blocked_by[0].package, "acme/sdk");
+    }
+
+    #[test]
+    fn test_no_fix_when_every_newer_release_is_affected() {
+        let result = plan(
+            json!({"acme/lib": "^7.0"}),
+            json!([{"name": "acme/lib", "version": "7.4.0"}]),
+            ">=7.0",
+            json!([{"version": "7.5.0"}, {"version": "7.3.0"}]),
+        );
+
+        assert_eq!(result.resolution, "no-fix-available");
+        assert!(result.target_version.is_none());
+    }
+}
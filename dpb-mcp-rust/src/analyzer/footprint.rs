@@ -0,0 +1,165 @@
+//! Dependency footprint
+//! Estimates the disk space each installed package takes under `vendor/`,
+//! e.g. to find which dependencies dominate a container image
+
+use anyhow::Result;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::path::Path;
+use walkdir::WalkDir;
+
+use crate::composer::{is_valid_package_name, read_composer_lock};
+use crate::types::PackageInfo;
+
+#[derive(Debug, Serialize)]
+pub struct FootprintResult {
+    /// "vendor" when sizes were measured on disk, "composer.lock" when vendor/ isn't installed
+    pub source: String,
+    #[serde(rename = "totalBytes")]
+    pub total_bytes: u64,
+    #[serde(rename = "totalSize")]
+    pub total_size: String,
+    #[serde(rename = "totalFiles")]
+    pub total_files: usize,
+    #[serde(rename = "packageCount")]
+    pub package_count: usize,
+    /// The largest installed packages, biggest first; empty without vendor/, since
+    /// composer.lock records no download sizes
+    #[serde(rename = "largestPackages")]
+    pub largest_packages: Vec<PackageFootprint>,
+    /// Locked packages with no directory under vendor/
+    #[serde(rename = "notInstalled")]
+    pub not_installed: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PackageFootprint {
+    pub name: String,
+    pub version: String,
+    #[serde(rename = "isDev")]
+    pub is_dev: bool,
+    pub bytes: u64,
+    pub size: String,
+    pub files: usize,
+}
+
+/// Estimate each locked package's installed size by walking `vendor/<vendor>/<name>`
+/// and return the `limit` largest
+pub fn analyze_footprint<P: AsRef<Path>>(repo_path: P, limit: usize) -> Result<String> {
+    let repo_path = repo_path.as_ref();
+    let lock = read_composer_lock(repo_path)?;
+    let vendor_dir = repo_path.join("vendor");
+    let installed = vendor_dir.is_dir();
+
+    let packages: Vec<(&PackageInfo, bool)> = lock
+        .packages
+        .iter()
+        .map(|p| (p, false))
+        .chain(lock.packages_dev.iter().flatten().map(|p| (p, true)))
+        .collect();
+
+    // None for packages missing from vendor/; a name that could point outside it isn't measured
+    let measured: Vec<Option<PackageFootprint>> = packages
+        .par_iter()
+        .map(|(pkg, is_dev)| {
+            let dir = vendor_dir.join(&pkg.name);
+            if !installed || !is_valid_package_name(&pkg.name) || !dir.is_dir() {
+                return None;
+            }
+            let (bytes, files) = directory_size(&dir);
+            Some(PackageFootprint {
+                name: pkg.name.clone(),
+                version: pkg.version.clone(),
+                is_dev: *is_dev,
+                bytes,
+                size: human_size(bytes),
+                files,
+            })
+        })
+        .collect();
+
+    let mut not_installed: Vec<String> = if installed {
+        packages
+            .iter()
+            .zip(&measured)
+            .filter(|(_, footprint)| footprint.is_none())
+            .map(|((pkg, _), _)| pkg.name.clone())
+            .collect()
+    } else {
+        Vec::new()
+    };
+    not_installed.sort();
+
+    let mut footprints: Vec<PackageFootprint> = measured.into_iter().flatten().collect();
+    let total_bytes = footprints.iter().map(|f| f.bytes).sum();
+    let total_files = footprints.iter().map(|f| f.files).sum();
+    footprints.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.name.cmp(&b.name)));
+    footprints.truncate(limit);
+
+    let result = FootprintResult {
+        source: if installed { "vendor" } else { "composer.lock" }.to_string(),
+        total_bytes,
+        total_size: human_size(total_bytes),
+        total_files,
+        package_count: packages.len(),
+        largest_packages: footprints,
+        not_installed,
+    };
+
+    Ok(serde_json::to_string_pretty(&result)?)
+}
+
+/// Total size and number of regular files below `dir`; symlinks inside it aren't followed
+fn directory_size(dir: &Path) -> (u64, usize) {
+    WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .fold((0, 0), |(bytes, files), meta| (bytes + meta.len(), files + 1))
+}
+
+fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+    use std::fs;
+
+    #[test]
+    fn test_footprint_stays_inside_vendor() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        fs::create_dir_all(dir.join("vendor/acme/lib")).unwrap();
+        fs::write(dir.join("vendor/acme/lib/Lib.php"), "x".repeat(2048)).unwrap();
+        fs::create_dir_all(dir.join("outside")).unwrap();
+        fs::write(dir.join("outside/secret"), "x".repeat(4096)).unwrap();
+        let lock = json!({"packages": [
+            {"name": "acme/lib", "version": "1.0.0"},
+            {"name": "acme/missing", "version": "1.0.0"},
+            {"name": "../outside", "version": "1.0.0"}
+        ]});
+        fs::write(dir.join("composer.lock"), lock.to_string()).unwrap();
+
+        let result: Value = serde_json::from_str(&analyze_footprint(dir, 10).unwrap()).unwrap();
+
+        assert_eq!(result["source"], "vendor");
+        assert_eq!(result["totalBytes"], 2048);
+        assert_eq!(result["largestPackages"][0]["size"], "2.0 KB");
+        assert_eq!(result["largestPackages"].as_array().unwrap().len(), 1);
+        assert_eq!(result["notInstalled"], json!(["../outside", "acme/missing"]));
+    }
+}
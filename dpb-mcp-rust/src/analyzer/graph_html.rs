@@ -0,0 +1,182 @@
+//! Interactive HTML dependency graph
+//! Renders the dependency tree as a force-directed SVG graph, which copes with graphs far
+//! larger than a browser can lay out as a static Mermaid diagram. The page loads nothing
+//! from the network, so it works offline and keeps (possibly private) package data local.
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
+
+use super::dependency::{analyze_dependencies_filtered, DependencyFilter};
+use crate::composer::normalize_package_name;
+use crate::types::DependencyNode;
+
+const TEMPLATE: &str = include_str!("graph.html");
+const ROOT_ID: &str = "Your Application";
+
+#[derive(Debug, Serialize)]
+struct GraphData {
+    nodes: Vec<GraphNode>,
+    edges: Vec<GraphEdge>,
+}
+
+#[derive(Debug, Serialize)]
+struct GraphNode {
+    id: String,
+    version: String,
+    /// "root", "production" or "development"
+    group: String,
+    license: Option<String>,
+    dependencies: Vec<String>,
+    #[serde(rename = "usedBy")]
+    used_by: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct GraphEdge {
+    from: String,
+    to: String,
+}
+
+/// Generate a self-contained HTML page with a draggable, filterable dependency graph.
+/// Without a focus package every locked package is shown; with one, only the packages
+/// within `max_depth` levels of it.
+pub fn generate_dependency_graph_html<P: AsRef<Path>>(
+    repo_path: P,
+    max_depth: usize,
+    filter: &DependencyFilter,
+    focus_package: Option<String>,
+) -> Result<String> {
+    let analysis = analyze_dependencies_filtered(&repo_path, filter)?;
+    let tree: HashMap<&str, &DependencyNode> =
+        analysis.tree.iter().map(|n| (n.name.as_str(), n)).collect();
+
+    let mut edges = Vec::new();
+    let mut show_root = false;
+    let visible: HashSet<&str> = match focus_package {
+        Some(focus) => {
+            let focus = normalize_package_name(&focus);
+            let start = tree
+                .get_key_value(focus.as_str())
+                .map(|(name, _)| *name)
+                .ok_or_else(|| anyhow!("Package {} not found in composer.lock", focus))?;
+            reachable(&tree, start, max_depth.max(1))
+        }
+        None => {
+            let mut direct: Vec<&str> = analysis
+                .production
+                .keys()
+                .chain(analysis.development.keys())
+                .map(|s| s.as_str())
+                .filter(|name| tree.contains_key(name))
+                .collect();
+            direct.sort();
+            direct.dedup();
+            show_root = !direct.is_empty();
+            edges.extend(direct.iter().map(|name| GraphEdge {
+                from: ROOT_ID.to_string(),
+                to: name.to_string(),
+            }));
+            tree.keys().copied().collect()
+        }
+    };
+
+    let mut nodes: Vec<GraphNode> = analysis
+        .tree
+        .iter()
+        .filter(|n| visible.contains(n.name.as_str()))
+        .map(|n| GraphNode {
+            id: n.name.clone(),
+            version: n.version.clone(),
+            group: n.node_type.clone(),
+            license: n.license.clone(),
+            dependencies: n.dependencies.clone(),
+            used_by: n.used_by.clone(),
+        })
+        .collect();
+    nodes.sort_by(|a, b| a.id.cmp(&b.id));
+
+    for node in &nodes {
+        let mut deps: Vec<&String> = node
+            .dependencies
+            .iter()
+            .filter(|d| visible.contains(d.as_str()))
+            .collect();
+        deps.sort();
+        edges.extend(deps.into_iter().map(|d| GraphEdge {
+            from: node.id.clone(),
+            to: d.clone(),
+        }));
+    }
+
+    if show_root {
+        nodes.insert(0, GraphNode {
+            id: ROOT_ID.to_string(),
+            version: String::new(),
+            group: "root".to_string(),
+            license: None,
+            dependencies: Vec::new(),
+            used_by: Vec::new(),
+        });
+    }
+
+    // Keep a package name like "</script>" from closing the inline script early
+    let data = serde_json::to_string(&GraphData { nodes, edges })?.replace("</", "<\\/");
+    let title = repo_path
+        .as_ref()
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+
+    Ok(TEMPLATE.replace("__TITLE__", &title).replace("__GRAPH_DATA__", &data))
+}
+
+/// Packages reachable from `start` in at most `max_depth` dependency hops
+fn reachable<'a>(tree: &HashMap<&'a str, &'a DependencyNode>, start: &'a str, max_depth: usize) -> HashSet<&'a str> {
+    let mut seen = HashSet::from([start]);
+    let mut queue = VecDeque::from([(start, 0)]);
+
+    while let Some((name, depth)) = queue.pop_front() {
+        if depth >= max_depth {
+            continue;
+        }
+        let Some(node) = tree.get(name) else { continue };
+        for dep in &node.dependencies {
+            if let Some((dep_name, _)) = tree.get_key_value(dep.as_str()) {
+                if seen.insert(*dep_name) {
+                    queue.push_back((*dep_name, depth + 1));
+                }
+            }
+        }
+    }
+
+    seen
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graph_page_embeds_data_and_loads_no_scripts() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        std::fs::write(dir.join("composer.json"), r#"{"require": {"acme/lib": "^1.0"}}"#).unwrap();
+        let lock = r#"{"packages": [
+            {"name": "acme/lib", "version": "1.0.0", "require": {"psr/log": "^3.0"}},
+            {"name": "psr/log", "version": "3.0.0"}
+        ]}"#;
+        std::fs::write(dir.join("composer.lock"), lock).unwrap();
+
+        let page = generate_dependency_graph_html(dir, 3, &DependencyFilter::default(), None).unwrap();
+
+        assert!(!page.contains("<script src"));
+        assert!(!page.contains("https://"));
+        assert!(page.contains(r#"{"from":"acme/lib","to":"psr/log"}"#));
+        assert!(page.contains(r#"{"from":"Your Application","to":"acme/lib"}"#));
+    }
+}
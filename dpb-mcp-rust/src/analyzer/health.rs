@@ -0,0 +1,163 @@
+//! Composite repository health
+//! Rolls the per-analysis verdicts (security, licenses, PSR-4, staleness) into one
+//! weighted 0–100 score that can be tracked over time or compared across services
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::path::Path;
+
+use crate::composer::read_composer_lock;
+use crate::config::load_config;
+use super::psr4::analyze_psr4_autoloading_raw;
+use super::security::{analyze_licenses_raw, audit_security_raw};
+use super::verdict::{worst_status, Verdict};
+
+#[derive(Debug, Serialize)]
+pub struct HealthReport {
+    /// Weighted average of the category scores
+    pub score: u8,
+    pub status: String,
+    /// Issues from the categories dragging the score down most
+    #[serde(rename = "topIssues")]
+    pub top_issues: Vec<String>,
+    pub categories: Vec<CategoryHealth>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CategoryHealth {
+    pub category: String,
+    /// Configured weight; categories that could not be analyzed don't count
+    pub weight: f64,
+    /// Points the category costs the overall score
+    #[serde(rename = "scoreImpact")]
+    pub score_impact: f64,
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub verdict: Option<Verdict>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Run every analysis and weigh their verdicts using `health.weights` from `.dpb.json`
+pub fn overall_health<P: AsRef<Path> + Sync>(repo_path: P) -> Result<String> {
+    let config = load_config(&repo_path)?;
+    let weights = &config.health.weights;
+    let stale_years = config.security.stale_years;
+
+    let analyses: Vec<(&str, f64, Result<Verdict>)> = vec![
+        (
+            "security",
+            weights.security,
+            audit_security_raw(&repo_path, None, None, &config.security, true).map(|r| r.summary.verdict),
+        ),
+        ("licenses", weights.licenses, analyze_licenses_raw(&repo_path, true).map(|r| r.summary.verdict)),
+        ("psr4", weights.psr4, analyze_psr4_autoloading_raw(&repo_path).map(|r| r.summary)),
+        ("staleness", weights.staleness, staleness_verdict(&repo_path, stale_years)),
+    ];
+
+    let categories: Vec<CategoryHealth> = analyses
+        .into_iter()
+        .filter(|(_, weight, _)| *weight > 0.0)
+        .map(|(category, weight, verdict)| {
+            let (verdict, error) = match verdict {
+                Ok(v) => (Some(v), None),
+                Err(e) => (None, Some(e.to_string())),
+            };
+            CategoryHealth { category: category.to_string(), weight, score_impact: 0.0, verdict, error }
+        })
+        .collect();
+
+    let report = combine(categories)?;
+    Ok(serde_json::to_string_pretty(&report)?)
+}
+
+/// Weigh the analyzed categories into one report
+fn combine(mut categories: Vec<CategoryHealth>) -> Result<HealthReport> {
+    let total_weight: f64 = categories.iter().filter(|c| c.verdict.is_some()).map(|c| c.weight).sum();
+    if total_weight == 0.0 {
+        return Err(anyhow!("No analysis could be run; composer.json and composer.lock are required"));
+    }
+    for category in &mut categories {
+        if let Some(verdict) = &category.verdict {
+            let impact = category.weight / total_weight * (100.0 - verdict.score as f64);
+            category.score_impact = (impact * 10.0).round() / 10.0;
+        }
+    }
+    let score = 100.0 - categories.iter().map(|c| c.score_impact).sum::<f64>();
+
+    // The worst category status carries over whatever its weight, so one failing analysis
+    // fails the repo
+    let status = worst_status(categories.iter().filter_map(|c| c.verdict.as_ref()).map(|v| v.status.as_str()));
+    let mut dragging: Vec<&CategoryHealth> = categories.iter().filter(|c| c.score_impact > 0.0).collect();
+    dragging.sort_by(|a, b| b.score_impact.total_cmp(&a.score_impact));
+    let issues = dragging.into_iter().flat_map(|c| {
+        c.verdict
+            .iter()
+            .flat_map(|v| v.top_issues.iter())
+            .map(move |issue| format!("[{}] {}", c.category, issue))
+    });
+    let verdict = Verdict::new(score, status, issues);
+
+    Ok(HealthReport {
+        score: verdict.score,
+        status: verdict.status,
+        top_issues: verdict.top_issues,
+        categories,
+    })
+}
+
+/// Share of locked packages released within `stale_years`, oldest releases listed first
+fn staleness_verdict<P: AsRef<Path>>(repo_path: P, stale_years: i64) -> Result<Verdict> {
+    let lock = read_composer_lock(&repo_path)?;
+    let cutoff = Utc::now() - chrono::Duration::days(stale_years * 365);
+
+    let packages: Vec<_> = lock.packages.iter().chain(lock.packages_dev.iter().flatten()).collect();
+    let mut stale: Vec<(DateTime<Utc>, String)> = packages
+        .iter()
+        .filter_map(|p| {
+            let time = p.time.as_deref()?.parse::<DateTime<Utc>>().ok()?;
+            let issue = format!(
+                "{} {} was released {}, over {} years ago",
+                p.name,
+                p.version,
+                time.format("%Y-%m-%d"),
+                stale_years
+            );
+            (time < cutoff).then_some((time, issue))
+        })
+        .collect();
+    stale.sort();
+
+    let share = if packages.is_empty() { 0.0 } else { stale.len() as f64 / packages.len() as f64 };
+    let status = if stale.is_empty() { "ok" } else { "warn" };
+    Ok(Verdict::new(100.0 * (1.0 - share), status, stale.into_iter().map(|(_, issue)| issue)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn category(name: &str, weight: f64, verdict: Option<Verdict>) -> CategoryHealth {
+        CategoryHealth { category: name.to_string(), weight, score_impact: 0.0, verdict, error: None }
+    }
+
+    #[test]
+    fn test_failing_category_fails_the_repo() {
+        let report = combine(vec![
+            category("security", 3.0, Some(Verdict::new(100.0, "ok", Vec::new()))),
+            category("licenses", 1.0, Some(Verdict::new(90.0, "fail", vec!["GPL-3.0 in a/b".to_string()]))),
+            category("psr4", 1.0, None),
+        ])
+        .unwrap();
+
+        assert_eq!(report.status, "fail");
+        assert_eq!(report.score, 98);
+        assert_eq!(report.categories[1].score_impact, 2.5);
+        assert_eq!(report.top_issues, ["[licenses] GPL-3.0 in a/b"]);
+    }
+
+    #[test]
+    fn test_no_analyzed_category_is_an_error() {
+        assert!(combine(vec![category("psr4", 1.0, None)]).is_err());
+    }
+}
@@ -0,0 +1,106 @@
+//! Incremental namespace / PSR-4 analysis for editor integrations
+//! The first call for a repository runs a full scan and keeps the result in memory;
+//! later calls re-analyze only the files reported as changed and merge them in.
+
+use anyhow::Result;
+use lazy_static::lazy_static;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use super::namespace::{detect_namespaces_raw, update_namespaces, NamespaceDetectionResult};
+use super::psr4::{analyze_psr4_autoloading_raw, is_scanned_php_file, update_psr4_autoloading, Psr4AnalysisResult};
+use crate::config::load_config;
+
+lazy_static! {
+    static ref RESULTS: Mutex<HashMap<PathBuf, CachedAnalysis>> = Mutex::new(HashMap::new());
+}
+
+/// Files whose change invalidates the autoload mappings or scan settings, forcing a full rescan
+const MANIFEST_FILES: [&str; 3] = ["composer.json", "composer.lock", ".dpb.json"];
+
+struct CachedAnalysis {
+    namespaces: NamespaceDetectionResult,
+    psr4: Psr4AnalysisResult,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IncrementalAnalysisResult<'a> {
+    /// Whether this call scanned the whole repository instead of just the changes
+    #[serde(rename = "fullScan")]
+    pub full_scan: bool,
+    /// Files read on this call (empty after a full scan)
+    #[serde(rename = "reanalyzedFiles")]
+    pub reanalyzed_files: Vec<String>,
+    pub namespaces: &'a NamespaceDetectionResult,
+    pub psr4: &'a Psr4AnalysisResult,
+}
+
+/// Bring the cached namespace and PSR-4 results for `repo_path` up to date with
+/// `changed_files` (absolute or repository-relative; deleted files included)
+pub fn reanalyze_changed_files<P: AsRef<Path>>(repo_path: P, changed_files: &[String]) -> Result<String> {
+    let repo_path = repo_path.as_ref();
+    let key = repo_path.canonicalize().unwrap_or_else(|_| repo_path.to_path_buf());
+
+    let changed: Vec<String> = changed_files
+        .iter()
+        .map(|f| {
+            let path = Path::new(f);
+            path.strip_prefix(repo_path)
+                .or_else(|_| path.strip_prefix(&key))
+                .unwrap_or(path)
+                .to_string_lossy()
+                .trim_start_matches("./")
+                .to_string()
+        })
+        .collect();
+    let manifest_changed = changed.iter().any(|f| MANIFEST_FILES.contains(&f.as_str()));
+
+    // Take the entry out so the scan runs without holding the lock; a failed update leaves
+    // nothing behind and the next call starts over with a full scan
+    let cached = if manifest_changed { None } else { cached_results().remove(&key) };
+    let full_scan = cached.is_none();
+    let mut reanalyzed_files = Vec::new();
+
+    let cached = match cached {
+        None => CachedAnalysis {
+            namespaces: detect_namespaces_raw(repo_path, true)?,
+            psr4: analyze_psr4_autoloading_raw(repo_path)?,
+        },
+        Some(mut cached) => {
+            let extensions = load_config(repo_path)?.analysis.php_extensions;
+            let php_changes: Vec<String> = changed
+                .into_iter()
+                .filter(|f| is_scanned_php_file(Path::new(f), &extensions))
+                .collect();
+
+            // Whether each file was part of the previous analysis, before it gets updated
+            let known = |file: &String| {
+                cached.namespaces.namespaces.iter().any(|ns| ns.files.contains(file))
+                    || cached.namespaces.files_without_namespace.iter().flatten().any(|f| f == file)
+            };
+            let psr4_changes: Vec<(String, bool)> = php_changes.iter().map(|f| (f.clone(), known(f))).collect();
+
+            reanalyzed_files = update_namespaces(&mut cached.namespaces, repo_path, &php_changes)?;
+            update_psr4_autoloading(&mut cached.psr4, repo_path, &psr4_changes);
+            cached
+        }
+    };
+
+    let result = IncrementalAnalysisResult {
+        full_scan,
+        reanalyzed_files,
+        namespaces: &cached.namespaces,
+        psr4: &cached.psr4,
+    };
+    let json = serde_json::to_string_pretty(&result)?;
+    cached_results().insert(key, cached);
+
+    Ok(json)
+}
+
+/// The cache is only ever read or written whole, so a panic elsewhere can't leave it inconsistent
+fn cached_results() -> MutexGuard<'static, HashMap<PathBuf, CachedAnalysis>> {
+    RESULTS.lock().unwrap_or_else(PoisonError::into_inner)
+}
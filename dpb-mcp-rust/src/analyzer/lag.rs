@@ -0,0 +1,173 @@
+//! Major-version lag against Packagist
+//! Counts how many major releases each locked package trails the latest stable one by;
+//! several majors behind is a stronger upgrade-risk signal than release dates
+
+use anyhow::Result;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::composer::constraint::Version;
+use crate::composer::{get_inline_packages, is_platform_package, read_composer_json, read_composer_lock};
+use crate::packagist::fetch_package;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VersionLagResult {
+    /// Locked at the latest stable major ("0 behind")
+    pub current: Vec<VersionLag>,
+    /// "1 major behind"
+    #[serde(rename = "oneMajorBehind")]
+    pub one_major_behind: Vec<VersionLag>,
+    /// "2+ majors behind"
+    #[serde(rename = "twoOrMoreMajorsBehind")]
+    pub two_or_more_majors_behind: Vec<VersionLag>,
+    pub skipped: Vec<SkippedLag>,
+    pub summary: VersionLagSummary,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionLag {
+    pub name: String,
+    pub installed: String,
+    #[serde(rename = "installedMajor")]
+    pub installed_major: u64,
+    /// Highest stable release on Packagist
+    pub latest: String,
+    #[serde(rename = "latestMajor")]
+    pub latest_major: u64,
+    #[serde(rename = "majorsBehind")]
+    pub majors_behind: u64,
+    /// Required directly in composer.json
+    pub direct: bool,
+    pub dev: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedLag {
+    pub name: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VersionLagSummary {
+    pub checked: usize,
+    pub current: usize,
+    #[serde(rename = "oneMajorBehind")]
+    pub one_major_behind: usize,
+    #[serde(rename = "twoOrMoreMajorsBehind")]
+    pub two_or_more_majors_behind: usize,
+    pub skipped: usize,
+    /// Highest lag across all checked packages
+    #[serde(rename = "maxMajorsBehind")]
+    pub max_majors_behind: u64,
+}
+
+enum Outcome {
+    Checked(VersionLag),
+    Skipped(SkippedLag),
+}
+
+/// Compare each locked package's major with the latest stable major on Packagist;
+/// `include_dev: false` skips packages-dev
+pub fn analyze_version_lag<P: AsRef<Path>>(repo_path: P, include_dev: bool) -> Result<String> {
+    let lock = read_composer_lock(&repo_path)?;
+    let composer = read_composer_json(&repo_path).ok();
+
+    let direct: HashSet<&str> = composer
+        .iter()
+        .flat_map(|c| c.require.iter().chain(c.require_dev.iter()))
+        .flat_map(|r| r.keys().map(|k| k.as_str()))
+        .collect();
+    // Inline packages come from composer.json itself, so Packagist knows nothing useful about them
+    let inline: HashSet<String> = composer
+        .as_ref()
+        .map(|c| get_inline_packages(c).into_iter().map(|p| p.name).collect())
+        .unwrap_or_default();
+
+    let dev_packages = if include_dev { lock.packages_dev.as_deref().unwrap_or_default() } else { &[] };
+    let mut packages: Vec<(&str, &str, bool)> = lock
+        .packages
+        .iter()
+        .map(|p| (p.name.as_str(), p.version.as_str(), false))
+        .chain(dev_packages.iter().map(|p| (p.name.as_str(), p.version.as_str(), true)))
+        .filter(|(name, _, _)| !is_platform_package(name))
+        .collect();
+    packages.sort();
+
+    let outcomes: Vec<Outcome> = packages
+        .par_iter()
+        .map(|&(name, version, dev)| {
+            let skip = |reason: String| Outcome::Skipped(SkippedLag { name: name.to_string(), reason });
+
+            if inline.contains(name) {
+                return skip("Provided by an inline package repository".to_string());
+            }
+            let installed = match Version::parse(version) {
+                Some(v) if !v.is_branch() => v,
+                _ => return skip(format!("Locked to {}, which has no major version", version)),
+            };
+            let package = match fetch_package(name) {
+                Ok(p) => p,
+                Err(e) => return skip(e.to_string()),
+            };
+            let Some((latest, latest_version)) = package
+                .latest_stable()
+                .and_then(|v| Version::parse(&v.version).map(|parsed| (v.version.clone(), parsed)))
+            else {
+                return skip("No stable release on Packagist".to_string());
+            };
+
+            Outcome::Checked(VersionLag {
+                name: name.to_string(),
+                installed: version.to_string(),
+                installed_major: installed.parts[0],
+                latest,
+                latest_major: latest_version.parts[0],
+                majors_behind: latest_version.parts[0].saturating_sub(installed.parts[0]),
+                direct: direct.contains(name),
+                dev,
+            })
+        })
+        .collect();
+
+    let mut current = Vec::new();
+    let mut one_major_behind = Vec::new();
+    let mut two_or_more_majors_behind = Vec::new();
+    let mut skipped = Vec::new();
+
+    for outcome in outcomes {
+        match outcome {
+            Outcome::Checked(lag) => match lag.majors_behind {
+                0 => current.push(lag),
+                1 => one_major_behind.push(lag),
+                _ => two_or_more_majors_behind.push(lag),
+            },
+            Outcome::Skipped(s) => skipped.push(s),
+        }
+    }
+    // Furthest behind first
+    two_or_more_majors_behind.sort_by(|a, b| b.majors_behind.cmp(&a.majors_behind).then_with(|| a.name.cmp(&b.name)));
+
+    let result = VersionLagResult {
+        summary: VersionLagSummary {
+            checked: packages.len() - skipped.len(),
+            current: current.len(),
+            one_major_behind: one_major_behind.len(),
+            two_or_more_majors_behind: two_or_more_majors_behind.len(),
+            skipped: skipped.len(),
+            max_majors_behind: two_or_more_majors_behind
+                .iter()
+                .chain(&one_major_behind)
+                .map(|l| l.majors_behind)
+                .max()
+                .unwrap_or(0),
+        },
+        current,
+        one_major_behind,
+        two_or_more_majors_behind,
+        skipped,
+    };
+
+    Ok(serde_json::to_string_pretty(&result)?)
+}
@@ -0,0 +1,146 @@
+//! Bus-factor risk from package authors
+//! Flags production dependencies that list a single author, or none, in the lock metadata;
+//! a lone maintainer on a package that has also gone stale is a strong abandonment signal
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use crate::composer::read_composer_lock;
+use crate::config::load_config;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BusFactorResult {
+    /// Highest risk first
+    pub risks: Vec<BusFactorRisk>,
+    pub summary: BusFactorSummary,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BusFactorRisk {
+    pub name: String,
+    pub version: String,
+    /// Distinct author names from the lock metadata
+    pub authors: Vec<String>,
+    /// "single-maintainer" or "no-author-data"
+    pub reason: String,
+    /// Release date of the locked version
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub released: Option<String>,
+    /// Released more than `security.staleYears` ago
+    pub stale: bool,
+    /// "high" when also stale, otherwise "medium"
+    pub risk: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BusFactorSummary {
+    /// Production packages checked
+    pub checked: usize,
+    #[serde(rename = "singleMaintainer")]
+    pub single_maintainer: usize,
+    #[serde(rename = "noAuthorData")]
+    pub no_author_data: usize,
+    #[serde(rename = "highRisk")]
+    pub high_risk: usize,
+}
+
+/// Report production packages with fewer than two listed authors, weighted by staleness
+/// (`security.staleYears` from `.dpb.json`)
+pub fn analyze_bus_factor<P: AsRef<Path>>(repo_path: P) -> Result<String> {
+    let lock = read_composer_lock(&repo_path)?;
+    let stale_years = load_config(&repo_path)?.security.stale_years;
+    let cutoff = Utc::now() - chrono::Duration::days(stale_years * 365);
+
+    let mut risks: Vec<BusFactorRisk> = lock
+        .packages
+        .iter()
+        .filter_map(|pkg| {
+            // The same person is often listed twice, e.g. with work and personal emails
+            let authors: BTreeSet<String> = pkg
+                .authors
+                .iter()
+                .flatten()
+                .map(|a| a.name.trim().to_string())
+                .filter(|name| !name.is_empty())
+                .collect();
+            let reason = match authors.len() {
+                0 => "no-author-data",
+                1 => "single-maintainer",
+                _ => return None,
+            };
+
+            let released = pkg.time.as_deref().and_then(|t| t.parse::<DateTime<Utc>>().ok());
+            let stale = released.is_some_and(|time| time < cutoff);
+            Some(BusFactorRisk {
+                name: pkg.name.clone(),
+                version: pkg.version.clone(),
+                authors: authors.into_iter().collect(),
+                reason: reason.to_string(),
+                released: released.map(|time| time.format("%Y-%m-%d").to_string()),
+                stale,
+                risk: if stale { "high" } else { "medium" }.to_string(),
+            })
+        })
+        .collect();
+    risks.sort_by(|a, b| b.stale.cmp(&a.stale).then_with(|| a.name.cmp(&b.name)));
+
+    let result = BusFactorResult {
+        summary: BusFactorSummary {
+            checked: lock.packages.len(),
+            single_maintainer: risks.iter().filter(|r| r.reason == "single-maintainer").count(),
+            no_author_data: risks.iter().filter(|r| r.reason == "no-author-data").count(),
+            high_risk: risks.iter().filter(|r| r.stale).count(),
+        },
+        risks,
+    };
+
+    Ok(serde_json::to_string_pretty(&result)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_single_and_missing_authors_weighted_by_staleness() {
+        let temp = crate::test_support::temp_dir();
+        let recent = (Utc::now() - chrono::Duration::days(30)).to_rfc3339();
+        let lock = json!({
+            "packages": [
+                {"name": "acme/team", "version": "1.0.0", "authors": [{"name": "Ann"}, {"name": "Bob"}]},
+                {"name": "acme/solo", "version": "2.0.0", "time": recent,
+                 "authors": [{"name": "Ann", "email": "ann@work.test"}, {"name": " Ann ", "email": "ann@home.test"}]},
+                {"name": "acme/old", "version": "0.1.0", "time": "2012-03-01T10:00:00+00:00", "authors": [{"name": "Cy"}]},
+                {"name": "acme/anon", "version": "1.0.0", "authors": [{"name": ""}]}
+            ],
+            "packages-dev": [{"name": "acme/dev-only", "version": "1.0.0"}]
+        });
+        std::fs::write(temp.path().join("composer.lock"), lock.to_string()).unwrap();
+
+        let result: BusFactorResult = serde_json::from_str(&analyze_bus_factor(temp.path()).unwrap()).unwrap();
+
+        let found: Vec<(&str, &str, &str)> = result
+            .risks
+            .iter()
+            .map(|r| (r.name.as_str(), r.reason.as_str(), r.risk.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("acme/old", "single-maintainer", "high"),
+                ("acme/anon", "no-author-data", "medium"),
+                ("acme/solo", "single-maintainer", "medium"),
+            ]
+        );
+        assert_eq!(result.risks[0].released.as_deref(), Some("2012-03-01"));
+        assert_eq!(result.risks[2].authors, ["Ann"]);
+        assert_eq!(result.summary.checked, 4);
+        assert_eq!(result.summary.single_maintainer, 2);
+        assert_eq!(result.summary.no_author_data, 1);
+        assert_eq!(result.summary.high_risk, 1);
+    }
+}
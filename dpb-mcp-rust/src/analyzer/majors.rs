@@ -0,0 +1,244 @@
+//! Major-version conflict risks
+//! Flags packages installed at more than one major version, and packages whose
+//! requirers expect different, incompatible majors (e.g. one wants guzzle 6, another 7)
+
+use anyhow::Result;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+
+use crate::composer::constraint::{Constraint, Version};
+use crate::composer::{is_platform_package, read_composer_json, read_composer_lock};
+
+lazy_static! {
+    static ref VERSION_LITERAL_RE: Regex = Regex::new(r"\d+(?:\.\d+){0,3}").unwrap();
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MajorVersionResult {
+    pub risks: Vec<MajorVersionRisk>,
+    #[serde(rename = "checkedPackages")]
+    pub checked_packages: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MajorVersionRisk {
+    pub package: String,
+    #[serde(rename = "installedVersions")]
+    pub installed_versions: Vec<String>,
+    /// "multiple-installed", "disjoint-constraints" or "installed-major-excluded"
+    pub kind: String,
+    pub constraints: Vec<RequirerConstraint>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RequirerConstraint {
+    #[serde(rename = "requiredBy")]
+    pub required_by: String,
+    pub constraint: String,
+    /// Major versions the constraint accepts
+    pub majors: Vec<u64>,
+}
+
+/// Find packages at risk of a runtime conflict between major versions
+pub fn find_major_version_conflicts<P: AsRef<Path>>(repo_path: P) -> Result<String> {
+    let lock = read_composer_lock(&repo_path)?;
+    let composer = read_composer_json(&repo_path).ok();
+
+    let mut installed: HashMap<&str, Vec<&str>> = HashMap::new();
+    for pkg in lock.packages.iter().chain(lock.packages_dev.iter().flatten()) {
+        installed.entry(pkg.name.as_str()).or_default().push(pkg.version.as_str());
+    }
+
+    // Who requires what, including the root project
+    let root_name = composer
+        .as_ref()
+        .and_then(|c| c.name.clone())
+        .unwrap_or_else(|| "root".to_string());
+    let mut requirements: HashMap<&str, Vec<(String, &str)>> = HashMap::new();
+    if let Some(composer) = &composer {
+        for require in [&composer.require, &composer.require_dev].into_iter().flatten() {
+            for (name, constraint) in require {
+                requirements.entry(name.as_str()).or_default().push((root_name.clone(), constraint.as_str()));
+            }
+        }
+    }
+    for pkg in lock.packages.iter().chain(lock.packages_dev.iter().flatten()) {
+        for (name, constraint) in pkg.require.iter().flatten() {
+            requirements.entry(name.as_str()).or_default().push((pkg.name.clone(), constraint.as_str()));
+        }
+    }
+
+    let mut risks = Vec::new();
+    for (name, versions) in &installed {
+        if is_platform_package(name) {
+            continue;
+        }
+        let installed_majors: BTreeSet<u64> = versions
+            .iter()
+            .filter_map(|v| Version::parse(v))
+            .filter(|v| !v.is_branch())
+            .map(|v| v.parts[0])
+            .collect();
+
+        let constraints = requirer_constraints(requirements.get(name).map(Vec::as_slice).unwrap_or_default(), versions);
+        let mut installed_versions: Vec<String> = versions.iter().map(|v| v.to_string()).collect();
+        installed_versions.sort();
+
+        let risk = |kind: &str, message: String, constraints: Vec<RequirerConstraint>| MajorVersionRisk {
+            package: name.to_string(),
+            installed_versions: installed_versions.clone(),
+            kind: kind.to_string(),
+            constraints,
+            message,
+        };
+
+        if installed_majors.len() > 1 {
+            risks.push(risk(
+                "multiple-installed",
+                format!("{} is locked at several major versions ({})", name, join_majors(&installed_majors)),
+                constraints,
+            ));
+            continue;
+        }
+
+        // Requirers that can't agree on a major can't all be satisfied at runtime
+        let mut common: Option<BTreeSet<u64>> = None;
+        for c in &constraints {
+            let accepted: BTreeSet<u64> = c.majors.iter().copied().collect();
+            common = Some(match common {
+                Some(set) => set.intersection(&accepted).copied().collect(),
+                None => accepted,
+            });
+        }
+        if constraints.len() > 1 && common.is_some_and(|set| set.is_empty()) {
+            risks.push(risk(
+                "disjoint-constraints",
+                format!("Packages requiring {} expect incompatible major versions", name),
+                constraints,
+            ));
+            continue;
+        }
+
+        if let Some(major) = installed_majors.iter().next() {
+            let excluding: Vec<RequirerConstraint> = constraints
+                .into_iter()
+                .filter(|c| !c.majors.contains(major))
+                .collect();
+            if !excluding.is_empty() {
+                risks.push(risk(
+                    "installed-major-excluded",
+                    format!(
+                        "{} is installed at major {} but {} expect(s) a different major",
+                        name,
+                        major,
+                        excluding.iter().map(|c| c.required_by.as_str()).collect::<Vec<_>>().join(", ")
+                    ),
+                    excluding,
+                ));
+            }
+        }
+    }
+
+    risks.sort_by(|a, b| a.package.cmp(&b.package));
+    let result = MajorVersionResult {
+        risks,
+        checked_packages: installed.len(),
+    };
+
+    Ok(serde_json::to_string_pretty(&result)?)
+}
+
+/// Resolve the majors each requirer's constraint accepts. Versions are sampled at every
+/// literal written in the constraints, the start of each of those majors and the installed
+/// versions, which is enough to tell `^6.5` from `^7.0` without enumerating releases.
+fn requirer_constraints(requirements: &[(String, &str)], installed: &[&str]) -> Vec<RequirerConstraint> {
+    let mut samples: Vec<Version> = installed.iter().filter_map(|v| Version::parse(v)).collect();
+    for (_, constraint) in requirements {
+        for literal in VERSION_LITERAL_RE.find_iter(constraint) {
+            if let Some(v) = Version::parse(literal.as_str()) {
+                samples.push(Version::parse(&format!("{}.0.0", v.parts[0])).unwrap_or_else(|| v.clone()));
+                samples.push(v);
+            }
+        }
+    }
+    samples.retain(|v| !v.is_branch());
+
+    let mut constraints: Vec<RequirerConstraint> = requirements
+        .iter()
+        .filter_map(|(required_by, constraint)| {
+            let parsed = Constraint::parse(constraint)?;
+            let majors: BTreeSet<u64> = samples
+                .iter()
+                .filter(|v| parsed.matches(v))
+                .map(|v| v.parts[0])
+                .collect();
+            // `*` and branch-only constraints say nothing about majors
+            if majors.is_empty() || constraint.trim() == "*" {
+                return None;
+            }
+            Some(RequirerConstraint {
+                required_by: required_by.clone(),
+                constraint: constraint.to_string(),
+                majors: majors.into_iter().collect(),
+            })
+        })
+        .collect();
+    constraints.sort_by(|a, b| a.required_by.cmp(&b.required_by));
+    constraints
+}
+
+fn join_majors(majors: &BTreeSet<u64>) -> String {
+    majors.iter().map(|m| m.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_major_version_risk_kinds() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        let composer = json!({"name": "acme/app", "require": {"guzzlehttp/guzzle": "^6.5", "psr/log": "^1.0 || ^2.0"}});
+        std::fs::write(dir.join("composer.json"), composer.to_string()).unwrap();
+        let lock = json!({"packages": [
+            {"name": "guzzlehttp/guzzle", "version": "7.8.1"},
+            {"name": "acme/sdk", "version": "1.0.0", "require": {"guzzlehttp/guzzle": "^7.2", "psr/log": "^2.0"}},
+            {"name": "acme/legacy", "version": "1.0.0", "require": {"monolog/monolog": "^1.0"}},
+            {"name": "acme/modern", "version": "1.0.0", "require": {"monolog/monolog": "^3.0"}},
+            {"name": "monolog/monolog", "version": "3.5.0"},
+            {"name": "psr/log", "version": "2.0.0"}
+        ]});
+        std::fs::write(dir.join("composer.lock"), lock.to_string()).unwrap();
+
+        let result: MajorVersionResult = serde_json::from_str(&find_major_version_conflicts(dir).unwrap()).unwrap();
+
+        let kinds: Vec<(&str, &str)> = result.risks.iter().map(|r| (r.package.as_str(), r.kind.as_str())).collect();
+        assert_eq!(kinds, [("guzzlehttp/guzzle", "disjoint-constraints"), ("monolog/monolog", "disjoint-constraints")]);
+        assert_eq!(result.risks[0].constraints[0].required_by, "acme/app");
+        assert_eq!(result.risks[0].constraints[0].majors, [6]);
+        assert_eq!(result.checked_packages, 6);
+    }
+
+    #[test]
+    fn test_installed_major_excluded_by_a_single_requirer() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        let lock = json!({"packages": [
+            {"name": "acme/sdk", "version": "1.0.0", "require": {"psr/log": "^1.1"}},
+            {"name": "psr/log", "version": "3.0.0"}
+        ]});
+        std::fs::write(dir.join("composer.lock"), lock.to_string()).unwrap();
+
+        let result: MajorVersionResult = serde_json::from_str(&find_major_version_conflicts(dir).unwrap()).unwrap();
+
+        assert_eq!(result.risks.len(), 1);
+        assert_eq!(result.risks[0].kind, "installed-major-excluded");
+        assert_eq!(result.risks[0].message, "psr/log is installed at major 3 but acme/sdk expect(s) a different major");
+    }
+}
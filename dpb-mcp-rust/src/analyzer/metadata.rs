@@ -0,0 +1,128 @@
+//! Publishing metadata checks
+//! Reports the support, funding and author metadata and the security policy that
+//! Packagist and GitHub surface for a package, before it is published
+
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+
+use crate::composer::read_composer_json;
+
+/// Security policy files: where GitHub looks for SECURITY.md, plus an RFC 9116 security.txt
+const SECURITY_POLICY_FILES: &[(&str, &str)] = &[
+    ("", "SECURITY.md"),
+    (".github", "SECURITY.md"),
+    ("docs", "SECURITY.md"),
+    (".well-known", "security.txt"),
+];
+
+#[derive(Debug, Serialize)]
+pub struct MetadataReport {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+    pub checks: Vec<MetadataCheck>,
+    /// Names of the checks that failed
+    pub missing: Vec<String>,
+    /// Every recommended item is present
+    pub complete: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MetadataCheck {
+    pub name: String,
+    pub present: bool,
+    /// What was found, e.g. the URL or file path
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub found: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recommendation: Option<String>,
+}
+
+impl MetadataCheck {
+    fn new(name: &str, found: Option<String>, recommendation: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            present: found.is_some(),
+            recommendation: found.is_none().then(|| recommendation.to_string()),
+            found,
+        }
+    }
+}
+
+/// Check composer.json `support`, `funding` and `authors`, and look for a security policy
+pub fn check_publishing_metadata<P: AsRef<Path>>(repo_path: P) -> Result<String> {
+    let composer = read_composer_json(&repo_path)?;
+
+    let support = |key: &str| -> Option<String> {
+        let value = composer.support.as_ref()?.get(key)?.as_str()?.trim();
+        (!value.is_empty()).then(|| value.to_string())
+    };
+
+    let authors: Vec<&str> = composer
+        .authors
+        .iter()
+        .flatten()
+        .filter_map(|a| a.get("name").and_then(Value::as_str))
+        .filter(|name| !name.trim().is_empty())
+        .collect();
+    let funding: Vec<&str> = composer
+        .funding
+        .iter()
+        .flatten()
+        .filter_map(|f| f.get("url").and_then(Value::as_str))
+        .collect();
+
+    // A policy file or a `support.security` URL both tell reporters where to go
+    let security_policy = find_security_policy(repo_path.as_ref()).or_else(|| support("security"));
+
+    let checks = vec![
+        MetadataCheck::new(
+            "support.issues",
+            support("issues"),
+            "Add support.issues with the issue tracker URL so users know where to report bugs",
+        ),
+        MetadataCheck::new(
+            "support.source",
+            support("source"),
+            "Add support.source with the repository URL so Packagist links to the code",
+        ),
+        MetadataCheck::new(
+            "funding",
+            (!funding.is_empty()).then(|| funding.join(", ")),
+            "Add a funding entry (e.g. {\"type\": \"github\", \"url\": ...}) so `composer fund` lists the project",
+        ),
+        MetadataCheck::new(
+            "authors",
+            (!authors.is_empty()).then(|| authors.join(", ")),
+            "Add authors with at least a name for each maintainer",
+        ),
+        MetadataCheck::new(
+            "securityPolicy",
+            security_policy,
+            "Add a SECURITY.md (or support.security) describing how to report vulnerabilities privately",
+        ),
+    ];
+
+    let missing: Vec<String> = checks.iter().filter(|c| !c.present).map(|c| c.name.clone()).collect();
+    let report = MetadataReport {
+        package: composer.name.clone(),
+        complete: missing.is_empty(),
+        missing,
+        checks,
+    };
+
+    Ok(serde_json::to_string_pretty(&report)?)
+}
+
+/// The first security policy file present, with file names matched case-insensitively
+fn find_security_policy(repo_path: &Path) -> Option<String> {
+    SECURITY_POLICY_FILES.iter().find_map(|(dir, file)| {
+        let entries = std::fs::read_dir(repo_path.join(dir)).ok()?;
+        entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.file_name().to_string_lossy().into_owned())
+            .find(|name| name.eq_ignore_ascii_case(file))
+            .map(|name| Path::new(dir).join(name).to_string_lossy().into_owned())
+    })
+}
@@ -1,17 +1,24 @@
 //! MkDocs Documentation Generator
 //! Generates MkDocs-compatible documentation structure with multi-file layout
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use chrono::Utc;
+use serde::Serialize;
 use std::fs;
+use std::process::Command;
 
 use crate::composer::read_composer_json;
-use super::dependency::analyze_dependencies;
+use crate::i18n::{fill, Locale, Messages};
+use super::dependency::{analyze_dependencies, DependencyFilter, DependencyScope};
 use super::psr4::analyze_psr4_autoloading;
 use super::namespace::detect_namespaces;
 use super::security::{audit_security, analyze_licenses};
 use super::generate_dependency_graph;
-use super::tracker::{create_dependency_snapshot, load_tracker, compare_snapshots};
+use super::redaction::Redactor;
+use super::tracker::{
+    create_dependency_snapshot, load_tracker, compare_snapshots, describe_snapshot_span,
+    snapshot_from_lock_contents, DependencyChange,
+};
 
 pub struct MkDocsOptions {
     pub repo_path: String,
@@ -20,30 +27,108 @@ pub struct MkDocsOptions {
     pub format: String, // "mkdocs", "html", "markdown"
     pub site_name: Option<String>,
     pub site_description: Option<String>,
+    /// Language of the generated pages; the changelog stays in English
+    pub locale: Locale,
+    /// Replace packages matching `redaction.patterns` in `.dpb.json` with placeholders
+    pub redact: bool,
+    /// Report the files that would be written instead of writing them
+    pub dry_run: bool,
 }
 
-/// Generate MkDocs-compatible documentation structure
+/// A documentation file rendered in memory, with its path relative to the output directory
+pub struct GeneratedFile {
+    pub path: String,
+    pub content: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DocsPlan {
+    #[serde(rename = "outputDir")]
+    pub output_dir: String,
+    pub files: Vec<PlannedFile>,
+    #[serde(rename = "totalBytes")]
+    pub total_bytes: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PlannedFile {
+    pub path: String,
+    pub bytes: usize,
+    /// Whether writing would replace an existing file
+    pub exists: bool,
+}
+
+/// Generate MkDocs-compatible documentation structure, or with `dry_run` list the
+/// files it would write without touching the filesystem
 pub fn generate_mkdocs_docs(options: MkDocsOptions) -> Result<String> {
     let output_dir = options.output_dir
+        .clone()
         .unwrap_or_else(|| format!("{}/docs", options.repo_path));
-    
+    let dry_run = options.dry_run;
+    let files = render_mkdocs_docs(options)?;
+
+    if dry_run {
+        let files: Vec<PlannedFile> = files
+            .iter()
+            .map(|f| PlannedFile {
+                path: f.path.clone(),
+                bytes: f.content.len(),
+                exists: std::path::Path::new(&output_dir).join(&f.path).exists(),
+            })
+            .collect();
+        let plan = DocsPlan {
+            total_bytes: files.iter().map(|f| f.bytes).sum(),
+            output_dir,
+            files,
+        };
+        return Ok(serde_json::to_string_pretty(&plan)?);
+    }
+
+    fs::create_dir_all(&output_dir)?;
+    for file in &files {
+        fs::write(format!("{}/{}", output_dir, file.path), &file.content)?;
+    }
+
+    Ok(format!("Documentation generated successfully in {}", output_dir))
+}
+
+/// Render every documentation file for the requested format in memory
+pub fn render_mkdocs_docs(options: MkDocsOptions) -> Result<Vec<GeneratedFile>> {
     let format = if options.format.is_empty() {
         "mkdocs".to_string()
     } else {
         options.format
     };
 
-    // Ensure output directory exists
-    fs::create_dir_all(&output_dir)?;
+    let redactor = if options.redact {
+        Some(if options.dry_run {
+            Redactor::for_preview(&options.repo_path)?
+        } else {
+            Redactor::for_repo(&options.repo_path)?
+        })
+    } else {
+        None
+    };
+    let redact = |text: String| match &redactor {
+        Some(redactor) => redactor.apply(&text),
+        None => text,
+    };
 
     // Gather all analysis data
     let composer = read_composer_json(&options.repo_path)?;
-    let deps_json = analyze_dependencies(&options.repo_path)?;
+    let deps_json = analyze_dependencies(&options.repo_path, false, &DependencyFilter::default())?;
     let psr4_json = analyze_psr4_autoloading(&options.repo_path)?;
-    let namespaces_json = detect_namespaces(&options.repo_path)?;
-    let security_json = audit_security(&options.repo_path)?;
-    let licenses_json = analyze_licenses(&options.repo_path)?;
-    let dep_graph = generate_dependency_graph(&options.repo_path, 2, false, None)?;
+    let namespaces_json = detect_namespaces(&options.repo_path, false, false, None, None)?;
+    let security_config = crate::config::load_config(&options.repo_path)?.security;
+    let severity_floor = crate::config::severity_floor(&options.repo_path, None)?;
+    let security_json = audit_security(&options.repo_path, None, severity_floor, &security_config, true)?;
+    let licenses_json = analyze_licenses(&options.repo_path, true)?;
+    let dep_graph = generate_dependency_graph(
+        &options.repo_path,
+        2,
+        &DependencyFilter::with_scope(DependencyScope::Production),
+        None,
+    )?;
     
     // Parse JSON strings (simplified - in production would properly handle errors)
     let deps: serde_json::Value = serde_json::from_str(&deps_json).unwrap_or(serde_json::json!({}));
@@ -52,35 +137,39 @@ pub fn generate_mkdocs_docs(options: MkDocsOptions) -> Result<String> {
     let security: serde_json::Value = serde_json::from_str(&security_json).unwrap_or(serde_json::json!({}));
     let licenses: serde_json::Value = serde_json::from_str(&licenses_json).unwrap_or(serde_json::json!({}));
 
+    let m = options.locale.messages();
+
     // Get project info
-    let project_name = options.site_name.unwrap_or_else(|| {
+    let project_name = redact(options.site_name.unwrap_or_else(|| {
         composer.name.clone()
             .unwrap_or_else(|| std::path::PathBuf::from(&options.repo_path)
                 .file_name()
                 .and_then(|n| n.to_str())
                 .unwrap_or("Project")
                 .to_string())
-    });
+    }));
 
-    let project_desc = options.site_description.unwrap_or_else(|| {
-        composer.description.clone().unwrap_or_else(|| "Dependency Analysis Documentation".to_string())
-    });
+    let project_desc = redact(options.site_description.unwrap_or_else(|| {
+        composer.description.clone().unwrap_or_else(|| m.default_site_description.to_string())
+    }));
 
     // Generate changelog if requested
     let changelog_content = if options.include_changelog {
-        generate_changelog(&options.repo_path).unwrap_or_default()
+        redact(generate_changelog(&options.repo_path).unwrap_or_default())
     } else {
         String::new()
     };
 
     // Generate individual markdown files
-    let index_content = generate_index(&project_name, &project_desc, &composer, &deps, options.include_changelog);
-    let dependencies_content = generate_dependencies_doc(&deps, &dep_graph);
-    let security_content = generate_security_doc(&security);
-    let licenses_content = generate_licenses_doc(&licenses);
-    let architecture_content = generate_architecture_doc(&psr4, &namespaces);
+    let index_content = redact(generate_index(&project_name, &project_desc, &composer, &deps, options.include_changelog, m));
+    let dependencies_content = redact(generate_dependencies_doc(&deps, &dep_graph, m));
+    let security_content = redact(generate_security_doc(&security, m));
+    let licenses_content = redact(generate_licenses_doc(&licenses, m));
+    let architecture_content = redact(generate_architecture_doc(&psr4, &namespaces, m));
+
+    let mut files = Vec::new();
+    let mut add = |path: &str, content: String| files.push(GeneratedFile { path: path.to_string(), content });
 
-    // Generate HTML if format is html (before writing markdown files, so we can reuse the strings)
     if format == "html" {
         let html_content = generate_html_site(
             &project_name,
@@ -91,28 +180,31 @@ pub fn generate_mkdocs_docs(options: MkDocsOptions) -> Result<String> {
             &licenses_content,
             &architecture_content,
             &changelog_content,
+            options.locale,
         );
-        fs::write(format!("{}/index.html", output_dir), html_content)?;
+        add("index.html", html_content);
     }
 
-    // Write markdown files
-    fs::write(format!("{}/index.md", output_dir), &index_content)?;
-    fs::write(format!("{}/dependencies.md", output_dir), &dependencies_content)?;
-    fs::write(format!("{}/security.md", output_dir), &security_content)?;
-    fs::write(format!("{}/licenses.md", output_dir), &licenses_content)?;
-    fs::write(format!("{}/architecture.md", output_dir), &architecture_content)?;
-    
+    // Markdown files
+    add("index.md", index_content);
+    add("dependencies.md", dependencies_content);
+    add("security.md", security_content);
+    add("licenses.md", licenses_content);
+    add("architecture.md", architecture_content);
+
     if !changelog_content.is_empty() {
-        fs::write(format!("{}/changelog.md", output_dir), &changelog_content)?;
+        add("changelog.md", changelog_content);
     }
 
-    // Generate mkdocs.yml if format is mkdocs
+    // mkdocs.yml if format is mkdocs
     if format == "mkdocs" {
-        let mkdocs_config = generate_mkdocs_config(&project_name, &project_desc, options.include_changelog);
-        fs::write(format!("{}/mkdocs.yml", output_dir), mkdocs_config)?;
+        add(
+            "mkdocs.yml",
+            generate_mkdocs_config(&project_name, &project_desc, options.include_changelog, options.locale),
+        );
     }
 
-    Ok(format!("Documentation generated successfully in {}", output_dir))
+    Ok(files)
 }
 
 fn generate_index(
@@ -121,12 +213,13 @@ fn generate_index(
     composer: &crate::types::ComposerJson,
     deps: &serde_json::Value,
     include_changelog: bool,
+    m: &Messages,
 ) -> String {
     let now = Utc::now().to_rfc3339();
     let project_type = composer.package_type.as_deref().unwrap_or("library");
     let licenses = crate::composer::get_licenses(composer);
     let license_str = if licenses.is_empty() {
-        "Not specified".to_string()
+        m.not_specified.to_string()
     } else {
         licenses.join(", ")
     };
@@ -143,23 +236,23 @@ fn generate_index(
 
     let mut content = format!("# {}\n\n", project_name);
     content.push_str(&format!("{}\n\n", description));
-    content.push_str(&format!("**Generated:** {}\n\n", now));
-    content.push_str("## Quick Overview\n\n");
-    content.push_str(&format!("- **Project Type:** {}\n", project_type));
-    content.push_str(&format!("- **License:** {}\n", license_str));
-    content.push_str(&format!("- **Production Dependencies:** {}\n", prod_count));
-    content.push_str(&format!("- **Development Dependencies:** {}\n\n", dev_count));
-    content.push_str("## Documentation Sections\n\n");
-    content.push_str("- [Dependencies](./dependencies.md) - Complete dependency analysis and tree\n");
-    content.push_str("- [Security](./security.md) - Security audit and vulnerability report\n");
-    content.push_str("- [Licenses](./licenses.md) - License compliance and distribution\n");
-    content.push_str("- [Architecture](./architecture.md) - Namespace structure and PSR-4 compliance\n");
+    content.push_str(&format!("**{}:** {}\n\n", m.generated, now));
+    content.push_str(&format!("## {}\n\n", m.quick_overview));
+    content.push_str(&format!("- **{}:** {}\n", m.project_type, project_type));
+    content.push_str(&format!("- **{}:** {}\n", m.license, license_str));
+    content.push_str(&format!("- **{}:** {}\n", m.production_dependencies, prod_count));
+    content.push_str(&format!("- **{}:** {}\n\n", m.development_dependencies, dev_count));
+    content.push_str(&format!("## {}\n\n", m.documentation_sections));
+    content.push_str(&format!("- [{}](./dependencies.md) - {}\n", m.dependencies, m.dependencies_section));
+    content.push_str(&format!("- [{}](./security.md) - {}\n", m.security, m.security_section));
+    content.push_str(&format!("- [{}](./licenses.md) - {}\n", m.licenses, m.licenses_section));
+    content.push_str(&format!("- [{}](./architecture.md) - {}\n", m.architecture, m.architecture_section));
     if include_changelog {
-        content.push_str("- [Changelog](./changelog.md) - Dependency change history\n");
+        content.push_str(&format!("- [{}](./changelog.md) - {}\n", m.changelog, m.changelog_section));
     }
-    content.push_str("\n## Getting Started\n\n");
-    content.push_str("This documentation was automatically generated by dependency-buster MCP.\n\n");
-    content.push_str("To view with MkDocs:\n");
+    content.push_str(&format!("\n## {}\n\n", m.getting_started));
+    content.push_str(&format!("{}\n\n", m.generated_by));
+    content.push_str(&format!("{}\n", m.view_with_mkdocs));
     content.push_str("```bash\n");
     content.push_str("cd docs\n");
     content.push_str("mkdocs serve\n");
@@ -168,33 +261,33 @@ fn generate_index(
     content
 }
 
-fn generate_dependencies_doc(deps: &serde_json::Value, graph: &str) -> String {
-    let mut content = String::from("# Dependencies\n\n");
+fn generate_dependencies_doc(deps: &serde_json::Value, graph: &str, m: &Messages) -> String {
+    let mut content = format!("# {}\n\n", m.dependencies);
     
     // Extract stats
     if let Some(stats) = deps.get("stats") {
         let prod = stats.get("totalProduction").and_then(|v| v.as_u64()).unwrap_or(0);
         let dev = stats.get("totalDevelopment").and_then(|v| v.as_u64()).unwrap_or(0);
-        content.push_str("## Summary\n\n");
-        content.push_str(&format!("- **Production:** {} packages\n", prod));
-        content.push_str(&format!("- **Development:** {} packages\n", dev));
-        content.push_str(&format!("- **Total:** {} packages\n\n", prod + dev));
+        content.push_str(&format!("## {}\n\n", m.summary));
+        content.push_str(&format!("- **{}:** {}\n", m.production, fill(m.packages_count, &[&prod])));
+        content.push_str(&format!("- **{}:** {}\n", m.development, fill(m.packages_count, &[&dev])));
+        content.push_str(&format!("- **{}:** {}\n\n", m.total, fill(m.packages_count, &[&(prod + dev)])));
     }
     
     // Extract production dependencies
     if let Some(production) = deps.get("production").and_then(|v| v.as_object()) {
         if !production.is_empty() {
-            content.push_str("## Production Dependencies\n\n");
-            content.push_str("| Package | Version |\n");
+            content.push_str(&format!("## {}\n\n", m.production_dependencies));
+            content.push_str(&format!("| {} | {} |\n", m.package, m.version));
             content.push_str("|---------|----------|\n");
             for (name, version) in production.iter().take(50) {
                 let ver_str = version.as_str().unwrap_or("");
                 content.push_str(&format!("| `{}` | `{}` |\n", name, ver_str));
             }
             if production.len() > 50 {
-                content.push_str(&format!("\n*... and {} more*\n\n", production.len() - 50));
+                content.push_str(&format!("\n*{}*\n\n", fill(m.and_more, &[&(production.len() - 50)])));
             } else {
-                content.push_str("\n");
+                content.push('\n');
             }
         }
     }
@@ -202,34 +295,34 @@ fn generate_dependencies_doc(deps: &serde_json::Value, graph: &str) -> String {
     // Extract development dependencies
     if let Some(development) = deps.get("development").and_then(|v| v.as_object()) {
         if !development.is_empty() {
-            content.push_str("## Development Dependencies\n\n");
-            content.push_str("| Package | Version |\n");
+            content.push_str(&format!("## {}\n\n", m.development_dependencies));
+            content.push_str(&format!("| {} | {} |\n", m.package, m.version));
             content.push_str("|---------|----------|\n");
             for (name, version) in development.iter().take(50) {
                 let ver_str = version.as_str().unwrap_or("");
                 content.push_str(&format!("| `{}` | `{}` |\n", name, ver_str));
             }
             if development.len() > 50 {
-                content.push_str(&format!("\n*... and {} more*\n\n", development.len() - 50));
+                content.push_str(&format!("\n*{}*\n\n", fill(m.and_more, &[&(development.len() - 50)])));
             } else {
-                content.push_str("\n");
+                content.push('\n');
             }
         }
     }
     
-    content.push_str("## Dependency Graph\n\n");
+    content.push_str(&format!("## {}\n\n", m.dependency_graph));
     content.push_str("```mermaid\n");
     content.push_str(graph);
     content.push_str("\n```\n\n");
-    content.push_str("*For detailed dependency information, use the `analyze_dependencies` tool.*\n");
+    content.push_str(&format!("*{}*\n", m.dependencies_tool_hint));
     content
 }
 
-fn generate_security_doc(security: &serde_json::Value) -> String {
-    let mut content = String::from("# Security Audit\n\n");
+fn generate_security_doc(security: &serde_json::Value, m: &Messages) -> String {
+    let mut content = format!("# {}\n\n", m.security_audit);
     
     if let Some(risk_level) = security.get("riskLevel").and_then(|v| v.as_str()) {
-        content.push_str(&format!("## Risk Level: {}\n\n", risk_level.to_uppercase()));
+        content.push_str(&format!("## {}: {}\n\n", m.risk_level, m.severity_label(risk_level).to_uppercase()));
     }
     
     if let Some(summary) = security.get("summary") {
@@ -238,19 +331,23 @@ fn generate_security_doc(security: &serde_json::Value) -> String {
         let medium = summary.get("medium").and_then(|v| v.as_u64()).unwrap_or(0);
         let low = summary.get("low").and_then(|v| v.as_u64()).unwrap_or(0);
         
-        content.push_str("## Summary\n\n");
-        content.push_str(&format!("- **Critical:** {}\n", critical));
-        content.push_str(&format!("- **High:** {}\n", high));
-        content.push_str(&format!("- **Medium:** {}\n", medium));
-        content.push_str(&format!("- **Low:** {}\n", low));
+        content.push_str(&format!("## {}\n\n", m.summary));
+        content.push_str(&format!("- **{}:** {}\n", m.critical, critical));
+        content.push_str(&format!("- **{}:** {}\n", m.high, high));
+        content.push_str(&format!("- **{}:** {}\n", m.medium, medium));
+        content.push_str(&format!("- **{}:** {}\n", m.low, low));
         
         if let Some(vulns) = security.get("vulnerabilities").and_then(|v| v.as_array()) {
             let total = vulns.len();
-            content.push_str(&format!("- **Total Issues:** {}\n\n", total));
+            content.push_str(&format!("- **{}:** {}\n\n", m.total_issues, total));
             
             if !vulns.is_empty() {
-                content.push_str("## Vulnerabilities\n\n");
-                content.push_str("| Package | Version | Severity | Description |\n");
+                // Findings arrive sorted by severity, so truncation keeps the most urgent
+                content.push_str(&format!("## {}\n\n", m.vulnerabilities));
+                content.push_str(&format!(
+                    "| {} | {} | {} | {} |\n",
+                    m.package, m.version, m.severity, m.description
+                ));
                 content.push_str("|---------|---------|----------|-------------|\n");
                 for vuln in vulns.iter().take(100) {
                     let pkg = vuln.get("package").and_then(|v| v.as_str()).unwrap_or("");
@@ -260,73 +357,96 @@ fn generate_security_doc(security: &serde_json::Value) -> String {
                     content.push_str(&format!("| `{}` | `{}` | {} | {} |\n", pkg, ver, sev, desc));
                 }
                 if vulns.len() > 100 {
-                    content.push_str(&format!("\n*... and {} more vulnerabilities*\n", vulns.len() - 100));
+                    content.push_str(&format!("\n*{}*\n", fill(m.and_more_vulnerabilities, &[&(vulns.len() - 100)])));
                 }
             } else {
-                content.push_str("## Status\n\n✅ No known vulnerabilities found.\n");
+                content.push_str(&format!("## {}\n\n✅ {}\n", m.status, m.no_vulnerabilities));
             }
         }
     } else {
-        content.push_str("*For detailed security information, use the `audit_security` tool.*\n");
+        content.push_str(&format!("*{}*\n", m.security_tool_hint));
     }
     
     content
 }
 
-fn generate_licenses_doc(licenses: &serde_json::Value) -> String {
-    let mut content = String::from("# License Compliance\n\n");
+fn generate_licenses_doc(licenses: &serde_json::Value, m: &Messages) -> String {
+    let heading = format!("# {}\n\n", m.license_compliance);
+    let mut content = heading.clone();
     
     if let Some(summary) = licenses.get("summary") {
         let total = summary.get("totalPackages").and_then(|v| v.as_u64()).unwrap_or(0);
         let unique = summary.get("uniqueLicenses").and_then(|v| v.as_u64()).unwrap_or(0);
-        let unknown = summary.get("unknownLicenses").and_then(|v| v.as_u64()).unwrap_or(0);
+        let no_license = summary.get("noLicenseDeclared").and_then(|v| v.as_u64()).unwrap_or(0);
+        let unrecognized = summary.get("unrecognizedLicenses").and_then(|v| v.as_u64()).unwrap_or(0);
         
-        content.push_str("## Summary\n\n");
-        content.push_str(&format!("- **Total Packages:** {}\n", total));
-        content.push_str(&format!("- **Unique Licenses:** {}\n", unique));
-        content.push_str(&format!("- **Unknown Licenses:** {}\n\n", unknown));
+        content.push_str(&format!("## {}\n\n", m.summary));
+        content.push_str(&format!("- **{}:** {}\n", m.total_packages, total));
+        content.push_str(&format!("- **{}:** {}\n", m.unique_licenses, unique));
+        content.push_str(&format!("- **{}:** {}\n", m.no_license_declared, no_license));
+        content.push_str(&format!("- **{}:** {}\n\n", m.unrecognized_licenses, unrecognized));
+
+        if let Some(rollup) = summary.get("riskRollup") {
+            content.push_str(&format!("## {}\n\n", m.risk_rollup));
+            content.push_str(&format!("| {} | {} | {} |\n", m.risk_level, m.count, m.percentage));
+            content.push_str("|------------|-------|------------|\n");
+            for (label, key) in [(m.risk_safe, "safe"), (m.risk_caution, "caution"), (m.risk_review_required, "reviewRequired")] {
+                let bucket = rollup.get(key);
+                let count = bucket.and_then(|b| b.get("count")).and_then(|v| v.as_u64()).unwrap_or(0);
+                let pct = bucket.and_then(|b| b.get("percentage")).and_then(|v| v.as_f64()).unwrap_or(0.0);
+                content.push_str(&format!("| {} | {} | {:.1}% |\n", label, count, pct));
+            }
+            content.push('\n');
+        }
     }
     
     if let Some(dist) = licenses.get("distribution").and_then(|v| v.as_array()) {
         if !dist.is_empty() {
-            content.push_str("## License Distribution\n\n");
-            content.push_str("| License | Count | Percentage |\n");
+            content.push_str(&format!("## {}\n\n", m.license_distribution));
+            content.push_str(&format!("| {} | {} | {} |\n", m.license, m.count, m.percentage));
             content.push_str("|---------|-------|------------|\n");
-            let total = licenses.get("summary")
-                .and_then(|s| s.get("totalPackages"))
-                .and_then(|v| v.as_u64())
-                .unwrap_or(1) as f64;
             for item in dist {
                 let license = item.get("license").and_then(|v| v.as_str()).unwrap_or("");
                 let count = item.get("count").and_then(|v| v.as_u64()).unwrap_or(0);
-                let pct = if total > 0.0 { (count as f64 / total) * 100.0 } else { 0.0 };
+                let pct = item.get("percentage").and_then(|v| v.as_f64()).unwrap_or(0.0);
                 content.push_str(&format!("| {} | {} | {:.1}% |\n", license, count, pct));
             }
-            content.push_str("\n");
+            content.push('\n');
         }
     }
     
     if let Some(issues) = licenses.get("compatibilityIssues").and_then(|v| v.as_array()) {
         if !issues.is_empty() {
-            content.push_str("## Compatibility Issues\n\n");
+            content.push_str(&format!("## {}\n\n", m.compatibility_issues));
             for issue in issues {
                 if let Some(issue_str) = issue.as_str() {
                     content.push_str(&format!("- {}\n", issue_str));
                 }
             }
-            content.push_str("\n");
+            content.push('\n');
+        }
+    }
+
+    if let Some(packages) = licenses.get("packagesWithoutLicense").and_then(|v| v.as_array()) {
+        if !packages.is_empty() {
+            content.push_str(&format!("## {}\n\n", m.packages_without_license));
+            content.push_str(&format!("{}\n\n", m.packages_without_license_note));
+            for package in packages.iter().filter_map(|p| p.as_str()) {
+                content.push_str(&format!("- {}\n", package));
+            }
+            content.push('\n');
         }
     }
     
-    if content == "# License Compliance\n\n" {
-        content.push_str("*For detailed license information, use the `analyze_licenses` tool.*\n");
+    if content == heading {
+        content.push_str(&format!("*{}*\n", m.licenses_tool_hint));
     }
     
     content
 }
 
-fn generate_architecture_doc(psr4: &serde_json::Value, namespaces: &serde_json::Value) -> String {
-    let mut content = String::from("# Architecture\n\n");
+fn generate_architecture_doc(psr4: &serde_json::Value, namespaces: &serde_json::Value, m: &Messages) -> String {
+    let mut content = format!("# {}\n\n", m.architecture);
     
     // Parse PSR-4 data
     if let Some(stats) = psr4.get("stats") {
@@ -335,17 +455,17 @@ fn generate_architecture_doc(psr4: &serde_json::Value, namespaces: &serde_json::
         let valid = stats.get("validFiles").and_then(|v| v.as_u64()).unwrap_or(0);
         let violations = stats.get("violationCount").and_then(|v| v.as_u64()).unwrap_or(0);
         
-        content.push_str("## PSR-4 Autoloading\n\n");
-        content.push_str("### Summary\n\n");
-        content.push_str(&format!("- **Total Mappings:** {}\n", mappings));
-        content.push_str(&format!("- **Files Analyzed:** {}\n", files));
-        content.push_str(&format!("- **PSR-4 Compliant:** {}\n", valid));
-        content.push_str(&format!("- **Violations:** {}\n\n", violations));
+        content.push_str(&format!("## {}\n\n", m.psr4_autoloading));
+        content.push_str(&format!("### {}\n\n", m.summary));
+        content.push_str(&format!("- **{}:** {}\n", m.total_mappings, mappings));
+        content.push_str(&format!("- **{}:** {}\n", m.files_analyzed, files));
+        content.push_str(&format!("- **{}:** {}\n", m.psr4_compliant, valid));
+        content.push_str(&format!("- **{}:** {}\n\n", m.violations, violations));
         
         if let Some(mapping_list) = psr4.get("mappings").and_then(|v| v.as_array()) {
             if !mapping_list.is_empty() {
-                content.push_str("### Mappings\n\n");
-                content.push_str("| Namespace Prefix | Directory |\n");
+                content.push_str(&format!("### {}\n\n", m.mappings));
+                content.push_str(&format!("| {} | {} |\n", m.namespace_prefix, m.directory));
                 content.push_str("|------------------|-----------|\n");
                 for mapping in mapping_list.iter().take(20) {
                     let ns = mapping.get("namespace").and_then(|v| v.as_str()).unwrap_or("");
@@ -359,42 +479,42 @@ fn generate_architecture_doc(psr4: &serde_json::Value, namespaces: &serde_json::
                     content.push_str(&format!("| `{}` | `{}` |\n", ns, paths));
                 }
                 if mapping_list.len() > 20 {
-                    content.push_str(&format!("\n*... and {} more mappings*\n", mapping_list.len() - 20));
+                    content.push_str(&format!("\n*{}*\n", fill(m.and_more_mappings, &[&(mapping_list.len() - 20)])));
                 }
-                content.push_str("\n");
+                content.push('\n');
             }
         }
     } else {
-        content.push_str("## PSR-4 Autoloading\n\n");
-        content.push_str("*For detailed PSR-4 information, use the `analyze_psr4` tool.*\n\n");
+        content.push_str(&format!("## {}\n\n", m.psr4_autoloading));
+        content.push_str(&format!("*{}*\n\n", m.psr4_tool_hint));
     }
     
     // Parse namespaces data
     if let Some(ns_list) = namespaces.get("namespaces").and_then(|v| v.as_array()) {
-        content.push_str("## Namespaces\n\n");
+        content.push_str(&format!("## {}\n\n", m.namespaces));
         if !ns_list.is_empty() {
-            content.push_str(&format!("Found **{}** namespaces:\n\n", ns_list.len()));
+            content.push_str(&format!("{}\n\n", fill(m.namespaces_found, &[&ns_list.len()])));
             for ns in ns_list.iter().take(30) {
                 let ns_name = ns.get("namespace").and_then(|v| v.as_str()).unwrap_or("");
                 let files = ns.get("files").and_then(|v| v.as_array()).map(|a| a.len()).unwrap_or(0);
-                content.push_str(&format!("- `{}` ({} files)\n", ns_name, files));
+                content.push_str(&format!("- `{}` ({})\n", ns_name, fill(m.namespace_files, &[&files])));
             }
             if ns_list.len() > 30 {
-                content.push_str(&format!("\n*... and {} more namespaces*\n", ns_list.len() - 30));
+                content.push_str(&format!("\n*{}*\n", fill(m.and_more_namespaces, &[&(ns_list.len() - 30)])));
             }
         } else {
-            content.push_str("*No namespaces detected.*\n");
+            content.push_str(&format!("*{}*\n", m.no_namespaces));
         }
     } else {
-        content.push_str("## Namespaces\n\n");
-        content.push_str("*For detailed namespace information, use the `detect_namespaces` tool.*\n");
+        content.push_str(&format!("## {}\n\n", m.namespaces));
+        content.push_str(&format!("*{}*\n", m.namespaces_tool_hint));
     }
     
     content
 }
 
 fn generate_changelog(repo_path: &str) -> Result<String> {
-    let current_snapshot = create_dependency_snapshot(repo_path)?;
+    let current_snapshot = create_dependency_snapshot(repo_path, None)?;
     let now = Utc::now().format("%Y-%m-%d").to_string();
 
     let old_snapshot = load_tracker(repo_path).ok();
@@ -407,21 +527,86 @@ fn generate_changelog(repo_path: &str) -> Result<String> {
         ));
     }
 
-    let changes = compare_snapshots(&old_snapshot.unwrap(), &current_snapshot);
+    let old_snapshot = old_snapshot.unwrap();
+    let changes = compare_snapshots(&old_snapshot, &current_snapshot);
+    let span = describe_snapshot_span(&old_snapshot, &current_snapshot)
+        .map(|s| format!("Changes {}.\n\n", s))
+        .unwrap_or_default();
     
     if changes.is_empty() {
         return Ok(format!(
-            "# Dependency Changelog\n\n## {}\n\nNo changes detected since last snapshot.\n\n**Total Dependencies:** {}\n",
+            "# Dependency Changelog\n\n## {}\n\n{}No changes detected since last snapshot.\n\n**Total Dependencies:** {}\n",
             now,
+            span,
             current_snapshot.metadata.total_count
         ));
     }
 
-    let added: Vec<_> = changes.iter().filter(|c| c.change_type == "added").collect();
-    let updated: Vec<_> = changes.iter().filter(|c| c.change_type == "updated").collect();
-    let removed: Vec<_> = changes.iter().filter(|c| c.change_type == "removed").collect();
+    let mut content = format!("# Dependency Changelog\n\n## {}\n\n{}", now, span);
+    content.push_str(&changelog_sections(&changes));
+    Ok(content)
+}
+
+/// Dependency changelog for a release: the current composer.lock against the one at
+/// `from_ref` (default: the most recent git tag), read with `git show`
+pub fn generate_release_changelog(repo_path: &str, from_ref: Option<&str>) -> Result<String> {
+    let from_ref = match from_ref {
+        Some(r) => r.to_string(),
+        None => git_output(repo_path, &["describe", "--tags", "--abbrev=0"])
+            .map_err(|e| anyhow!("No from_ref given and no git tag found: {}", e))?,
+    };
+    let commit = resolve_commit(repo_path, &from_ref)?;
+    let old_lock = git_output(repo_path, &["show", &format!("{}:./composer.lock", commit)])
+        .map_err(|e| anyhow!("composer.lock not found at {}: {}", from_ref, e))?;
+
+    let old_snapshot = snapshot_from_lock_contents(repo_path, &old_lock, &from_ref)?;
+    let current_snapshot = create_dependency_snapshot(repo_path, Some("working tree"))?;
+    let changes = compare_snapshots(&old_snapshot, &current_snapshot);
+
+    let mut content = format!("# Dependency Changes Since {}\n\n", from_ref);
+    if changes.is_empty() {
+        content.push_str(&format!(
+            "No dependency changes since {}.\n\n**Total Dependencies:** {}\n",
+            from_ref, current_snapshot.metadata.total_count
+        ));
+        return Ok(content);
+    }
+    content.push_str(&changelog_sections(&changes));
+    Ok(content)
+}
+
+/// Resolve a user-supplied ref to a commit SHA, so it can't be read as a git option
+/// (`--output=...`) when passed on to other git commands
+pub(crate) fn resolve_commit(repo_path: &str, git_ref: &str) -> Result<String> {
+    if git_ref.starts_with('-') {
+        return Err(anyhow!("Invalid git ref: {}", git_ref));
+    }
+    git_output(repo_path, &["rev-parse", "--verify", "--end-of-options", &format!("{}^{{commit}}", git_ref)])
+        .map_err(|e| anyhow!("Invalid git ref {}: {}", git_ref, e))
+}
 
-    let mut content = format!("# Dependency Changelog\n\n## {}\n\n### Summary\n\n", now);
+/// Run git in the repository and return trimmed stdout
+pub(crate) fn git_output(repo_path: &str, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(repo_path)
+        .output()
+        .map_err(|e| anyhow!("Failed to run git: {}", e))?;
+    if !output.status.success() {
+        return Err(anyhow!("{}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Summary plus Added / Updated / Removed sections, sorted by package name
+fn changelog_sections(changes: &[DependencyChange]) -> String {
+    let mut changes: Vec<&DependencyChange> = changes.iter().collect();
+    changes.sort_by(|a, b| a.name.cmp(&b.name));
+    let added: Vec<_> = changes.iter().copied().filter(|c| c.change_type == "added").collect();
+    let updated: Vec<_> = changes.iter().copied().filter(|c| c.change_type == "updated").collect();
+    let removed: Vec<_> = changes.iter().copied().filter(|c| c.change_type == "removed").collect();
+
+    let mut content = String::from("### Summary\n\n");
     content.push_str(&format!("- **Added:** {}\n", added.len()));
     content.push_str(&format!("- **Updated:** {}\n", updated.len()));
     content.push_str(&format!("- **Removed:** {}\n\n", removed.len()));
@@ -431,7 +616,7 @@ fn generate_changelog(repo_path: &str) -> Result<String> {
         for change in added {
             content.push_str(&format!("- `{}` `{}`\n", change.name, change.new_version.as_ref().unwrap_or(&"".to_string())));
         }
-        content.push_str("\n");
+        content.push('\n');
     }
 
     if !updated.is_empty() {
@@ -444,7 +629,7 @@ fn generate_changelog(repo_path: &str) -> Result<String> {
                 change.new_version.as_ref().unwrap_or(&"".to_string())
             ));
         }
-        content.push_str("\n");
+        content.push('\n');
     }
 
     if !removed.is_empty() {
@@ -452,18 +637,20 @@ fn generate_changelog(repo_path: &str) -> Result<String> {
         for change in removed {
             content.push_str(&format!("- `{}` `{}`\n", change.name, change.old_version.as_ref().unwrap_or(&"".to_string())));
         }
-        content.push_str("\n");
+        content.push('\n');
     }
 
-    Ok(content)
+    content
 }
 
-fn generate_mkdocs_config(site_name: &str, site_description: &str, include_changelog: bool) -> String {
+fn generate_mkdocs_config(site_name: &str, site_description: &str, include_changelog: bool, locale: Locale) -> String {
+    let m = locale.messages();
     let mut config = format!("site_name: {}\n", site_name);
     config.push_str(&format!("site_description: {}\n", site_description));
     config.push_str("site_url: https://example.com\n\n");
     config.push_str("theme:\n");
     config.push_str("  name: material\n");
+    config.push_str(&format!("  language: {}\n", locale.code()));
     config.push_str("  palette:\n");
     config.push_str("    primary: blue\n");
     config.push_str("    accent: blue\n\n");
@@ -478,18 +665,19 @@ fn generate_mkdocs_config(site_name: &str, site_description: &str, include_chang
     config.push_str("          class: mermaid\n");
     config.push_str("          format: !!python/name:pymdownx.superfences.fence_code_format\n\n");
     config.push_str("nav:\n");
-    config.push_str("  - Home: index.md\n");
-    config.push_str("  - Dependencies: dependencies.md\n");
-    config.push_str("  - Security: security.md\n");
-    config.push_str("  - Licenses: licenses.md\n");
-    config.push_str("  - Architecture: architecture.md\n");
+    config.push_str(&format!("  - {}: index.md\n", m.home));
+    config.push_str(&format!("  - {}: dependencies.md\n", m.dependencies));
+    config.push_str(&format!("  - {}: security.md\n", m.security));
+    config.push_str(&format!("  - {}: licenses.md\n", m.licenses));
+    config.push_str(&format!("  - {}: architecture.md\n", m.architecture));
     if include_changelog {
-        config.push_str("  - Changelog: changelog.md\n");
+        config.push_str(&format!("  - {}: changelog.md\n", m.changelog));
     }
 
     config
 }
 
+#[allow(clippy::too_many_arguments)]
 fn generate_html_site(
     site_name: &str,
     site_description: &str,
@@ -499,7 +687,9 @@ fn generate_html_site(
     licenses: &str,
     architecture: &str,
     changelog: &str,
+    locale: Locale,
 ) -> String {
+    let m = locale.messages();
     // Escape markdown content for JavaScript strings
     let escape_js = |s: &str| -> String {
         s.replace('\\', "\\\\")
@@ -516,21 +706,21 @@ fn generate_html_site(
     let arch_escaped = escape_js(architecture);
     
     let changelog_nav = if !changelog.is_empty() {
-        "\n    <a href=\"#changelog\">Changelog</a>"
+        format!("\n    <a href=\"#changelog\">{}</a>", m.changelog)
     } else {
-        ""
+        String::new()
     };
     
     let changelog_section = if !changelog.is_empty() {
-        "\n  <div id=\"changelog\" class=\"section\">\n    <h2>Changelog</h2>\n    <div id=\"changelog-content\"></div>\n  </div>"
+        format!("\n  <div id=\"changelog\" class=\"section\">\n    <h2>{}</h2>\n    <div id=\"changelog-content\"></div>\n  </div>", m.changelog)
     } else {
-        ""
+        String::new()
     };
     
     // Build HTML string piece by piece to avoid format! macro issues with nested {}
     let mut html = String::new();
     html.push_str("<!DOCTYPE html>\n");
-    html.push_str("<html lang=\"en\">\n");
+    html.push_str(&format!("<html lang=\"{}\">\n", locale.code()));
     html.push_str("<head>\n");
     html.push_str("  <meta charset=\"UTF-8\">\n");
     html.push_str("  <meta name=\"viewport\" content=\"width=device-width, initial-scale=1.0\">\n");
@@ -557,24 +747,24 @@ fn generate_html_site(
     html.push_str("</head>\n");
     html.push_str("<body>\n");
     html.push_str("  <nav>\n");
-    html.push_str("    <a href=\"#index\">Home</a>\n");
-    html.push_str("    <a href=\"#dependencies\">Dependencies</a>\n");
-    html.push_str("    <a href=\"#security\">Security</a>\n");
-    html.push_str("    <a href=\"#licenses\">Licenses</a>\n");
-    html.push_str("    <a href=\"#architecture\">Architecture</a>");
-    html.push_str(changelog_nav);
+    html.push_str(&format!("    <a href=\"#index\">{}</a>\n", m.home));
+    html.push_str(&format!("    <a href=\"#dependencies\">{}</a>\n", m.dependencies));
+    html.push_str(&format!("    <a href=\"#security\">{}</a>\n", m.security));
+    html.push_str(&format!("    <a href=\"#licenses\">{}</a>\n", m.licenses));
+    html.push_str(&format!("    <a href=\"#architecture\">{}</a>", m.architecture));
+    html.push_str(&changelog_nav);
     html.push_str("\n  </nav>\n");
     html.push_str("  \n");
     html.push_str(&format!("  <div id=\"index\" class=\"section\">\n    <h1>{}</h1>\n    <p class=\"meta\">{}</p>\n    <div id=\"index-content\"></div>\n  </div>\n", site_name, site_description));
     html.push_str("  \n");
-    html.push_str("  <div id=\"dependencies\" class=\"section\">\n    <h2>Dependencies</h2>\n    <div id=\"dependencies-content\"></div>\n  </div>\n");
+    html.push_str(&format!("  <div id=\"dependencies\" class=\"section\">\n    <h2>{}</h2>\n    <div id=\"dependencies-content\"></div>\n  </div>\n", m.dependencies));
     html.push_str("  \n");
-    html.push_str("  <div id=\"security\" class=\"section\">\n    <h2>Security</h2>\n    <div id=\"security-content\"></div>\n  </div>\n");
+    html.push_str(&format!("  <div id=\"security\" class=\"section\">\n    <h2>{}</h2>\n    <div id=\"security-content\"></div>\n  </div>\n", m.security));
     html.push_str("  \n");
-    html.push_str("  <div id=\"licenses\" class=\"section\">\n    <h2>Licenses</h2>\n    <div id=\"licenses-content\"></div>\n  </div>\n");
+    html.push_str(&format!("  <div id=\"licenses\" class=\"section\">\n    <h2>{}</h2>\n    <div id=\"licenses-content\"></div>\n  </div>\n", m.licenses));
     html.push_str("  \n");
-    html.push_str("  <div id=\"architecture\" class=\"section\">\n    <h2>Architecture</h2>\n    <div id=\"architecture-content\"></div>\n  </div>");
-    html.push_str(changelog_section);
+    html.push_str(&format!("  <div id=\"architecture\" class=\"section\">\n    <h2>{}</h2>\n    <div id=\"architecture-content\"></div>\n  </div>", m.architecture));
+    html.push_str(&changelog_section);
     html.push_str("\n  \n");
     html.push_str("  <script>\n");
     html.push_str("    function markdownToHTML(md) {\n");
@@ -630,3 +820,65 @@ fn generate_html_site(
     
     html
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_commit_rejects_option_like_refs() {
+        let temp = crate::test_support::temp_dir();
+        let target = temp.path().join("git-output");
+        let git_ref = format!("--output={}", target.display());
+
+        let error = resolve_commit(".", &git_ref).unwrap_err();
+        assert!(error.to_string().starts_with("Invalid git ref"));
+        assert!(!target.exists());
+    }
+
+    #[test]
+    fn test_dry_run_plans_files_without_writing() {
+        let temp = crate::test_support::temp_dir();
+        let repo = temp.path();
+        fs::write(repo.join("composer.json"), r#"{"name": "acme/app", "description": "Demo"}"#).unwrap();
+        fs::write(repo.join("composer.lock"), r#"{"packages": [{"name": "psr/log", "version": "3.0.0"}]}"#).unwrap();
+        fs::write(repo.join(".dpb.json"), r#"{"redaction": {"patterns": ["psr/*"]}}"#).unwrap();
+        let output = repo.join("site");
+        fs::create_dir(&output).unwrap();
+        fs::write(output.join("index.md"), "old").unwrap();
+        let home = crate::test_support::temp_dir();
+        let mut env = crate::test_support::EnvGuard::lock();
+        env.set("HOME", home.path());
+
+        let plan = generate_mkdocs_docs(MkDocsOptions {
+            repo_path: repo.display().to_string(),
+            output_dir: Some(output.display().to_string()),
+            include_changelog: false,
+            format: "mkdocs".to_string(),
+            site_name: None,
+            site_description: None,
+            locale: Locale::En,
+            redact: true,
+            dry_run: true,
+        })
+        .unwrap();
+        let plan: serde_json::Value = serde_json::from_str(&plan).unwrap();
+
+        let files = plan["files"].as_array().unwrap();
+        let paths: Vec<&str> = files.iter().map(|f| f["path"].as_str().unwrap()).collect();
+        assert_eq!(
+            paths,
+            ["index.md", "dependencies.md", "security.md", "licenses.md", "architecture.md", "mkdocs.yml"]
+        );
+        let exists: Vec<bool> = files.iter().map(|f| f["exists"].as_bool().unwrap()).collect();
+        assert_eq!(exists, [true, false, false, false, false, false]);
+        let bytes: u64 = files.iter().map(|f| f["bytes"].as_u64().unwrap()).sum();
+        assert_eq!(plan["totalBytes"], bytes);
+        assert!(bytes > 0);
+
+        assert_eq!(fs::read_to_string(output.join("index.md")).unwrap(), "old");
+        assert!(!output.join("mkdocs.yml").exists());
+        // Redacting a preview must not create the installation's redaction key either
+        assert!(!home.path().join(".dpb").exists());
+    }
+}
@@ -5,20 +5,53 @@ pub mod security;
 pub mod tracker;
 pub mod suggestions;
 pub mod mkdocs;
-
-use anyhow::Result;
-use serde::Serialize;
+pub mod updates;
+pub mod contents;
+pub mod compatibility;
+pub mod autoload;
+pub mod graph_html;
+pub mod majors;
+pub mod policy;
+pub mod incremental;
+pub mod footprint;
+pub mod reproducibility;
+pub mod redaction;
+pub mod fixes;
+pub mod binaries;
+pub mod overlaps;
+pub mod lag;
+pub mod verdict;
+pub mod health;
+pub mod metadata;
+pub mod constraint_diff;
+pub mod maintainers;
+pub mod autoload_config;
+pub mod vendor_integrity;
+pub mod extra;
+pub mod platform;
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
-use crate::composer::{get_licenses, read_composer_json, read_composer_lock};
-use crate::types::{RepoConfig, RepoVersion, VersionConflict};
+use crate::composer::{
+    get_licenses, is_platform_package, lock_path, normalize_package_name, read_composer_json, read_composer_lock,
+};
+use crate::composer::constraint::{Constraint, Version};
+use crate::config::expand_existing_path;
+use crate::i18n::{Locale, Messages};
+use crate::types::{FailedRepo, RepoConfig, RepoVersion, VersionConflict};
+use dependency::DependencyFilter;
+
+const MULTI_REPO_CACHE_DIR: &str = ".dpb/multirepo-cache";
 
 pub fn generate_dependency_graph<P: AsRef<Path>>(
     repo_path: P,
     max_depth: usize,
-    include_dev: bool,
+    filter: &DependencyFilter,
     focus_package: Option<String>,
 ) -> Result<String> {
     let lock = match read_composer_lock(&repo_path) {
@@ -30,23 +63,36 @@ pub fn generate_dependency_graph<P: AsRef<Path>>(
 
     let mut mermaid = String::from("graph TD\n");
 
-    let mut packages = lock.packages.clone();
-    if include_dev {
-        if let Some(dev_packages) = &lock.packages_dev {
-            packages.extend(dev_packages.clone());
-        }
+    let mut packages: Vec<crate::types::PackageInfo> = lock
+        .packages
+        .iter()
+        .filter(|p| filter.includes(&p.name, false))
+        .cloned()
+        .collect();
+    if let Some(dev_packages) = &lock.packages_dev {
+        packages.extend(
+            dev_packages
+                .iter()
+                .filter(|p| filter.includes(&p.name, true))
+                .cloned(),
+        );
     }
 
     if let Some(focus) = focus_package {
-        mermaid.push_str(&generate_focused_graph(&packages, &focus, max_depth));
+        let focus = normalize_package_name(&focus);
+        mermaid.push_str(&generate_focused_graph(&packages, &focus, max_depth, filter));
     } else {
-        mermaid.push_str(&generate_full_graph(&packages, max_depth));
+        mermaid.push_str(&generate_full_graph(&packages, max_depth, filter));
     }
 
     Ok(mermaid)
 }
 
-fn generate_full_graph(packages: &[crate::types::PackageInfo], max_depth: usize) -> String {
+fn generate_full_graph(
+    packages: &[crate::types::PackageInfo],
+    max_depth: usize,
+    filter: &DependencyFilter,
+) -> String {
     let mut result = String::from("  Root[Your Application]\n");
 
     let limit = packages.len().min(15);
@@ -64,7 +110,10 @@ fn generate_full_graph(packages: &[crate::types::PackageInfo], max_depth: usize)
             if let Some(require) = &pkg.require {
                 let mut dep_count = 0;
                 for (dep, version) in require {
-                    if !dep.starts_with("php") && !dep.starts_with("ext-") && dep_count < 3 {
+                    if !is_platform_package(dep)
+                        && !filter.is_excluded(dep)
+                        && dep_count < 3
+                    {
                         let dep_sanitized = sanitize_for_mermaid(dep);
                         result.push_str(&format!(
                             "  {} --> {}[\"{}...
@@ -86,6 +135,7 @@ fn generate_focused_graph(
     packages: &[crate::types::PackageInfo],
     focus_package: &str,
     _max_depth: usize,
+    filter: &DependencyFilter,
 ) -> String {
     let focus_sanitized = sanitize_for_mermaid(focus_package);
     let mut result = format!("  {}[{}]\n", focus_sanitized, focus_package);
@@ -93,7 +143,7 @@ fn generate_focused_graph(
     if let Some(pkg) = packages.iter().find(|p| p.name == focus_package) {
         if let Some(require) = &pkg.require {
             for (dep, version) in require {
-                if !dep.starts_with("php") && !dep.starts_with("ext-") {
+                if !is_platform_package(dep) && !filter.is_excluded(dep) {
                     let dep_sanitized = sanitize_for_mermaid(dep);
                     result.push_str(&format!(
                         "  {} --> {}[\"{}...
@@ -110,13 +160,9 @@ fn generate_focused_graph(
 }
 
 fn sanitize_for_mermaid(name: &str) -> String {
-    name.replace('/', "_")
-        .replace('-', "_")
-        .replace('.', "_")
-        .replace('@', "_")
+    name.replace(['/', '-', '.', '@'], "_")
 }
 
-#[allow(dead_code)]
 #[derive(Debug, Serialize)]
 pub struct MultiRepoAnalysisResult {
     pub repositories: Vec<RepoConfig>,
@@ -128,46 +174,96 @@ pub struct MultiRepoAnalysisResult {
     pub total_packages: usize,
     #[serde(rename = "commonLicenses")]
     pub common_licenses: HashMap<String, usize>,
+    #[serde(rename = "failedRepos")]
+    pub failed_repos: Vec<FailedRepo>,
+    #[serde(rename = "phpVersions")]
+    pub php_versions: Vec<PhpVersionGroup>,
+}
+
+/// Repos sharing one declared `php` constraint
+#[derive(Debug, Serialize)]
+pub struct PhpVersionGroup {
+    /// The `require.php` constraint, or "unspecified"
+    pub constraint: String,
+    /// Lowest PHP version the constraint admits
+    #[serde(rename = "minimumVersion")]
+    pub minimum_version: Option<String>,
+    pub repos: Vec<String>,
 }
 
-pub fn analyze_multiple_repositories<P: AsRef<Path>>(config_path: P) -> Result<String> {
+/// Analyze every repo listed in a config file. `~`, `$VAR` and `${VAR}` are
+/// expanded in the config path and in each repo path.
+///
+/// With `use_cache`, each repo's scan is saved under `.dpb/multirepo-cache/` next to the
+/// config as soon as it completes, keyed by a hash of its composer.lock.
+/// Unchanged repos are then skipped on the next run, which also resumes an interrupted scan;
+/// failed repos are never cached, so they are retried.
+pub fn analyze_multiple_repositories<P: AsRef<Path>>(config_path: P, use_cache: bool, locale: Locale) -> Result<String> {
+    let config_path = expand_existing_path(&config_path.as_ref().to_string_lossy())
+        .map_err(|e| anyhow!("Multi-repo config: {}", e))?;
     let contents = fs::read_to_string(&config_path)?;
-    let repos: Vec<RepoConfig> = serde_json::from_str(&contents)?;
+    let configured: Vec<RepoConfig> = serde_json::from_str(&contents)?;
+    let cache_dir = use_cache.then(|| {
+        config_path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join(MULTI_REPO_CACHE_DIR)
+    });
+
+    // Repos that cannot be read are reported rather than silently dropped
+    let mut repos = Vec::new();
+    let mut scans: HashMap<String, RepoScan> = HashMap::new();
+    let mut failed_repos = Vec::new();
+    let mut cached_repos = 0;
+    for mut repo in configured {
+        let loaded = expand_existing_path(&repo.path).and_then(|path| {
+            let Some(cache_dir) = &cache_dir else {
+                return Ok((path.clone(), scan_repo(&path)?, false));
+            };
+            let (scan, hit) = load_cached_repo(cache_dir, &path)?;
+            Ok((path, scan, hit))
+        });
+        match loaded {
+            Ok((path, scan, hit)) => {
+                if hit {
+                    cached_repos += 1;
+                }
+                repo.path = path.to_string_lossy().into_owned();
+                scans.insert(repo.name.clone(), scan);
+                repos.push(repo);
+            }
+            Err(e) => failed_repos.push(FailedRepo {
+                name: repo.name,
+                path: repo.path,
+                error: format!("{:#}", e),
+            }),
+        }
+    }
 
     let mut package_usage: HashMap<String, Vec<String>> = HashMap::new();
     let mut all_packages = std::collections::HashSet::new();
     let mut license_count: HashMap<String, usize> = HashMap::new();
+    let mut php_constraints: HashMap<String, Vec<String>> = HashMap::new();
 
     for repo in &repos {
-        if let Ok(composer) = read_composer_json(&repo.path) {
+        if let Some(scan) = scans.get(&repo.name) {
             // Collect dependencies
-            if let Some(require) = &composer.require {
-                for (pkg, _) in require {
-                    if pkg != "php" {
-                        all_packages.insert(pkg.clone());
-                        package_usage
-                            .entry(pkg.clone())
-                            .or_insert_with(Vec::new)
-                            .push(repo.name.clone());
-                    }
-                }
-            }
-
-            if let Some(require_dev) = &composer.require_dev {
-                for (pkg, _) in require_dev {
-                    all_packages.insert(pkg.clone());
-                    package_usage
-                        .entry(pkg.clone())
-                        .or_insert_with(Vec::new)
-                        .push(repo.name.clone());
-                }
+            for pkg in scan.requires.keys() {
+                all_packages.insert(pkg.clone());
+                package_usage
+                    .entry(pkg.clone())
+                    .or_default()
+                    .push(repo.name.clone());
             }
 
             // Collect licenses
-            let licenses = get_licenses(&composer);
-            for license in licenses {
-                *license_count.entry(license).or_insert(0) += 1;
+            for license in &scan.licenses {
+                *license_count.entry(license.clone()).or_insert(0) += 1;
             }
+
+            // Collect the PHP platform requirement kept out of `requires`
+            let php = scan.php.clone().unwrap_or_else(|| "unspecified".to_string());
+            php_constraints.entry(php).or_default().push(repo.name.clone());
         }
     }
 
@@ -184,21 +280,11 @@ pub fn analyze_multiple_repositories<P: AsRef<Path>>(config_path: P) -> Result<S
         let mut versions: HashMap<String, Vec<String>> = HashMap::new();
 
         for repo in used_by_repos {
-            if let Some(repo_config) = repos.iter().find(|r| &r.name == repo) {
-                if let Ok(composer) = read_composer_json(&repo_config.path) {
-                    let version = composer
-                        .require
-                        .as_ref()
-                        .and_then(|r| r.get(pkg))
-                        .or_else(|| composer.require_dev.as_ref().and_then(|r| r.get(pkg)));
-
-                    if let Some(v) = version {
-                        versions
-                            .entry(v.clone())
-                            .or_insert_with(Vec::new)
-                            .push(repo.clone());
-                    }
-                }
+            if let Some(v) = scans.get(repo).and_then(|scan| scan.requires.get(pkg)) {
+                versions
+                    .entry(v.clone())
+                    .or_default()
+                    .push(repo.clone());
             }
         }
 
@@ -206,7 +292,8 @@ pub fn analyze_multiple_repositories<P: AsRef<Path>>(config_path: P) -> Result<S
             let mut conflict_versions = Vec::new();
             for (version, repos) in versions {
                 for repo in repos {
-                    conflict_versions.push(RepoVersion { repo, version: version.clone() });
+                    let installed = scans.get(&repo).and_then(|scan| scan.installed.get(pkg)).cloned();
+                    conflict_versions.push(RepoVersion { repo, version: version.clone(), installed });
                 }
             }
 
@@ -217,33 +304,273 @@ pub fn analyze_multiple_repositories<P: AsRef<Path>>(config_path: P) -> Result<S
         }
     }
 
+    let result = MultiRepoAnalysisResult {
+        repositories: repos,
+        shared_dependencies,
+        version_conflicts,
+        total_packages: all_packages.len(),
+        common_licenses: license_count,
+        failed_repos,
+        php_versions: php_version_spread(php_constraints),
+    };
+
     // Generate markdown report
-    let report = generate_multi_repo_report(
-        &repos,
-        &shared_dependencies,
-        &version_conflicts,
-        all_packages.len(),
-        &license_count,
-    );
+    let report = generate_multi_repo_report(&result, cache_dir.is_some().then_some(cached_repos), locale.messages());
 
     Ok(report)
 }
 
-fn generate_multi_repo_report(
-    repos: &[RepoConfig],
-    shared_deps: &HashMap<String, Vec<String>>,
-    conflicts: &[VersionConflict],
-    total_pkgs: usize,
-    licenses: &HashMap<String, usize>,
-) -> String {
-    let mut report = String::from("# Multi-Repository Dependency Analysis\n\n");
-    report.push_str(&format!("**Generated:** {}\n\n", chrono::Utc::now().to_rfc3339()));
+/// What the fleet report needs from one repo, saved so an unchanged repo needn't be re-scanned
+#[derive(Debug, Serialize, Deserialize)]
+struct RepoScan {
+    /// Direct requirements (require-dev included, `php` excluded): package -> constraint
+    requires: HashMap<String, String>,
+    /// Locked versions: package -> version
+    installed: HashMap<String, String>,
+    licenses: Vec<String>,
+    /// The `require.php` constraint
+    php: Option<String>,
+}
+
+/// One cache entry: a repo's scan and the composer.json and composer.lock it was taken from
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedRepoScan {
+    path: String,
+    #[serde(rename = "sourceHash")]
+    source_hash: String,
+    scan: RepoScan,
+}
+
+/// Read composer.json and composer.lock (when there is one) into a [`RepoScan`]
+fn scan_repo(repo_path: &Path) -> Result<RepoScan> {
+    let composer = read_composer_json(repo_path)?;
+    let installed = match read_composer_lock(repo_path) {
+        Ok(lock) => lock
+            .packages
+            .into_iter()
+            .chain(lock.packages_dev.into_iter().flatten())
+            .map(|pkg| (pkg.name, pkg.version))
+            .collect(),
+        Err(_) => HashMap::new(),
+    };
+    // require wins over require-dev for a package listed in both
+    let requires = composer
+        .require_dev
+        .iter()
+        .chain(composer.require.iter())
+        .flatten()
+        .filter(|(name, _)| name.as_str() != "php")
+        .map(|(name, constraint)| (name.clone(), constraint.clone()))
+        .collect();
+
+    Ok(RepoScan {
+        requires,
+        installed,
+        licenses: get_licenses(&composer),
+        php: composer.require.as_ref().and_then(|r| r.get("php")).cloned(),
+    })
+}
+
+/// Scan a repo through the cache, storing a fresh entry on a miss. Returns whether it was a hit.
+/// Repos without a composer.lock are scanned every time.
+fn load_cached_repo(cache_dir: &Path, repo_path: &Path) -> Result<(RepoScan, bool)> {
+    // requires, licenses and the PHP constraint come from composer.json, so it is part of the key
+    let Ok(lock) = fs::read(lock_path(repo_path)) else {
+        return Ok((scan_repo(repo_path)?, false));
+    };
+    let mut source = Sha256::new();
+    source.update(Sha256::digest(fs::read(repo_path.join("composer.json")).unwrap_or_default()));
+    source.update(Sha256::digest(&lock));
+    let source_hash = hex::encode(source.finalize());
+    let mut hasher = Sha256::new();
+    hasher.update(repo_path.to_string_lossy().as_bytes());
+    let entry_path = cache_dir.join(format!("{}.json", hex::encode(&hasher.finalize()[..8])));
+
+    let cached: Option<CachedRepoScan> = fs::read_to_string(&entry_path)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok());
+    if let Some(cached) = cached.filter(|c| c.source_hash == source_hash) {
+        return Ok((cached.scan, true));
+    }
+
+    let entry = CachedRepoScan {
+        path: repo_path.to_string_lossy().into_owned(),
+        source_hash,
+        scan: scan_repo(repo_path)?,
+    };
+    fs::create_dir_all(cache_dir)
+        .with_context(|| format!("Failed to create cache directory {:?}", cache_dir))?;
+    fs::write(&entry_path, serde_json::to_string_pretty(&entry)?)?;
+    Ok((entry.scan, false))
+}
+
+#[derive(Debug, Serialize)]
+pub struct RepoComparisonResult {
+    #[serde(rename = "repoA")]
+    pub repo_a: RepoVersionSource,
+    #[serde(rename = "repoB")]
+    pub repo_b: RepoVersionSource,
+    #[serde(rename = "onlyInA")]
+    pub only_in_a: Vec<PackageVersion>,
+    #[serde(rename = "onlyInB")]
+    pub only_in_b: Vec<PackageVersion>,
+    #[serde(rename = "sameVersion")]
+    pub same_version: Vec<PackageVersion>,
+    #[serde(rename = "differentVersion")]
+    pub different_version: Vec<PackageVersionDiff>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RepoVersionSource {
+    pub path: String,
+    /// "composer.lock" (installed versions) or "composer.json" (declared constraints)
+    pub source: String,
+}
 
-    report.push_str("## Repositories Analyzed\n\n");
+#[derive(Debug, Serialize)]
+pub struct PackageVersion {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PackageVersionDiff {
+    pub name: String,
+    #[serde(rename = "versionA")]
+    pub version_a: String,
+    #[serde(rename = "versionB")]
+    pub version_b: String,
+}
+
+/// Versions a repo uses: locked versions when composer.lock is readable, otherwise declared constraints
+fn repo_package_versions<P: AsRef<Path>>(repo_path: P) -> Result<(HashMap<String, String>, &'static str)> {
+    if let Ok(lock) = read_composer_lock(&repo_path) {
+        let versions = lock
+            .packages
+            .into_iter()
+            .chain(lock.packages_dev.unwrap_or_default())
+            .map(|p| (p.name, p.version))
+            .collect();
+        return Ok((versions, "composer.lock"));
+    }
+
+    let composer = read_composer_json(&repo_path)?;
+    let versions = composer
+        .require
+        .unwrap_or_default()
+        .into_iter()
+        .chain(composer.require_dev.unwrap_or_default())
+        .filter(|(name, _)| !is_platform_package(name))
+        .collect();
+    Ok((versions, "composer.json"))
+}
+
+/// Compare the dependencies of two repos, e.g. staging vs production or a fork vs upstream
+pub fn compare_repos<P: AsRef<Path>>(repo_a: P, repo_b: P) -> Result<String> {
+    let (versions_a, source_a) = repo_package_versions(&repo_a)?;
+    let (versions_b, source_b) = repo_package_versions(&repo_b)?;
+
+    let mut only_in_a = Vec::new();
+    let mut same_version = Vec::new();
+    let mut different_version = Vec::new();
+
+    for (name, version_a) in &versions_a {
+        match versions_b.get(name) {
+            None => only_in_a.push(PackageVersion { name: name.clone(), version: version_a.clone() }),
+            Some(version_b) if version_b == version_a => {
+                same_version.push(PackageVersion { name: name.clone(), version: version_a.clone() })
+            }
+            Some(version_b) => different_version.push(PackageVersionDiff {
+                name: name.clone(),
+                version_a: version_a.clone(),
+                version_b: version_b.clone(),
+            }),
+        }
+    }
+
+    let mut only_in_b: Vec<PackageVersion> = versions_b
+        .iter()
+        .filter(|(name, _)| !versions_a.contains_key(*name))
+        .map(|(name, version)| PackageVersion { name: name.clone(), version: version.clone() })
+        .collect();
+
+    only_in_a.sort_by(|a, b| a.name.cmp(&b.name));
+    only_in_b.sort_by(|a, b| a.name.cmp(&b.name));
+    same_version.sort_by(|a, b| a.name.cmp(&b.name));
+    different_version.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let result = RepoComparisonResult {
+        repo_a: RepoVersionSource {
+            path: repo_a.as_ref().display().to_string(),
+            source: source_a.to_string(),
+        },
+        repo_b: RepoVersionSource {
+            path: repo_b.as_ref().display().to_string(),
+            source: source_b.to_string(),
+        },
+        only_in_a,
+        only_in_b,
+        same_version,
+        different_version,
+    };
+
+    Ok(serde_json::to_string_pretty(&result)?)
+}
+
+/// Group repos by PHP constraint, oldest minimum version first and unparseable constraints last
+fn php_version_spread(php_constraints: HashMap<String, Vec<String>>) -> Vec<PhpVersionGroup> {
+    let mut groups: Vec<(Option<Version>, PhpVersionGroup)> = php_constraints
+        .into_iter()
+        .map(|(constraint, mut repos)| {
+            repos.sort();
+            let minimum = lowest_admitted_version(&constraint);
+            let group = PhpVersionGroup {
+                minimum_version: minimum.as_ref().map(|(literal, _)| literal.clone()),
+                constraint,
+                repos,
+            };
+            (minimum.map(|(_, version)| version), group)
+        })
+        .collect();
+    groups.sort_by(|(a, ga), (b, gb)| match (a, b) {
+        (Some(a), Some(b)) => a.cmp(b).then_with(|| ga.constraint.cmp(&gb.constraint)),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => ga.constraint.cmp(&gb.constraint),
+    });
+    groups.into_iter().map(|(_, group)| group).collect()
+}
+
+/// The smallest version written in a constraint that the constraint also accepts
+fn lowest_admitted_version(constraint: &str) -> Option<(String, Version)> {
+    let parsed = Constraint::parse(constraint)?;
+    constraint
+        .split(|c: char| !c.is_ascii_digit() && c != '.')
+        .filter(|literal| !literal.is_empty())
+        .filter_map(|literal| Some((literal.to_string(), Version::parse(literal)?)))
+        .filter(|(_, version)| parsed.matches(version))
+        .min_by(|(_, a), (_, b)| a.cmp(b))
+}
+
+fn generate_multi_repo_report(result: &MultiRepoAnalysisResult, cached_repos: Option<usize>, m: &Messages) -> String {
+    let MultiRepoAnalysisResult {
+        repositories: repos,
+        shared_dependencies: shared_deps,
+        version_conflicts: conflicts,
+        total_packages: total_pkgs,
+        common_licenses: licenses,
+        failed_repos,
+        php_versions,
+    } = result;
+
+    let mut report = format!("# {}\n\n", m.multi_repo_title);
+    report.push_str(&format!("**{}:** {}\n\n", m.generated, chrono::Utc::now().to_rfc3339()));
+
+    report.push_str(&format!("## {}\n\n", m.repositories_analyzed));
     for repo in repos {
         report.push_str(&format!("- **{}** ({})", repo.name, repo.repo_type));
         if let Some(team) = &repo.team {
-            report.push_str(&format!(" - Team: {}", team));
+            report.push_str(&format!(" - {}: {}", m.team, team));
         }
         if let Some(desc) = &repo.description {
             report.push_str(&format!("\n  {}", desc));
@@ -251,14 +578,30 @@ fn generate_multi_repo_report(
         report.push('\n');
     }
 
-    report.push_str("\n## Summary\n\n");
-    report.push_str(&format!("- Total unique packages: {}\n", total_pkgs));
-    report.push_str(&format!("- Shared dependencies: {}\n", shared_deps.len()));
-    report.push_str(&format!("- Version conflicts: {}\n\n", conflicts.len()));
+    report.push_str(&format!("\n## {}\n\n", m.summary));
+    report.push_str(&format!("- {}: {}\n", m.total_unique_packages, total_pkgs));
+    report.push_str(&format!("- {}: {}\n", m.shared_dependencies_count, shared_deps.len()));
+    report.push_str(&format!("- {}: {}\n", m.version_conflicts_count, conflicts.len()));
+    report.push_str(&format!("- {}: {}\n", m.failed_repositories_count, failed_repos.len()));
+    if let Some(cached) = cached_repos {
+        report.push_str(&format!("- {}: {}\n", m.cached_repositories_count, cached));
+    }
+    report.push('\n');
+
+    if !failed_repos.is_empty() {
+        report.push_str(&format!("## ⚠️ {}\n\n", m.failed_repositories));
+        report.push_str(&format!("{}\n\n", m.failed_repositories_note));
+        report.push_str(&format!("| {} | {} | {} |\n", m.repository, m.path, m.error));
+        report.push_str("|------------|------|-------|\n");
+        for failed in failed_repos {
+            report.push_str(&format!("| {} | {} | {} |\n", failed.name, failed.path, failed.error.replace('|', "\\|")));
+        }
+        report.push('\n');
+    }
 
     if !shared_deps.is_empty() {
-        report.push_str("## Shared Dependencies\n\n");
-        report.push_str("| Package | Used By |\n");
+        report.push_str(&format!("## {}\n\n", m.shared_dependencies));
+        report.push_str(&format!("| {} | {} |\n", m.package, m.used_by));
         report.push_str("|---------|----------|\n");
         for (pkg, repos) in shared_deps {
             report.push_str(&format!("| {} | {} |\n", pkg, repos.join(", ")));
@@ -267,19 +610,52 @@ fn generate_multi_repo_report(
     }
 
     if !conflicts.is_empty() {
-        report.push_str("## ⚠️ Version Conflicts\n\n");
+        report.push_str(&format!("## ⚠️ {}\n\n", m.version_conflicts));
         for conflict in conflicts {
             report.push_str(&format!("### {}\n\n", conflict.package));
             for version in &conflict.versions {
-                report.push_str(&format!("- **{}**: {}\n", version.repo, version.version));
+                match &version.installed {
+                    Some(installed) => report.push_str(&format!(
+                        "- **{}**: {} ({} {})\n",
+                        version.repo, version.version, m.installed, installed
+                    )),
+                    None => report.push_str(&format!("- **{}**: {}\n", version.repo, version.version)),
+                }
             }
             report.push('\n');
         }
     }
 
+    if !php_versions.is_empty() {
+        report.push_str(&format!("## {}\n\n", m.php_platform_versions));
+        report.push_str(&format!("| {} | {} | {} | {} |\n", m.php_constraint, m.lowest_version, m.repos, m.count));
+        report.push_str("|----------------|----------------|-------|-------|\n");
+        for group in php_versions {
+            report.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                group.constraint.replace('|', "\\|"),
+                group.minimum_version.as_deref().unwrap_or("-"),
+                group.repos.join(", "),
+                group.repos.len()
+            ));
+        }
+        // Groups are ordered by lowest version, so the first one gates a fleet-wide bump
+        if let [oldest, _, ..] = php_versions.as_slice() {
+            if oldest.minimum_version.is_some() {
+                report.push_str(&format!(
+                    "\n**{}:** {} (`php {}`)\n",
+                    m.php_upgrade_blockers,
+                    oldest.repos.join(", "),
+                    oldest.constraint
+                ));
+            }
+        }
+        report.push('\n');
+    }
+
     if !licenses.is_empty() {
-        report.push_str("## License Distribution\n\n");
-        report.push_str("| License | Count |\n");
+        report.push_str(&format!("## {}\n\n", m.license_distribution));
+        report.push_str(&format!("| {} | {} |\n", m.license, m.count));
         report.push_str("|---------|-------|\n");
         for (license, count) in licenses {
             report.push_str(&format!("| {} | {} |\n", license, count));
@@ -292,41 +668,46 @@ fn generate_multi_repo_report(
 pub fn generate_comprehensive_docs<P: AsRef<Path>>(
     repo_path: P,
     output_path: Option<P>,
+    locale: Locale,
 ) -> Result<String> {
     let composer = read_composer_json(&repo_path)?;
+    let m = locale.messages();
 
-    let mut report = String::from("# PHP Dependency Documentation\n\n");
-    report.push_str(&format!("**Generated:** {}\n\n", chrono::Utc::now().to_rfc3339()));
+    let mut report = format!("# {}\n\n", m.docs_title);
+    report.push_str(&format!("**{}:** {}\n\n", m.generated, chrono::Utc::now().to_rfc3339()));
 
-    report.push_str("## Project Information\n\n");
+    report.push_str(&format!("## {}\n\n", m.project_information));
     report.push_str(&format!(
-        "- **Name:** {}\n",
-        composer.name.as_deref().unwrap_or("Unknown")
+        "- **{}:** {}\n",
+        m.name,
+        composer.name.as_deref().unwrap_or(m.unknown)
     ));
     report.push_str(&format!(
-        "- **Description:** {}\n",
-        composer.description.as_deref().unwrap_or("N/A")
+        "- **{}:** {}\n",
+        m.description,
+        composer.description.as_deref().unwrap_or(m.not_available)
     ));
     report.push_str(&format!(
-        "- **Type:** {}\n",
+        "- **{}:** {}\n",
+        m.project_type_short,
         composer.package_type.as_deref().unwrap_or("library")
     ));
 
     let licenses = get_licenses(&composer);
     let license_str = if licenses.is_empty() {
-        "Not specified".to_string()
+        m.not_specified.to_string()
     } else {
         licenses.join(", ")
     };
-    report.push_str(&format!("- **License:** {}\n\n", license_str));
+    report.push_str(&format!("- **{}:** {}\n\n", m.license, license_str));
 
-    report.push_str("## Dependency Summary\n\n");
+    report.push_str(&format!("## {}\n\n", m.dependency_summary));
     let prod_count = composer.require.as_ref().map(|r| r.len()).unwrap_or(0);
     let dev_count = composer.require_dev.as_ref().map(|r| r.len()).unwrap_or(0);
-    report.push_str(&format!("- **Production Dependencies:** {}\n", prod_count));
-    report.push_str(&format!("- **Development Dependencies:** {}\n\n", dev_count));
+    report.push_str(&format!("- **{}:** {}\n", m.production_dependencies, prod_count));
+    report.push_str(&format!("- **{}:** {}\n\n", m.development_dependencies, dev_count));
 
-    report.push_str("For detailed analysis, use the individual tools:\n");
+    report.push_str(&format!("{}\n", m.individual_tools_hint));
     report.push_str("- `analyze_dependencies`\n");
     report.push_str("- `analyze_psr4`\n");
     report.push_str("- `audit_security`\n");
@@ -339,3 +720,71 @@ pub fn generate_comprehensive_docs<P: AsRef<Path>>(
 
     Ok(report)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_compare_repos_splits_packages_by_version() {
+        let temp = crate::test_support::temp_dir();
+        let (a, b) = (temp.path().join("a"), temp.path().join("b"));
+        fs::create_dir(&a).unwrap();
+        fs::create_dir(&b).unwrap();
+        let lock = json!({
+            "packages": [{"name": "acme/same", "version": "1.0.0"}, {"name": "acme/drift", "version": "1.0.0"}],
+            "packages-dev": [{"name": "acme/only-a", "version": "2.0.0"}]
+        });
+        fs::write(a.join("composer.lock"), lock.to_string()).unwrap();
+        let composer = json!({"require": {"php": "^8.1", "acme/same": "1.0.0", "acme/drift": "^2.0", "acme/only-b": "^1.0"}});
+        fs::write(b.join("composer.json"), composer.to_string()).unwrap();
+
+        let result: serde_json::Value = serde_json::from_str(&compare_repos(&a, &b).unwrap()).unwrap();
+
+        assert_eq!(result["repoA"]["source"], "composer.lock");
+        assert_eq!(result["repoB"]["source"], "composer.json");
+        assert_eq!(result["onlyInA"], json!([{"name": "acme/only-a", "version": "2.0.0"}]));
+        assert_eq!(result["onlyInB"], json!([{"name": "acme/only-b", "version": "^1.0"}]));
+        assert_eq!(result["sameVersion"], json!([{"name": "acme/same", "version": "1.0.0"}]));
+        assert_eq!(result["differentVersion"], json!([{"name": "acme/drift", "versionA": "1.0.0", "versionB": "^2.0"}]));
+    }
+
+    #[test]
+    fn test_php_versions_are_grouped_oldest_first() {
+        let constraints = HashMap::from([
+            ("^8.2".to_string(), vec!["billing".to_string()]),
+            ("unspecified".to_string(), vec!["legacy".to_string()]),
+            (">=7.4 <8.0 || ^8.1".to_string(), vec!["shop".to_string(), "api".to_string()]),
+            ("~8.1.0".to_string(), vec!["auth".to_string()]),
+        ]);
+
+        let groups = php_version_spread(constraints);
+
+        let order: Vec<(&str, Option<&str>)> =
+            groups.iter().map(|g| (g.constraint.as_str(), g.minimum_version.as_deref())).collect();
+        assert_eq!(
+            order,
+            [(">=7.4 <8.0 || ^8.1", Some("7.4")), ("~8.1.0", Some("8.1.0")), ("^8.2", Some("8.2")), ("unspecified", None)]
+        );
+        assert_eq!(groups[0].repos, ["api", "shop"]);
+    }
+
+    #[test]
+    fn test_cached_scan_is_invalidated_by_composer_json_edits() {
+        let temp = crate::test_support::temp_dir();
+        let repo = temp.path().join("repo");
+        let cache = temp.path().join("cache");
+        fs::create_dir(&repo).unwrap();
+        fs::write(repo.join("composer.json"), json!({"require": {"php": "^8.1"}}).to_string()).unwrap();
+        fs::write(repo.join("composer.lock"), json!({"packages": []}).to_string()).unwrap();
+
+        assert!(!load_cached_repo(&cache, &repo).unwrap().1);
+        assert!(load_cached_repo(&cache, &repo).unwrap().1);
+
+        fs::write(repo.join("composer.json"), json!({"require": {"php": "^8.2"}}).to_string()).unwrap();
+        let (scan, hit) = load_cached_repo(&cache, &repo).unwrap();
+        assert!(!hit);
+        assert_eq!(scan.php.as_deref(), Some("^8.2"));
+    }
+}
@@ -1,14 +1,16 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use lazy_static::lazy_static;
 use rayon::prelude::*;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::fs;
-use std::path::Path;
-use std::sync::{Arc, Mutex};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::panic;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 
-use crate::analyzer::psr4::find_php_files;
+use crate::analyzer::psr4::{find_php_files, package_prefixes, par_for_each_bounded};
+use crate::composer::{get_psr4_mappings, read_composer_json, read_composer_lock, read_to_string_with_retry};
+use crate::config::{load_config, AnalysisConfig};
 use crate::types::NamespaceInfo;
 
 lazy_static! {
@@ -17,15 +19,95 @@ lazy_static! {
     static ref INTERFACE_RE: Regex = Regex::new(r"interface\s+(\w+)").unwrap();
     static ref TRAIT_RE: Regex = Regex::new(r"trait\s+(\w+)").unwrap();
     static ref USE_RE: Regex = Regex::new(r"use\s+([\w\\]+)(?:\s+as\s+\w+)?;").unwrap();
+    static ref MARKER_RE: Regex = Regex::new(r"(@deprecated|\bTODO|\bFIXME)\b:?\s*(.*)").unwrap();
 }
 
+/// Where markers from files without a namespace declaration are grouped
+const GLOBAL_NAMESPACE: &str = "\\";
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct NamespaceDetectionResult {
+    /// Sorted by namespace; one page of them when `limit` is given
     pub namespaces: Vec<NamespaceInfo>,
+    /// Namespaces across all pages
+    #[serde(rename = "totalNamespaces", default)]
+    pub total_namespaces: usize,
+    /// Pass as `cursor` to fetch the next page; absent on the last page
+    #[serde(rename = "nextCursor", default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
     #[serde(rename = "totalFiles")]
     pub total_files: usize,
-    #[serde(rename = "filesWithoutNamespace")]
-    pub files_without_namespace: Vec<String>,
+    /// Files without a namespace, rolled up per directory (largest first)
+    #[serde(rename = "filesWithoutNamespaceByDirectory")]
+    pub files_without_namespace_by_directory: Vec<DirectoryFileCount>,
+    /// The flat list, only when requested; it gets huge in legacy codebases
+    #[serde(rename = "filesWithoutNamespace", skip_serializing_if = "Option::is_none")]
+    pub files_without_namespace: Option<Vec<String>>,
+    #[serde(rename = "duplicateClasses")]
+    pub duplicate_classes: Vec<DuplicateClass>,
+    /// Files skipped because analyzing them panicked
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    /// TODO/FIXME comments and @deprecated tags per namespace, only when requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub markers: Option<Vec<NamespaceMarkers>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NamespaceMarkers {
+    /// `\\` for files without a namespace declaration
+    pub namespace: String,
+    /// In file and line order
+    pub markers: Vec<CodeMarker>,
+}
+
+/// A TODO/FIXME comment or @deprecated docblock tag
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeMarker {
+    /// "todo", "fixme" or "deprecated"
+    pub kind: String,
+    pub file: String,
+    pub line: usize,
+    /// The rest of the comment after the marker
+    pub text: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DirectoryFileCount {
+    pub directory: String,
+    pub count: usize,
+}
+
+/// A class-like declared in more than one file within the same namespace
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DuplicateClass {
+    pub namespace: String,
+    #[serde(rename = "className")]
+    pub class_name: String,
+    pub files: Vec<String>,
+}
+
+/// Lowercased (namespace, class-like name) -> (file, namespace, name) of each declaration.
+/// PHP resolves both case-insensitively, so `App\User` and `app\user` are the same class.
+type DeclarationMap = HashMap<(String, String), Vec<(String, String, String)>>;
+
+fn record_declarations(declarations: &mut DeclarationMap, file: &str, info: &FileInfo) {
+    for name in info.classes.iter().chain(&info.interfaces).chain(&info.traits) {
+        declarations
+            .entry((info.namespace.to_lowercase(), name.to_lowercase()))
+            .or_default()
+            .push((file.to_string(), info.namespace.clone(), name.clone()));
+    }
+}
+
+/// Names declared in more than one file, spelled as in the first file
+fn duplicates(declarations: DeclarationMap) -> impl Iterator<Item = DuplicateClass> {
+    declarations.into_values().filter(|found| found.len() > 1).map(|mut found| {
+        found.sort();
+        let (_, namespace, class_name) = found[0].clone();
+        let files = found.into_iter().map(|(file, _, _)| file).collect();
+        DuplicateClass { namespace, class_name, files }
+    })
 }
 
 #[derive(Debug, Default)]
@@ -35,64 +117,323 @@ struct FileInfo {
     interfaces: Vec<String>,
     traits: Vec<String>,
     uses: Vec<String>,
+    /// (kind, line, text); only collected when asked for
+    markers: Vec<(String, usize, String)>,
 }
 
-pub fn detect_namespaces<P: AsRef<Path> + Sync>(repo_path: P) -> Result<String> {
-    let php_files = find_php_files(repo_path.as_ref())?;
+pub fn detect_namespaces<P: AsRef<Path> + Sync>(
+    repo_path: P,
+    list_files_without_namespace: bool,
+    include_markers: bool,
+    limit: Option<usize>,
+    cursor: Option<&str>,
+) -> Result<String> {
+    let mut result = scan_namespaces(repo_path, list_files_without_namespace, include_markers)?;
+    if let Some(limit) = limit {
+        paginate_namespaces(&mut result, limit, cursor)?;
+    }
+    Ok(serde_json::to_string_pretty(&result)?)
+}
+
+/// Keep the `limit` namespaces after `cursor`. The cursor is the last namespace of the
+/// previous page, so walking the pages stays consistent even if namespaces were added
+/// or removed in between.
+fn paginate_namespaces(result: &mut NamespaceDetectionResult, limit: usize, cursor: Option<&str>) -> Result<()> {
+    if limit == 0 {
+        return Err(anyhow!("Invalid limit: must be at least 1"));
+    }
+    let start = cursor.map_or(0, |c| result.namespaces.partition_point(|ns| ns.namespace.as_str() <= c));
+    let mut page: Vec<NamespaceInfo> = result.namespaces.drain(start..).take(limit + 1).collect();
+    let has_more = page.len() > limit;
+    page.truncate(limit);
+
+    result.next_cursor = has_more.then(|| page.last().map(|ns| ns.namespace.clone())).flatten();
+    // Global markers have no namespace entry of their own, so they come with the first page
+    if let Some(markers) = &mut result.markers {
+        markers.retain(|m| {
+            (m.namespace == GLOBAL_NAMESPACE && start == 0) || page.iter().any(|ns| ns.namespace == m.namespace)
+        });
+    }
+    result.namespaces = page;
+    Ok(())
+}
+
+/// Detect namespaces and return the raw struct
+pub fn detect_namespaces_raw<P: AsRef<Path> + Sync>(
+    repo_path: P,
+    list_files_without_namespace: bool,
+) -> Result<NamespaceDetectionResult> {
+    scan_namespaces(repo_path, list_files_without_namespace, false)
+}
+
+/// One pass over the PHP files, optionally collecting TODO/FIXME/@deprecated markers
+/// from the same reads
+fn scan_namespaces<P: AsRef<Path> + Sync>(
+    repo_path: P,
+    list_files_without_namespace: bool,
+    include_markers: bool,
+) -> Result<NamespaceDetectionResult> {
+    let analysis = load_config(&repo_path)?.analysis;
+    let php_files = find_php_files(repo_path.as_ref(), &analysis.php_extensions)?;
 
     let namespace_map: Arc<Mutex<HashMap<String, NamespaceInfo>>> =
         Arc::new(Mutex::new(HashMap::new()));
     let files_without: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
+    let declarations: Arc<Mutex<DeclarationMap>> = Arc::new(Mutex::new(HashMap::new()));
 
-    // Process files in parallel
-    php_files.par_iter().for_each(|file| {
-        if let Ok(info) = analyze_file(file) {
-            if let Ok(relative_path) = file.strip_prefix(&repo_path) {
-                let rel_str = relative_path.to_string_lossy().to_string();
+    let warnings: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
+    let markers: Arc<Mutex<HashMap<String, Vec<CodeMarker>>>> = Arc::new(Mutex::new(HashMap::new()));
 
-                if !info.namespace.is_empty() {
-                    let mut map = namespace_map.lock().unwrap();
-                    let ns_info = map.entry(info.namespace.clone()).or_insert_with(|| {
-                        NamespaceInfo {
-                            namespace: info.namespace.clone(),
-                            files: Vec::new(),
-                            classes: Vec::new(),
-                            interfaces: Vec::new(),
-                            traits: Vec::new(),
-                        }
-                    });
+    // Process files in parallel, up to maxParallelFiles at a time
+    par_for_each_bounded(&php_files, analysis.max_parallel_files, |file| {
+        let Ok(relative_path) = file.strip_prefix(&repo_path) else { return };
+        let rel_str = relative_path.to_string_lossy().to_string();
+        let info = match analyze_file_guarded(file, include_markers) {
+            Ok(Some(info)) => info,
+            Ok(None) => return,
+            Err(panic) => {
+                lock(&warnings).push(format!("Skipped {}: analysis panicked ({})", rel_str, panic));
+                return;
+            }
+        };
 
-                    ns_info.files.push(rel_str);
-                    ns_info.classes.extend(info.classes);
-                    ns_info.interfaces.extend(info.interfaces);
-                    ns_info.traits.extend(info.traits);
-                } else {
-                    let mut files = files_without.lock().unwrap();
-                    files.push(rel_str);
+        if !info.markers.is_empty() {
+            let namespace = if info.namespace.is_empty() { GLOBAL_NAMESPACE } else { &info.namespace };
+            lock(&markers).entry(namespace.to_string()).or_default().extend(
+                info.markers.iter().map(|(kind, line, text)| CodeMarker {
+                    kind: kind.clone(),
+                    file: rel_str.clone(),
+                    line: *line,
+                    text: text.clone(),
+                }),
+            );
+        }
+
+        record_declarations(&mut lock(&declarations), &rel_str, &info);
+
+        if !info.namespace.is_empty() {
+            let mut map = lock(&namespace_map);
+            let ns_info = map.entry(info.namespace.clone()).or_insert_with(|| {
+                NamespaceInfo {
+                    namespace: info.namespace.clone(),
+                    files: Vec::new(),
+                    classes: Vec::new(),
+                    interfaces: Vec::new(),
+                    traits: Vec::new(),
                 }
-            }
+            });
+
+            ns_info.files.push(rel_str);
+            ns_info.classes.extend(info.classes);
+            ns_info.interfaces.extend(info.interfaces);
+            ns_info.traits.extend(info.traits);
+        } else {
+            lock(&files_without).push(rel_str);
         }
     });
 
-    let namespace_map = Arc::try_unwrap(namespace_map).unwrap().into_inner().unwrap();
-    let namespaces: Vec<NamespaceInfo> = namespace_map.into_values().collect();
-    let files_without_namespace = Arc::try_unwrap(files_without).unwrap().into_inner().unwrap();
+    let namespace_map = into_inner(namespace_map);
+    let mut namespaces: Vec<NamespaceInfo> = namespace_map.into_values().collect();
+    namespaces.sort_by(|a, b| a.namespace.cmp(&b.namespace));
+    let mut files_without_namespace = into_inner(files_without);
+    files_without_namespace.sort();
+    let declarations = into_inner(declarations);
+    let mut warnings = into_inner(warnings);
+    warnings.sort();
+    let markers = include_markers.then(|| {
+        let mut markers: Vec<NamespaceMarkers> = into_inner(markers)
+            .into_iter()
+            .map(|(namespace, mut markers)| {
+                markers.sort_by(|a, b| (&a.file, a.line).cmp(&(&b.file, b.line)));
+                NamespaceMarkers { namespace, markers }
+            })
+            .collect();
+        markers.sort_by(|a, b| a.namespace.cmp(&b.namespace));
+        markers
+    });
+
+    // The same name declared in two files of one namespace is a fatal redeclaration
+    let mut duplicate_classes: Vec<DuplicateClass> = duplicates(declarations).collect();
+    duplicate_classes.sort_by(|a, b| {
+        (&a.namespace, &a.class_name).cmp(&(&b.namespace, &b.class_name))
+    });
 
-    let result = NamespaceDetectionResult {
+    Ok(NamespaceDetectionResult {
+        total_namespaces: namespaces.len(),
+        next_cursor: None,
         namespaces,
         total_files: php_files.len(),
-        files_without_namespace,
+        files_without_namespace_by_directory: count_by_directory(&files_without_namespace),
+        files_without_namespace: list_files_without_namespace.then_some(files_without_namespace),
+        duplicate_classes,
+        warnings,
+        markers,
+    })
+}
+
+/// Re-analyze only the namespaces touched by `changed_files` (repository-relative) and merge
+/// them into a previous result, which must have been produced with the flat file list.
+/// Every file of an affected namespace is rescanned, since class lists aren't kept per file.
+/// Returns the files that were read.
+pub fn update_namespaces<P: AsRef<Path>>(
+    result: &mut NamespaceDetectionResult,
+    repo_path: P,
+    changed_files: &[String],
+) -> Result<Vec<String>> {
+    let repo_path = repo_path.as_ref();
+    let Some(without_namespace) = result.files_without_namespace.as_mut() else {
+        return Err(anyhow!("Previous namespace result has no file list to update"));
     };
 
-    Ok(serde_json::to_string_pretty(&result)?)
+    // Namespace each file belonged to last time; "" for files without one
+    let mut previous: HashMap<String, String> = without_namespace
+        .iter()
+        .map(|f| (f.clone(), String::new()))
+        .collect();
+    for ns in &result.namespaces {
+        for file in &ns.files {
+            previous.insert(file.clone(), ns.namespace.clone());
+        }
+    }
+
+    let mut analyzed: HashMap<String, FileInfo> = HashMap::new();
+    let mut affected: HashSet<String> = HashSet::new();
+    for rel in changed_files {
+        if let Some(namespace) = previous.get(rel) {
+            affected.insert(namespace.to_lowercase());
+        }
+        match analyze_file_guarded(&repo_path.join(rel), false) {
+            Ok(Some(info)) => {
+                affected.insert(info.namespace.to_lowercase());
+                analyzed.insert(rel.clone(), info);
+            }
+            failed => {
+                let skipped = format!("Skipped {}:", rel);
+                result.warnings.retain(|w| !w.starts_with(&skipped));
+                if let Err(panic) = failed {
+                    result.warnings.push(format!("Skipped {}: analysis panicked ({})", rel, panic));
+                }
+                if previous.contains_key(rel) {
+                    result.total_files -= 1;
+                }
+            }
+        }
+        if analyzed.contains_key(rel) && !previous.contains_key(rel) {
+            result.total_files += 1;
+        }
+    }
+
+    // Pull in the unchanged files of every affected namespace
+    for (file, namespace) in &previous {
+        if affected.contains(&namespace.to_lowercase()) && !changed_files.contains(file) {
+            if let Ok(Some(info)) = analyze_file_guarded(&repo_path.join(file), false) {
+                analyzed.insert(file.clone(), info);
+            }
+        }
+    }
+
+    result.namespaces.retain(|ns| !affected.contains(&ns.namespace.to_lowercase()));
+    result.duplicate_classes.retain(|d| !affected.contains(&d.namespace.to_lowercase()));
+    without_namespace.retain(|f| !changed_files.contains(f));
+
+    let mut rebuilt: HashMap<String, NamespaceInfo> = HashMap::new();
+    let mut declarations: DeclarationMap = HashMap::new();
+    let mut rescanned: Vec<String> = analyzed.keys().cloned().collect();
+    rescanned.sort();
+    for file in &rescanned {
+        let info = analyzed.remove(file).unwrap();
+        record_declarations(&mut declarations, file, &info);
+        if info.namespace.is_empty() {
+            if !without_namespace.contains(file) {
+                without_namespace.push(file.clone());
+            }
+            continue;
+        }
+        let ns_info = rebuilt.entry(info.namespace.clone()).or_insert_with(|| NamespaceInfo {
+            namespace: info.namespace.clone(),
+            files: Vec::new(),
+            classes: Vec::new(),
+            interfaces: Vec::new(),
+            traits: Vec::new(),
+        });
+        ns_info.files.push(file.clone());
+        ns_info.classes.extend(info.classes);
+        ns_info.interfaces.extend(info.interfaces);
+        ns_info.traits.extend(info.traits);
+    }
+    without_namespace.sort();
+
+    result.namespaces.extend(rebuilt.into_values());
+    result.namespaces.sort_by(|a, b| a.namespace.cmp(&b.namespace));
+    result.total_namespaces = result.namespaces.len();
+    result.duplicate_classes.extend(duplicates(declarations));
+    result.duplicate_classes.sort_by(|a, b| {
+        (&a.namespace, &a.class_name).cmp(&(&b.namespace, &b.class_name))
+    });
+    result.files_without_namespace_by_directory = count_by_directory(without_namespace);
+
+    Ok(rescanned)
+}
+
+/// Count files per parent directory; files at the repository root count under "."
+fn count_by_directory(files: &[String]) -> Vec<DirectoryFileCount> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for file in files {
+        let directory = Path::new(file)
+            .parent()
+            .map(|p| p.to_string_lossy().to_string())
+            .filter(|p| !p.is_empty())
+            .unwrap_or_else(|| ".".to_string());
+        *counts.entry(directory).or_insert(0) += 1;
+    }
+
+    let mut directories: Vec<DirectoryFileCount> = counts
+        .into_iter()
+        .map(|(directory, count)| DirectoryFileCount { directory, count })
+        .collect();
+    directories.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.directory.cmp(&b.directory)));
+    directories
+}
+
+/// [`analyze_file`] with a panic (e.g. a regex or slicing bug tripped by one malformed file)
+/// caught and returned as its message, so it can't take down the whole scan. `Ok(None)`
+/// means the file couldn't be read.
+fn analyze_file_guarded(file_path: &Path, include_markers: bool) -> std::result::Result<Option<FileInfo>, String> {
+    panic::catch_unwind(|| analyze_file(file_path, include_markers).ok()).map_err(|payload| {
+        payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string())
+    })
+}
+
+/// Lock a mutex shared with worker threads; a worker that panicked while holding it leaves
+/// the data as it was at that point, which is still worth reporting
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Take the collected data back once the workers are done
+fn into_inner<T: Default>(shared: Arc<Mutex<T>>) -> T {
+    match Arc::try_unwrap(shared) {
+        Ok(mutex) => mutex.into_inner().unwrap_or_else(PoisonError::into_inner),
+        Err(shared) => std::mem::take(&mut *lock(&shared)),
+    }
 }
 
-fn analyze_file(file_path: &Path) -> Result<FileInfo> {
-    let contents = fs::read_to_string(file_path)?;
+fn analyze_file(file_path: &Path, include_markers: bool) -> Result<FileInfo> {
+    let contents = read_to_string_with_retry(file_path)?;
 
     let mut info = FileInfo::default();
 
-    for line in contents.lines() {
+    for (index, line) in contents.lines().enumerate() {
+        if include_markers {
+            if let Some(marker) = find_marker(line) {
+                info.markers.push((marker.0, index + 1, marker.1));
+            }
+        }
+
         // Extract namespace
         if let Some(captures) = NAMESPACE_RE.captures(line) {
             info.namespace = captures[1].to_string();
@@ -122,6 +463,20 @@ fn analyze_file(file_path: &Path) -> Result<FileInfo> {
     Ok(info)
 }
 
+/// A TODO/FIXME/@deprecated marker in the comment part of a line, as (kind, text)
+fn find_marker(line: &str) -> Option<(String, String)> {
+    let trimmed = line.trim_start();
+    let comment = if ["//", "#", "/*", "*"].iter().any(|p| trimmed.starts_with(p)) {
+        trimmed
+    } else {
+        &line[line.find("//")?..]
+    };
+    let captures = MARKER_RE.captures(comment)?;
+    let kind = captures[1].trim_start_matches('@').to_lowercase();
+    let text = captures[2].trim().trim_end_matches("*/").trim_end().to_string();
+    Some((kind, text))
+}
+
 #[derive(Debug, Serialize)]
 pub struct NamespaceUsageResult {
     #[serde(rename = "definedIn")]
@@ -142,19 +497,19 @@ pub fn analyze_namespace_usage<P: AsRef<Path> + Sync>(
     repo_path: P,
     target_namespace: &str,
 ) -> Result<String> {
-    let php_files = find_php_files(repo_path.as_ref())?;
+    let extensions = load_config(&repo_path)?.analysis.php_extensions;
+    let php_files = find_php_files(repo_path.as_ref(), &extensions)?;
 
     let defined_in: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
     let imported_by: Arc<Mutex<Vec<ImportInfo>>> = Arc::new(Mutex::new(Vec::new()));
 
     php_files.par_iter().for_each(|file| {
-        if let Ok(info) = analyze_file(file) {
+        if let Ok(Some(info)) = analyze_file_guarded(file, false) {
             if let Ok(relative_path) = file.strip_prefix(&repo_path) {
                 let rel_str = relative_path.to_string_lossy().to_string();
 
                 if info.namespace == target_namespace {
-                    let mut defined = defined_in.lock().unwrap();
-                    defined.push(rel_str.clone());
+                    lock(&defined_in).push(rel_str.clone());
                 }
 
                 let relevant_imports: Vec<String> = info
@@ -164,8 +519,7 @@ pub fn analyze_namespace_usage<P: AsRef<Path> + Sync>(
                     .collect();
 
                 if !relevant_imports.is_empty() {
-                    let mut imported = imported_by.lock().unwrap();
-                    imported.push(ImportInfo {
+                    lock(&imported_by).push(ImportInfo {
                         file: rel_str,
                         imports: relevant_imports,
                     });
@@ -174,8 +528,8 @@ pub fn analyze_namespace_usage<P: AsRef<Path> + Sync>(
         }
     });
 
-    let defined_in = Arc::try_unwrap(defined_in).unwrap().into_inner().unwrap();
-    let imported_by = Arc::try_unwrap(imported_by).unwrap().into_inner().unwrap();
+    let defined_in = into_inner(defined_in);
+    let imported_by = into_inner(imported_by);
 
     let result = NamespaceUsageResult {
         total_usages: defined_in.len() + imported_by.len(),
@@ -185,3 +539,436 @@ pub fn analyze_namespace_usage<P: AsRef<Path> + Sync>(
 
     Ok(serde_json::to_string_pretty(&result)?)
 }
+
+#[derive(Debug, Serialize)]
+pub struct DevAutoloadLeakResult {
+    /// `autoload-dev` PSR-4 prefixes that production code must not import from
+    #[serde(rename = "devNamespaces")]
+    pub dev_namespaces: Vec<String>,
+    pub leaks: Vec<DevAutoloadLeak>,
+    #[serde(rename = "checkedFiles")]
+    pub checked_files: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DevAutoloadLeak {
+    pub file: String,
+    pub import: String,
+    #[serde(rename = "devNamespace")]
+    pub dev_namespace: String,
+}
+
+/// Find `use` imports of `autoload-dev` classes in files outside the dev PSR-4 paths.
+/// Those work in development but fatal in production, where dev autoloads aren't dumped.
+pub fn find_dev_autoload_leaks<P: AsRef<Path> + Sync>(repo_path: P) -> Result<String> {
+    let composer = read_composer_json(&repo_path)?;
+    let mappings = get_psr4_mappings(&composer);
+    let dev_paths: Vec<String> = mappings
+        .iter()
+        .filter(|m| m.is_dev)
+        .flat_map(|m| &m.paths)
+        .map(|p| p.trim_start_matches("./").trim_end_matches('/').to_string())
+        .collect();
+
+    let extensions = load_config(&repo_path)?.analysis.php_extensions;
+    let php_files = find_php_files(repo_path.as_ref(), &extensions)?;
+    let production_files: Vec<(String, &PathBuf)> = php_files
+        .iter()
+        .filter_map(|file| {
+            let relative = file.strip_prefix(&repo_path).ok()?;
+            let is_dev = dev_paths.iter().any(|p| relative.starts_with(p));
+            (!is_dev).then(|| (relative.to_string_lossy().to_string(), file))
+        })
+        .collect();
+
+    let mut leaks: Vec<DevAutoloadLeak> = production_files
+        .par_iter()
+        .filter_map(|(relative, file)| Some((relative, analyze_file(file, false).ok()?)))
+        .flat_map_iter(|(relative, info)| {
+            info.uses
+                .into_iter()
+                .filter_map(|import| {
+                    let import = import.trim_start_matches('\\').to_string();
+                    // The most specific prefix decides, so a production `App\` doesn't mask a dev `App\Tests\`
+                    let mapping = mappings
+                        .iter()
+                        .filter(|m| import.starts_with(m.namespace.as_str()))
+                        .max_by_key(|m| m.namespace.len())?;
+                    mapping.is_dev.then(|| DevAutoloadLeak {
+                        file: relative.clone(),
+                        import,
+                        dev_namespace: mapping.namespace.clone(),
+                    })
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    leaks.sort_by(|a, b| (&a.file, &a.import).cmp(&(&b.file, &b.import)));
+
+    let result = DevAutoloadLeakResult {
+        dev_namespaces: mappings.iter().filter(|m| m.is_dev).map(|m| m.namespace.clone()).collect(),
+        leaks,
+        checked_files: production_files.len(),
+    };
+
+    Ok(serde_json::to_string_pretty(&result)?)
+}
+
+/// How many entries each ranking in the metrics report keeps
+const METRICS_TOP_N: usize = 10;
+
+#[derive(Debug, Serialize)]
+pub struct NamespaceMetrics {
+    #[serde(rename = "totalNamespaces")]
+    pub total_namespaces: usize,
+    #[serde(rename = "averageDepth")]
+    pub average_depth: f64,
+    #[serde(rename = "maxDepth")]
+    pub max_depth: usize,
+    #[serde(rename = "deepestNamespaces")]
+    pub deepest_namespaces: Vec<NamespaceMetric>,
+    /// Namespaces holding the most classes, interfaces and traits
+    #[serde(rename = "largestNamespaces")]
+    pub largest_namespaces: Vec<NamespaceMetric>,
+    /// Namespaces imported by the most files outside themselves
+    #[serde(rename = "mostImported")]
+    pub most_imported: Vec<NamespaceMetric>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NamespaceMetric {
+    pub namespace: String,
+    pub depth: usize,
+    #[serde(rename = "classCount")]
+    pub class_count: usize,
+    #[serde(rename = "fileCount")]
+    pub file_count: usize,
+    /// Files in other namespaces importing from this one
+    #[serde(rename = "importedBy")]
+    pub imported_by: usize,
+}
+
+/// Architectural health snapshot: namespace depth, size and fan-in
+pub fn namespace_metrics<P: AsRef<Path> + Sync>(repo_path: P) -> Result<String> {
+    let detection = detect_namespaces_raw(&repo_path, false)?;
+    let extensions = load_config(&repo_path)?.analysis.php_extensions;
+    let php_files = find_php_files(repo_path.as_ref(), &extensions)?;
+
+    // Importing files per defined namespace; an import's namespace is everything before its last segment
+    let defined: HashSet<&str> = detection.namespaces.iter().map(|ns| ns.namespace.as_str()).collect();
+    let imports: Vec<HashSet<String>> = php_files
+        .par_iter()
+        .filter_map(|file| analyze_file(file, false).ok())
+        .map(|info| {
+            info.uses
+                .iter()
+                .filter_map(|u| u.trim_start_matches('\\').rsplit_once('\\').map(|(ns, _)| ns.to_string()))
+                .filter(|ns| defined.contains(ns.as_str()) && *ns != info.namespace)
+                .collect()
+        })
+        .collect();
+    let mut imported_by: HashMap<String, usize> = HashMap::new();
+    for ns in imports.into_iter().flatten() {
+        *imported_by.entry(ns).or_insert(0) += 1;
+    }
+
+    let metrics: Vec<NamespaceMetric> = detection
+        .namespaces
+        .iter()
+        .map(|ns| NamespaceMetric {
+            namespace: ns.namespace.clone(),
+            depth: ns.namespace.split('\\').count(),
+            class_count: ns.classes.len() + ns.interfaces.len() + ns.traits.len(),
+            file_count: ns.files.len(),
+            imported_by: imported_by.get(ns.namespace.as_str()).copied().unwrap_or(0),
+        })
+        .collect();
+
+    let total_namespaces = metrics.len();
+    let max_depth = metrics.iter().map(|m| m.depth).max().unwrap_or(0);
+    let average_depth = if total_namespaces == 0 {
+        0.0
+    } else {
+        let total: usize = metrics.iter().map(|m| m.depth).sum();
+        (total as f64 / total_namespaces as f64 * 100.0).round() / 100.0
+    };
+
+    let top_by = |key: fn(&NamespaceMetric) -> usize| -> Vec<NamespaceMetric> {
+        let mut ranked: Vec<&NamespaceMetric> = metrics.iter().filter(|m| key(m) > 0).collect();
+        ranked.sort_by(|a, b| key(b).cmp(&key(a)).then_with(|| a.namespace.cmp(&b.namespace)));
+        ranked
+            .into_iter()
+            .take(METRICS_TOP_N)
+            .map(|m| NamespaceMetric {
+                namespace: m.namespace.clone(),
+                ..*m
+            })
+            .collect()
+    };
+
+    let result = NamespaceMetrics {
+        total_namespaces,
+        average_depth,
+        max_depth,
+        deepest_namespaces: top_by(|m| m.depth),
+        largest_namespaces: top_by(|m| m.class_count),
+        most_imported: top_by(|m| m.imported_by),
+    };
+
+    Ok(serde_json::to_string_pretty(&result)?)
+}
+
+#[derive(Debug, Serialize)]
+pub struct UnresolvedImportResult {
+    pub unresolved: Vec<UnresolvedImport>,
+    /// Imports that likely belong to an unverifiable package, judged by its vendor name
+    pub unverified: Vec<UnresolvedImport>,
+    #[serde(rename = "checkedImports")]
+    pub checked_imports: usize,
+    /// Locked packages with no PSR-4/PSR-0 prefix and no vendor/ copy to scan; imports of
+    /// their classes can't be verified and may show up as unresolved
+    #[serde(rename = "unverifiablePackages")]
+    pub unverifiable_packages: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UnresolvedImport {
+    pub file: String,
+    pub import: String,
+    /// Namespace the import points into
+    pub namespace: String,
+    #[serde(rename = "likelyPackage", skip_serializing_if = "Option::is_none")]
+    pub likely_package: Option<String>,
+}
+
+/// Flag `use` imports whose namespace is neither declared in the repository nor provided by
+/// any locked package: typos, or leftovers from a removed dependency.
+/// Imports of global classes (`use Exception;`) are not checked.
+pub fn find_unresolved_imports<P: AsRef<Path> + Sync>(repo_path: P) -> Result<String> {
+    let repo_path = repo_path.as_ref();
+    let config = load_config(repo_path)?;
+    let php_files = find_php_files(repo_path, &config.analysis.php_extensions)?;
+    let infos: Vec<(String, FileInfo)> = php_files
+        .par_iter()
+        .filter_map(|file| {
+            let relative = file.strip_prefix(repo_path).ok()?.to_string_lossy().to_string();
+            Some((relative, analyze_file(file, false).ok()?))
+        })
+        .collect();
+
+    let mut defined: HashSet<String> = infos
+        .iter()
+        .map(|(_, info)| info.namespace.clone())
+        .filter(|ns| !ns.is_empty())
+        .collect();
+
+    // Vendor namespaces: autoload prefixes, or for classmap-only packages, what their installed files declare
+    let mut vendor_prefixes: Vec<String> = Vec::new();
+    let mut unverifiable_packages = Vec::new();
+    if let Ok(lock) = read_composer_lock(repo_path) {
+        for package in lock.packages.iter().chain(lock.packages_dev.iter().flatten()) {
+            let prefixes: Vec<String> = package_prefixes(package)
+                .into_iter()
+                .map(|p| p.trim_matches('\\').to_string())
+                .filter(|p| !p.is_empty())
+                .collect();
+            if !prefixes.is_empty() {
+                vendor_prefixes.extend(prefixes);
+                continue;
+            }
+            let installed = repo_path.join("vendor").join(&package.name);
+            // Packages are scanned with the defaults; the extension setting is about this repository
+            match find_php_files(&installed, &AnalysisConfig::default().php_extensions) {
+                Ok(files) if installed.is_dir() => defined.extend(
+                    files
+                        .par_iter()
+                        .filter_map(|f| analyze_file(f, false).ok())
+                        .map(|info| info.namespace)
+                        .filter(|ns| !ns.is_empty())
+                        .collect::<Vec<_>>(),
+                ),
+                _ => unverifiable_packages.push(package.name.clone()),
+            }
+        }
+    }
+    unverifiable_packages.sort();
+
+    let is_within = |name: &str, prefix: &str| name == prefix || name.starts_with(&format!("{}\\", prefix));
+    // `monolog/monolog` most likely provides `Monolog\...`
+    let vendor_key = |name: &str| name.replace(['-', '_'], "").to_lowercase();
+    let likely_package = |import: &str| {
+        let root = vendor_key(import.split('\\').next().unwrap_or_default());
+        unverifiable_packages
+            .iter()
+            .find(|package| package.split('/').next().is_some_and(|vendor| vendor_key(vendor) == root))
+            .cloned()
+    };
+
+    let mut checked_imports = 0;
+    let mut unresolved = Vec::new();
+    let mut unverified = Vec::new();
+    for (file, info) in &infos {
+        for import in &info.uses {
+            let import = import.trim_start_matches('\\');
+            let Some((namespace, _)) = import.rsplit_once('\\') else {
+                continue;
+            };
+            checked_imports += 1;
+
+            // `use App\Models;` imports a namespace rather than a class
+            let resolved = defined.contains(namespace)
+                || defined.iter().any(|ns| is_within(ns, import))
+                || vendor_prefixes.iter().any(|prefix| is_within(import, prefix));
+            if resolved {
+                continue;
+            }
+            let likely_package = likely_package(import);
+            let list = if likely_package.is_some() { &mut unverified } else { &mut unresolved };
+            list.push(UnresolvedImport {
+                file: file.clone(),
+                import: import.to_string(),
+                namespace: namespace.to_string(),
+                likely_package,
+            });
+        }
+    }
+    unresolved.sort_by(|a, b| (&a.file, &a.import).cmp(&(&b.file, &b.import)));
+    unverified.sort_by(|a, b| (&a.file, &a.import).cmp(&(&b.file, &b.import)));
+
+    let result = UnresolvedImportResult {
+        unresolved,
+        unverified,
+        checked_imports,
+        unverifiable_packages,
+    };
+
+    Ok(serde_json::to_string_pretty(&result)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markers_without_namespace_are_grouped_globally() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        std::fs::write(dir.join("bootstrap.php"), "<?php\n// TODO: load config lazily\n").unwrap();
+        std::fs::write(dir.join("Foo.php"), "<?php\nnamespace App;\n// FIXME: rename\nclass Foo {}\n").unwrap();
+
+        let result = scan_namespaces(dir, false, true);
+        let markers = result.unwrap().markers.unwrap();
+
+        let namespaces: Vec<&str> = markers.iter().map(|m| m.namespace.as_str()).collect();
+        assert_eq!(namespaces, ["App", "\\"]);
+        let global = &markers[1].markers;
+        assert_eq!((global[0].kind.as_str(), global[0].file.as_str(), global[0].line), ("todo", "bootstrap.php", 2));
+        assert_eq!(global[0].text, "load config lazily");
+    }
+
+    #[test]
+    fn test_namespace_pages_follow_the_cursor() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        std::fs::write(dir.join("bootstrap.php"), "<?php\n// TODO: drop globals\n").unwrap();
+        for name in ["A", "B", "C", "D", "E"] {
+            let body = format!("<?php\nnamespace App\\{};\n// TODO: split\nclass {} {{}}\n", name, name);
+            std::fs::write(dir.join(format!("{}.php", name)), body).unwrap();
+        }
+        let page = |cursor: Option<&str>| -> NamespaceDetectionResult {
+            serde_json::from_str(&detect_namespaces(dir, false, true, Some(2), cursor).unwrap()).unwrap()
+        };
+        let names = |result: &NamespaceDetectionResult| -> Vec<String> {
+            result.namespaces.iter().map(|ns| ns.namespace.clone()).collect()
+        };
+
+        let first = page(None);
+        assert_eq!(names(&first), ["App\\A", "App\\B"]);
+        assert_eq!(first.total_namespaces, 5);
+        assert_eq!(first.next_cursor.as_deref(), Some("App\\B"));
+        let marked: Vec<&str> = first.markers.iter().flatten().map(|m| m.namespace.as_str()).collect();
+        assert_eq!(marked, ["App\\A", "App\\B", "\\"]);
+
+        let second = page(first.next_cursor.as_deref());
+        assert_eq!(names(&second), ["App\\C", "App\\D"]);
+        assert!(second.markers.iter().flatten().all(|m| m.namespace != GLOBAL_NAMESPACE));
+
+        let last = page(second.next_cursor.as_deref());
+        assert_eq!(names(&last), ["App\\E"]);
+        assert!(last.next_cursor.is_none());
+
+        // A cursor whose namespace has since disappeared still resumes after it
+        std::fs::remove_file(dir.join("B.php")).unwrap();
+        assert_eq!(names(&page(Some("App\\B"))), ["App\\C", "App\\D"]);
+        assert!(detect_namespaces(dir, false, false, Some(0), None).is_err());
+    }
+
+    #[test]
+    fn test_namespace_metrics_rank_depth_size_and_fan_in() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        let files = [
+            ("Kernel.php", "namespace App;\nuse App\\Http\\Controllers\\Home;\nuse App\\Models\\User;\nclass Kernel {}"),
+            ("Home.php", "namespace App\\Http\\Controllers;\nuse App\\Models\\User;\nclass Home {}"),
+            ("User.php", "namespace App\\Models;\nuse App\\Models\\HasName;\nclass User {}\ninterface HasName {}"),
+        ];
+        for (file, body) in files {
+            std::fs::write(dir.join(file), format!("<?php\n{}\n", body)).unwrap();
+        }
+
+        let metrics: serde_json::Value = serde_json::from_str(&namespace_metrics(dir).unwrap()).unwrap();
+
+        assert_eq!(metrics["totalNamespaces"], 3);
+        assert_eq!(metrics["maxDepth"], 3);
+        assert_eq!(metrics["averageDepth"], 2.0);
+        assert_eq!(metrics["deepestNamespaces"][0]["namespace"], "App\\Http\\Controllers");
+        assert_eq!(metrics["largestNamespaces"][0]["namespace"], "App\\Models");
+        assert_eq!(metrics["largestNamespaces"][0]["classCount"], 2);
+        let imported: Vec<(&str, u64)> = metrics["mostImported"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| (m["namespace"].as_str().unwrap(), m["importedBy"].as_u64().unwrap()))
+            .collect();
+        assert_eq!(imported, [("App\\Models", 2), ("App\\Http\\Controllers", 1)]);
+    }
+
+    #[test]
+    fn test_unresolved_imports_split_typos_from_unverifiable_packages() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        let lock = serde_json::json!({"packages": [
+            {"name": "monolog/monolog", "version": "3.5.0", "autoload": {"psr-4": {"Monolog\\": "src/"}}},
+            {"name": "acme-corp/legacy", "version": "1.0.0"}
+        ]});
+        std::fs::write(dir.join("composer.lock"), lock.to_string()).unwrap();
+        std::fs::write(dir.join("User.php"), "<?php\nnamespace App\\Models;\nclass User {}\n").unwrap();
+        let uses = ["Monolog\\Logger", "App\\Models\\User", "App\\Models", "Exception", "Typo\\Thing", "AcmeCorp\\Legacy\\Client"];
+        let body: String = uses.iter().map(|u| format!("use {};\n", u)).collect();
+        std::fs::write(dir.join("App.php"), format!("<?php\nnamespace App;\n{}class App {{}}\n", body)).unwrap();
+
+        let result: serde_json::Value = serde_json::from_str(&find_unresolved_imports(dir).unwrap()).unwrap();
+
+        assert_eq!(result["checkedImports"], 5);
+        assert_eq!(result["unverifiablePackages"], serde_json::json!(["acme-corp/legacy"]));
+        assert_eq!(result["unresolved"].as_array().unwrap().len(), 1);
+        assert_eq!(result["unresolved"][0]["import"], "Typo\\Thing");
+        assert_eq!(result["unverified"][0]["import"], "AcmeCorp\\Legacy\\Client");
+        assert_eq!(result["unverified"][0]["likelyPackage"], "acme-corp/legacy");
+    }
+
+    #[test]
+    fn test_duplicate_classes_ignore_case() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        std::fs::write(dir.join("A.php"), "<?php\nnamespace App\\Models;\nclass User {}\n").unwrap();
+        std::fs::write(dir.join("B.php"), "<?php\nnamespace app\\models;\nclass user {}\n").unwrap();
+        std::fs::write(dir.join("C.php"), "<?php\nnamespace App\\Models;\nclass Users {}\n").unwrap();
+
+        let duplicates = scan_namespaces(dir, false, false).unwrap().duplicate_classes;
+
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!((duplicates[0].namespace.as_str(), duplicates[0].class_name.as_str()), ("App\\Models", "User"));
+        assert_eq!(duplicates[0].files, ["A.php", "B.php"]);
+    }
+}
@@ -0,0 +1,207 @@
+//! Overlapping dependencies
+//! Flags direct dependencies that serve the same role (two HTTP clients, three date
+//! libraries), using a curated map of PHP package families extendable via `.dpb.json`
+
+use anyhow::Result;
+use serde::Serialize;
+use std::path::Path;
+
+use crate::composer::{package_name_matches, read_composer_json};
+use crate::config::{load_config, PackageFamily};
+
+/// Built-in families of packages that do the same job; projects rarely need more than one of each
+const FAMILIES: &[(&str, &[&str])] = &[
+    ("HTTP client", &[
+        "guzzlehttp/guzzle", "symfony/http-client", "kriswallsmith/buzz", "rmccue/requests",
+        "php-curl-class/php-curl-class", "nategood/httpful", "laminas/laminas-http",
+    ]),
+    ("Logging", &["monolog/monolog", "analog/analog", "katzgrau/klogger", "laminas/laminas-log"]),
+    ("Date and time", &["nesbot/carbon", "cakephp/chronos", "fightbulc/moment"]),
+    ("UUID generation", &["ramsey/uuid", "symfony/uid", "webpatser/laravel-uuid"]),
+    ("Markdown parsing", &["league/commonmark", "erusev/parsedown", "michelf/php-markdown", "cebe/markdown"]),
+    ("YAML parsing", &["symfony/yaml", "mustangostang/spyc"]),
+    ("Templating", &["twig/twig", "smarty/smarty", "league/plates", "mustache/mustache"]),
+    ("Mailing", &["symfony/mailer", "swiftmailer/swiftmailer", "phpmailer/phpmailer"]),
+    ("JWT", &["firebase/php-jwt", "lcobucci/jwt"]),
+    (".env loading", &["vlucas/phpdotenv", "symfony/dotenv", "josegonzalez/dotenv"]),
+    ("PDF generation", &["dompdf/dompdf", "tecnickcom/tcpdf", "mpdf/mpdf", "setasign/fpdf"]),
+    ("Spreadsheets", &["phpoffice/phpspreadsheet", "box/spout", "openspout/openspout"]),
+    ("Image manipulation", &["intervention/image", "imagine/imagine"]),
+    ("Money", &["moneyphp/money", "brick/money"]),
+    ("CSV", &["league/csv", "parsecsv/php-parsecsv"]),
+    ("Serialization", &["jms/serializer", "symfony/serializer"]),
+    ("Validation", &["respect/validation", "symfony/validator", "rakit/validation"]),
+    ("Dependency injection container", &[
+        "php-di/php-di", "pimple/pimple", "league/container", "symfony/dependency-injection",
+        "laminas/laminas-servicemanager",
+    ]),
+    ("Event dispatching", &["symfony/event-dispatcher", "league/event", "evenement/evenement"]),
+    ("Routing", &["nikic/fast-route", "symfony/routing", "altorouter/altorouter"]),
+    ("Caching", &["symfony/cache", "doctrine/cache", "matthiasmullie/scrapbook"]),
+    ("Mocking", &["mockery/mockery", "phpspec/prophecy"]),
+    ("Fake data", &["fakerphp/faker", "fzaninotto/faker"]),
+];
+
+#[derive(Debug, Serialize)]
+pub struct OverlapResult {
+    pub overlaps: Vec<Overlap>,
+    #[serde(rename = "checkedPackages")]
+    pub checked_packages: usize,
+    /// Number of families checked, built-in plus configured
+    #[serde(rename = "familiesChecked")]
+    pub families_checked: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Overlap {
+    pub role: String,
+    pub packages: Vec<OverlappingPackage>,
+    pub suggestion: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OverlappingPackage {
+    pub name: String,
+    pub constraint: String,
+    #[serde(rename = "isDev")]
+    pub is_dev: bool,
+}
+
+/// Find roles covered by more than one package required in composer.json;
+/// `include_dev: false` only looks at `require`
+pub fn find_overlapping_dependencies<P: AsRef<Path>>(repo_path: P, include_dev: bool) -> Result<String> {
+    let composer = read_composer_json(&repo_path)?;
+    let families = merged_families(load_config(&repo_path)?.overlaps.families);
+
+    let mut requires: Vec<(String, String, bool)> = composer
+        .require
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(name, constraint)| (name, constraint, false))
+        .chain(
+            composer
+                .require_dev
+                .filter(|_| include_dev)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(name, constraint)| (name, constraint, true)),
+        )
+        .collect();
+    requires.sort();
+
+    let overlaps = families
+        .iter()
+        .filter_map(|family| {
+            let packages: Vec<OverlappingPackage> = requires
+                .iter()
+                .filter(|(name, _, _)| family.packages.iter().any(|p| package_name_matches(p, name)))
+                .map(|(name, constraint, is_dev)| OverlappingPackage {
+                    name: name.clone(),
+                    constraint: constraint.clone(),
+                    is_dev: *is_dev,
+                })
+                .collect();
+            if packages.len() < 2 {
+                return None;
+            }
+            let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+            Some(Overlap {
+                suggestion: format!(
+                    "{} overlap ({}); consider standardizing on one",
+                    names.join(", "),
+                    family.role
+                ),
+                role: family.role.clone(),
+                packages,
+            })
+        })
+        .collect();
+
+    let result = OverlapResult {
+        overlaps,
+        checked_packages: requires.len(),
+        families_checked: families.len(),
+    };
+
+    Ok(serde_json::to_string_pretty(&result)?)
+}
+
+/// Built-in families with the configured ones merged in by role (case-insensitive)
+fn merged_families(configured: Vec<PackageFamily>) -> Vec<PackageFamily> {
+    let mut families: Vec<PackageFamily> = FAMILIES
+        .iter()
+        .map(|(role, packages)| PackageFamily {
+            role: role.to_string(),
+            packages: packages.iter().map(|p| p.to_string()).collect(),
+        })
+        .collect();
+
+    for family in configured {
+        match families.iter_mut().find(|f| f.role.eq_ignore_ascii_case(&family.role)) {
+            Some(existing) => existing.packages.extend(family.packages),
+            None => families.push(family),
+        }
+    }
+
+    families
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn overlaps(dir: &Path, include_dev: bool) -> Value {
+        serde_json::from_str(&find_overlapping_dependencies(dir, include_dev).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_builtin_families_across_require_and_require_dev() {
+        let temp = crate::test_support::temp_dir();
+        let composer = json!({
+            "require": {"guzzlehttp/guzzle": "^7.0", "symfony/http-client": "^6.4", "monolog/monolog": "^3.0"},
+            "require-dev": {"mockery/mockery": "^1.6", "phpspec/prophecy": "^1.17", "symfony/mailer": "^6.4"}
+        });
+        std::fs::write(temp.path().join("composer.json"), composer.to_string()).unwrap();
+
+        let all = overlaps(temp.path(), true);
+        let prod = overlaps(temp.path(), false);
+
+        let roles = |result: &Value| -> Vec<String> {
+            result["overlaps"].as_array().unwrap().iter().map(|o| o["role"].as_str().unwrap().to_string()).collect()
+        };
+        assert_eq!(roles(&all), ["HTTP client", "Mocking"]);
+        assert_eq!(all["checkedPackages"], 6);
+        assert_eq!(all["overlaps"][1]["packages"][0]["isDev"], true);
+        assert_eq!(
+            all["overlaps"][0]["suggestion"],
+            "guzzlehttp/guzzle, symfony/http-client overlap (HTTP client); consider standardizing on one"
+        );
+        assert_eq!(roles(&prod), ["HTTP client"]);
+        assert_eq!(prod["checkedPackages"], 3);
+        assert_eq!(prod["familiesChecked"], FAMILIES.len());
+    }
+
+    #[test]
+    fn test_configured_families_merge_by_role() {
+        let temp = crate::test_support::temp_dir();
+        let composer = json!({"require": {"acme/http": "^1.0", "guzzlehttp/guzzle": "^7.0", "acme/queue": "^1.0", "other/queue": "^2.0"}});
+        std::fs::write(temp.path().join("composer.json"), composer.to_string()).unwrap();
+        let config = json!({"overlaps": {"families": [
+            {"role": "http client", "packages": ["acme/http"]},
+            {"role": "Queues", "packages": ["acme/*", "other/queue"]}
+        ]}});
+        std::fs::write(temp.path().join(".dpb.json"), config.to_string()).unwrap();
+
+        let result = overlaps(temp.path(), true);
+
+        let found: Vec<(&str, usize)> = result["overlaps"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|o| (o["role"].as_str().unwrap(), o["packages"].as_array().unwrap().len()))
+            .collect();
+        assert_eq!(found, [("HTTP client", 2), ("Queues", 3)]);
+        assert_eq!(result["familiesChecked"], FAMILIES.len() + 1);
+    }
+}
@@ -0,0 +1,234 @@
+//! Platform requirements (php, ext-*, lib-*) against the effective platform
+//! The effective platform is the host's, with composer.json `config.platform` overrides on top;
+//! an override that hides a requirement the host can't meet is the classic "works here, breaks there"
+
+use anyhow::Result;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::process::Command;
+
+use crate::composer::constraint::{Constraint, Version};
+use crate::composer::{get_platform_overrides, is_platform_package, lock_path, read_composer_json, read_composer_lock};
+
+lazy_static! {
+    /// Leading numeric part of a host version, e.g. `7.4.33` in `7.4.33-nmm1`
+    static ref NUMERIC_VERSION_RE: Regex = Regex::new(r"^\d+(?:\.\d+)*").unwrap();
+}
+
+/// Prints PHP_VERSION, then one `name version` line per loaded extension
+const HOST_PLATFORM_SCRIPT: &str =
+    r#"echo PHP_VERSION; foreach (get_loaded_extensions() as $e) { echo "\n", strtolower(str_replace(' ', '-', $e)), ' ', phpversion($e) ?: PHP_VERSION; }"#;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlatformAnalysisResult {
+    /// `config.platform` entries, with the host version they stand in for
+    pub overrides: Vec<PlatformOverride>,
+    /// One entry per platform package and constraint, problems first
+    pub requirements: Vec<PlatformRequirement>,
+    pub summary: PlatformSummary,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlatformOverride {
+    pub package: String,
+    /// The overridden version; null when the override is `false` (not installed)
+    #[serde(rename = "override")]
+    pub override_version: Option<String>,
+    /// What the host actually provides, when known
+    #[serde(rename = "hostVersion", skip_serializing_if = "Option::is_none")]
+    pub host_version: Option<String>,
+    /// Requirements the override satisfies that the host does not
+    pub masks: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlatformRequirement {
+    pub package: String,
+    pub constraint: String,
+    /// "(root)" for composer.json, otherwise the locked packages declaring it
+    #[serde(rename = "requiredBy")]
+    pub required_by: Vec<String>,
+    /// Version Composer resolves against
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effective: Option<String>,
+    /// "override", "host" or "unknown"
+    pub source: String,
+    /// "masked" (met only thanks to an override), "unsatisfied", "satisfied" or "unknown"
+    pub status: String,
+    #[serde(rename = "hostVersion", skip_serializing_if = "Option::is_none")]
+    pub host_version: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlatformSummary {
+    pub requirements: usize,
+    pub overrides: usize,
+    pub masked: usize,
+    pub unsatisfied: usize,
+    /// Whether host versions were given or detected from the `php` binary
+    #[serde(rename = "hostKnown")]
+    pub host_known: bool,
+}
+
+/// Check the platform requirements of composer.json and every locked package against the
+/// effective platform. `host_platform` (package -> version) describes the target host; without
+/// it the `php` binary on PATH is asked, and with neither only overrides can be checked.
+pub fn analyze_platform_requirements<P: AsRef<Path>>(
+    repo_path: P,
+    host_platform: Option<HashMap<String, String>>,
+) -> Result<String> {
+    let repo_path = repo_path.as_ref();
+    let composer = read_composer_json(repo_path)?;
+    let overrides = get_platform_overrides(&composer);
+    let host: HashMap<String, String> = host_platform
+        .map(|h| h.into_iter().map(|(name, version)| (name.to_lowercase(), version)).collect())
+        .or_else(detect_host_platform)
+        .unwrap_or_default();
+    let host_known = !host.is_empty();
+
+    // (package, constraint) -> declared by
+    let mut declared: BTreeMap<(String, String), Vec<String>> = BTreeMap::new();
+    let root_requires = composer.require.iter().chain(composer.require_dev.iter()).flatten();
+    for (name, constraint) in root_requires.filter(|(name, _)| is_platform_package(name)) {
+        declared.entry((name.to_lowercase(), constraint.clone())).or_default().push("(root)".to_string());
+    }
+    if lock_path(repo_path).exists() {
+        let lock = read_composer_lock(repo_path)?;
+        for pkg in lock.packages.iter().chain(lock.packages_dev.iter().flatten()) {
+            for (name, constraint) in pkg.require.iter().flatten().filter(|(name, _)| is_platform_package(name)) {
+                declared.entry((name.to_lowercase(), constraint.clone())).or_default().push(pkg.name.clone());
+            }
+        }
+    }
+
+    let mut requirements: Vec<PlatformRequirement> = declared
+        .into_iter()
+        .map(|((package, constraint), mut required_by)| {
+            required_by.sort();
+            required_by.dedup();
+            let host_version = host.get(&package).cloned();
+            let (effective, source) = match overrides.get(&package) {
+                Some(version) => (version.clone(), "override"),
+                // A known host lacking php or an extension altogether doesn't meet the requirement
+                None if host_version.is_some() || (host_known && reported_by_host(&package)) => {
+                    (host_version.clone(), "host")
+                }
+                None => (None, "unknown"),
+            };
+            let status = match (source, satisfies(&constraint, effective.as_deref())) {
+                ("unknown", _) | (_, None) => "unknown",
+                ("override", Some(true)) if host_known && satisfies(&constraint, host_version.as_deref()) == Some(false) => {
+                    "masked"
+                }
+                (_, Some(true)) => "satisfied",
+                (_, Some(false)) => "unsatisfied",
+            };
+            PlatformRequirement {
+                package,
+                constraint,
+                required_by,
+                effective,
+                source: source.to_string(),
+                status: status.to_string(),
+                host_version,
+            }
+        })
+        .collect();
+    requirements.sort_by_key(|r| match r.status.as_str() {
+        "masked" => 0,
+        "unsatisfied" => 1,
+        "unknown" => 2,
+        _ => 3,
+    });
+
+    let mut overrides: Vec<PlatformOverride> = overrides
+        .into_iter()
+        .map(|(package, override_version)| PlatformOverride {
+            masks: requirements.iter().filter(|r| r.package == package && r.status == "masked").count(),
+            host_version: host.get(&package).cloned(),
+            package,
+            override_version,
+        })
+        .collect();
+    overrides.sort_by(|a, b| a.package.cmp(&b.package));
+
+    let result = PlatformAnalysisResult {
+        summary: PlatformSummary {
+            requirements: requirements.len(),
+            overrides: overrides.len(),
+            masked: requirements.iter().filter(|r| r.status == "masked").count(),
+            unsatisfied: requirements.iter().filter(|r| r.status == "unsatisfied").count(),
+            host_known,
+        },
+        overrides,
+        requirements,
+    };
+
+    Ok(serde_json::to_string_pretty(&result)?)
+}
+
+/// Whether `version` meets `constraint`; a missing version (or `false` override) never does,
+/// and `None` means either side couldn't be parsed
+fn satisfies(constraint: &str, version: Option<&str>) -> Option<bool> {
+    let Some(version) = version else {
+        return Some(false);
+    };
+    let constraint = Constraint::parse(constraint)?;
+    let version = NUMERIC_VERSION_RE.find(version).and_then(|m| Version::parse(m.as_str()))?;
+    Some(constraint.matches(&version))
+}
+
+/// Whether a host description lists the package when it is installed: php and extensions are,
+/// while lib-* and composer-* versions are never detected
+fn reported_by_host(package: &str) -> bool {
+    package == "php" || package.starts_with("ext-")
+}
+
+/// php and ext-* versions from the `php` binary on PATH, if there is one
+fn detect_host_platform() -> Option<HashMap<String, String>> {
+    let output = Command::new("php").args(["-r", HOST_PLATFORM_SCRIPT]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout.lines();
+    let mut host = HashMap::from([("php".to_string(), lines.next()?.trim().to_string())]);
+    for line in lines {
+        if let Some((name, version)) = line.split_once(' ') {
+            host.insert(format!("ext-{}", name), version.trim().to_string());
+        }
+    }
+    Some(host)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extension_missing_from_known_host_is_unsatisfied() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        std::fs::write(
+            dir.join("composer.json"),
+            r#"{"require": {"php": "^8.1", "ext-intl": "*", "lib-icu": ">=60"}}"#,
+        )
+        .unwrap();
+
+        let host = HashMap::from([("php".to_string(), "8.2.7".to_string())]);
+        let result = analyze_platform_requirements(dir, Some(host));
+        let result: PlatformAnalysisResult = serde_json::from_str(&result.unwrap()).unwrap();
+
+        let status = |package: &str| {
+            let requirement = result.requirements.iter().find(|r| r.package == package).unwrap();
+            (requirement.source.as_str(), requirement.status.as_str(), requirement.effective.clone())
+        };
+        assert_eq!(status("ext-intl"), ("host", "unsatisfied", None));
+        assert_eq!(status("php"), ("host", "satisfied", Some("8.2.7".to_string())));
+        assert_eq!(status("lib-icu"), ("unknown", "unknown", None));
+        assert_eq!(result.summary.unsatisfied, 1);
+    }
+}
@@ -0,0 +1,280 @@
+//! Package allow/deny policy for supply-chain governance
+//! A policy file lists packages that must never be installed, the only
+//! packages that may be, and packages every repository has to require.
+//!
+//! ```json
+//! {
+//!   "deny": ["evil-corp/*", { "package": "foo/bar", "severity": "critical", "reason": "Compromised" }],
+//!   "allow": ["symfony/*", "psr/*"],
+//!   "require": ["roave/security-advisories"],
+//!   "failOn": "high"
+//! }
+//! ```
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use super::security::{severity_rank, within_severity_floor};
+use crate::composer::{package_name_matches, read_composer_lock};
+use crate::config::expand_existing_path;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct PackagePolicy {
+    /// Packages that must not be installed
+    pub deny: Vec<PolicyRule>,
+    /// When non-empty, every installed package has to match one of these
+    pub allow: Vec<PolicyRule>,
+    /// Packages that must be installed
+    pub require: Vec<PolicyRule>,
+    /// Lowest severity that fails the check (default: any violation fails)
+    #[serde(rename = "failOn")]
+    pub fail_on: Option<String>,
+}
+
+/// A package name or vendor wildcard, optionally with its own severity and reason
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum PolicyRule {
+    Pattern(String),
+    Detailed {
+        package: String,
+        severity: Option<String>,
+        reason: Option<String>,
+    },
+}
+
+impl PolicyRule {
+    fn pattern(&self) -> &str {
+        match self {
+            Self::Pattern(pattern) | Self::Detailed { package: pattern, .. } => pattern,
+        }
+    }
+
+    fn severity(&self, default: &str) -> String {
+        match self {
+            Self::Detailed { severity: Some(severity), .. } => severity.to_lowercase(),
+            _ => default.to_string(),
+        }
+    }
+
+    fn reason(&self) -> Option<String> {
+        match self {
+            Self::Detailed { reason, .. } => reason.clone(),
+            Self::Pattern(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PolicyCheckResult {
+    pub passed: bool,
+    #[serde(rename = "policyPath")]
+    pub policy_path: String,
+    #[serde(rename = "checkedPackages")]
+    pub checked_packages: usize,
+    pub violations: Vec<PolicyViolation>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PolicyViolation {
+    pub package: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// "denied", "not-allowed" or "missing-required"
+    pub rule: String,
+    /// The policy entry that was violated; absent for allow-list misses
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    pub severity: String,
+    pub message: String,
+}
+
+/// Evaluate every locked package (including dev) against the policy at `policy_path`.
+/// A relative policy path is resolved against the repository. Violations below
+/// `severity_floor` are left out of the report, but still count towards `passed`.
+pub fn check_package_policy<P: AsRef<Path>>(
+    repo_path: P,
+    policy_path: &str,
+    severity_floor: Option<u8>,
+) -> Result<PolicyCheckResult> {
+    let repo_path = repo_path.as_ref();
+    let policy_file = match expand_existing_path(policy_path) {
+        Ok(path) => path,
+        Err(_) if Path::new(policy_path).is_relative() => {
+            let in_repo = repo_path.join(policy_path);
+            if !in_repo.exists() {
+                return Err(anyhow!("Policy file {} not found", policy_path));
+            }
+            in_repo
+        }
+        Err(e) => return Err(e),
+    };
+    let contents = fs::read_to_string(&policy_file)
+        .with_context(|| format!("Failed to read policy {:?}", policy_file))?;
+    let policy: PackagePolicy = serde_json::from_str(&contents)
+        .map_err(|e| anyhow!("Invalid policy file {}: {}", policy_file.display(), e))?;
+
+    let fail_rank = match &policy.fail_on {
+        Some(severity) => valid_severity_rank(severity)?,
+        None => severity_rank("low"),
+    };
+    for rule in policy.deny.iter().chain(&policy.allow).chain(&policy.require) {
+        valid_severity_rank(&rule.severity("low"))?;
+    }
+
+    let lock = read_composer_lock(repo_path)?;
+    let installed: Vec<_> = lock
+        .packages
+        .iter()
+        .chain(lock.packages_dev.iter().flatten())
+        .collect();
+
+    let mut violations = Vec::new();
+    for pkg in &installed {
+        if let Some(rule) = policy.deny.iter().find(|r| package_name_matches(r.pattern(), &pkg.name)) {
+            violations.push(PolicyViolation {
+                package: pkg.name.clone(),
+                version: Some(pkg.version.clone()),
+                rule: "denied".to_string(),
+                pattern: Some(rule.pattern().to_string()),
+                severity: rule.severity("high"),
+                message: rule
+                    .reason()
+                    .unwrap_or_else(|| format!("{} is on the deny list ({})", pkg.name, rule.pattern())),
+            });
+            continue;
+        }
+
+        if !policy.allow.is_empty() && !policy.allow.iter().any(|r| package_name_matches(r.pattern(), &pkg.name)) {
+            violations.push(PolicyViolation {
+                package: pkg.name.clone(),
+                version: Some(pkg.version.clone()),
+                rule: "not-allowed".to_string(),
+                pattern: None,
+                severity: "medium".to_string(),
+                message: format!("{} does not match any allow-list entry", pkg.name),
+            });
+        }
+    }
+
+    for rule in &policy.require {
+        if !installed.iter().any(|pkg| package_name_matches(rule.pattern(), &pkg.name)) {
+            violations.push(PolicyViolation {
+                package: rule.pattern().to_string(),
+                version: None,
+                rule: "missing-required".to_string(),
+                pattern: Some(rule.pattern().to_string()),
+                severity: rule.severity("high"),
+                message: rule
+                    .reason()
+                    .unwrap_or_else(|| format!("{} is required by policy but not installed", rule.pattern())),
+            });
+        }
+    }
+
+    // The policy's own failOn decides the outcome; the floor only trims what is reported
+    let passed = !violations.iter().any(|v| severity_rank(&v.severity) <= fail_rank);
+    violations.retain(|v| within_severity_floor(&v.severity, severity_floor));
+    violations.sort_by(|a, b| {
+        severity_rank(&a.severity)
+            .cmp(&severity_rank(&b.severity))
+            .then_with(|| a.package.cmp(&b.package))
+    });
+
+    Ok(PolicyCheckResult {
+        passed,
+        policy_path: policy_file.display().to_string(),
+        checked_packages: installed.len(),
+        violations,
+    })
+}
+
+fn valid_severity_rank(severity: &str) -> Result<u8> {
+    match severity_rank(&severity.to_lowercase()) {
+        rank if rank < 4 => Ok(rank),
+        _ => Err(anyhow!("Invalid severity in policy: {} (expected critical, high, medium or low)", severity)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_severity_floor_trims_reported_violations() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        fs::write(
+            dir.join("composer.lock"),
+            r#"{"packages": [{"name": "evil-corp/tool", "version": "1.0.0"}, {"name": "other/lib", "version": "2.0.0"}]}"#,
+        )
+        .unwrap();
+        fs::write(dir.join("policy.json"), r#"{"deny": ["evil-corp/*"], "allow": ["symfony/*"], "failOn": "medium"}"#)
+            .unwrap();
+
+        let everything = check_package_policy(dir, "policy.json", None);
+        let floored = check_package_policy(dir, "policy.json", Some(severity_rank("high")));
+
+        let rules = |result: &PolicyCheckResult| result.violations.iter().map(|v| v.rule.clone()).collect::<Vec<_>>();
+        assert_eq!(rules(&everything.unwrap()), ["denied", "not-allowed"]);
+        let floored = floored.unwrap();
+        assert_eq!(rules(&floored), ["denied"]);
+        assert!(!floored.passed);
+    }
+
+    #[test]
+    fn test_deny_allow_and_require_rules() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        let policy = json!({
+            "deny": ["evil-corp/*", {"package": "foo/bar", "severity": "critical", "reason": "Compromised"}],
+            "allow": ["symfony/*", "evil-corp/*", "foo/*"],
+            "require": ["roave/security-advisories"],
+            "failOn": "critical"
+        });
+        fs::write(dir.join("dpb-policy.json"), policy.to_string()).unwrap();
+        let lock = json!({
+            "packages": [
+                {"name": "evil-corp/miner", "version": "1.0.0"},
+                {"name": "foo/bar", "version": "2.0.0"},
+                {"name": "symfony/console", "version": "6.4.0"}
+            ],
+            "packages-dev": [{"name": "acme/tool", "version": "0.1.0"}]
+        });
+        fs::write(dir.join("composer.lock"), lock.to_string()).unwrap();
+
+        let result = check_package_policy(dir, "dpb-policy.json", None).unwrap();
+
+        assert!(!result.passed);
+        assert_eq!(result.checked_packages, 4);
+        let found: Vec<(&str, &str, &str)> = result
+            .violations
+            .iter()
+            .map(|v| (v.package.as_str(), v.rule.as_str(), v.severity.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("foo/bar", "denied", "critical"),
+                ("evil-corp/miner", "denied", "high"),
+                ("roave/security-advisories", "missing-required", "high"),
+                ("acme/tool", "not-allowed", "medium"),
+            ]
+        );
+        assert_eq!(result.violations[0].message, "Compromised");
+    }
+
+    #[test]
+    fn test_invalid_rule_severity_is_rejected() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        fs::write(dir.join("dpb-policy.json"), r#"{"deny": [{"package": "a/b", "severity": "urgent"}]}"#).unwrap();
+        fs::write(dir.join("composer.lock"), r#"{"packages": []}"#).unwrap();
+
+        assert!(check_package_policy(dir, "dpb-policy.json", None).is_err());
+    }
+}
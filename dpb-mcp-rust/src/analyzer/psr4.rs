@@ -3,13 +3,17 @@ use lazy_static::lazy_static;
 use rayon::prelude::*;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use walkdir::WalkDir;
 
-use crate::composer::{calculate_expected_namespace, get_psr4_mappings, read_composer_json};
-use crate::types::{Psr4Mapping, Psr4Violation};
+use crate::composer::{
+    calculate_expected_namespace, classmap_exclusions, get_psr4_mappings, read_composer_json, read_composer_lock,
+    read_to_string_with_retry,
+};
+use crate::config::load_config;
+use crate::types::{ComposerLock, PackageInfo, Psr4Mapping, Psr4Violation};
+use super::verdict::Verdict;
 
 lazy_static! {
     static ref NAMESPACE_RE: Regex = Regex::new(r"namespace\s+([\w\\]+)\s*;").unwrap();
@@ -19,7 +23,48 @@ lazy_static! {
 pub struct Psr4AnalysisResult {
     pub mappings: Vec<Psr4Mapping>,
     pub violations: Vec<Psr4Violation>,
+    /// Root prefixes that overlap a locked package's autoload prefix
+    #[serde(rename = "namespaceCollisions", default)]
+    pub namespace_collisions: Vec<NamespaceCollision>,
+    /// Root prefixes that overlap each other, e.g. `App\` and `App\Sub\`
+    #[serde(rename = "prefixOverlaps", default)]
+    pub prefix_overlaps: Vec<PrefixOverlap>,
     pub stats: Psr4Stats,
+    #[serde(default)]
+    pub summary: Verdict,
+}
+
+/// A root PSR-4 prefix that shares namespace space with a dependency, so
+/// either side's autoloader may resolve classes meant for the other
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamespaceCollision {
+    #[serde(rename = "rootPrefix")]
+    pub root_prefix: String,
+    #[serde(rename = "isDev")]
+    pub is_dev: bool,
+    pub package: String,
+    #[serde(rename = "packagePrefix")]
+    pub package_prefix: String,
+    /// "identical", "rootIsBroader" or "packageIsBroader"
+    pub overlap: String,
+}
+
+/// Two of the project's own PSR-4 prefixes covering the same namespace. Composer tries the
+/// narrower prefix's directories first and falls back to the broader one's subdirectory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrefixOverlap {
+    /// Namespace both prefixes cover (the narrower prefix)
+    pub namespace: String,
+    #[serde(rename = "broaderPrefix")]
+    pub broader_prefix: String,
+    /// "identical" (declared in both autoload and autoload-dev) or "nested"
+    pub overlap: String,
+    /// Directories a class in `namespace` could resolve to, in the order Composer tries them
+    #[serde(rename = "candidateDirectories")]
+    pub candidate_directories: Vec<String>,
+    /// More than one candidate directory exists, so the same class name could live in either
+    pub ambiguous: bool,
+    pub message: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -32,58 +77,57 @@ pub struct Psr4Stats {
     pub valid_files: usize,
     #[serde(rename = "violationCount")]
     pub violation_count: usize,
+    /// Files skipped because they match `exclude-from-classmap`, as Composer skips them
+    #[serde(rename = "excludedFiles", default)]
+    pub excluded_files: usize,
 }
 
 pub fn analyze_psr4_autoloading<P: AsRef<Path> + Sync>(repo_path: P) -> Result<String> {
+    let result = analyze_psr4_autoloading_raw(repo_path)?;
+    Ok(serde_json::to_string_pretty(&result)?)
+}
+
+pub fn analyze_psr4_autoloading_raw<P: AsRef<Path> + Sync>(repo_path: P) -> Result<Psr4AnalysisResult> {
     let composer_json = read_composer_json(&repo_path)?;
     let mappings = get_psr4_mappings(&composer_json);
+    let exclusions = classmap_exclusions(&composer_json);
+    let analysis = load_config(&repo_path)?.analysis;
+    let extensions = analysis.php_extensions;
+    let max_parallel_files = analysis.max_parallel_files;
 
     let violations = Arc::new(Mutex::new(Vec::new()));
     let total_files = Arc::new(Mutex::new(0usize));
     let valid_files = Arc::new(Mutex::new(0usize));
+    let excluded_files = Arc::new(Mutex::new(0usize));
 
-    // Process each mapping in parallel
-    mappings.par_iter().for_each(|mapping| {
+    // Process each mapping in parallel; with a file cap, one mapping at a time so the cap holds overall
+    par_for_each_bounded(&mappings, max_parallel_files.map(|_| 1), |mapping| {
         for relative_path in &mapping.paths {
             let abs_path = repo_path.as_ref().join(relative_path);
 
-            if let Ok(php_files) = find_php_files(&abs_path) {
+            if let Ok(php_files) = find_php_files(&abs_path, &extensions) {
                 // Process files in parallel
-                php_files.par_iter().for_each(|file| {
+                par_for_each_bounded(&php_files, max_parallel_files, |file| {
+                    if is_excluded(&exclusions, repo_path.as_ref(), file) {
+                        let mut count = excluded_files.lock().unwrap();
+                        *count += 1;
+                        return;
+                    }
                     {
                         let mut count = total_files.lock().unwrap();
                         *count += 1;
                     }
 
-                    if let Ok(namespace) = extract_namespace(file) {
-                        if let Ok(rel_to_root) = file.strip_prefix(&abs_path) {
-                            let expected_ns = calculate_expected_namespace(
-                                &mapping.namespace,
-                                &rel_to_root.to_string_lossy(),
-                            );
-
-                            if namespace == expected_ns {
-                                let mut count = valid_files.lock().unwrap();
-                                *count += 1;
-                            } else {
-                                let issue = if namespace.is_empty() {
-                                    "Missing namespace declaration"
-                                } else {
-                                    "Namespace mismatch"
-                                };
-
-                                let mut viols = violations.lock().unwrap();
-                                viols.push(Psr4Violation {
-                                    file: PathBuf::from(relative_path)
-                                        .join(rel_to_root)
-                                        .to_string_lossy()
-                                        .to_string(),
-                                    expected_namespace: expected_ns,
-                                    actual_namespace: Some(namespace),
-                                    issue: issue.to_string(),
-                                });
-                            }
+                    match check_psr4_file(mapping, relative_path, &abs_path, file) {
+                        Some(Ok(())) => {
+                            let mut count = valid_files.lock().unwrap();
+                            *count += 1;
                         }
+                        Some(Err(violation)) => {
+                            let mut viols = violations.lock().unwrap();
+                            viols.push(violation);
+                        }
+                        None => {}
                     }
                 });
             }
@@ -93,46 +137,432 @@ pub fn analyze_psr4_autoloading<P: AsRef<Path> + Sync>(repo_path: P) -> Result<S
     let violations = Arc::try_unwrap(violations).unwrap().into_inner().unwrap();
     let total_files = *total_files.lock().unwrap();
     let valid_files = *valid_files.lock().unwrap();
+    let excluded_files = *excluded_files.lock().unwrap();
     let total_mappings = mappings.len();
     let violation_count = violations.len();
 
+    let namespace_collisions = match read_composer_lock(&repo_path) {
+        Ok(lock) => find_namespace_collisions(&mappings, &lock),
+        Err(_) => Vec::new(),
+    };
+    let prefix_overlaps = find_prefix_overlaps(&mappings, repo_path.as_ref());
+    let summary = psr4_verdict(&violations, &namespace_collisions, &prefix_overlaps, total_files);
+
     let result = Psr4AnalysisResult {
         mappings,
         violations,
+        namespace_collisions,
+        prefix_overlaps,
         stats: Psr4Stats {
             total_mappings,
             total_files,
             valid_files,
             violation_count,
+            excluded_files,
         },
+        summary,
     };
 
-    Ok(serde_json::to_string_pretty(&result)?)
+    Ok(result)
 }
 
-pub fn find_php_files(dir: &Path) -> Result<Vec<PathBuf>> {
-    let files: Vec<PathBuf> = WalkDir::new(dir)
+/// Score by the share of files that autoload correctly; any violation or ambiguity warns
+fn psr4_verdict(
+    violations: &[Psr4Violation],
+    collisions: &[NamespaceCollision],
+    overlaps: &[PrefixOverlap],
+    total_files: usize,
+) -> Verdict {
+    let ambiguous: Vec<&PrefixOverlap> = overlaps.iter().filter(|o| o.ambiguous).collect();
+    let violation_share = if total_files == 0 { 0.0 } else { violations.len() as f64 / total_files as f64 };
+    let penalty = violation_share * 100.0 + (ambiguous.len() * 10 + collisions.len() * 5) as f64;
+    let status = if violations.is_empty() && ambiguous.is_empty() && collisions.is_empty() { "ok" } else { "warn" };
+
+    let mut sorted: Vec<&Psr4Violation> = violations.iter().collect();
+    sorted.sort_by(|a, b| a.file.cmp(&b.file));
+    let issues = ambiguous
         .into_iter()
-        .filter_entry(|e| {
-            let name = e.file_name().to_string_lossy();
-            !name.starts_with('.') && name != "vendor" && name != "node_modules"
+        .map(|o| o.message.clone())
+        .chain(collisions.iter().map(|c| {
+            format!("{} overlaps {}'s autoload prefix {}", c.root_prefix, c.package, c.package_prefix)
+        }))
+        .chain(sorted.into_iter().map(|v| format!("{}: {}", v.file, v.issue)));
+
+    Verdict::new(100.0 - penalty, status, issues)
+}
+
+/// Whether Composer's `exclude-from-classmap` rules leave `file` out of autoloading
+fn is_excluded(exclusions: &[Regex], repo_path: &Path, file: &Path) -> bool {
+    let Ok(relative) = file.strip_prefix(repo_path) else {
+        return false;
+    };
+    let relative = relative.to_string_lossy().replace('\\', "/");
+    let relative = relative.trim_start_matches("./");
+    exclusions.iter().any(|re| re.is_match(relative))
+}
+
+/// Check one file found under a mapping path. `None` when the file can't be read.
+fn check_psr4_file(
+    mapping: &Psr4Mapping,
+    relative_path: &str,
+    abs_path: &Path,
+    file: &Path,
+) -> Option<std::result::Result<(), Psr4Violation>> {
+    let declaration = extract_namespace(file).ok()?;
+    let namespace = declaration.namespace;
+    let rel_to_root = file.strip_prefix(abs_path).ok()?;
+    let expected_ns = calculate_expected_namespace(&mapping.namespace, &rel_to_root.to_string_lossy());
+
+    if namespace == expected_ns {
+        return Some(Ok(()));
+    }
+    let issue = if namespace.is_empty() {
+        "Missing namespace declaration"
+    } else {
+        "Namespace mismatch"
+    };
+    Some(Err(Psr4Violation {
+        file: PathBuf::from(relative_path)
+            .join(rel_to_root)
+            .to_string_lossy()
+            .to_string(),
+        expected_namespace: expected_ns,
+        actual_namespace: Some(namespace),
+        issue: issue.to_string(),
+        line: declaration.line,
+        column: declaration.column,
+    }))
+}
+
+/// Re-check only `changed_files` (repository-relative, with whether each existed at the
+/// time of the previous analysis) and merge the outcome into `result`
+pub fn update_psr4_autoloading<P: AsRef<Path>>(
+    result: &mut Psr4AnalysisResult,
+    repo_path: P,
+    changed_files: &[(String, bool)],
+) {
+    let repo_path = repo_path.as_ref();
+    let same_file = |a: &str, b: &str| a.trim_start_matches("./") == b.trim_start_matches("./");
+    // composer.json changes trigger a full rescan, so the exclusions are the same as last time
+    let exclusions = read_composer_json(repo_path)
+        .map(|c| classmap_exclusions(&c))
+        .unwrap_or_default();
+
+    for (rel, existed) in changed_files {
+        let file = repo_path.join(rel);
+        if is_excluded(&exclusions, repo_path, &file) {
+            // Counted once per mapping path containing the file, like the full scan
+            let paths = result
+                .mappings
+                .iter()
+                .flat_map(|m| &m.paths)
+                .filter(|p| file.starts_with(repo_path.join(p)))
+                .count();
+            if *existed {
+                result.stats.excluded_files = result.stats.excluded_files.saturating_sub(paths);
+            }
+            if file.is_file() {
+                result.stats.excluded_files += paths;
+            }
+            continue;
+        }
+        let before = result.violations.len();
+        result.violations.retain(|v| !same_file(&v.file, rel));
+        let old_violations = before - result.violations.len();
+
+        for mapping in &result.mappings {
+            for relative_path in &mapping.paths {
+                let abs_path = repo_path.join(relative_path);
+                if !file.starts_with(&abs_path) {
+                    continue;
+                }
+
+                // Take back what the file contributed last time...
+                if *existed {
+                    result.stats.total_files = result.stats.total_files.saturating_sub(1);
+                    if old_violations == 0 {
+                        result.stats.valid_files = result.stats.valid_files.saturating_sub(1);
+                    }
+                }
+                // ...and count it afresh
+                if file.is_file() {
+                    result.stats.total_files += 1;
+                    match check_psr4_file(mapping, relative_path, &abs_path, &file) {
+                        Some(Ok(())) => result.stats.valid_files += 1,
+                        Some(Err(violation)) => result.violations.push(violation),
+                        None => {}
+                    }
+                }
+            }
+        }
+    }
+
+    result.violations.sort_by(|a, b| a.file.cmp(&b.file));
+    result.stats.violation_count = result.violations.len();
+}
+
+/// Namespace prefixes a locked package autoloads (PSR-4 and PSR-0)
+pub fn package_prefixes(package: &PackageInfo) -> Vec<String> {
+    let Some(autoload) = &package.autoload else {
+        return Vec::new();
+    };
+    autoload
+        .psr4
+        .iter()
+        .chain(autoload.psr0.iter())
+        .flat_map(|map| map.keys().cloned())
+        .collect()
+}
+
+/// Compare prefixes segment-wise, so `App\` overlaps `App\Http\` but not `Application\`
+fn normalize_prefix(prefix: &str) -> String {
+    format!("{}\\", prefix.trim_matches('\\'))
+}
+
+/// Cross-reference the root PSR-4 prefixes against every locked package's autoload prefixes.
+/// Empty (fallback) prefixes are skipped since they overlap everything by design.
+fn find_namespace_collisions(mappings: &[Psr4Mapping], lock: &ComposerLock) -> Vec<NamespaceCollision> {
+    let mut collisions = Vec::new();
+
+    for package in lock.packages.iter().chain(lock.packages_dev.iter().flatten()) {
+        for package_prefix in package_prefixes(package) {
+            let theirs = normalize_prefix(&package_prefix);
+            if theirs == "\\" {
+                continue;
+            }
+            for mapping in mappings {
+                let ours = normalize_prefix(&mapping.namespace);
+                if ours == "\\" {
+                    continue;
+                }
+                let overlap = if ours == theirs {
+                    "identical"
+                } else if theirs.starts_with(&ours) {
+                    "rootIsBroader"
+                } else if ours.starts_with(&theirs) {
+                    "packageIsBroader"
+                } else {
+                    continue;
+                };
+                collisions.push(NamespaceCollision {
+                    root_prefix: mapping.namespace.clone(),
+                    is_dev: mapping.is_dev,
+                    package: package.name.clone(),
+                    package_prefix: package_prefix.clone(),
+                    overlap: overlap.to_string(),
+                });
+            }
+        }
+    }
+
+    collisions.sort_by(|a, b| a.root_prefix.cmp(&b.root_prefix).then_with(|| a.package.cmp(&b.package)));
+    collisions
+}
+
+/// Compare the root PSR-4 prefixes pairwise. Empty (fallback) prefixes are skipped as above.
+fn find_prefix_overlaps(mappings: &[Psr4Mapping], repo_path: &Path) -> Vec<PrefixOverlap> {
+    let mut overlaps = Vec::new();
+
+    for (i, a) in mappings.iter().enumerate() {
+        for b in &mappings[i + 1..] {
+            let (ours, theirs) = (normalize_prefix(&a.namespace), normalize_prefix(&b.namespace));
+            if ours == "\\" || theirs == "\\" {
+                continue;
+            }
+            let (narrower, broader, overlap) = if ours == theirs {
+                (a, b, "identical")
+            } else if ours.starts_with(&theirs) {
+                (a, b, "nested")
+            } else if theirs.starts_with(&ours) {
+                (b, a, "nested")
+            } else {
+                continue;
+            };
+
+            // Where the broader prefix would look for the narrower namespace
+            let narrower_prefix = normalize_prefix(&narrower.namespace);
+            let remainder = narrower_prefix[normalize_prefix(&broader.namespace).len()..].replace('\\', "/");
+            let mut candidates: Vec<String> = narrower.paths.iter().map(|p| directory(p, "")).collect();
+            for path in &broader.paths {
+                let candidate = directory(path, &remainder);
+                if !candidates.contains(&candidate) {
+                    candidates.push(candidate);
+                }
+            }
+            let existing: Vec<&String> = candidates.iter().filter(|c| repo_path.join(c).is_dir()).collect();
+            let ambiguous = existing.len() > 1;
+
+            let message = if ambiguous {
+                format!(
+                    "{} classes may live in any of {}; Composer loads the first match, so a duplicate name is silently shadowed",
+                    narrower.namespace,
+                    existing.iter().map(|c| c.as_str()).collect::<Vec<_>>().join(", ")
+                )
+            } else if candidates.len() == 1 {
+                format!(
+                    "{} maps to {}, the directory {} already resolves it to; the narrower mapping is redundant",
+                    narrower.namespace, candidates[0], broader.namespace
+                )
+            } else if overlap == "identical" {
+                format!(
+                    "{} is declared in both autoload and autoload-dev, but at most one of its directories exists",
+                    narrower.namespace
+                )
+            } else {
+                format!(
+                    "{} is also covered by {}, but at most one candidate directory exists",
+                    narrower.namespace, broader.namespace
+                )
+            };
+            overlaps.push(PrefixOverlap {
+                namespace: narrower.namespace.clone(),
+                broader_prefix: broader.namespace.clone(),
+                overlap: overlap.to_string(),
+                candidate_directories: candidates,
+                ambiguous,
+                message,
+            });
+        }
+    }
+
+    overlaps.sort_by(|a, b| a.namespace.cmp(&b.namespace).then_with(|| a.broader_prefix.cmp(&b.broader_prefix)));
+    overlaps
+}
+
+/// `base` joined with a `/`-separated `subdir`, with a trailing slash as Composer writes paths
+fn directory(base: &str, subdir: &str) -> String {
+    let base = base.trim_start_matches("./").trim_end_matches('/');
+    let joined = match (base, subdir.trim_matches('/')) {
+        ("" | ".", rest) => rest.to_string(),
+        (base, "") => base.to_string(),
+        (base, rest) => format!("{}/{}", base, rest),
+    };
+    if joined.is_empty() { "./".to_string() } else { format!("{}/", joined) }
+}
+
+/// Hidden entries and dependency directories are never scanned
+fn is_skipped_name(name: &str) -> bool {
+    name.starts_with('.') || name == "vendor" || name == "node_modules"
+}
+
+/// Whether a repository-relative path is a file `find_php_files` would pick up
+pub fn is_scanned_php_file(relative: &Path, extensions: &[String]) -> bool {
+    has_php_extension(relative, extensions)
+        && !relative.components().any(|part| match part {
+            Component::Normal(name) => is_skipped_name(&name.to_string_lossy()),
+            _ => false,
         })
+}
+
+/// Extensions are matched case-insensitively, with or without a leading dot
+fn has_php_extension(path: &Path, extensions: &[String]) -> bool {
+    path.extension()
+        .map(|ext| ext.to_string_lossy())
+        .is_some_and(|ext| extensions.iter().any(|e| e.trim_start_matches('.').eq_ignore_ascii_case(&ext)))
+}
+
+/// Find PHP source files under `dir`, skipping hidden directories, vendor and node_modules
+pub fn find_php_files(dir: &Path, extensions: &[String]) -> Result<Vec<PathBuf>> {
+    let files: Vec<PathBuf> = WalkDir::new(dir)
+        .into_iter()
+        .filter_entry(|e| !is_skipped_name(&e.file_name().to_string_lossy()))
         .filter_map(|e| e.ok())
-        .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("php"))
+        .filter(|e| has_php_extension(e.path(), extensions))
         .map(|e| e.path().to_path_buf())
         .collect();
 
     Ok(files)
 }
 
-fn extract_namespace(file_path: &Path) -> Result<String> {
-    let contents = fs::read_to_string(file_path)?;
+/// Run `f` over `items` in parallel, in chunks of at most `max_in_flight` when set so
+/// only that many are being processed at once
+pub fn par_for_each_bounded<T, F>(items: &[T], max_in_flight: Option<usize>, f: F)
+where
+    T: Sync,
+    F: Fn(&T) + Sync + Send,
+{
+    match max_in_flight {
+        Some(max) => items.chunks(max.max(1)).for_each(|chunk| chunk.par_iter().for_each(&f)),
+        None => items.par_iter().for_each(f),
+    }
+}
+
+/// Namespace declared in a file and where the declaration sits
+struct NamespaceDeclaration {
+    /// Empty when the file declares no namespace
+    namespace: String,
+    line: usize,
+    column: usize,
+}
+
+fn extract_namespace(file_path: &Path) -> Result<NamespaceDeclaration> {
+    let contents = read_to_string_with_retry(file_path)?;
 
-    for line in contents.lines() {
+    for (index, line) in contents.lines().enumerate() {
         if let Some(captures) = NAMESPACE_RE.captures(line) {
-            return Ok(captures[1].to_string());
+            let start = captures.get(0).map(|m| m.start()).unwrap_or(0);
+            return Ok(NamespaceDeclaration {
+                namespace: captures[1].to_string(),
+                line: index + 1,
+                column: line[..start].chars().count() + 1,
+            });
         }
     }
 
-    Ok(String::new())
+    Ok(NamespaceDeclaration {
+        namespace: String::new(),
+        line: 1,
+        column: 1,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exclude_from_classmap_skips_files() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        let composer = serde_json::json!({"autoload": {
+            "psr-4": {"App\\": "src/"},
+            "exclude-from-classmap": ["src/Legacy/", "/src/**/Fixtures/"]
+        }});
+        std::fs::write(dir.join("composer.json"), composer.to_string()).unwrap();
+        for (file, namespace) in [
+            ("src/Kernel.php", "App"),
+            ("src/Legacy/Old.php", "Legacy"),
+            ("src/Http/Tests/Fixtures/Stub.php", "Stubs"),
+            ("src/LegacyBridge.php", "Wrong"),
+        ] {
+            let path = dir.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, format!("<?php\nnamespace {};\n", namespace)).unwrap();
+        }
+
+        let result = analyze_psr4_autoloading_raw(dir).unwrap();
+
+        assert_eq!(result.stats.excluded_files, 2);
+        let files: Vec<&str> = result.violations.iter().map(|v| v.file.as_str()).collect();
+        assert_eq!(files, ["src/LegacyBridge.php"]);
+    }
+
+    #[test]
+    fn test_redundant_mapping_names_the_shared_directory() {
+        let mapping = |namespace: &str, path: &str| Psr4Mapping {
+            namespace: namespace.to_string(),
+            paths: vec![path.to_string()],
+            mapping_type: "psr-4".to_string(),
+            is_dev: false,
+        };
+        let mappings = [mapping("App\\", "src/"), mapping("App\\Http\\", "src/Http")];
+
+        let overlaps = find_prefix_overlaps(&mappings, Path::new("/nonexistent"));
+        assert_eq!(overlaps.len(), 1);
+        assert_eq!(overlaps[0].candidate_directories, vec!["src/Http/".to_string()]);
+        assert_eq!(
+            overlaps[0].message,
+            "App\\Http\\ maps to src/Http/, the directory App\\ already resolves it to; the narrower mapping is redundant"
+        );
+    }
 }
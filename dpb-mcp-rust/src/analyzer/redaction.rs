@@ -0,0 +1,233 @@
+//! Redaction of private package names
+//! Replaces packages matching `redaction.patterns` in `.dpb.json` with stable hashed
+//! placeholders, so generated reports can be shared without leaking internal names.
+//! The hash is keyed: an unkeyed digest of a guessable name could be reversed by hashing
+//! candidate names.
+
+use anyhow::{anyhow, Context, Result};
+use hmac::{Hmac, Mac};
+use regex::Regex;
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::sanitize_for_mermaid;
+use crate::composer::{package_name_matches, read_composer_json, read_composer_lock};
+use crate::config::load_config;
+
+pub struct Redactor {
+    /// Alternation of every redacted name (and its Mermaid node id), longest first
+    names: Regex,
+    placeholders: HashMap<String, String>,
+}
+
+impl Redactor {
+    /// Collect the repository's package names matching the configured patterns: the root
+    /// package, its requirements, locked packages and their requirements
+    pub fn for_repo<P: AsRef<Path>>(repo_path: P) -> Result<Self> {
+        Self::build(repo_path, true)
+    }
+
+    /// Like [`Redactor::for_repo`], but never creates the installation key: an existing one is
+    /// reused, otherwise a throwaway key stands in. For previews that must not write anything.
+    pub fn for_preview<P: AsRef<Path>>(repo_path: P) -> Result<Self> {
+        Self::build(repo_path, false)
+    }
+
+    fn build<P: AsRef<Path>>(repo_path: P, create_key: bool) -> Result<Self> {
+        let config = load_config(&repo_path)?.redaction;
+        let patterns = config.patterns;
+        if patterns.is_empty() {
+            return Err(anyhow!("redaction.patterns required in .dpb.json to redact reports"));
+        }
+
+        let mut names: Vec<String> = Vec::new();
+        if let Ok(composer) = read_composer_json(&repo_path) {
+            names.extend(composer.name.clone());
+            names.extend(composer.require.iter().chain(&composer.require_dev).flat_map(|r| r.keys().cloned()));
+        }
+        if let Ok(lock) = read_composer_lock(&repo_path) {
+            for pkg in lock.packages.iter().chain(lock.packages_dev.iter().flatten()) {
+                names.push(pkg.name.clone());
+                names.extend(pkg.require.iter().chain(&pkg.require_dev).flat_map(|r| r.keys().cloned()));
+            }
+        }
+        names.retain(|name| patterns.iter().any(|p| package_name_matches(p, name)));
+        names.sort();
+        names.dedup();
+
+        let key = match config.secret {
+            Some(secret) => secret.into_bytes(),
+            None if create_key => install_key(&install_key_path()?)?,
+            None => match install_key_path().ok().map(|path| read_key(&path)).transpose()?.flatten() {
+                Some(key) => key,
+                None => rand::random::<[u8; 32]>().to_vec(),
+            },
+        };
+        let mut placeholders = HashMap::new();
+        for name in &names {
+            let placeholder = placeholder_for(&key, name);
+            placeholders.insert(sanitize_for_mermaid(name), sanitize_for_mermaid(&placeholder));
+            placeholders.insert(name.clone(), placeholder);
+        }
+
+        let mut keys: Vec<&String> = placeholders.keys().collect();
+        keys.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        let alternation: Vec<String> = keys.iter().map(|k| regex::escape(k)).collect();
+        // Nothing to redact: a pattern no name can contain keeps `apply` a no-op
+        let pattern = if alternation.is_empty() { "[^\\s\\S]".to_string() } else { format!("(?i){}", alternation.join("|")) };
+
+        Ok(Self {
+            names: Regex::new(&pattern)?,
+            placeholders,
+        })
+    }
+
+    /// Replace redacted names in `text`; a match inside a longer package name is left alone
+    pub fn apply(&self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut last = 0;
+        for found in self.names.find_iter(text) {
+            let before = text[..found.start()].chars().next_back();
+            if before.is_some_and(is_name_char) || continues_name(&text[found.end()..]) {
+                continue;
+            }
+            let key = found.as_str().to_lowercase();
+            let Some(placeholder) = self.placeholders.get(&key) else {
+                continue;
+            };
+            out.push_str(&text[last..found.start()]);
+            out.push_str(placeholder);
+            last = found.end();
+        }
+        out.push_str(&text[last..]);
+        out
+    }
+}
+
+/// `private/<hash>`: keeps the vendor/name shape, and the same name always maps to the same
+/// placeholder under one key
+fn placeholder_for(key: &[u8], name: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(name.as_bytes());
+    let digest = hex::encode(mac.finalize().into_bytes());
+    format!("private/{}", &digest[..10])
+}
+
+fn install_key_path() -> Result<PathBuf> {
+    let home = std::env::var("HOME").map_err(|_| anyhow!("HOME is not set; set redaction.secret in .dpb.json"))?;
+    Ok(Path::new(&home).join(".dpb").join("redaction-key"))
+}
+
+/// This installation's random key at `path`, created on first use. An unreadable key is an
+/// error rather than replaced, since a new key would stop published placeholders from matching.
+fn install_key(path: &Path) -> Result<Vec<u8>> {
+    if let Some(key) = read_key(path)? {
+        return Ok(key);
+    }
+
+    let key: [u8; 32] = rand::random();
+    let dir = path.parent().ok_or_else(|| anyhow!("Invalid redaction key path {:?}", path))?;
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {:?}", dir))?;
+    // Written aside and linked into place, so a concurrent first call never reads a partial
+    // key, and whichever call links first wins
+    let staged = dir.join(format!(".redaction-key.{}.{}", std::process::id(), hex::encode(&key[..4])));
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let written = options
+        .open(&staged)
+        .and_then(|mut file| std::io::Write::write_all(&mut file, hex::encode(key).as_bytes()))
+        .and_then(|_| fs::hard_link(&staged, path));
+    let _ = fs::remove_file(&staged);
+    match written {
+        Ok(()) => Ok(key.to_vec()),
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+            read_key(path)?.ok_or_else(|| anyhow!("Redaction key {:?} disappeared while being created", path))
+        }
+        Err(e) => Err(e).with_context(|| format!("Failed to write redaction key {:?}", path)),
+    }
+}
+
+/// The key stored at `path`, or None when there is no key yet
+fn read_key(path: &Path) -> Result<Option<Vec<u8>>> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read redaction key {:?}", path)),
+    };
+    match hex::decode(contents.trim()) {
+        Ok(key) if !key.is_empty() => Ok(Some(key)),
+        _ => Err(anyhow!(
+            "Redaction key {:?} is not a hex key; restore it, or delete it to start a new one",
+            path
+        )),
+    }
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '/')
+}
+
+/// Whether `rest` carries on the name just matched; a trailing `.` (as in "vendor/name...") doesn't
+fn continues_name(rest: &str) -> bool {
+    let mut chars = rest.chars();
+    match chars.next() {
+        Some('.') => chars.next().is_some_and(|c| c.is_ascii_alphanumeric()),
+        Some(c) => is_name_char(c),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_replaces_whole_names_only() {
+        let placeholders = HashMap::from([
+            ("acme/lib".to_string(), "private/1".to_string()),
+            ("acme_lib".to_string(), "private_1".to_string()),
+        ]);
+        let redactor = Redactor {
+            names: Regex::new("(?i)acme/lib|acme_lib").unwrap(),
+            placeholders,
+        };
+
+        assert_eq!(
+            redactor.apply("Root --> acme_lib[\"acme/lib...\n1.0\"] acme/lib-extra acme/library Acme/Lib."),
+            "Root --> private_1[\"private/1...\n1.0\"] acme/lib-extra acme/library private/1."
+        );
+    }
+
+    #[test]
+    fn test_placeholders_depend_on_the_key() {
+        let placeholder = placeholder_for(b"first", "acme/lib");
+
+        assert_eq!(placeholder, placeholder_for(b"first", "acme/lib"));
+        assert_ne!(placeholder, placeholder_for(b"second", "acme/lib"));
+        assert_ne!(placeholder, placeholder_for(b"first", "acme/other"));
+        assert_eq!(placeholder.len(), "private/".len() + 10);
+    }
+
+    #[test]
+    fn test_install_key_is_created_once_and_never_replaced() {
+        let temp = crate::test_support::temp_dir();
+        let path = temp.path().join(".dpb").join("redaction-key");
+
+        let keys: Vec<Vec<u8>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..8).map(|_| scope.spawn(|| install_key(&path).unwrap())).collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        assert!(keys.iter().all(|key| *key == keys[0]));
+        assert_eq!(keys[0].len(), 32);
+        assert_eq!(install_key(&path).unwrap(), keys[0]);
+        assert_eq!(fs::read_dir(path.parent().unwrap()).unwrap().count(), 1);
+
+        fs::write(&path, "not hex").unwrap();
+        assert!(install_key(&path).unwrap_err().to_string().contains("is not a hex key"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "not hex");
+    }
+}
@@ -0,0 +1,172 @@
+//! Reproducibility check
+//! Verifies every locked package is pinned to a concrete commit, so `composer install`
+//! fetches exactly the same bytes on every run
+
+use anyhow::Result;
+use serde::Serialize;
+use std::path::Path;
+
+use crate::composer::read_composer_lock;
+use crate::types::PackageInfo;
+
+#[derive(Debug, Serialize)]
+pub struct ReproducibilityResult {
+    pub reproducible: bool,
+    #[serde(rename = "checkedPackages")]
+    pub checked_packages: usize,
+    #[serde(rename = "pinnedPackages")]
+    pub pinned_packages: usize,
+    pub issues: Vec<ReproducibilityIssue>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReproducibilityIssue {
+    pub package: String,
+    pub version: String,
+    #[serde(rename = "isDev")]
+    pub is_dev: bool,
+    /// "missing-reference", "branch-reference" or "path-repository"
+    pub kind: String,
+    /// The offending reference, when there is one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+    pub message: String,
+}
+
+/// Report locked packages whose `source`/`dist` reference isn't a pinned commit
+pub fn check_reproducibility_raw<P: AsRef<Path>>(repo_path: P) -> Result<ReproducibilityResult> {
+    let lock = read_composer_lock(repo_path)?;
+
+    let packages: Vec<(&PackageInfo, bool)> = lock
+        .packages
+        .iter()
+        .map(|p| (p, false))
+        .chain(lock.packages_dev.iter().flatten().map(|p| (p, true)))
+        .collect();
+
+    let mut issues: Vec<ReproducibilityIssue> = packages
+        .iter()
+        .filter_map(|(pkg, is_dev)| check_package(pkg, *is_dev))
+        .collect();
+    issues.sort_by(|a, b| a.package.cmp(&b.package));
+
+    Ok(ReproducibilityResult {
+        reproducible: issues.is_empty(),
+        checked_packages: packages.len(),
+        pinned_packages: packages.len() - issues.len(),
+        issues,
+    })
+}
+
+fn check_package(pkg: &PackageInfo, is_dev: bool) -> Option<ReproducibilityIssue> {
+    let issue = |kind: &str, reference: Option<&str>, message: String| {
+        Some(ReproducibilityIssue {
+            package: pkg.name.clone(),
+            version: pkg.version.clone(),
+            is_dev,
+            kind: kind.to_string(),
+            reference: reference.map(|r| r.to_string()),
+            message,
+        })
+    };
+
+    // Path repositories install whatever is on disk at the time, whatever the reference says
+    if pkg.dist.as_ref().is_some_and(|d| d.dist_type == "path") {
+        return issue(
+            "path-repository",
+            None,
+            format!("{} is installed from a local path, so its contents aren't fixed by the lock", pkg.name),
+        );
+    }
+
+    let references: Vec<&str> = pkg
+        .source
+        .iter()
+        .map(|s| s.reference.as_str())
+        .chain(pkg.dist.iter().map(|d| d.reference.as_str()))
+        .map(str::trim)
+        .filter(|r| !r.is_empty())
+        .collect();
+
+    if references.is_empty() {
+        return issue(
+            "missing-reference",
+            None,
+            format!("{} has no source or dist reference in composer.lock", pkg.name),
+        );
+    }
+
+    // A non-commit reference is a branch or tag name, which can move after locking
+    match references.iter().find(|r| !is_commit(r)) {
+        Some(reference) => issue(
+            "branch-reference",
+            Some(reference),
+            format!(
+                "{} is locked to \"{}\" instead of a commit, so a later install may fetch different code",
+                pkg.name, reference
+            ),
+        ),
+        None => None,
+    }
+}
+
+/// A full SHA-1 or SHA-256 commit hash (git, Mercurial)
+fn is_commit(reference: &str) -> bool {
+    matches!(reference.len(), 40 | 64) && reference.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_reproducibility_issue_kinds() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        let sha = "a".repeat(40);
+        let lock = json!({
+            "packages": [
+                {"name": "acme/pinned", "version": "1.0.0",
+                 "source": {"type": "git", "url": "https://example.com/pinned.git", "reference": sha},
+                 "dist": {"type": "zip", "url": "https://example.com/pinned.zip", "reference": sha}},
+                {"name": "acme/branch", "version": "dev-main",
+                 "source": {"type": "git", "url": "https://example.com/branch.git", "reference": "main"}},
+                {"name": "acme/local", "version": "dev-main",
+                 "dist": {"type": "path", "url": "../local", "reference": sha}}
+            ],
+            "packages-dev": [{"name": "acme/bare", "version": "1.0.0",
+                "dist": {"type": "zip", "url": "https://example.com/bare.zip", "reference": " "}}]
+        });
+        std::fs::write(dir.join("composer.lock"), lock.to_string()).unwrap();
+
+        let result = check_reproducibility_raw(dir).unwrap();
+
+        assert!(!result.reproducible);
+        assert_eq!(result.checked_packages, 4);
+        assert_eq!(result.pinned_packages, 1);
+        let found: Vec<(&str, &str, bool)> = result
+            .issues
+            .iter()
+            .map(|i| (i.package.as_str(), i.kind.as_str(), i.is_dev))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("acme/bare", "missing-reference", true),
+                ("acme/branch", "branch-reference", false),
+                ("acme/local", "path-repository", false),
+            ]
+        );
+        assert_eq!(result.issues[1].reference.as_deref(), Some("main"));
+    }
+
+    #[test]
+    fn test_commit_references() {
+        assert!(is_commit(&"0123456789abcdef".repeat(4)[..40]));
+        assert!(is_commit(&"F".repeat(64)));
+        assert!(!is_commit("v1.2.3"));
+        assert!(!is_commit(&"g".repeat(40)));
+        assert!(!is_commit(&"a".repeat(39)));
+    }
+}
@@ -1,11 +1,16 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::collections::HashMap;
+use std::fs;
 use std::path::Path;
 
+use crate::composer::constraint::{Constraint, Version};
 use crate::composer::read_composer_lock;
-use crate::types::{LicenseDistribution, SecurityVulnerability};
+use crate::types::{ComposerLock, LicenseDistribution, PackageInfo, SecurityVulnerability};
+use super::compatibility::is_recognized_license;
+use super::verdict::Verdict;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SecurityAuditResult {
@@ -21,68 +26,379 @@ pub struct SecuritySummary {
     pub high: usize,
     pub medium: usize,
     pub low: usize,
+    #[serde(flatten)]
+    pub verdict: Verdict,
 }
 
-pub fn audit_security<P: AsRef<Path>>(repo_path: P) -> Result<String> {
+/// Severities assigned to the lock-metadata heuristics. A `null` (or "off")
+/// severity disables that heuristic entirely.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SecurityConfig {
+    /// Packages locked at a non-stable dev build
+    #[serde(rename = "devVersion")]
+    pub dev_version: Option<String>,
+    /// Dev versions pinned to an exact commit SHA
+    #[serde(rename = "commitPinned")]
+    pub commit_pinned: Option<String>,
+    /// Packages still below 1.0
+    #[serde(rename = "preRelease")]
+    pub pre_release: Option<String>,
+    /// Packages whose release is older than `staleYears`
+    pub stale: Option<String>,
+    #[serde(rename = "staleYears")]
+    pub stale_years: i64,
+    /// Installed versions that another installed package declares a `conflict` with
+    pub conflict: Option<String>,
+}
+
+impl Default for SecurityConfig {
+    fn default() -> Self {
+        Self {
+            dev_version: Some("medium".to_string()),
+            commit_pinned: Some("medium".to_string()),
+            pre_release: Some("low".to_string()),
+            stale: Some("medium".to_string()),
+            stale_years: 5,
+            conflict: Some("high".to_string()),
+        }
+    }
+}
+
+/// Resolve a configured heuristic severity, treating "off" as disabled
+fn enabled_severity(severity: &Option<String>) -> Option<String> {
+    severity
+        .as_deref()
+        .map(|s| s.to_lowercase())
+        .filter(|s| s != "off" && s != "none")
+}
+
+/// Rank severities so the most urgent sort first (critical = 0)
+pub fn severity_rank(severity: &str) -> u8 {
+    match severity {
+        "critical" => 0,
+        "high" => 1,
+        "medium" => 2,
+        "low" => 3,
+        _ => 4,
+    }
+}
+
+/// Whether a finding is at or above the severity floor (a rank from [`severity_rank`]);
+/// no floor keeps everything
+pub fn within_severity_floor(severity: &str, floor: Option<u8>) -> bool {
+    floor.is_none_or(|floor| severity_rank(severity) <= floor)
+}
+
+/// Audit dependencies. Findings are ordered critical→low, then by package name;
+/// `max_per_severity` caps how many findings of each severity are returned,
+/// while the summary still counts everything found. Findings below `severity_floor`
+/// are left out of the list and counts, though not the verdict. `include_dev: false`
+/// limits the audit to production packages.
+pub fn audit_security<P: AsRef<Path>>(
+    repo_path: P,
+    max_per_severity: Option<usize>,
+    severity_floor: Option<u8>,
+    config: &SecurityConfig,
+    include_dev: bool,
+) -> Result<String> {
+    let result = audit_security_raw(repo_path, max_per_severity, severity_floor, config, include_dev)?;
+    Ok(serde_json::to_string_pretty(&result)?)
+}
+
+/// Audit dependencies and return the raw struct
+pub fn audit_security_raw<P: AsRef<Path>>(
+    repo_path: P,
+    max_per_severity: Option<usize>,
+    severity_floor: Option<u8>,
+    config: &SecurityConfig,
+    include_dev: bool,
+) -> Result<SecurityAuditResult> {
     let lock = read_composer_lock(&repo_path)?;
+    Ok(audit_lock(&lock, max_per_severity, severity_floor, config, include_dev))
+}
 
-    let mut vulnerabilities = Vec::new();
+/// Audit an already-parsed composer.lock
+pub fn audit_lock(
+    lock: &ComposerLock,
+    max_per_severity: Option<usize>,
+    severity_floor: Option<u8>,
+    config: &SecurityConfig,
+    include_dev: bool,
+) -> SecurityAuditResult {
+    build_audit_result(heuristic_findings(lock, config, include_dev), max_per_severity, severity_floor)
+}
 
-    let mut all_packages = lock.packages.clone();
-    if let Some(dev_packages) = &lock.packages_dev {
-        all_packages.extend(dev_packages.clone());
+/// Merge the JSON output of `composer audit --format=json` with the heuristic
+/// findings so real advisories and heuristics appear in one report
+pub fn import_composer_audit<P: AsRef<Path>>(
+    repo_path: P,
+    audit_json_path: P,
+    severity_floor: Option<u8>,
+    config: &SecurityConfig,
+) -> Result<String> {
+    let lock = read_composer_lock(&repo_path)?;
+    let contents = fs::read_to_string(audit_json_path.as_ref()).with_context(|| {
+        format!("Failed to read composer audit output at {:?}", audit_json_path.as_ref())
+    })?;
+    let audit: Value = serde_json::from_str(&contents)
+        .with_context(|| "Failed to parse composer audit output (expected --format=json)")?;
+
+    let installed: HashMap<&str, &str> = lock
+        .packages
+        .iter()
+        .chain(lock.packages_dev.iter().flatten())
+        .map(|p| (p.name.as_str(), p.version.as_str()))
+        .collect();
+
+    let mut vulnerabilities = heuristic_findings(&lock, config, true);
+
+    // composer emits `[]` instead of `{}` when a section is empty
+    if let Some(advisories) = audit.get("advisories").and_then(|v| v.as_object()) {
+        for (package, entries) in advisories {
+            // Advisories are a list, or an index-keyed object in older composer versions
+            let entries: Vec<&Value> = match entries {
+                Value::Array(list) => list.iter().collect(),
+                Value::Object(map) => map.values().collect(),
+                _ => continue,
+            };
+            for advisory in entries {
+                let field = |key: &str| advisory.get(key).and_then(|v| v.as_str());
+                let affected = field("affectedVersions").unwrap_or("unknown");
+                let mut recommendation =
+                    format!("Upgrade to a version outside the affected range ({})", affected);
+                if let Some(link) = field("link") {
+                    recommendation.push_str(&format!("; see {}", link));
+                }
+
+                vulnerabilities.push(SecurityVulnerability {
+                    package: package.clone(),
+                    version: installed.get(package.as_str()).unwrap_or(&"unknown").to_string(),
+                    // Advisories without a severity are still confirmed vulnerabilities
+                    severity: field("severity").unwrap_or("high").to_lowercase(),
+                    cve: field("cve").map(|s| s.to_string()),
+                    description: field("title").unwrap_or("Security advisory").to_string(),
+                    recommendation,
+                    source: "composer-audit".to_string(),
+                });
+            }
+        }
     }
 
-    for pkg in &all_packages {
-        // Check for dev versions
-        if pkg.version.contains("dev") && !pkg.version.starts_with("dev-") {
+    if let Some(abandoned) = audit.get("abandoned").and_then(|v| v.as_object()) {
+        for (package, replacement) in abandoned {
+            let recommendation = match replacement.as_str() {
+                Some(r) => format!("Migrate to {}", r),
+                None => "Find a maintained alternative".to_string(),
+            };
             vulnerabilities.push(SecurityVulnerability {
-                package: pkg.name.clone(),
-                version: pkg.version.clone(),
+                package: package.clone(),
+                version: installed.get(package.as_str()).unwrap_or(&"unknown").to_string(),
                 severity: "medium".to_string(),
                 cve: None,
-                description: "Using development version in production".to_string(),
-                recommendation: "Pin to a stable release version".to_string(),
+                description: "Package is abandoned".to_string(),
+                recommendation,
+                source: "composer-audit".to_string(),
             });
         }
+    }
 
-        // Check for pre-1.0 versions
-        if pkg.version.starts_with("0.") {
+    let result = build_audit_result(vulnerabilities, None, severity_floor);
+    Ok(serde_json::to_string_pretty(&result)?)
+}
+
+/// Heuristic findings derived from lock metadata alone
+fn heuristic_findings(lock: &ComposerLock, config: &SecurityConfig, include_dev: bool) -> Vec<SecurityVulnerability> {
+    let mut vulnerabilities = Vec::new();
+
+    let dev_severity = enabled_severity(&config.dev_version);
+    let commit_pinned_severity = enabled_severity(&config.commit_pinned);
+    let pre_release_severity = enabled_severity(&config.pre_release);
+    let stale_severity = enabled_severity(&config.stale);
+
+    let mut all_packages = lock.packages.clone();
+    if let Some(dev_packages) = lock.packages_dev.as_ref().filter(|_| include_dev) {
+        all_packages.extend(dev_packages.clone());
+    }
+
+    for pkg in &all_packages {
+        let pinned_commit = pinned_commit(pkg);
+
+        // Check for dev versions installed at an exact commit
+        if let (Some(severity), Some(sha)) = (&commit_pinned_severity, pinned_commit) {
             vulnerabilities.push(SecurityVulnerability {
                 package: pkg.name.clone(),
                 version: pkg.version.clone(),
-                severity: "low".to_string(),
+                severity: severity.clone(),
                 cve: None,
-                description: "Using pre-1.0 version (potentially unstable)".to_string(),
-                recommendation: "Consider upgrading to a stable 1.x+ version if available"
+                description: format!("Pinned to commit {} instead of a tagged release", sha),
+                recommendation: "Pin to a tagged release for reproducible, auditable installs"
                     .to_string(),
+                source: "heuristic".to_string(),
             });
         }
 
-        // Check for very old packages (5+ years)
-        if let Some(time_str) = &pkg.time {
+        // Check for dev versions (commit-pinned ones are reported above)
+        if let Some(severity) = &dev_severity {
+            if pkg.version.contains("dev")
+                && !pkg.version.starts_with("dev-")
+                && pinned_commit.is_none()
+            {
+                vulnerabilities.push(SecurityVulnerability {
+                    package: pkg.name.clone(),
+                    version: pkg.version.clone(),
+                    severity: severity.clone(),
+                    cve: None,
+                    description: "Using development version in production".to_string(),
+                    recommendation: "Pin to a stable release version".to_string(),
+                    source: "heuristic".to_string(),
+                });
+            }
+        }
+
+        // Check for pre-1.0 versions
+        if let Some(severity) = &pre_release_severity {
+            if pkg.version.starts_with("0.") {
+                vulnerabilities.push(SecurityVulnerability {
+                    package: pkg.name.clone(),
+                    version: pkg.version.clone(),
+                    severity: severity.clone(),
+                    cve: None,
+                    description: "Using pre-1.0 version (potentially unstable)".to_string(),
+                    recommendation: "Consider upgrading to a stable 1.x+ version if available"
+                        .to_string(),
+                    source: "heuristic".to_string(),
+                });
+            }
+        }
+
+        // Check for very old packages (5+ years by default)
+        if let (Some(severity), Some(time_str)) = (&stale_severity, &pkg.time) {
             if let Ok(pkg_time) = time_str.parse::<DateTime<Utc>>() {
-                let five_years_ago = Utc::now() - chrono::Duration::days(5 * 365);
-                if pkg_time < five_years_ago {
+                let cutoff = Utc::now() - chrono::Duration::days(config.stale_years * 365);
+                if pkg_time < cutoff {
                     vulnerabilities.push(SecurityVulnerability {
                         package: pkg.name.clone(),
                         version: pkg.version.clone(),
-                        severity: "medium".to_string(),
+                        severity: severity.clone(),
                         cve: None,
-                        description: "Package has not been updated in over 5 years".to_string(),
+                        description: format!(
+                            "Package has not been updated in over {} years",
+                            config.stale_years
+                        ),
                         recommendation: "Check for maintained alternatives or security advisories"
                             .to_string(),
+                        source: "heuristic".to_string(),
                     });
                 }
             }
         }
     }
 
+    if let Some(severity) = enabled_severity(&config.conflict) {
+        for conflict in find_installed_conflicts(lock, include_dev) {
+            vulnerabilities.push(SecurityVulnerability {
+                package: conflict.package.clone(),
+                version: conflict.version.clone(),
+                severity: severity.clone(),
+                cve: None,
+                description: conflict.describe(),
+                recommendation: format!(
+                    "Update {} or {} so their versions no longer conflict",
+                    conflict.package, conflict.declared_by
+                ),
+                source: "heuristic".to_string(),
+            });
+        }
+    }
+
+    vulnerabilities
+}
+
+/// An installed package whose version falls in another installed package's `conflict` range
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstalledConflict {
+    pub package: String,
+    pub version: String,
+    #[serde(rename = "declaredBy")]
+    pub declared_by: String,
+    #[serde(rename = "declaredByVersion")]
+    pub declared_by_version: String,
+    pub constraint: String,
+}
+
+impl InstalledConflict {
+    pub fn describe(&self) -> String {
+        format!(
+            "{} {} declares a conflict with {} {}",
+            self.declared_by, self.declared_by_version, self.package, self.constraint
+        )
+    }
+}
+
+/// Cross-check every locked package's `conflict` section against the versions actually installed
+pub fn find_installed_conflicts(lock: &ComposerLock, include_dev: bool) -> Vec<InstalledConflict> {
+    let packages: Vec<&PackageInfo> = lock
+        .packages
+        .iter()
+        .chain(lock.packages_dev.iter().flatten().filter(|_| include_dev))
+        .collect();
+    let installed: HashMap<&str, &str> = packages
+        .iter()
+        .map(|p| (p.name.as_str(), p.version.as_str()))
+        .collect();
+
+    let mut conflicts = Vec::new();
+    for pkg in &packages {
+        for (name, constraint) in pkg.conflict.iter().flatten() {
+            let Some(version) = installed.get(name.as_str()) else {
+                continue;
+            };
+            let (Some(parsed_constraint), Some(parsed_version)) = (Constraint::parse(constraint), Version::parse(version)) else {
+                continue;
+            };
+            if parsed_constraint.matches(&parsed_version) {
+                conflicts.push(InstalledConflict {
+                    package: name.clone(),
+                    version: version.to_string(),
+                    declared_by: pkg.name.clone(),
+                    declared_by_version: pkg.version.clone(),
+                    constraint: constraint.clone(),
+                });
+            }
+        }
+    }
+
+    conflicts.sort_by(|a, b| a.package.cmp(&b.package).then_with(|| a.declared_by.cmp(&b.declared_by)));
+    conflicts
+}
+
+/// The commit SHA a dev version is locked to, if any
+fn pinned_commit(pkg: &PackageInfo) -> Option<&str> {
+    let is_dev = pkg.version.starts_with("dev-") || pkg.version.ends_with("-dev");
+    let reference = pkg.source.as_ref().map(|s| s.reference.as_str())?;
+    let is_sha = reference.len() == 40 && reference.chars().all(|c| c.is_ascii_hexdigit());
+    if is_dev && is_sha {
+        Some(reference)
+    } else {
+        None
+    }
+}
+
+/// Sort and summarize a set of findings, then drop those below the floor and optionally
+/// cap the rest. The verdict and risk level are judged on every finding, so the floor
+/// only changes what is reported.
+fn build_audit_result(
+    mut vulnerabilities: Vec<SecurityVulnerability>,
+    max_per_severity: Option<usize>,
+    severity_floor: Option<u8>,
+) -> SecurityAuditResult {
     let mut summary = SecuritySummary {
         critical: 0,
         high: 0,
         medium: 0,
         low: 0,
+        verdict: Verdict::default(),
     };
 
     let mut risk_level = "low";
@@ -110,13 +426,69 @@ pub fn audit_security<P: AsRef<Path>>(repo_path: P) -> Result<String> {
         }
     }
 
-    let result = SecurityAuditResult {
+    vulnerabilities.sort_by(|a, b| {
+        severity_rank(&a.severity)
+            .cmp(&severity_rank(&b.severity))
+            .then_with(|| a.package.cmp(&b.package))
+    });
+
+    // Critical and high findings fail the audit; medium ones need a look
+    let penalty = summary.critical * 40 + summary.high * 20 + summary.medium * 8 + summary.low * 2;
+    let status = match risk_level {
+        "critical" | "high" => "fail",
+        "medium" => "warn",
+        _ => "ok",
+    };
+    summary.verdict = Verdict::new(
+        100.0 - penalty as f64,
+        status,
+        vulnerabilities
+            .iter()
+            .map(|v| format!("{} {}: {} ({})", v.package, v.version, v.description, v.severity)),
+    );
+
+    if let Some(cap) = max_per_severity {
+        let mut taken: HashMap<String, usize> = HashMap::new();
+        vulnerabilities.retain(|v| {
+            let count = taken.entry(v.severity.clone()).or_insert(0);
+            *count += 1;
+            *count <= cap
+        });
+    }
+
+    let mut result = SecurityAuditResult {
         vulnerabilities,
         risk_level: risk_level.to_string(),
         summary,
     };
+    apply_severity_floor(&mut result, severity_floor);
+    result
+}
 
-    Ok(serde_json::to_string_pretty(&result)?)
+/// Findings counted in `summary` at `rank` (from [`severity_rank`]) or more severe
+pub fn findings_at_or_above(summary: &SecuritySummary, rank: u8) -> usize {
+    [summary.critical, summary.high, summary.medium, summary.low]
+        .into_iter()
+        .take(rank as usize + 1)
+        .sum()
+}
+
+/// Drop findings below `severity_floor` from the list and the per-severity counts. The
+/// verdict and risk level are left as they were, and anything gating on the findings
+/// (strict mode) should be decided before the floor is applied.
+pub fn apply_severity_floor(result: &mut SecurityAuditResult, severity_floor: Option<u8>) {
+    result.vulnerabilities.retain(|v| within_severity_floor(&v.severity, severity_floor));
+    let summary = &mut result.summary;
+    for (severity, count) in [
+        ("critical", &mut summary.critical),
+        ("high", &mut summary.high),
+        ("medium", &mut summary.medium),
+        ("low", &mut summary.low),
+    ] {
+        if !within_severity_floor(severity, severity_floor) {
+            *count = 0;
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -124,6 +496,9 @@ pub struct LicenseAnalysisResult {
     pub distribution: Vec<LicenseDistribution>,
     #[serde(rename = "compatibilityIssues")]
     pub compatibility_issues: Vec<String>,
+    /// Packages with no license field at all, i.e. legally "all rights reserved"
+    #[serde(rename = "packagesWithoutLicense", default)]
+    pub packages_without_license: Vec<String>,
     pub summary: LicenseSummary,
 }
 
@@ -133,31 +508,126 @@ pub struct LicenseSummary {
     pub total_packages: usize,
     #[serde(rename = "uniqueLicenses")]
     pub unique_licenses: usize,
+    /// Packages with no license declared plus those with an unrecognized one
     #[serde(rename = "unknownLicenses")]
     pub unknown_licenses: usize,
+    #[serde(rename = "noLicenseDeclared", default)]
+    pub no_license_declared: usize,
+    /// Packages declaring only license ids that aren't recognized (e.g. a custom license)
+    #[serde(rename = "unrecognizedLicenses", default)]
+    pub unrecognized_licenses: usize,
+    #[serde(rename = "riskRollup")]
+    pub risk_rollup: LicenseRiskRollup,
+    #[serde(flatten)]
+    pub verdict: Verdict,
+}
+
+/// Package counts per license risk level
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct LicenseRiskRollup {
+    pub safe: RiskBucket,
+    pub caution: RiskBucket,
+    #[serde(rename = "reviewRequired")]
+    pub review_required: RiskBucket,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RiskBucket {
+    pub count: usize,
+    pub percentage: f64,
+}
+
+/// Percentage of `total`, rounded to one decimal place
+fn percentage_of(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    (count as f64 / total as f64 * 1000.0).round() / 10.0
 }
 
-pub fn analyze_licenses<P: AsRef<Path>>(repo_path: P) -> Result<String> {
+/// Rank of a risk level from `assess_license_risk`, lowest risk first
+fn license_risk_rank(risk_level: &str) -> u8 {
+    match risk_level {
+        "safe" => 0,
+        "review-required" => 2,
+        _ => 1,
+    }
+}
+
+/// Roll packages up into risk buckets, one risk level per package. A package listing several
+/// licenses may be used under any of them, so it counts under its most permissive option and
+/// the buckets add up to the package total.
+fn license_risk_rollup(package_risks: &[String]) -> LicenseRiskRollup {
+    let mut rollup = LicenseRiskRollup::default();
+    for risk_level in package_risks {
+        let bucket = match risk_level.as_str() {
+            "safe" => &mut rollup.safe,
+            "review-required" => &mut rollup.review_required,
+            _ => &mut rollup.caution,
+        };
+        bucket.count += 1;
+    }
+    for bucket in [&mut rollup.safe, &mut rollup.caution, &mut rollup.review_required] {
+        bucket.percentage = percentage_of(bucket.count, package_risks.len());
+    }
+    rollup
+}
+
+/// Summarize licenses across locked packages; `include_dev: false` skips `packages-dev`
+pub fn analyze_licenses<P: AsRef<Path>>(repo_path: P, include_dev: bool) -> Result<String> {
+    let result = analyze_licenses_raw(repo_path, include_dev)?;
+    Ok(serde_json::to_string_pretty(&result)?)
+}
+
+/// Summarize licenses and return the raw struct
+pub fn analyze_licenses_raw<P: AsRef<Path>>(repo_path: P, include_dev: bool) -> Result<LicenseAnalysisResult> {
     let lock = read_composer_lock(&repo_path)?;
+    Ok(analyze_lock_licenses(&lock, include_dev))
+}
+
+/// Distribution entry for packages without a license field (SPDX's `NONE`)
+const NO_LICENSE: &str = "NONE";
 
+/// Summarize licenses of an already-parsed composer.lock
+pub fn analyze_lock_licenses(lock: &ComposerLock, include_dev: bool) -> LicenseAnalysisResult {
     let mut license_map: HashMap<String, Vec<String>> = HashMap::new();
-    let mut unknown_count = 0;
+    let mut packages_without_license = Vec::new();
+    let mut unrecognized_count = 0;
+    let mut package_risks = Vec::new();
 
     let mut all_packages = lock.packages.clone();
-    if let Some(dev_packages) = &lock.packages_dev {
+    if let Some(dev_packages) = lock.packages_dev.as_ref().filter(|_| include_dev) {
         all_packages.extend(dev_packages.clone());
     }
 
     for pkg in &all_packages {
-        let licenses = pkg.license.clone().unwrap_or_else(|| vec!["Unknown".to_string()]);
+        let licenses = match pkg.license.as_ref().filter(|l| !l.is_empty()) {
+            Some(licenses) => licenses.clone(),
+            None => {
+                packages_without_license.push(pkg.name.clone());
+                vec![NO_LICENSE.to_string()]
+            }
+        };
+
+        let recognized = licenses
+            .iter()
+            .flat_map(|l| spdx_ids(l))
+            .any(|id| id == NO_LICENSE || is_recognized_license(id));
+        if !recognized {
+            unrecognized_count += 1;
+        }
+
+        package_risks.extend(
+            licenses
+                .iter()
+                .map(|license| assess_license_risk(license))
+                .min_by_key(|risk_level| license_risk_rank(risk_level)),
+        );
 
         for license in licenses {
-            if license == "Unknown" {
-                unknown_count += 1;
-            }
             license_map
                 .entry(license)
-                .or_insert_with(Vec::new)
+                .or_default()
                 .push(pkg.name.clone());
         }
     }
@@ -169,6 +639,7 @@ pub fn analyze_licenses<P: AsRef<Path>>(repo_path: P) -> Result<String> {
         .map(|(license, packages)| LicenseDistribution {
             risk_level: assess_license_risk(&license),
             count: packages.len(),
+            percentage: percentage_of(packages.len(), all_packages.len()),
             license,
             packages,
         })
@@ -186,19 +657,115 @@ pub fn analyze_licenses<P: AsRef<Path>>(repo_path: P) -> Result<String> {
         );
     }
 
-    let result = LicenseAnalysisResult {
+    let risk_rollup = license_risk_rollup(&package_risks);
+    packages_without_license.sort();
+    let no_license_count = packages_without_license.len();
+    let verdict = license_verdict(&distribution, &compatibility_issues, &packages_without_license, &risk_rollup);
+    LicenseAnalysisResult {
         distribution,
         compatibility_issues,
+        packages_without_license,
         summary: LicenseSummary {
             total_packages: all_packages.len(),
             unique_licenses: unique_license_count,
-            unknown_licenses: unknown_count,
+            unknown_licenses: no_license_count + unrecognized_count,
+            no_license_declared: no_license_count,
+            unrecognized_licenses: unrecognized_count,
+            risk_rollup,
+            verdict,
         },
+    }
+}
+
+/// Compatibility issues fail the analysis; packages needing legal review warn
+fn license_verdict(
+    distribution: &[LicenseDistribution],
+    compatibility_issues: &[String],
+    packages_without_license: &[String],
+    risk_rollup: &LicenseRiskRollup,
+) -> Verdict {
+    let penalty = compatibility_issues.len() * 30 + risk_rollup.review_required.count * 10 + risk_rollup.caution.count * 2;
+    let status = if !compatibility_issues.is_empty() {
+        "fail"
+    } else if risk_rollup.review_required.count > 0 {
+        "warn"
+    } else {
+        "ok"
+    };
+
+    let mut review: Vec<&LicenseDistribution> = distribution
+        .iter()
+        .filter(|d| d.risk_level == "review-required" && d.license != NO_LICENSE)
+        .collect();
+    review.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.license.cmp(&b.license)));
+    let issues = compatibility_issues
+        .iter()
+        .cloned()
+        .chain(packages_without_license.iter().map(|p| format!("{} declares no license", p)))
+        .chain(review.into_iter().map(|d| format!("{} needs review: {}", d.license, d.packages.join(", "))));
+
+    Verdict::new(100.0 - penalty as f64, status, issues)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LicensePackagesResult {
+    pub query: String,
+    /// License strings from composer.lock that matched the query
+    #[serde(rename = "matchedLicenses")]
+    pub matched_licenses: Vec<String>,
+    pub packages: Vec<String>,
+    pub count: usize,
+}
+
+/// List packages whose license matches `license`. Matching is case-insensitive and
+/// looks at each SPDX id inside an expression such as `(MIT or GPL-3.0-only)`.
+/// A bare family name like `GPL` matches every GPL version but not LGPL or AGPL.
+pub fn packages_by_license<P: AsRef<Path>>(repo_path: P, license: &str, include_dev: bool) -> Result<String> {
+    let analysis = analyze_licenses_raw(repo_path, include_dev)?;
+    let query = license.trim();
+
+    let mut matched_licenses = Vec::new();
+    let mut packages = Vec::new();
+    for item in &analysis.distribution {
+        if spdx_ids(&item.license).any(|id| license_id_matches(id, query)) {
+            matched_licenses.push(item.license.clone());
+            packages.extend(item.packages.iter().cloned());
+        }
+    }
+    matched_licenses.sort();
+    packages.sort();
+    packages.dedup();
+
+    let result = LicensePackagesResult {
+        query: query.to_string(),
+        matched_licenses,
+        count: packages.len(),
+        packages,
     };
 
     Ok(serde_json::to_string_pretty(&result)?)
 }
 
+/// SPDX ids in a license expression, skipping operators and parentheses
+pub fn spdx_ids(expression: &str) -> impl Iterator<Item = &str> {
+    expression
+        .split(|c: char| c.is_whitespace() || c == '(' || c == ')')
+        .filter(|t| !t.is_empty())
+        .filter(|t| !["or", "and", "with"].iter().any(|op| t.eq_ignore_ascii_case(op)))
+}
+
+/// Family part of an SPDX id: `GPL-3.0-or-later` → `GPL`, `BSD-3-Clause` → `BSD`
+fn license_family(id: &str) -> &str {
+    let id = id.trim_end_matches('+');
+    id.match_indices('-')
+        .find(|(i, _)| id[i + 1..].starts_with(|c: char| c.is_ascii_digit()))
+        .map_or(id, |(i, _)| &id[..i])
+}
+
+fn license_id_matches(id: &str, query: &str) -> bool {
+    id.eq_ignore_ascii_case(query) || license_family(id).eq_ignore_ascii_case(query)
+}
+
 fn assess_license_risk(license: &str) -> String {
     let safe_licenses = ["MIT", "Apache-2.0", "BSD-3-Clause", "BSD-2-Clause", "ISC"];
 
@@ -213,9 +780,97 @@ fn assess_license_risk(license: &str) -> String {
         }
     }
 
-    if license.contains("GPL") || license == "Unknown" || license.contains("Proprietary") {
+    if license.contains("GPL") || license == NO_LICENSE || license.contains("Proprietary") {
         return "review-required".to_string();
     }
 
     "caution".to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::composer::parse_composer_lock;
+
+    #[test]
+    fn test_risk_rollup_counts_dual_licensed_packages_once() {
+        let lock = parse_composer_lock(
+            r#"{"packages": [
+                {"name": "a/dual", "version": "1.0.0", "license": ["MIT", "GPL-3.0-only"]},
+                {"name": "b/gpl", "version": "1.0.0", "license": ["GPL-3.0-only"]}
+            ]}"#,
+        )
+        .unwrap();
+
+        let rollup = analyze_lock_licenses(&lock, true).summary.risk_rollup;
+        assert_eq!(rollup.safe.count, 1);
+        assert_eq!(rollup.review_required.count, 1);
+        assert_eq!(rollup.safe.percentage + rollup.caution.percentage + rollup.review_required.percentage, 100.0);
+    }
+
+    #[test]
+    fn test_license_query_matches_ids_and_families() {
+        let temp = crate::test_support::temp_dir();
+        let lock = r#"{"packages": [
+            {"name": "a/dual", "version": "1.0.0", "license": ["(MIT or GPL-3.0-or-later)"]},
+            {"name": "b/gpl2", "version": "1.0.0", "license": ["GPL-2.0+"]},
+            {"name": "c/lgpl", "version": "1.0.0", "license": ["LGPL-2.1-only"]},
+            {"name": "d/bsd", "version": "1.0.0", "license": ["BSD-3-Clause"]}
+        ]}"#;
+        std::fs::write(temp.path().join("composer.lock"), lock).unwrap();
+        let query = |license: &str| -> LicensePackagesResult {
+            serde_json::from_str(&packages_by_license(temp.path(), license, true).unwrap()).unwrap()
+        };
+
+        let gpl = query("gpl");
+        assert_eq!(gpl.packages, ["a/dual", "b/gpl2"]);
+        assert_eq!(gpl.count, 2);
+        assert_eq!(query("MIT").packages, ["a/dual"]);
+        assert_eq!(query("LGPL-2.1-only").packages, ["c/lgpl"]);
+        assert_eq!(query(" bsd ").packages, ["d/bsd"]);
+        assert!(query("Apache-2.0").packages.is_empty());
+        assert_eq!(license_family("GPL-3.0-or-later"), "GPL");
+        assert_eq!(spdx_ids("(MIT or GPL-2.0) and Apache-2.0").collect::<Vec<_>>(), ["MIT", "GPL-2.0", "Apache-2.0"]);
+    }
+
+    #[test]
+    fn test_installed_conflicts_match_declared_ranges() {
+        let lock = parse_composer_lock(
+            r#"{"packages": [
+                {"name": "acme/app-kit", "version": "2.0.0", "conflict": {"acme/http": "<1.5", "acme/log": "^3.0", "acme/gone": "*"}},
+                {"name": "acme/http", "version": "1.4.2"},
+                {"name": "acme/log", "version": "2.1.0"}
+            ], "packages-dev": [
+                {"name": "acme/tool", "version": "1.0.0", "conflict": {"acme/http": "1.4.*"}}
+            ]}"#,
+        )
+        .unwrap();
+
+        let conflicts = find_installed_conflicts(&lock, true);
+        let pairs: Vec<(&str, &str)> = conflicts.iter().map(|c| (c.package.as_str(), c.declared_by.as_str())).collect();
+        assert_eq!(pairs, [("acme/http", "acme/app-kit"), ("acme/http", "acme/tool")]);
+        assert_eq!(conflicts[0].describe(), "acme/app-kit 2.0.0 declares a conflict with acme/http <1.5");
+
+        assert_eq!(find_installed_conflicts(&lock, false).len(), 1);
+    }
+
+    #[test]
+    fn test_severity_floor_leaves_gate_and_verdict_alone() {
+        let lock = parse_composer_lock(r#"{"packages": [{"name": "a/dev", "version": "1.0.x-dev"}]}"#).unwrap();
+        let config = SecurityConfig::default();
+
+        // As audit_security does: gate on the unfloored summary, then trim the report
+        let mut result = audit_lock(&lock, None, None, &config, true);
+        let failing = findings_at_or_above(&result.summary, severity_rank("medium"));
+        apply_severity_floor(&mut result, Some(severity_rank("high")));
+
+        assert_eq!(failing, 1);
+        assert!(result.vulnerabilities.is_empty());
+        assert_eq!(result.summary.medium, 0);
+        assert_eq!(result.summary.verdict.status, "warn");
+
+        let floored = audit_lock(&lock, None, Some(severity_rank("high")), &config, true);
+        assert_eq!(floored.summary.verdict.status, "warn");
+        assert_eq!(floored.risk_level, "medium");
+    }
+}
@@ -1,11 +1,26 @@
 //! Agent Suggestion Hooks - Integration with IDE agents (Cursor, Cline, Claude Code)
 //! Provides structured suggestions for non-compliant dependencies
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
 
-use super::tracker::{check_compliance, get_dependency_history};
+use super::psr4::analyze_psr4_autoloading_raw;
+use super::security::{severity_rank, within_severity_floor};
+use super::tracker::{check_compliance, get_dependency_history, move_to_dev_command};
+use crate::composer::{read_composer_json, read_composer_lock};
+use crate::config::severity_floor;
+use crate::i18n::{fill, Locale, Messages};
+use crate::types::{AbandonedField, Psr4Violation};
+
+lazy_static! {
+    /// `namespace Foo;` or the bracketed `namespace Foo {` (brace possibly on the next line)
+    static ref NAMESPACE_DECLARATION_RE: Regex = Regex::new(r"namespace\s+[\w\\]+(\s*(?:[;{]|$))").unwrap();
+}
 
 /// A structured suggestion for AI agents
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,6 +54,20 @@ pub struct AgentAction {
     pub confirm_required: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    /// Exact textual replacement for "file-edit" actions
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub edit: Option<FileEdit>,
+}
+
+/// A single-line replacement an agent can apply without re-deriving it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileEdit {
+    /// Path relative to the repository root
+    pub file: String,
+    /// 1-based line holding `old_text`
+    pub line: usize,
+    pub old_text: String,
+    pub new_text: String,
 }
 
 /// Summary of all suggestions
@@ -57,8 +86,17 @@ pub struct AgentSuggestionsResponse {
     pub terminal_output: String,
 }
 
-/// Generate structured suggestions for AI agents
-pub fn generate_agent_suggestions(repo_path: &str) -> Result<AgentSuggestionsResponse> {
+/// Generate structured suggestions for AI agents. Only suggestions at `min_severity` or
+/// above (default: `reporting.minReportedSeverity`) are returned and counted. Titles and the
+/// terminal output use `locale`; descriptions and action labels stay in English.
+pub fn generate_agent_suggestions(
+    repo_path: &str,
+    min_severity: Option<&str>,
+    locale: Locale,
+) -> Result<AgentSuggestionsResponse> {
+    let m = locale.messages();
+    let min_rank = severity_floor(repo_path, min_severity)?;
+
     let issues = check_compliance(repo_path)?;
     let history = get_dependency_history(repo_path)?;
     
@@ -80,9 +118,26 @@ pub fn generate_agent_suggestions(repo_path: &str) -> Result<AgentSuggestionsRes
                 auto_apply: Some(issue.severity == "low"),
                 confirm_required: Some(issue.severity != "low"),
                 description: None,
+                edit: None,
             });
         }
         
+        if issue.issue == "misconfigured" {
+            let constraint = read_composer_json(repo_path)
+                .ok()
+                .and_then(|composer| composer.require?.get(&issue.dependency).cloned());
+            actions.push(AgentAction {
+                id: format!("{}-move", suggestion_id),
+                label: "Move to require-dev".to_string(),
+                command: move_to_dev_command(&issue.dependency, constraint.as_deref()),
+                action_type: "shell".to_string(),
+                auto_apply: Some(false),
+                confirm_required: Some(true),
+                description: None,
+                edit: None,
+            });
+        }
+
         // Add documentation link
         actions.push(AgentAction {
             id: format!("{}-docs", suggestion_id),
@@ -92,6 +147,7 @@ pub fn generate_agent_suggestions(repo_path: &str) -> Result<AgentSuggestionsRes
             auto_apply: None,
             confirm_required: None,
             description: None,
+            edit: None,
         });
         
         let suggestion_type = if issue.severity == "critical" || issue.severity == "high" {
@@ -107,7 +163,7 @@ pub fn generate_agent_suggestions(repo_path: &str) -> Result<AgentSuggestionsRes
         suggestions.push(AgentSuggestion {
             id: suggestion_id,
             suggestion_type: suggestion_type.to_string(),
-            title: format!("{} Issue: {}", capitalize(&issue.issue), issue.dependency),
+            title: fill(m.issue_title, &[&capitalize(&issue.issue), &issue.dependency]),
             description: issue.description.clone(),
             severity: issue.severity.clone(),
             category: issue.issue.clone(),
@@ -118,6 +174,113 @@ pub fn generate_agent_suggestions(repo_path: &str) -> Result<AgentSuggestionsRes
         });
     }
     
+    // Add migration suggestions for abandoned packages
+    if let Ok(lock) = read_composer_lock(repo_path) {
+        let all_packages = lock.packages.iter().chain(lock.packages_dev.iter().flatten());
+        for pkg in all_packages {
+            let replacement = match &pkg.abandoned {
+                Some(AbandonedField::Flag(true)) => None,
+                Some(AbandonedField::Replacement(r)) if !r.is_empty() => Some(r.clone()),
+                _ => continue,
+            };
+            let suggestion_id = format!("abandoned-{}", pkg.name.replace("/", "-"));
+
+            let mut actions = Vec::new();
+            if let Some(ref replacement) = replacement {
+                actions.push(AgentAction {
+                    id: format!("{}-migrate", suggestion_id),
+                    label: format!("Migrate to {}", replacement),
+                    command: format!("composer remove {} && composer require {}", pkg.name, replacement),
+                    action_type: "shell".to_string(),
+                    auto_apply: Some(false),
+                    confirm_required: Some(true),
+                    description: Some("Replacement APIs may differ; review usages after migrating".to_string()),
+                    edit: None,
+                });
+            }
+            actions.push(AgentAction {
+                id: format!("{}-docs", suggestion_id),
+                label: "View on Packagist".to_string(),
+                command: format!("https://packagist.org/packages/{}", pkg.name),
+                action_type: "link".to_string(),
+                auto_apply: None,
+                confirm_required: None,
+                description: None,
+                edit: None,
+            });
+
+            let mut metadata = HashMap::new();
+            metadata.insert("abandoned".to_string(), serde_json::json!(true));
+            if let Some(ref replacement) = replacement {
+                metadata.insert("replacement".to_string(), serde_json::json!(replacement));
+            }
+
+            suggestions.push(AgentSuggestion {
+                id: suggestion_id,
+                suggestion_type: "warning".to_string(),
+                title: fill(m.abandoned_package_title, &[&pkg.name]),
+                description: match replacement {
+                    Some(ref r) => format!("This package is abandoned; the maintainers suggest {} instead", r),
+                    None => "This package is abandoned and no replacement was suggested".to_string(),
+                },
+                severity: "high".to_string(),
+                category: "deprecated".to_string(),
+                dependency: Some(pkg.name.clone()),
+                version: Some(pkg.version.clone()),
+                actions,
+                metadata,
+            });
+        }
+    }
+
+    // Add namespace fixes for PSR-4 violations
+    if let Ok(psr4) = analyze_psr4_autoloading_raw(repo_path) {
+        for violation in &psr4.violations {
+            if violation.expected_namespace.is_empty() {
+                continue;
+            }
+            let suggestion_id = format!("psr4-{}", violation.file.replace(['/', '\\', '.'], "-"));
+
+            let mut actions = Vec::new();
+            if let Some(edit) = psr4_namespace_edit(repo_path, violation) {
+                actions.push(AgentAction {
+                    id: format!("{}-fix", suggestion_id),
+                    label: format!("Set namespace to {}", violation.expected_namespace),
+                    command: format!("{}:{}", violation.file, edit.line),
+                    action_type: "file-edit".to_string(),
+                    auto_apply: Some(false),
+                    confirm_required: Some(true),
+                    description: Some("References to classes in this file may need updating too".to_string()),
+                    edit: Some(edit),
+                });
+            }
+
+            let mut metadata = HashMap::new();
+            metadata.insert("file".to_string(), serde_json::json!(violation.file));
+            metadata.insert("line".to_string(), serde_json::json!(violation.line));
+            metadata.insert("expectedNamespace".to_string(), serde_json::json!(violation.expected_namespace));
+
+            suggestions.push(AgentSuggestion {
+                id: suggestion_id,
+                suggestion_type: "warning".to_string(),
+                title: fill(m.psr4_violation_title, &[&violation.file]),
+                description: match violation.actual_namespace.as_deref() {
+                    Some(actual) if !actual.is_empty() => format!(
+                        "Declares namespace {} but its location requires {}",
+                        actual, violation.expected_namespace
+                    ),
+                    _ => format!("Missing namespace declaration; expected {}", violation.expected_namespace),
+                },
+                severity: "medium".to_string(),
+                category: "psr4".to_string(),
+                dependency: None,
+                version: None,
+                actions,
+                metadata,
+            });
+        }
+    }
+
     // Add suggestions for stale dependencies (limit to 5)
     for stale_dep in history.stale.iter().take(5) {
         let mut metadata = HashMap::new();
@@ -131,7 +294,7 @@ pub fn generate_agent_suggestions(repo_path: &str) -> Result<AgentSuggestionsRes
         suggestions.push(AgentSuggestion {
             id: format!("stale-{}", stale_dep.name.replace("/", "-")),
             suggestion_type: "info".to_string(),
-            title: format!("Stale Dependency: {}", stale_dep.name),
+            title: fill(m.stale_dependency_title, &[&stale_dep.name]),
             description: format!("This dependency hasn't been updated since {}", 
                 stale_dep.updated_at.as_deref().unwrap_or("unknown")),
             severity: "low".to_string(),
@@ -146,11 +309,17 @@ pub fn generate_agent_suggestions(repo_path: &str) -> Result<AgentSuggestionsRes
                 auto_apply: None,
                 confirm_required: None,
                 description: None,
+                edit: None,
             }],
             metadata,
         });
     }
     
+    suggestions.retain(|s| within_severity_floor(&s.severity, min_rank));
+
+    let mut suggestions = consolidate_by_dependency(suggestions, m);
+    ensure_unique_ids(&mut suggestions);
+
     // Add summary suggestion if there are issues
     if !suggestions.is_empty() {
         let critical_count = suggestions.iter().filter(|s| s.severity == "critical").count();
@@ -171,7 +340,7 @@ pub fn generate_agent_suggestions(repo_path: &str) -> Result<AgentSuggestionsRes
         let summary = AgentSuggestion {
             id: "summary".to_string(),
             suggestion_type: summary_type.to_string(),
-            title: "Dependency Analysis Summary".to_string(),
+            title: m.analysis_summary_title.to_string(),
             description: format!("Found {} issues: {} critical, {} high severity", 
                 suggestions.len(), critical_count, high_count),
             severity: summary_severity.to_string(),
@@ -187,6 +356,7 @@ pub fn generate_agent_suggestions(repo_path: &str) -> Result<AgentSuggestionsRes
                     auto_apply: None,
                     confirm_required: None,
                     description: None,
+                    edit: None,
                 },
                 AgentAction {
                     id: "summary-update-all".to_string(),
@@ -196,6 +366,7 @@ pub fn generate_agent_suggestions(repo_path: &str) -> Result<AgentSuggestionsRes
                     auto_apply: None,
                     confirm_required: Some(true),
                     description: None,
+                    edit: None,
                 },
             ],
             metadata,
@@ -219,12 +390,143 @@ pub fn generate_agent_suggestions(repo_path: &str) -> Result<AgentSuggestionsRes
             by_category,
         },
         suggestions: suggestions.clone(),
-        terminal_output: format_suggestions_for_terminal(&suggestions),
+        terminal_output: format_suggestions_for_terminal(&suggestions, m),
     })
 }
 
+/// Merge suggestions about the same package into one card, so agents see one
+/// entry per dependency. The merged card takes the most severe issue's severity.
+fn consolidate_by_dependency(suggestions: Vec<AgentSuggestion>, m: &Messages) -> Vec<AgentSuggestion> {
+    // Each slot is one output card, kept in first-seen order
+    let mut slots: Vec<Vec<AgentSuggestion>> = Vec::new();
+    let mut slot_of: HashMap<String, usize> = HashMap::new();
+
+    for suggestion in suggestions {
+        match suggestion.dependency.clone() {
+            Some(dependency) => match slot_of.get(&dependency) {
+                Some(&slot) => slots[slot].push(suggestion),
+                None => {
+                    slot_of.insert(dependency, slots.len());
+                    slots.push(vec![suggestion]);
+                }
+            },
+            None => slots.push(vec![suggestion]),
+        }
+    }
+
+    slots.into_iter().map(|group| merge_suggestions(group, m)).collect()
+}
+
+fn merge_suggestions(mut group: Vec<AgentSuggestion>, m: &Messages) -> AgentSuggestion {
+    if group.len() == 1 {
+        return group.remove(0);
+    }
+    group.sort_by_key(|s| severity_rank(&s.severity));
+    let dependency = group[0].dependency.clone().unwrap_or_default();
+
+    let categories: Vec<String> = group.iter().map(|s| s.category.clone()).collect();
+    let mut metadata = HashMap::new();
+    let mut actions: Vec<AgentAction> = Vec::new();
+    for suggestion in &group {
+        for (key, value) in &suggestion.metadata {
+            metadata.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+        for action in &suggestion.actions {
+            if !actions.iter().any(|a| a.action_type == action.action_type && a.command == action.command) {
+                actions.push(action.clone());
+            }
+        }
+    }
+    metadata.insert("issues".to_string(), serde_json::json!(categories));
+    metadata.insert(
+        "issueDescriptions".to_string(),
+        serde_json::json!(group.iter().map(|s| s.description.as_str()).collect::<Vec<_>>()),
+    );
+
+    let primary = &group[0];
+    AgentSuggestion {
+        id: format!("dep-{}", dependency.replace('/', "-")),
+        suggestion_type: primary.suggestion_type.clone(),
+        title: fill(m.merged_issues_title, &[&group.len(), &dependency]),
+        description: group.iter().map(|s| s.description.as_str()).collect::<Vec<_>>().join("; "),
+        severity: primary.severity.clone(),
+        category: primary.category.clone(),
+        dependency: Some(dependency),
+        version: primary.version.clone(),
+        actions,
+        metadata,
+    }
+}
+
+/// Suffix repeated ids (`-2`, `-3`, ...) in order, so ids stay unique and stable for the same input.
+/// A suffix already taken by another id (e.g. a dependency literally named `...-2`) is skipped.
+fn ensure_unique_ids(suggestions: &mut [AgentSuggestion]) {
+    let mut taken: HashSet<String> = suggestions.iter().map(|s| s.id.clone()).collect();
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut next_suffix: HashMap<String, usize> = HashMap::new();
+    for suggestion in suggestions.iter_mut() {
+        if seen.insert(suggestion.id.clone()) {
+            continue;
+        }
+        let suffix = next_suffix.entry(suggestion.id.clone()).or_insert(2);
+        let id = loop {
+            let candidate = format!("{}-{}", suggestion.id, suffix);
+            *suffix += 1;
+            if taken.insert(candidate.clone()) {
+                break candidate;
+            }
+        };
+        suggestion.id = id;
+    }
+}
+
+/// Compute the exact line replacement that fixes a PSR-4 violation. A wrong declaration is
+/// rewritten in place, keeping its `;` or `{`; a missing one is inserted after `<?php` (and
+/// any leading `declare`). `None` when the declaration can't be rewritten on its line.
+fn psr4_namespace_edit(repo_path: &str, violation: &Psr4Violation) -> Option<FileEdit> {
+    let contents = fs::read_to_string(Path::new(repo_path).join(&violation.file)).ok()?;
+    let lines: Vec<&str> = contents.lines().collect();
+    let declaration = format!("namespace {};", violation.expected_namespace);
+
+    match violation.actual_namespace.as_deref() {
+        Some(actual) if !actual.is_empty() => {
+            let old_text = lines.get(violation.line.checked_sub(1)?)?.to_string();
+            let renamed = format!("namespace {}${{1}}", violation.expected_namespace);
+            let new_text = NAMESPACE_DECLARATION_RE.replace(&old_text, renamed.as_str()).to_string();
+            if new_text == old_text {
+                return None;
+            }
+            Some(FileEdit {
+                file: violation.file.clone(),
+                line: violation.line,
+                old_text,
+                new_text,
+            })
+        }
+        _ => {
+            let open_tag = lines.iter().position(|l| l.trim_start().starts_with("<?php"))?;
+            // namespace must follow declare(strict_types=1), which may only be preceded by the open tag
+            let anchor = lines
+                .iter()
+                .enumerate()
+                .skip(open_tag)
+                .take_while(|(i, l)| *i == open_tag || l.trim().is_empty() || l.trim_start().starts_with("declare"))
+                .filter(|(i, l)| *i == open_tag || l.trim_start().starts_with("declare"))
+                .map(|(i, _)| i)
+                .last()?;
+            let old_text = lines[anchor].to_string();
+            Some(FileEdit {
+                file: violation.file.clone(),
+                line: anchor + 1,
+                new_text: format!("{}\n\n{}", old_text, declaration),
+                old_text,
+            })
+        }
+    }
+}
+
 /// Format suggestions as ASCII terminal output (Claude Code CLI style)
-pub fn format_suggestions_for_terminal(suggestions: &[AgentSuggestion]) -> String {
+pub fn format_suggestions_for_terminal(suggestions: &[AgentSuggestion], m: &Messages) -> String {
     let mut output = String::new();
     
     // ANSI colors
@@ -248,35 +550,27 @@ pub fn format_suggestions_for_terminal(suggestions: &[AgentSuggestion]) -> Strin
         }
     }
     
-    output.push_str("\n");
+    output.push('\n');
     output.push_str("╭─────────────────────────────────────────────────────────────────╮\n");
     output.push_str("│  dependency-buster                                              │\n");
     output.push_str("╰─────────────────────────────────────────────────────────────────╯\n");
-    output.push_str("\n");
+    output.push('\n');
     
     // Summary line
     let total = counts["critical"] + counts["high"] + counts["medium"] + counts["low"];
     if total == 0 {
-        output.push_str("  ✓ No issues found\n\n");
+        output.push_str(&format!("  ✓ {}\n\n", m.no_issues_found));
         return output;
     }
     
-    let mut parts: Vec<String> = Vec::new();
-    if counts["critical"] > 0 {
-        parts.push(format!("{} critical", counts["critical"]));
-    }
-    if counts["high"] > 0 {
-        parts.push(format!("{} high", counts["high"]));
-    }
-    if counts["medium"] > 0 {
-        parts.push(format!("{} medium", counts["medium"]));
-    }
-    if counts["low"] > 0 {
-        parts.push(format!("{} low", counts["low"]));
-    }
+    let parts: Vec<String> = ["critical", "high", "medium", "low"]
+        .iter()
+        .filter(|severity| counts[*severity] > 0)
+        .map(|severity| format!("{} {}", counts[*severity], m.severity_label(severity).to_lowercase()))
+        .collect();
     
-    let plural = if total != 1 { "s" } else { "" };
-    output.push_str(&format!("  Found {} issue{}: {}\n\n", total, plural, parts.join(", ")));
+    let found = if total == 1 { m.found_issue } else { m.found_issues };
+    output.push_str(&format!("  {}\n\n", fill(found, &[&total, &parts.join(", ")])));
     
     // Group by category
     let mut by_category: HashMap<&str, Vec<&AgentSuggestion>> = HashMap::new();
@@ -320,15 +614,15 @@ pub fn format_suggestions_for_terminal(suggestions: &[AgentSuggestion]) -> Strin
                 }
             }
             
-            output.push_str("\n");
+            output.push('\n');
         }
     }
     
     // Footer with quick commands
     output.push_str("  ─────────────────────────────────────────────────────────────\n\n");
-    output.push_str(&format!("  {}Quick commands:{}\n", dim, reset));
-    output.push_str("    composer audit          Run security audit\n");
-    output.push_str("    composer update         Update all dependencies\n\n");
+    output.push_str(&format!("  {}{}{}\n", dim, m.quick_commands, reset));
+    output.push_str(&format!("    composer audit          {}\n", m.run_security_audit));
+    output.push_str(&format!("    composer update         {}\n\n", m.update_all_dependencies));
     
     output
 }
@@ -340,3 +634,227 @@ fn capitalize(s: &str) -> String {
         Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
     }
 }
+
+/// The fuller "why" behind one suggestion, for agents presenting it to a person
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuggestionExplanation {
+    pub id: String,
+    pub title: String,
+    pub severity: String,
+    pub category: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dependency: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Why it was flagged, one entry per underlying issue
+    pub rationale: Vec<String>,
+    /// What happens if it is left alone
+    pub impact: Vec<String>,
+    pub links: Vec<ExplanationLink>,
+    pub actions: Vec<ActionTradeoff>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExplanationLink {
+    pub label: String,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionTradeoff {
+    pub id: String,
+    pub label: String,
+    pub command: String,
+    pub tradeoffs: String,
+}
+
+/// Explain the suggestion with `id` from `generate_agent_suggestions`, using the metadata
+/// attached to it. Ids are stable for the same repository state.
+pub fn explain_suggestion(repo_path: &str, id: &str) -> Result<SuggestionExplanation> {
+    let response = generate_agent_suggestions(repo_path, None, Locale::En)?;
+    let suggestion = response
+        .suggestions
+        .into_iter()
+        .find(|s| s.id == id)
+        .ok_or_else(|| anyhow!("Suggestion {} not found; ids come from get_agent_suggestions", id))?;
+
+    let meta = |key: &str| suggestion.metadata.get(key).and_then(|v| v.as_str()).map(|s| s.to_string());
+    let dependency = suggestion.dependency.as_deref().unwrap_or("This package");
+    let version = suggestion.version.as_deref().unwrap_or("the installed version");
+
+    // Merged cards list every issue they combine, with matching descriptions
+    let strings = |key: &str| -> Option<Vec<String>> {
+        let values = suggestion.metadata.get(key)?.as_array()?;
+        Some(values.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
+    };
+    let issues = strings("issues").unwrap_or_else(|| vec![suggestion.category.clone()]);
+    let descriptions = strings("issueDescriptions").unwrap_or_else(|| vec![suggestion.description.clone()]);
+
+    let mut rationale = Vec::new();
+    let mut impact = Vec::new();
+    for (index, issue) in issues.iter().enumerate() {
+        let description = descriptions.get(index).map_or(suggestion.description.as_str(), |d| d.as_str());
+        let (why, effect) = match issue.as_str() {
+            "license" => (
+                format!(
+                    "{} {} is a production dependency under a copyleft or source-available license ({}).",
+                    dependency, version, description
+                ),
+                "Distributing or hosting software that links it can oblige you to publish your own source under the same terms.".to_string(),
+            ),
+            "outdated" => (
+                format!(
+                    "{} has stayed at {} for over two years of tracked snapshots (last updated {}).",
+                    dependency,
+                    version,
+                    meta("lastUpdated").unwrap_or_else(|| "at an unknown date".to_string())
+                ),
+                "Long-untouched dependencies miss security fixes and drift away from current PHP versions, making the eventual upgrade larger.".to_string(),
+            ),
+            "conflict" => (
+                format!("{}.", description.trim_end_matches('.')),
+                "Composer refuses this combination on a fresh resolve, so the next `composer update` may fail, and the conflicting code paths can break at runtime.".to_string(),
+            ),
+            "misconfigured" => (
+                format!(
+                    "{} is a test or development tool, but composer.json lists it in require rather than require-dev.",
+                    dependency
+                ),
+                "It is installed on every production deploy, adding code and attack surface that never runs there; `composer install --no-dev` can't leave it out.".to_string(),
+            ),
+            "deprecated" => (
+                match meta("replacement") {
+                    Some(r) => format!("The maintainers marked {} as abandoned and point to {} instead.", dependency, r),
+                    None => format!("The maintainers marked {} as abandoned without naming a replacement.", dependency),
+                },
+                "Abandoned packages get no further fixes, including for security issues found later.".to_string(),
+            ),
+            "psr4" => (
+                format!(
+                    "{} declares a namespace that doesn't match its location under the PSR-4 mapping; {} is expected.",
+                    meta("file").unwrap_or_else(|| "The file".to_string()),
+                    meta("expectedNamespace").unwrap_or_default()
+                ),
+                "Composer's autoloader can't find the class by name, and `dump-autoload --optimize` skips or warns about it.".to_string(),
+            ),
+            _ => (description.to_string(), "See the individual suggestions for details.".to_string()),
+        };
+        rationale.push(why);
+        impact.push(effect);
+    }
+
+    let mut links: Vec<ExplanationLink> = Vec::new();
+    if let Some(dependency) = &suggestion.dependency {
+        links.push(ExplanationLink {
+            label: "Packagist".to_string(),
+            url: format!("https://packagist.org/packages/{}", dependency),
+        });
+    }
+    for action in suggestion.actions.iter().filter(|a| a.action_type == "link") {
+        if !links.iter().any(|l| l.url == action.command) {
+            links.push(ExplanationLink { label: action.label.clone(), url: action.command.clone() });
+        }
+    }
+
+    let actions = suggestion
+        .actions
+        .iter()
+        .map(|action| ActionTradeoff {
+            id: action.id.clone(),
+            label: action.label.clone(),
+            command: action.command.clone(),
+            tradeoffs: action_tradeoffs(action),
+        })
+        .collect();
+
+    Ok(SuggestionExplanation {
+        id: suggestion.id,
+        title: suggestion.title,
+        severity: suggestion.severity,
+        category: suggestion.category,
+        dependency: suggestion.dependency,
+        version: suggestion.version,
+        rationale,
+        impact,
+        links,
+        actions,
+    })
+}
+
+fn action_tradeoffs(action: &AgentAction) -> String {
+    let tradeoffs = match action.action_type.as_str() {
+        "link" => "Read-only; changes nothing in the project.",
+        "file-edit" => "Rewrites one line; code that refers to the class by its old namespace must change with it.",
+        _ if action.command.starts_with("composer outdated") || action.command == "composer audit" => {
+            "Read-only check; lists problems or newer releases without installing anything."
+        }
+        _ if action.id.ends_with("-migrate") => {
+            "Swaps in the suggested replacement; its API may differ, so usages need reviewing and testing."
+        }
+        _ if action.id.ends_with("-move") => {
+            "Keeps the package for development and CI; anything in production code that still calls it will fail once deployed with --no-dev."
+        }
+        _ if action.command == "composer update" => {
+            "Moves every dependency to the newest allowed release at once; fast, but the resulting diff is harder to review."
+        }
+        _ if action.command.starts_with("composer update") => {
+            "Takes the newest release your constraints allow; even minor releases can change behaviour, so check the changelog."
+        }
+        _ => "",
+    };
+
+    match &action.description {
+        Some(description) if tradeoffs.is_empty() => description.clone(),
+        _ => tradeoffs.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unique_ids_skip_suffixes_already_in_use() {
+        let suggestion = |id: &str| AgentSuggestion {
+            id: id.to_string(),
+            suggestion_type: "warning".to_string(),
+            title: String::new(),
+            description: String::new(),
+            severity: "low".to_string(),
+            category: "outdated".to_string(),
+            dependency: None,
+            version: None,
+            actions: Vec::new(),
+            metadata: HashMap::new(),
+        };
+        let mut suggestions: Vec<AgentSuggestion> =
+            ["dep-a", "dep-a", "dep-a-2", "dep-a", "dep-a-2"].into_iter().map(suggestion).collect();
+
+        ensure_unique_ids(&mut suggestions);
+
+        let ids: Vec<&str> = suggestions.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, ["dep-a", "dep-a-3", "dep-a-2", "dep-a-4", "dep-a-2-2"]);
+    }
+
+    #[test]
+    fn test_namespace_edit_keeps_the_declaration_style() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        std::fs::write(dir.join("A.php"), "<?php\nnamespace Wrong\\Name;\nclass A {}\n").unwrap();
+        std::fs::write(dir.join("B.php"), "<?php\nnamespace Wrong {\nclass B {}\n}\n").unwrap();
+        std::fs::write(dir.join("C.php"), "<?php\nnamespace\n    Wrong;\nclass C {}\n").unwrap();
+        let violation = |file: &str| Psr4Violation {
+            file: file.to_string(),
+            expected_namespace: "App\\Models".to_string(),
+            actual_namespace: Some("Wrong".to_string()),
+            issue: String::new(),
+            line: 2,
+            column: 1,
+        };
+        let repo_path = dir.to_str().unwrap();
+
+        assert_eq!(psr4_namespace_edit(repo_path, &violation("A.php")).unwrap().new_text, "namespace App\\Models;");
+        assert_eq!(psr4_namespace_edit(repo_path, &violation("B.php")).unwrap().new_text, "namespace App\\Models {");
+        assert!(psr4_namespace_edit(repo_path, &violation("C.php")).is_none());
+    }
+}
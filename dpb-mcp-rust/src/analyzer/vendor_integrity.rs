@@ -0,0 +1,319 @@
+//! Vendor integrity
+//! Finds installed packages whose files were edited in place after Composer installed them,
+//! so the changes can be moved into a patch mechanism before the next install wipes them
+
+use anyhow::{anyhow, Result};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+use walkdir::WalkDir;
+
+use super::mkdocs::git_output;
+use crate::archive;
+use crate::composer::{get_vendor_dir, read_composer_json, read_composer_lock};
+use crate::types::PackageInfo;
+
+/// Written by Composer after every install or update, so it dates the last install;
+/// relative to the vendor directory
+const INSTALLED_JSON: &str = "composer/installed.json";
+/// Settings that would let a checkout's own `.git/config` run commands, overridden on every
+/// git call: vendor checkouts come from third parties
+const UNTRUSTED_GIT_CONFIG: [&str; 4] = ["-c", "core.fsmonitor=false", "-c", "core.hooksPath=/dev/null"];
+/// How many changed files a finding lists
+const MAX_LISTED_FILES: usize = 10;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VendorIntegrityResult {
+    /// Packages with changes nothing accounts for
+    pub modified: Vec<ModifiedPackage>,
+    /// Packages with changes applied through cweagans/composer-patches
+    pub patched: Vec<ModifiedPackage>,
+    /// Locked packages with no directory under the vendor directory
+    #[serde(rename = "notInstalled")]
+    pub not_installed: Vec<String>,
+    pub summary: VendorIntegritySummary,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModifiedPackage {
+    pub name: String,
+    pub version: String,
+    /// "source" for git checkouts, "dist" for unpacked archives
+    pub install: String,
+    /// How the change was detected: "git-status", "git-head" or "mtime"
+    pub evidence: Vec<String>,
+    /// Changed files relative to the package directory (first few)
+    pub files: Vec<String>,
+    #[serde(rename = "fileCount")]
+    pub file_count: usize,
+    /// Patches declared for the package in composer.json
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub patches: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recommendation: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VendorIntegritySummary {
+    pub checked: usize,
+    pub modified: usize,
+    pub patched: usize,
+    #[serde(rename = "notInstalled")]
+    pub not_installed: usize,
+}
+
+/// Compare every installed package against what Composer put there. Source installs are
+/// checked with git (uncommitted changes, or HEAD moved off the locked reference); dist
+/// installs by files modified after `<vendor-dir>/composer/installed.json` was last written.
+/// Checkouts inside an extracted archive are never handed to git and count as dist installs.
+pub fn check_vendor_integrity<P: AsRef<Path>>(repo_path: P) -> Result<String> {
+    let repo_path = repo_path.as_ref();
+    let lock = read_composer_lock(repo_path)?;
+    let composer = read_composer_json(repo_path).ok();
+    let vendor_name = composer.as_ref().map(get_vendor_dir).unwrap_or_else(|| "vendor".to_string());
+    let vendor_dir = repo_path.join(&vendor_name);
+    let installed_at = fs::metadata(vendor_dir.join(INSTALLED_JSON))
+        .and_then(|m| m.modified())
+        .map_err(|_| anyhow!("{}/{} not found; run composer install first", vendor_name, INSTALLED_JSON))?;
+    let declared_patches = composer
+        .map(|c| declared_patches(Some(repo_path), c.extra.as_ref()))
+        .unwrap_or_default();
+    let use_git = !archive::is_extracted(repo_path);
+
+    let packages: Vec<&PackageInfo> = lock.packages.iter().chain(lock.packages_dev.iter().flatten()).collect();
+    let (installed, missing): (Vec<&PackageInfo>, Vec<&PackageInfo>) =
+        packages.iter().partition(|pkg| vendor_dir.join(&pkg.name).is_dir());
+    let mut not_installed: Vec<String> = missing.iter().map(|pkg| pkg.name.clone()).collect();
+    not_installed.sort();
+
+    let findings: Vec<ModifiedPackage> = installed
+        .par_iter()
+        .filter_map(|pkg| {
+            inspect_package(pkg, &vendor_dir.join(&pkg.name), installed_at, &declared_patches, use_git)
+        })
+        .collect();
+
+    let (mut patched, mut modified): (Vec<ModifiedPackage>, Vec<ModifiedPackage>) =
+        findings.into_iter().partition(|m| !m.patches.is_empty());
+    modified.sort_by(|a, b| a.name.cmp(&b.name));
+    patched.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let result = VendorIntegrityResult {
+        summary: VendorIntegritySummary {
+            checked: installed.len(),
+            modified: modified.len(),
+            patched: patched.len(),
+            not_installed: not_installed.len(),
+        },
+        modified,
+        patched,
+        not_installed,
+    };
+
+    Ok(serde_json::to_string_pretty(&result)?)
+}
+
+fn inspect_package(
+    pkg: &PackageInfo,
+    dir: &Path,
+    installed_at: SystemTime,
+    declared_patches: &HashMap<String, Vec<String>>,
+    use_git: bool,
+) -> Option<ModifiedPackage> {
+    let dir_str = dir.to_string_lossy();
+    let is_source = use_git && dir.join(".git").exists();
+
+    let mut files: Vec<String> = if is_source {
+        // Porcelain lines are "XY path"; git_output trims the first line's leading space
+        untrusted_git(&dir_str, &["status", "--porcelain"])
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|l| l.trim_start().split_once(' ').map(|(_, path)| path.trim_start().to_string()))
+            .collect()
+    } else {
+        WalkDir::new(dir)
+            .into_iter()
+            .filter_entry(|e| e.file_name() != ".git")
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .filter(|e| e.metadata().ok().and_then(|m| m.modified().ok()).is_some_and(|t| t > installed_at))
+            .filter_map(|e| e.path().strip_prefix(dir).ok().map(|p| p.to_string_lossy().into_owned()))
+            .collect()
+    };
+    // composer-patches v1 leaves a PATCHES.txt in every package it patched
+    files.retain(|f| f != "PATCHES.txt");
+    let mut patches = declared_patches.get(&pkg.name).cloned().unwrap_or_default();
+    if patches.is_empty() && dir.join("PATCHES.txt").is_file() {
+        patches.push("PATCHES.txt".to_string());
+    }
+
+    let mut evidence = Vec::new();
+    if !files.is_empty() {
+        evidence.push(if is_source { "git-status" } else { "mtime" }.to_string());
+    }
+    if is_source {
+        let locked = pkg.source.as_ref().map(|s| s.reference.as_str()).unwrap_or_default();
+        let head = untrusted_git(&dir_str, &["rev-parse", "HEAD"]).unwrap_or_default();
+        if !locked.is_empty() && !head.is_empty() && !head.starts_with(locked) {
+            evidence.push("git-head".to_string());
+        }
+    }
+    if evidence.is_empty() {
+        return None;
+    }
+
+    files.sort();
+    let file_count = files.len();
+    files.truncate(MAX_LISTED_FILES);
+    let recommendation = patches.is_empty().then(|| {
+        format!(
+            "Turn the changes into a patch applied by cweagans/composer-patches (or a fork), \
+             since the next install of {} will discard them",
+            pkg.name
+        )
+    });
+
+    Some(ModifiedPackage {
+        name: pkg.name.clone(),
+        version: pkg.version.clone(),
+        install: if is_source { "source" } else { "dist" }.to_string(),
+        evidence,
+        files,
+        file_count,
+        patches,
+        recommendation,
+    })
+}
+
+/// Run git in a checkout we didn't create, with its command-running settings switched off
+fn untrusted_git(dir: &str, args: &[&str]) -> Result<String> {
+    git_output(dir, &[&UNTRUSTED_GIT_CONFIG[..], args].concat())
+}
+
+/// Package -> patch descriptions from `extra.patches`, or the JSON file `extra.patches-file`
+/// names (relative to `base_dir`). Like composer-patches, only the root project's
+/// `patches-file` counts: pass `None` for a dependency, whose `extra` is third-party input.
+pub(crate) fn declared_patches(
+    base_dir: Option<&Path>,
+    extra: Option<&HashMap<String, Value>>,
+) -> HashMap<String, Vec<String>> {
+    let from_file = base_dir
+        .zip(extra.and_then(|e| e.get("patches-file")).and_then(Value::as_str))
+        .and_then(|(base_dir, file)| fs::read_to_string(base_dir.join(file)).ok())
+        .and_then(|contents| serde_json::from_str::<Value>(&contents).ok())
+        .and_then(|v| v.get("patches").cloned());
+    let Some(Value::Object(patches)) = extra.and_then(|e| e.get("patches").cloned()).or(from_file) else {
+        return HashMap::new();
+    };
+
+    patches
+        .into_iter()
+        .map(|(package, entries)| {
+            // {"description": "url"} in v1; a list of strings or {"description", "url"} objects in v2
+            let descriptions: BTreeSet<String> = match entries {
+                Value::Object(map) => map.into_iter().map(|(description, _)| description).collect(),
+                Value::Array(items) => items
+                    .iter()
+                    .filter_map(|item| item.as_str().or_else(|| item.get("description").and_then(Value::as_str)))
+                    .map(str::to_string)
+                    .collect(),
+                _ => BTreeSet::new(),
+            };
+            (package, descriptions.into_iter().collect())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+    use std::time::Duration;
+
+    fn write_project(dir: &Path, vendor: &str, packages: &[(&str, &str)]) {
+        let lock: Vec<Value> = packages
+            .iter()
+            .map(|(name, reference)| {
+                serde_json::json!({
+                    "name": name,
+                    "version": "1.0.0",
+                    "source": {"type": "git", "url": "", "reference": reference}
+                })
+            })
+            .collect();
+        fs::write(dir.join("composer.json"), serde_json::json!({"config": {"vendor-dir": vendor}}).to_string())
+            .unwrap();
+        fs::write(dir.join("composer.lock"), serde_json::json!({ "packages": lock }).to_string()).unwrap();
+        fs::create_dir_all(dir.join(vendor).join("composer")).unwrap();
+        fs::write(dir.join(vendor).join(INSTALLED_JSON), "[]").unwrap();
+    }
+
+    /// Date the last install an hour back, so files written by the test count as later edits
+    fn backdate_install(dir: &Path, vendor: &str) {
+        let file = fs::File::options().write(true).open(dir.join(vendor).join(INSTALLED_JSON)).unwrap();
+        file.set_modified(SystemTime::now() - Duration::from_secs(3600)).unwrap();
+    }
+
+    #[test]
+    fn test_dist_edits_are_found_in_the_configured_vendor_dir() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        write_project(dir, "lib/vendor", &[("acme/edited", ""), ("acme/missing", "")]);
+        fs::create_dir_all(dir.join("lib/vendor/acme/edited/src")).unwrap();
+        fs::write(dir.join("lib/vendor/acme/edited/src/Client.php"), "<?php // hotfix").unwrap();
+        backdate_install(dir, "lib/vendor");
+
+        let result: VendorIntegrityResult =
+            serde_json::from_str(&check_vendor_integrity(dir).unwrap()).unwrap();
+
+        assert_eq!(result.not_installed, ["acme/missing"]);
+        assert_eq!(result.modified.len(), 1);
+        let edited = &result.modified[0];
+        assert_eq!((edited.name.as_str(), edited.install.as_str()), ("acme/edited", "dist"));
+        assert_eq!(edited.evidence, ["mtime"]);
+        assert_eq!(edited.files, ["src/Client.php"]);
+    }
+
+    #[test]
+    fn test_source_checkout_config_cannot_run_commands() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        write_project(dir, "vendor", &[("acme/checkout", "0000000")]);
+        let checkout = dir.join("vendor/acme/checkout");
+        fs::create_dir_all(&checkout).unwrap();
+        let git = |args: &[&str]| {
+            let status = Command::new("git")
+                .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+                .args(args)
+                .current_dir(&checkout)
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success(), "git {:?}", args);
+        };
+        git(&["init", "-q"]);
+        fs::write(checkout.join("a.php"), "<?php").unwrap();
+        git(&["add", "a.php"]);
+        git(&["commit", "-qm", "init"]);
+        // What a hostile package would ship: commands git runs on status
+        let marker = dir.join("pwned");
+        let hook = format!("touch {}", marker.display());
+        git(&["config", "core.fsmonitor", &hook]);
+        fs::create_dir_all(checkout.join(".git/hooks")).unwrap();
+        fs::write(checkout.join("a.php"), "<?php // edited").unwrap();
+        backdate_install(dir, "vendor");
+
+        let result: VendorIntegrityResult =
+            serde_json::from_str(&check_vendor_integrity(dir).unwrap()).unwrap();
+
+        assert!(!marker.exists());
+        let checkout = &result.modified[0];
+        assert_eq!(checkout.install, "source");
+        assert_eq!(checkout.evidence, ["git-status", "git-head"]);
+        assert_eq!(checkout.files, ["a.php"]);
+    }
+}
@@ -0,0 +1,72 @@
+//! At-a-glance verdicts shared by the core analyses
+//! Every result's `summary` carries the same score/status/topIssues triple so a dashboard
+//! can render one card per tool without knowing each result's shape
+
+use serde::{Deserialize, Serialize};
+
+/// How many issues a verdict lists
+const TOP_ISSUES: usize = 3;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Verdict {
+    /// 0–100, higher is healthier
+    pub score: u8,
+    /// "ok", "warn" or "fail"
+    pub status: String,
+    /// The most important findings, most severe first
+    #[serde(rename = "topIssues")]
+    pub top_issues: Vec<String>,
+}
+
+impl Verdict {
+    /// Build a verdict from a score (100 minus penalties) and the least severe status the
+    /// findings warrant; a score below 80 is at least "warn" and below 50 is "fail"
+    pub fn new(score: f64, status: &str, issues: impl IntoIterator<Item = String>) -> Self {
+        let score = score.clamp(0.0, 100.0).round() as u8;
+        let by_score = match score {
+            0..=49 => "fail",
+            50..=79 => "warn",
+            _ => "ok",
+        };
+        let status = worst_status([status, by_score]);
+
+        Self {
+            score,
+            status: status.to_string(),
+            top_issues: issues.into_iter().take(TOP_ISSUES).collect(),
+        }
+    }
+}
+
+/// The most severe of several statuses; "ok" when there are none
+pub fn worst_status<'a>(statuses: impl IntoIterator<Item = &'a str>) -> &'a str {
+    statuses.into_iter().max_by_key(|s| status_rank(s)).unwrap_or("ok")
+}
+
+fn status_rank(status: &str) -> u8 {
+    match status {
+        "fail" => 2,
+        "warn" => 1,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_low_scores_escalate_status() {
+        let issues = || (1..=5).map(|i| format!("issue {}", i));
+
+        let verdict = Verdict::new(120.0, "ok", issues());
+        assert_eq!((verdict.score, verdict.status.as_str()), (100, "ok"));
+        assert_eq!(verdict.top_issues, ["issue 1", "issue 2", "issue 3"]);
+
+        assert_eq!(Verdict::new(92.0, "warn", issues()).status, "warn");
+        assert_eq!(Verdict::new(65.0, "ok", issues()).status, "warn");
+        assert_eq!(Verdict::new(-20.0, "warn", issues()).status, "fail");
+        assert_eq!(Verdict::new(90.0, "fail", issues()).status, "fail");
+    }
+}
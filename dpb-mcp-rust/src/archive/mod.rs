@@ -0,0 +1,332 @@
+//! Repository archives as `repo_path`
+//! A `.zip`, `.tar.gz`/`.tgz` or `.tar` (e.g. a CI build artifact) is unpacked to a temp
+//! directory for the duration of one tool call and removed afterwards
+
+use anyhow::{anyhow, Context, Result};
+use flate2::read::GzDecoder;
+use serde_json::Value;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Component, Path, PathBuf};
+use walkdir::WalkDir;
+
+/// How deep below the archive root composer.json is looked for (GitHub tarballs nest one level)
+const MAX_ROOT_DEPTH: usize = 3;
+/// Name prefix of the temp directories archives are unpacked to
+const EXTRACT_DIR_PREFIX: &str = "dpb-archive-";
+
+/// Caps on what one archive may unpack to, so a zip or tar bomb can't fill the temp disk
+#[derive(Debug, Clone, Copy)]
+struct Limits {
+    /// Total uncompressed bytes across all files
+    max_bytes: u64,
+    max_entries: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        // Room for a project with its vendor/ directory
+        Self { max_bytes: 1024 * 1024 * 1024, max_entries: 200_000 }
+    }
+}
+
+enum ArchiveKind {
+    Zip,
+    TarGz,
+    Tar,
+}
+
+fn archive_kind(path: &str) -> Option<ArchiveKind> {
+    let lower = path.to_lowercase();
+    if lower.ends_with(".zip") {
+        Some(ArchiveKind::Zip)
+    } else if lower.ends_with(".tar.gz") || lower.ends_with(".tgz") {
+        Some(ArchiveKind::TarGz)
+    } else if lower.ends_with(".tar") {
+        Some(ArchiveKind::Tar)
+    } else {
+        None
+    }
+}
+
+/// An unpacked archive; the temp directory is deleted when this is dropped
+pub struct ExtractedArchive {
+    temp_dir: PathBuf,
+    /// Directory holding the project's composer.json
+    pub root: PathBuf,
+}
+
+impl Drop for ExtractedArchive {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.temp_dir);
+    }
+}
+
+/// Unpack `archive` to a fresh temp directory and locate the PHP project inside it
+pub fn extract(archive: &Path) -> Result<ExtractedArchive> {
+    extract_with_limits(archive, Limits::default())
+}
+
+fn extract_with_limits(archive: &Path, limits: Limits) -> Result<ExtractedArchive> {
+    let name = archive.to_string_lossy();
+    let kind = archive_kind(&name).ok_or_else(|| anyhow!("Unsupported archive type: {}", name))?;
+    if !archive.is_file() {
+        return Err(anyhow!("Archive {} not found", name));
+    }
+
+    let temp_dir = std::env::temp_dir().join(format!("{}{:016x}", EXTRACT_DIR_PREFIX, rand::random::<u64>()));
+    fs::create_dir_all(&temp_dir).with_context(|| format!("Failed to create {:?}", temp_dir))?;
+    // Owns the directory from here on, so a failed unpack is cleaned up too
+    let mut extracted = ExtractedArchive { root: temp_dir.clone(), temp_dir };
+
+    let file = File::open(archive).with_context(|| format!("Failed to open archive {}", name))?;
+    let mut budget = Budget { limits, bytes: 0, entries: 0 };
+    match kind {
+        ArchiveKind::Zip => unpack_zip(file, &extracted.temp_dir, &mut budget),
+        ArchiveKind::TarGz => unpack_tar(GzDecoder::new(file), &extracted.temp_dir, &mut budget),
+        ArchiveKind::Tar => unpack_tar(file, &extracted.temp_dir, &mut budget),
+    }
+    .with_context(|| format!("Failed to extract {}", name))?;
+
+    extracted.root = find_project_root(&extracted.temp_dir)
+        .ok_or_else(|| anyhow!("No composer.json found in archive {}", name))?;
+    Ok(extracted)
+}
+
+/// What an archive has unpacked so far, checked against its [`Limits`]
+struct Budget {
+    limits: Limits,
+    bytes: u64,
+    entries: usize,
+}
+
+impl Budget {
+    fn add_entry(&mut self) -> Result<()> {
+        self.entries += 1;
+        if self.entries > self.limits.max_entries {
+            return Err(anyhow!("Archive has more than {} entries", self.limits.max_entries));
+        }
+        Ok(())
+    }
+
+    fn remaining_bytes(&self) -> u64 {
+        self.limits.max_bytes - self.bytes
+    }
+
+    fn add_bytes(&mut self, bytes: u64) -> Result<()> {
+        if bytes > self.remaining_bytes() {
+            return Err(anyhow!("Archive unpacks to more than {} bytes", self.limits.max_bytes));
+        }
+        self.bytes += bytes;
+        Ok(())
+    }
+}
+
+/// Git metadata is never unpacked: an archive's `.git/config` could make a later git
+/// call run commands
+fn is_git_path(path: &Path) -> bool {
+    path.components().any(|c| c == Component::Normal(".git".as_ref()))
+}
+
+fn unpack_zip(file: File, dest: &Path, budget: &mut Budget) -> Result<()> {
+    let mut zip = zip::ZipArchive::new(file)?;
+    for index in 0..zip.len() {
+        budget.add_entry()?;
+        let mut entry = zip.by_index(index)?;
+        // Entries that would land outside `dest`, and symlinks, are skipped
+        let Some(relative) = entry.enclosed_name().filter(|p| !is_git_path(p)) else {
+            continue;
+        };
+        if entry.is_symlink() {
+            continue;
+        }
+        let target = dest.join(relative);
+        if entry.is_dir() {
+            fs::create_dir_all(&target)?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // The declared size can lie; count what actually inflates, one byte past the budget at most
+        let mut out = File::create(&target)?;
+        let written = io::copy(&mut (&mut entry).take(budget.remaining_bytes() + 1), &mut out)?;
+        budget.add_bytes(written)?;
+    }
+    Ok(())
+}
+
+fn unpack_tar<R: Read>(reader: R, dest: &Path, budget: &mut Budget) -> Result<()> {
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries()? {
+        budget.add_entry()?;
+        let mut entry = entry?;
+        if is_git_path(&entry.path()?) {
+            continue;
+        }
+        // Links are skipped like in zips: `unpack_in` doesn't check where they point
+        if matches!(entry.header().entry_type(), tar::EntryType::Symlink | tar::EntryType::Link) {
+            continue;
+        }
+        // A sparse entry's stored size understates what it unpacks to
+        if entry.header().entry_type() == tar::EntryType::GNUSparse {
+            return Err(anyhow!("Sparse tar entries are not supported"));
+        }
+        budget.add_bytes(entry.size())?;
+        // Refuses entries that would land outside `dest`
+        entry.unpack_in(dest)?;
+    }
+    Ok(())
+}
+
+/// Whether `path` lies inside an archive unpacked by [`extract`], whose contents came from
+/// the caller and must not be handed to tools that honor their own configuration (git)
+pub fn is_extracted(path: &Path) -> bool {
+    let (Ok(path), Ok(temp)) = (path.canonicalize(), std::env::temp_dir().canonicalize()) else {
+        return false;
+    };
+    path.strip_prefix(&temp)
+        .ok()
+        .and_then(|rest| rest.components().next())
+        .is_some_and(|dir| dir.as_os_str().to_string_lossy().starts_with(EXTRACT_DIR_PREFIX))
+}
+
+/// The shallowest directory containing composer.json, ignoring vendored packages
+fn find_project_root(dir: &Path) -> Option<PathBuf> {
+    WalkDir::new(dir)
+        .max_depth(MAX_ROOT_DEPTH + 1)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| e.file_name() != "vendor" && e.file_name() != "node_modules")
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && e.file_name() == "composer.json")
+        .min_by_key(|e| e.depth())
+        .and_then(|e| e.path().parent().map(|p| p.to_path_buf()))
+}
+
+/// Run a tool call, first unpacking `repo_path` when it names an archive and pointing the
+/// call at the extracted project. Tools that write to the repository are refused, since
+/// their changes would be lost with the temp directory.
+pub fn call_with_archive<F>(tool: &str, mut args: Value, read_only: bool, call: F) -> Result<String>
+where
+    F: FnOnce(Value) -> Result<String>,
+{
+    let Some(path) = args.get("repo_path").and_then(|v| v.as_str()).filter(|p| archive_kind(p).is_some()) else {
+        return call(args);
+    };
+    if !read_only {
+        return Err(anyhow!(
+            "An archive repo_path is invalid for {}, which modifies the repository; extract the archive first",
+            tool
+        ));
+    }
+
+    let extracted = extract(Path::new(path))?;
+    args["repo_path"] = Value::String(extracted.root.to_string_lossy().into_owned());
+    call(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    const FILES: [(&str, &str); 3] = [
+        ("app/composer.json", r#"{"name": "acme/app"}"#),
+        ("app/vendor/acme/lib/.git/config", "[core]\n\tfsmonitor = touch /tmp/pwned\n"),
+        ("app/vendor/acme/lib/src/Lib.php", "<?php"),
+    ];
+
+    fn write_zip(path: &Path) {
+        let mut zip = zip::ZipWriter::new(File::create(path).unwrap());
+        for (name, contents) in FILES {
+            zip.start_file(name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(contents.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    fn write_tar(path: &Path) {
+        let mut tar = tar::Builder::new(File::create(path).unwrap());
+        for (name, contents) in FILES {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            tar.append_data(&mut header, name, contents.as_bytes()).unwrap();
+        }
+        tar.finish().unwrap();
+    }
+
+    #[test]
+    fn test_git_metadata_is_not_unpacked() {
+        let temp = crate::test_support::temp_dir();
+        for (name, write) in [("repo.zip", write_zip as fn(&Path)), ("repo.tar", write_tar)] {
+            let archive = temp.path().join(name);
+            write(&archive);
+
+            let extracted = extract(&archive).unwrap();
+            assert!(extracted.root.ends_with("app"), "{}", name);
+            assert!(extracted.root.join("vendor/acme/lib/src/Lib.php").is_file(), "{}", name);
+            assert!(!extracted.root.join("vendor/acme/lib/.git").exists(), "{}", name);
+            assert!(is_extracted(&extracted.root));
+        }
+        assert!(!is_extracted(temp.path()));
+    }
+
+    #[test]
+    fn test_links_are_not_unpacked() {
+        let temp = crate::test_support::temp_dir();
+        let secret = temp.path().join("secret.lock");
+        fs::write(&secret, "host file").unwrap();
+
+        let zip_path = temp.path().join("links.zip");
+        let mut zip = zip::ZipWriter::new(File::create(&zip_path).unwrap());
+        zip.start_file("app/composer.json", SimpleFileOptions::default()).unwrap();
+        zip.write_all(b"{}").unwrap();
+        zip.add_symlink("app/composer.lock", secret.to_str().unwrap(), SimpleFileOptions::default()).unwrap();
+        zip.finish().unwrap();
+
+        let tar_path = temp.path().join("links.tar");
+        let mut tar = tar::Builder::new(File::create(&tar_path).unwrap());
+        let mut header = tar::Header::new_gnu();
+        header.set_size(2);
+        header.set_mode(0o644);
+        tar.append_data(&mut header, "app/composer.json", &b"{}"[..]).unwrap();
+        for (name, entry_type) in [("app/composer.lock", tar::EntryType::Symlink), ("app/src", tar::EntryType::Link)] {
+            let mut header = tar::Header::new_gnu();
+            header.set_entry_type(entry_type);
+            header.set_size(0);
+            tar.append_link(&mut header, name, &secret).unwrap();
+        }
+        tar.finish().unwrap();
+
+        for archive in [zip_path, tar_path] {
+            let extracted = extract(&archive).unwrap();
+            assert!(extracted.root.join("composer.json").is_file(), "{}", archive.display());
+            assert!(fs::symlink_metadata(extracted.root.join("composer.lock")).is_err(), "{}", archive.display());
+            assert!(fs::symlink_metadata(extracted.root.join("src")).is_err(), "{}", archive.display());
+        }
+    }
+
+    #[test]
+    fn test_limits_stop_extraction() {
+        let temp = crate::test_support::temp_dir();
+        // Skipped .git entries still count as entries, but not as bytes
+        let total: u64 = FILES
+            .iter()
+            .filter(|(name, _)| !name.contains(".git"))
+            .map(|(_, contents)| contents.len() as u64)
+            .sum();
+        for (name, write) in [("repo.zip", write_zip as fn(&Path)), ("repo.tar", write_tar)] {
+            let archive = temp.path().join(name);
+            write(&archive);
+
+            let too_big = extract_with_limits(&archive, Limits { max_bytes: total - 1, max_entries: 10 });
+            assert!(format!("{:#}", too_big.err().unwrap()).contains("more than"), "{}", name);
+            let too_many = extract_with_limits(&archive, Limits { max_bytes: total, max_entries: 2 });
+            assert!(format!("{:#}", too_many.err().unwrap()).contains("more than 2 entries"), "{}", name);
+            assert!(extract_with_limits(&archive, Limits { max_bytes: total, max_entries: 3 }).is_ok(), "{}", name);
+        }
+    }
+}
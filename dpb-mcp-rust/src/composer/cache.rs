@@ -0,0 +1,145 @@
+//! Process-wide memo of parsed repository files
+//! Agents tend to call several tools on one repository in a row; the parsed composer.json,
+//! composer.lock and dependency analysis are shared between those calls until the files change
+
+use anyhow::Result;
+use lazy_static::lazy_static;
+use sha2::{Digest, Sha256};
+use std::any::Any;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use super::{lock_path, with_io_retry};
+
+lazy_static! {
+    static ref CACHE: Mutex<HashMap<(PathBuf, Kind), CacheEntry>> = Mutex::new(HashMap::new());
+}
+
+/// Entries kept before the least recently used one is dropped
+const MAX_ENTRIES: usize = 128;
+
+/// A kind of cached value, which decides the files it is fingerprinted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Kind {
+    /// Parsed composer.json
+    ComposerJson,
+    /// Parsed composer.lock (or its fallbacks)
+    ComposerLock,
+    /// Dependency analysis, derived from both files
+    Dependencies,
+}
+
+/// What a cached value was derived from: the content of the lock and/or composer.json
+#[derive(Clone, PartialEq, Eq)]
+struct Fingerprint {
+    /// `None` when the kind doesn't depend on the lock, `Some(None)` when it can't be read
+    lock_hash: Option<Option<Vec<u8>>>,
+    manifest_hash: Option<Option<Vec<u8>>>,
+}
+
+struct CacheEntry {
+    fingerprint: Fingerprint,
+    value: Box<dyn Any + Send>,
+    last_used: Instant,
+}
+
+/// Return the `kind` value cached for `repo_path`, or compute and cache it. The entry is
+/// recomputed once the content of a file it is derived from has changed; errors are never cached.
+pub fn memoize<T, F>(repo_path: &Path, kind: Kind, compute: F) -> Result<T>
+where
+    T: Clone + Send + 'static,
+    F: FnOnce() -> Result<T>,
+{
+    let key = (repo_path.canonicalize().unwrap_or_else(|_| repo_path.to_path_buf()), kind);
+    // Taken before computing, so a file changed mid-parse invalidates the entry on the next call
+    let fingerprint = fingerprint(repo_path, kind);
+
+    if let Some(entry) = CACHE.lock().unwrap().get_mut(&key).filter(|e| e.fingerprint == fingerprint) {
+        if let Some(value) = entry.value.downcast_ref::<T>() {
+            entry.last_used = Instant::now();
+            return Ok(value.clone());
+        }
+    }
+
+    // Computed without holding the lock, so calls on other repositories aren't held up
+    let value = compute()?;
+
+    let mut cache = CACHE.lock().unwrap();
+    if cache.len() >= MAX_ENTRIES && !cache.contains_key(&key) {
+        if let Some(oldest) = cache.iter().min_by_key(|(_, e)| e.last_used).map(|(k, _)| k.clone()) {
+            cache.remove(&oldest);
+        }
+    }
+    cache.insert(key, CacheEntry { fingerprint, value: Box::new(value.clone()), last_used: Instant::now() });
+    Ok(value)
+}
+
+fn fingerprint(repo_path: &Path, kind: Kind) -> Fingerprint {
+    // Content rather than mtime: a same-length edit can land within the mtime granularity
+    let lock_hash = (kind != Kind::ComposerJson).then(|| hash_file(&lock_path(repo_path)));
+    let manifest_hash = (kind != Kind::ComposerLock).then(|| hash_file(&repo_path.join("composer.json")));
+
+    Fingerprint { lock_hash, manifest_hash }
+}
+
+/// SHA-256 of a file, streamed through the hasher so a large lock isn't held in memory
+fn hash_file(path: &Path) -> Option<Vec<u8>> {
+    with_io_retry(|| {
+        let mut hasher = Sha256::new();
+        io::copy(&mut File::open(path)?, &mut hasher)?;
+        Ok(hasher.finalize().to_vec())
+    })
+    .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_entries_follow_only_their_own_files() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        fs::write(dir.join("composer.json"), "{}").unwrap();
+        fs::write(dir.join("composer.lock"), r#"{"packages": []}"#).unwrap();
+
+        let mut computed = Vec::new();
+        for kind in [Kind::ComposerJson, Kind::ComposerLock, Kind::ComposerJson, Kind::ComposerLock] {
+            memoize(dir, kind, || {
+                computed.push(kind);
+                Ok(computed.len())
+            })
+            .unwrap();
+        }
+        // A new lock leaves the composer.json entry alone
+        fs::write(dir.join("composer.lock"), r#"{"packages": [{"name": "a/b"}]}"#).unwrap();
+        for kind in [Kind::ComposerJson, Kind::ComposerLock] {
+            memoize(dir, kind, || {
+                computed.push(kind);
+                Ok(computed.len())
+            })
+            .unwrap();
+        }
+
+        assert_eq!(computed, [Kind::ComposerJson, Kind::ComposerLock, Kind::ComposerLock]);
+    }
+
+    #[test]
+    fn test_same_length_manifest_edit_invalidates() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        fs::write(dir.join("composer.json"), r#"{"require": {"php": "^7.4"}}"#).unwrap();
+        let first = memoize(dir, Kind::ComposerJson, || Ok("^7.4")).unwrap();
+
+        // Same length, and likely the same mtime tick
+        fs::write(dir.join("composer.json"), r#"{"require": {"php": "^8.1"}}"#).unwrap();
+        let second = memoize(dir, Kind::ComposerJson, || Ok("^8.1")).unwrap();
+
+        assert_eq!((first, second), ("^7.4", "^8.1"));
+    }
+}
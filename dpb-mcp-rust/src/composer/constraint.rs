@@ -0,0 +1,352 @@
+//! Composer version and constraint parsing
+//! Covers the constraint syntax used in practice: exact, comparison, ^, ~, wildcards,
+//! hyphen ranges, AND (space/comma), OR (||), `@stability` flags and `dev-` branches.
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::cmp::Ordering;
+
+lazy_static! {
+    static ref VERSION_RE: Regex = Regex::new(
+        r"(?i)^v?(\d+)(?:\.(\d+))?(?:\.(\d+))?(?:\.(\d+))?(?:[._-]?(stable|beta|b|rc|alpha|a|patch|pl|p)(?:[.-]?(\d+))?)?(?:[.-]?dev)?$"
+    )
+    .unwrap();
+    static ref WILDCARD_RE: Regex = Regex::new(r"(?i)^v?(\d+)(?:\.(\d+))?(?:\.(\d+))?\.[x*]$").unwrap();
+    static ref HYPHEN_RE: Regex = Regex::new(r"(\S+)\s+-\s+(\S+)").unwrap();
+    static ref OPERATOR_SPACE_RE: Regex = Regex::new(r"(>=|<=|!=|==|<>|[<>=^~])\s+").unwrap();
+}
+
+/// Release stability, ordered from least to most stable
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Stability {
+    Dev,
+    Alpha,
+    Beta,
+    RC,
+    Stable,
+    /// `-patchN` releases sort after the stable release they patch
+    Patch,
+}
+
+impl Stability {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "dev" => Some(Stability::Dev),
+            "alpha" | "a" => Some(Stability::Alpha),
+            "beta" | "b" => Some(Stability::Beta),
+            "rc" => Some(Stability::RC),
+            "stable" => Some(Stability::Stable),
+            "patch" | "pl" | "p" => Some(Stability::Patch),
+            _ => None,
+        }
+    }
+
+    /// Whether a release of this stability is installable under the given `minimum-stability`
+    pub fn accepted_by(self, minimum: Stability) -> bool {
+        self.min(Stability::Stable) >= minimum.min(Stability::Stable)
+    }
+}
+
+/// A parsed Composer version. Branch versions (`dev-main`) only compare equal to themselves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version {
+    pub parts: [u64; 4],
+    pub stability: Stability,
+    pub pre_number: u64,
+    pub branch: Option<String>,
+}
+
+impl Version {
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+
+        if let Some(branch) = value.strip_prefix("dev-") {
+            return Some(Version {
+                parts: [0; 4],
+                stability: Stability::Dev,
+                pre_number: 0,
+                branch: Some(branch.to_lowercase()),
+            });
+        }
+
+        // 1.0.x-dev is Composer's name for the tip of the 1.0 branch
+        if let Some(base) = value.strip_suffix("-dev").filter(|b| WILDCARD_RE.is_match(b)) {
+            let caps = WILDCARD_RE.captures(base)?;
+            let mut parts = [9_999_999; 4];
+            for (i, part) in parts.iter_mut().enumerate().take(3) {
+                match caps.get(i + 1) {
+                    Some(m) => *part = m.as_str().parse().ok()?,
+                    None => break,
+                }
+            }
+            return Some(Version { parts, stability: Stability::Dev, pre_number: 0, branch: None });
+        }
+
+        let caps = VERSION_RE.captures(value)?;
+        let mut parts = [0u64; 4];
+        for (i, part) in parts.iter_mut().enumerate() {
+            if let Some(m) = caps.get(i + 1) {
+                *part = m.as_str().parse().ok()?;
+            }
+        }
+
+        let mut stability = caps
+            .get(5)
+            .and_then(|m| Stability::parse(m.as_str()))
+            .unwrap_or(Stability::Stable);
+        let pre_number = caps.get(6).and_then(|m| m.as_str().parse().ok()).unwrap_or(0);
+        if value.to_lowercase().ends_with("dev") {
+            stability = Stability::Dev;
+        }
+
+        Some(Version { parts, stability, pre_number, branch: None })
+    }
+
+    pub fn is_branch(&self) -> bool {
+        self.branch.is_some()
+    }
+
+    pub fn is_stable(&self) -> bool {
+        self.branch.is_none() && self.stability >= Stability::Stable
+    }
+
+    /// Lowest possible version of a numeric prefix, e.g. `1.2` -> `1.2.0.0-dev`
+    fn floor(parts: [u64; 4]) -> Self {
+        Version { parts, stability: Stability::Dev, pre_number: 0, branch: None }
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.parts
+            .cmp(&other.parts)
+            .then(self.stability.cmp(&other.stability))
+            .then(self.pre_number.cmp(&other.pre_number))
+            .then(self.branch.cmp(&other.branch))
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Atom {
+    Any,
+    Branch(String),
+    Eq(Version),
+    NotEq(Version),
+    Gt(Version),
+    Gte(Version),
+    Lt(Version),
+    Lte(Version),
+    /// Inclusive lower bound, exclusive upper bound
+    Range(Version, Version),
+}
+
+impl Atom {
+    fn matches(&self, version: &Version) -> bool {
+        if let Some(branch) = &version.branch {
+            return matches!(self, Atom::Branch(b) if b == branch) || matches!(self, Atom::Any);
+        }
+        match self {
+            Atom::Any => true,
+            Atom::Branch(_) => false,
+            Atom::Eq(v) => version == v,
+            Atom::NotEq(v) => version != v,
+            Atom::Gt(v) => version > v,
+            Atom::Gte(v) => version >= v,
+            Atom::Lt(v) => version < v,
+            Atom::Lte(v) => version <= v,
+            Atom::Range(low, high) => version >= low && version < high,
+        }
+    }
+}
+
+/// A parsed constraint: OR of AND-groups of atoms
+#[derive(Debug, Clone)]
+pub struct Constraint {
+    groups: Vec<Vec<Atom>>,
+    /// Lowest stability the constraint itself opts into (`@dev` flags or pre-release bounds)
+    stability: Option<Stability>,
+}
+
+impl Constraint {
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut stability: Option<Stability> = None;
+        let mut groups = Vec::new();
+
+        for group in value.split('|').map(str::trim).filter(|g| !g.is_empty()) {
+            // `dev-main as 1.0.x-dev` is an inline alias; the left side is what gets installed
+            let group = group.split(" as ").next().unwrap_or(group);
+            let group = OPERATOR_SPACE_RE.replace_all(group, "$1");
+            let group = HYPHEN_RE.replace_all(&group, "$1~HYPHEN~$2");
+
+            let mut atoms = Vec::new();
+            for token in group.split(|c: char| c == ',' || c.is_whitespace()).filter(|t| !t.is_empty()) {
+                let (token, flag) = match token.rsplit_once('@') {
+                    Some((t, f)) => (t, Stability::parse(f)),
+                    None => (token, None),
+                };
+                if let Some(flag) = flag {
+                    stability = Some(stability.map_or(flag, |s| s.min(flag)));
+                }
+                if token.is_empty() {
+                    atoms.push(Atom::Any);
+                    continue;
+                }
+
+                // Writing a pre-release version (`^2.0-beta1`, `1.0.x-dev`) opts into that stability
+                let written = token.trim_start_matches(['<', '>', '=', '!', '^', '~']);
+                if let Some(v) = written.split("~HYPHEN~").next().and_then(Version::parse) {
+                    if !v.is_branch() && v.stability < Stability::Stable {
+                        stability = Some(stability.map_or(v.stability, |s| s.min(v.stability)));
+                    }
+                }
+                atoms.push(parse_atom(token)?);
+            }
+            if !atoms.is_empty() {
+                groups.push(atoms);
+            }
+        }
+
+        if groups.is_empty() {
+            return None;
+        }
+        Some(Constraint { groups, stability })
+    }
+
+    pub fn matches(&self, version: &Version) -> bool {
+        self.groups.iter().any(|atoms| atoms.iter().all(|a| a.matches(version)))
+    }
+
+    /// Whether `version` both satisfies the constraint and meets the stability Composer
+    /// would accept, given the project's `minimum-stability`
+    pub fn accepts(&self, version: &Version, minimum_stability: Stability) -> bool {
+        if !self.matches(version) {
+            return false;
+        }
+        if version.is_branch() {
+            return true;
+        }
+        let minimum = self.stability.map_or(minimum_stability, |s| s.min(minimum_stability));
+        version.stability.accepted_by(minimum)
+    }
+}
+
+/// Number of numeric parts written in a version string, e.g. `1.2` -> 2
+fn precision(value: &str) -> usize {
+    let numeric = value.trim_start_matches(['v', 'V']);
+    let numeric = numeric.split(['-', '@']).next().unwrap_or(numeric);
+    numeric.split('.').take_while(|p| p.chars().all(|c| c.is_ascii_digit()) && !p.is_empty()).count().clamp(1, 4)
+}
+
+fn bump(parts: [u64; 4], index: usize) -> [u64; 4] {
+    let mut bumped = [0u64; 4];
+    bumped[..index].copy_from_slice(&parts[..index]);
+    bumped[index] = parts[index] + 1;
+    bumped
+}
+
+fn parse_atom(token: &str) -> Option<Atom> {
+    if token == "*" || token.eq_ignore_ascii_case("x") {
+        return Some(Atom::Any);
+    }
+    if let Some((low, high)) = token.split_once("~HYPHEN~") {
+        let low_v = Version::parse(low)?;
+        let high_v = Version::parse(high)?;
+        // A partial upper bound covers the whole prefix: `1.0 - 2.1` means `<2.2`
+        let upper = Version::floor(bump(high_v.parts, precision(high) - 1));
+        return Some(Atom::Range(Version::floor(low_v.parts), upper));
+    }
+    if let Some(branch) = token.strip_prefix("dev-") {
+        return Some(Atom::Branch(branch.to_lowercase()));
+    }
+    if let Some(caps) = WILDCARD_RE.captures(token) {
+        let specified: Vec<u64> = (1..=3)
+            .filter_map(|i| caps.get(i).and_then(|m| m.as_str().parse().ok()))
+            .collect();
+        let mut parts = [0u64; 4];
+        parts[..specified.len()].copy_from_slice(&specified);
+        return Some(Atom::Range(Version::floor(parts), Version::floor(bump(parts, specified.len() - 1))));
+    }
+    if let Some(rest) = token.strip_prefix('^') {
+        let v = Version::parse(rest)?;
+        let n = precision(rest);
+        let index = v.parts[..n].iter().position(|p| *p != 0).unwrap_or(n - 1);
+        return Some(Atom::Range(lower_bound(rest, v.clone()), Version::floor(bump(v.parts, index))));
+    }
+    if let Some(rest) = token.strip_prefix('~') {
+        let v = Version::parse(rest)?;
+        let index = precision(rest).saturating_sub(2);
+        return Some(Atom::Range(lower_bound(rest, v.clone()), Version::floor(bump(v.parts, index))));
+    }
+
+    for (op, ctor) in [
+        (">=", Atom::Gte as fn(Version) -> Atom),
+        ("<=", Atom::Lte),
+        ("!=", Atom::NotEq),
+        ("<>", Atom::NotEq),
+        ("==", Atom::Eq),
+        (">", Atom::Gt),
+        ("<", Atom::Lt),
+        ("=", Atom::Eq),
+    ] {
+        if let Some(rest) = token.strip_prefix(op) {
+            let v = Version::parse(rest)?;
+            // Open bounds without an explicit stability include/exclude the pre-releases of that version
+            let v = match op {
+                ">=" | "<" => lower_bound(rest, v),
+                _ => v,
+            };
+            return Some(ctor(v));
+        }
+    }
+
+    Version::parse(token).map(Atom::Eq)
+}
+
+/// `^1.2` and `>=1.2` start at `1.2.0.0-dev` unless a pre-release was written explicitly
+fn lower_bound(written: &str, v: Version) -> Version {
+    if v.stability == Stability::Stable && !written.to_lowercase().contains("stable") {
+        Version::floor(v.parts)
+    } else {
+        v
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allows(constraint: &str, version: &str) -> bool {
+        Constraint::parse(constraint)
+            .unwrap()
+            .accepts(&Version::parse(version).unwrap(), Stability::Stable)
+    }
+
+    #[test]
+    fn test_constraints() {
+        assert!(allows("^1.2", "1.9.3"));
+        assert!(!allows("^1.2", "2.0.0"));
+        assert!(!allows("^1.2", "1.1.0"));
+        assert!(allows("^0.3", "0.3.9"));
+        assert!(!allows("^0.3", "0.4.0"));
+        assert!(allows("~1.2", "1.9.0"));
+        assert!(!allows("~1.2.3", "1.3.0"));
+        assert!(allows("1.2.*", "v1.2.7"));
+        assert!(allows(">=1.0 <2.0", "1.5.0"));
+        assert!(!allows(">=1.0, <2.0", "2.0.0-beta1"));
+        assert!(allows("^1.0 || ^2.0", "2.3.0"));
+        assert!(allows("1.0 - 2.1", "2.1.5"));
+        assert!(!allows("1.0 - 2.1", "2.2.0"));
+        assert!(allows("dev-main", "dev-main"));
+        assert!(!allows("^2.0", "2.1.0-beta1"));
+        assert!(allows("^2.0@beta", "2.1.0-beta1"));
+        assert!(allows("2.1.0-RC1", "2.1.0-rc1"));
+        assert!(Version::parse("2.10.0") > Version::parse("2.9.1"));
+        assert!(Version::parse("1.0.0-beta2") > Version::parse("1.0.0-alpha3"));
+    }
+}
@@ -1,32 +1,281 @@
-use anyhow::{Context, Result};
+pub mod cache;
+pub mod constraint;
+pub mod schema;
+
+use anyhow::{anyhow, Context, Result};
+use flate2::read::GzDecoder;
+use regex::Regex;
+use serde::Deserialize;
 use std::collections::HashMap;
-use std::fs;
-use std::path::Path;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use crate::types::{
+    AbandonedField, ComposerJson, ComposerLock, InlinePackage, LicenseField, PackageInfo, Psr4Mapping, Psr4Path,
+    RepositoriesField,
+};
 
-use crate::types::{ComposerJson, ComposerLock, LicenseField, Psr4Mapping, Psr4Path};
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const INSTALLED_JSON: &str = "vendor/composer/installed.json";
+const DEFAULT_IO_RETRIES: u32 = 3;
+/// Upper bound on `DPB_IO_RETRIES`, so a typo can't turn a dead mount into an endless wait
+const MAX_IO_RETRIES: u32 = 10;
+const IO_RETRY_BASE_DELAY: Duration = Duration::from_millis(50);
+const IO_RETRY_MAX_DELAY: Duration = Duration::from_secs(5);
 
+/// Read composer.json, reusing the parse from an earlier call while the file is unchanged
 pub fn read_composer_json<P: AsRef<Path>>(repo_path: P) -> Result<ComposerJson> {
+    cache::memoize(repo_path.as_ref(), cache::Kind::ComposerJson, || read_composer_json_uncached(&repo_path))
+}
+
+fn read_composer_json_uncached<P: AsRef<Path>>(repo_path: P) -> Result<ComposerJson> {
     let composer_path = repo_path.as_ref().join("composer.json");
-    let contents = fs::read_to_string(&composer_path)
+    let bytes = with_io_retry(|| fs::read(&composer_path))
         .with_context(|| format!("Failed to read composer.json at {:?}", composer_path))?;
+    let contents = decode_text(bytes).map_err(|e| {
+        anyhow!("Invalid encoding in composer.json at {:?}: {}; re-save it as UTF-8", composer_path, e)
+    })?;
+
+    parse_composer_json(&contents)
+}
+
+/// Decode a text file saved by any common editor: UTF-8 with or without a BOM (Windows
+/// editors often add one), or UTF-16 marked by its BOM. Anything else is rejected instead
+/// of being parsed as mojibake.
+pub fn decode_text(bytes: Vec<u8>) -> Result<String> {
+    let utf16 = |body: &[u8], from_bytes: fn([u8; 2]) -> u16| -> Result<String> {
+        if !body.len().is_multiple_of(2) {
+            return Err(anyhow!("UTF-16 content has an odd number of bytes"));
+        }
+        let units: Vec<u16> = body.chunks_exact(2).map(|c| from_bytes([c[0], c[1]])).collect();
+        String::from_utf16(&units).map_err(|_| anyhow!("UTF-16 content contains unpaired surrogates"))
+    };
+
+    match bytes.as_slice() {
+        [0xEF, 0xBB, 0xBF, rest @ ..] => String::from_utf8(rest.to_vec()).map_err(utf8_error),
+        [0xFF, 0xFE, 0x00, 0x00, ..] | [0x00, 0x00, 0xFE, 0xFF, ..] => Err(anyhow!("UTF-32 is not supported")),
+        [0xFF, 0xFE, rest @ ..] => utf16(rest, u16::from_le_bytes),
+        [0xFE, 0xFF, rest @ ..] => utf16(rest, u16::from_be_bytes),
+        // UTF-16 without a BOM puts a NUL next to every ASCII character, e.g. `{\0`
+        [0x00, _, ..] | [_, 0x00, ..] => Err(anyhow!("Looks like UTF-16 without a byte order mark")),
+        _ => String::from_utf8(bytes).map_err(utf8_error),
+    }
+}
+
+fn utf8_error(err: std::string::FromUtf8Error) -> anyhow::Error {
+    anyhow!("Not valid UTF-8 at byte {}", err.utf8_error().valid_up_to())
+}
+
+/// Run a filesystem operation, retrying errors that network mounts (NFS, SMB) raise
+/// transiently with exponential backoff. Not-found, permission and other definite
+/// errors fail immediately. The retry count comes from `DPB_IO_RETRIES` (default 3, at most
+/// 10) and no single wait exceeds 5 seconds.
+pub fn with_io_retry<T>(mut op: impl FnMut() -> io::Result<T>) -> io::Result<T> {
+    let retries = std::env::var("DPB_IO_RETRIES")
+        .ok()
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(DEFAULT_IO_RETRIES)
+        .min(MAX_IO_RETRIES);
 
-    let composer: ComposerJson = serde_json::from_str(&contents)
+    let mut attempt = 0;
+    loop {
+        match op() {
+            Err(e) if attempt < retries && is_transient_io_error(&e) => {
+                thread::sleep(retry_delay(attempt));
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Backoff before retry number `attempt + 1`: doubling from the base delay, capped
+fn retry_delay(attempt: u32) -> Duration {
+    IO_RETRY_BASE_DELAY.saturating_mul(2u32.saturating_pow(attempt)).min(IO_RETRY_MAX_DELAY)
+}
+
+/// `fs::read_to_string` with [`with_io_retry`]
+pub fn read_to_string_with_retry<P: AsRef<Path>>(path: P) -> io::Result<String> {
+    with_io_retry(|| fs::read_to_string(path.as_ref()))
+}
+
+fn is_transient_io_error(err: &io::Error) -> bool {
+    // EIO and ESTALE have no stable ErrorKind; both clear up on NFS once the server responds again
+    const EIO: i32 = 5;
+    const ESTALE: i32 = 116;
+
+    matches!(
+        err.kind(),
+        io::ErrorKind::Interrupted
+            | io::ErrorKind::WouldBlock
+            | io::ErrorKind::TimedOut
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::ConnectionAborted
+    ) || (cfg!(unix) && matches!(err.raw_os_error(), Some(EIO) | Some(ESTALE)))
+}
+
+/// Parse composer.json from a string, e.g. content sent by a client without filesystem access
+pub fn parse_composer_json(contents: &str) -> Result<ComposerJson> {
+    let mut composer: ComposerJson = serde_json::from_str(contents.trim_start_matches('\u{feff}'))
         .with_context(|| "Failed to parse composer.json")?;
 
+    normalize_requires(&mut composer.require);
+    normalize_requires(&mut composer.require_dev);
+    normalize_requires(&mut composer.conflict);
+
     Ok(composer)
 }
 
+/// Read composer.lock, falling back to a gzipped `composer.lock.gz` sibling and then to
+/// `vendor/composer/installed.json` for deployed artifacts that ship vendor without the lock.
+/// Either lock file is decompressed transparently when it starts with the gzip magic bytes.
+/// The file is deserialized straight from a buffered reader so large locks are never
+/// held in memory as a string as well as parsed, and the parse is reused by later calls
+/// while the file's content is unchanged.
 pub fn read_composer_lock<P: AsRef<Path>>(repo_path: P) -> Result<ComposerLock> {
-    let lock_path = repo_path.as_ref().join("composer.lock");
-    let contents = fs::read_to_string(&lock_path)
-        .with_context(|| format!("Failed to read composer.lock at {:?}", lock_path))?;
+    cache::memoize(repo_path.as_ref(), cache::Kind::ComposerLock, || read_composer_lock_uncached(&repo_path))
+}
 
-    let lock: ComposerLock = serde_json::from_str(&contents)
+/// The file [`read_composer_lock`] reads: composer.lock, else its gzipped sibling, else
+/// installed.json; composer.lock when none exists
+pub fn lock_path<P: AsRef<Path>>(repo_path: P) -> PathBuf {
+    let repo_path = repo_path.as_ref();
+    [repo_path.join("composer.lock"), repo_path.join("composer.lock.gz"), repo_path.join(INSTALLED_JSON)]
+        .into_iter()
+        .find(|p| p.exists())
+        .unwrap_or_else(|| repo_path.join("composer.lock"))
+}
+
+fn read_composer_lock_uncached<P: AsRef<Path>>(repo_path: P) -> Result<ComposerLock> {
+    let lock_path = lock_path(&repo_path);
+    if lock_path.ends_with(INSTALLED_JSON) {
+        return read_installed_json(&lock_path);
+    }
+
+    // A read can fail partway through on a network mount, so the whole parse is retried
+    let mut lock = with_io_retry(|| parse_lock_file(&lock_path))
+        .with_context(|| format!("Failed to read or parse composer.lock at {:?}", lock_path))?;
+
+    normalize_lock(&mut lock);
+    Ok(lock)
+}
+
+/// One attempt at reading a (possibly gzipped) lock file. Read errors keep their kind so
+/// transient ones can be retried; parse errors become `InvalidData`.
+fn parse_lock_file(path: &Path) -> io::Result<ComposerLock> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let lock = if reader.fill_buf()?.starts_with(&GZIP_MAGIC) {
+        serde_json::from_reader(BufReader::new(GzDecoder::new(reader)))?
+    } else {
+        serde_json::from_reader(reader)?
+    };
+    Ok(lock)
+}
+
+/// Installed packages as written by Composer 2 (`{"packages": [...]}`) or Composer 1 (a bare array)
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum InstalledJson {
+    V2 {
+        packages: Vec<PackageInfo>,
+        #[serde(rename = "dev-package-names", default)]
+        dev_package_names: Vec<String>,
+    },
+    V1(Vec<PackageInfo>),
+}
+
+/// Read `vendor/composer/installed.json` into the lock structure. Composer 1 doesn't
+/// record which packages are dev-only, so they all count as production there.
+fn read_installed_json(path: &Path) -> Result<ComposerLock> {
+    let installed: InstalledJson =
+        with_io_retry(|| Ok(serde_json::from_reader(BufReader::new(File::open(path)?))?))
+            .with_context(|| format!("Failed to read or parse {:?}", path))?;
+
+    let (packages, dev_names) = match installed {
+        InstalledJson::V2 { packages, dev_package_names } => (packages, dev_package_names),
+        InstalledJson::V1(packages) => (packages, Vec::new()),
+    };
+    let dev_names: Vec<String> = dev_names.iter().map(|n| normalize_package_name(n)).collect();
+    let (packages_dev, packages): (Vec<PackageInfo>, Vec<PackageInfo>) = packages
+        .into_iter()
+        .partition(|p| dev_names.contains(&normalize_package_name(&p.name)));
+
+    let mut lock = ComposerLock {
+        packages,
+        packages_dev: Some(packages_dev),
+        content_hash: None,
+    };
+    normalize_lock(&mut lock);
+    Ok(lock)
+}
+
+/// Parse composer.lock from a string
+pub fn parse_composer_lock(contents: &str) -> Result<ComposerLock> {
+    let mut lock: ComposerLock = serde_json::from_str(contents)
         .with_context(|| "Failed to parse composer.lock")?;
 
+    normalize_lock(&mut lock);
     Ok(lock)
 }
 
+fn normalize_lock(lock: &mut ComposerLock) {
+    for pkg in lock.packages.iter_mut().chain(lock.packages_dev.iter_mut().flatten()) {
+        pkg.name = normalize_package_name(&pkg.name);
+        normalize_requires(&mut pkg.require);
+        normalize_requires(&mut pkg.require_dev);
+        normalize_requires(&mut pkg.conflict);
+        if let Some(AbandonedField::Replacement(replacement)) = &mut pkg.abandoned {
+            *replacement = normalize_package_name(replacement);
+        }
+    }
+}
+
+/// Package names are case-insensitive on Packagist; everything is compared in lowercase
+pub fn normalize_package_name(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+/// Match a package name against an exact name or a glob such as `symfony/*`, case-insensitively
+pub fn package_name_matches(pattern: &str, name: &str) -> bool {
+    let options = glob::MatchOptions {
+        case_sensitive: false,
+        ..Default::default()
+    };
+    glob::Pattern::new(pattern)
+        .map(|p| p.matches_with(name, options))
+        .unwrap_or(pattern.eq_ignore_ascii_case(name))
+}
+
+/// Compile the root `exclude-from-classmap` entries (autoload and autoload-dev) the way Composer
+/// does: relative to the project root, `*` within a path segment and `**` across segments.
+/// The patterns match repository-relative paths using `/` separators.
+pub fn classmap_exclusions(composer: &ComposerJson) -> Vec<Regex> {
+    [&composer.autoload, &composer.autoload_dev]
+        .into_iter()
+        .flatten()
+        .flat_map(|a| a.exclude_from_classmap.iter().flatten())
+        .filter_map(|path| {
+            let path = path.replace('\\', "/");
+            let path = path.trim_start_matches("./").trim_matches('/');
+            let pattern = regex::escape(path).replace(r"\*\*", ".+?").replace(r"\*", "[^/]+?");
+            Regex::new(&format!("^{}($|/)", pattern)).ok()
+        })
+        .collect()
+}
+
+fn normalize_requires(requires: &mut Option<HashMap<String, String>>) {
+    if let Some(map) = requires.take() {
+        *requires = Some(
+            map.into_iter()
+                .map(|(name, constraint)| (normalize_package_name(&name), constraint))
+                .collect(),
+        );
+    }
+}
+
 pub fn get_psr4_mappings(composer: &ComposerJson) -> Vec<Psr4Mapping> {
     let mut mappings = Vec::new();
 
@@ -61,11 +310,13 @@ pub fn get_psr4_mappings(composer: &ComposerJson) -> Vec<Psr4Mapping> {
     mappings
 }
 
+/// Mapping paths with `/` separators, as Composer accepts `src\\Foo` from Windows authors too
 fn normalize_paths(paths: &Psr4Path) -> Vec<String> {
-    match paths {
+    let paths = match paths {
         Psr4Path::Single(s) => vec![s.clone()],
         Psr4Path::Multiple(v) => v.clone(),
-    }
+    };
+    paths.into_iter().map(|p| p.replace('\\', "/")).collect()
 }
 
 pub fn get_licenses(composer: &ComposerJson) -> Vec<String> {
@@ -76,34 +327,207 @@ pub fn get_licenses(composer: &ComposerJson) -> Vec<String> {
     }
 }
 
-pub fn calculate_expected_namespace(base_namespace: &str, relative_file_path: &str) -> String {
-    // Remove .php extension
-    let without_ext = relative_file_path.trim_end_matches(".php");
+/// `config.platform` overrides: platform package -> the version Composer resolves against,
+/// or `None` where the override is `false` (treated as not installed)
+pub fn get_platform_overrides(composer: &ComposerJson) -> HashMap<String, Option<String>> {
+    let Some(serde_json::Value::Object(platform)) = composer.config.as_ref().and_then(|c| c.get("platform")) else {
+        return HashMap::new();
+    };
+    platform
+        .iter()
+        .filter_map(|(name, value)| match value {
+            serde_json::Value::String(version) => Some((name.to_lowercase(), Some(version.clone()))),
+            serde_json::Value::Bool(false) => Some((name.to_lowercase(), None)),
+            _ => None,
+        })
+        .collect()
+}
 
-    // Split by directory separator
-    let parts: Vec<&str> = without_ext.split('/').collect();
+/// Where Composer installs packages: `config.vendor-dir`, relative to the project, or `vendor`
+pub fn get_vendor_dir(composer: &ComposerJson) -> String {
+    composer
+        .config
+        .as_ref()
+        .and_then(|c| c.get("vendor-dir"))
+        .and_then(|v| v.as_str())
+        .map(|dir| dir.trim_end_matches('/').to_string())
+        .filter(|dir| !dir.is_empty())
+        .unwrap_or_else(|| "vendor".to_string())
+}
 
-    // Remove the filename (last part) to get directory structure
-    let dir_parts = if parts.len() > 1 {
-        &parts[..parts.len() - 1]
-    } else {
-        &[]
+/// Collect packages defined inline by `"type": "package"` repositories.
+/// These never exist on Packagist, so they must not be looked up remotely.
+pub fn get_inline_packages(composer: &ComposerJson) -> Vec<InlinePackage> {
+    let repositories: Vec<&serde_json::Value> = match &composer.repositories {
+        Some(RepositoriesField::List(v)) => v.iter().collect(),
+        Some(RepositoriesField::Map(m)) => m.values().collect(),
+        None => return Vec::new(),
     };
 
-    // Build expected namespace
-    let namespace = base_namespace.trim_end_matches('\\');
+    let mut packages: HashMap<String, Vec<String>> = HashMap::new();
 
-    if dir_parts.is_empty() {
-        namespace.to_string()
-    } else {
-        let sub_namespace = dir_parts.join("\\");
-        format!("{}\\{}", namespace, sub_namespace)
+    for repo in repositories {
+        if repo.get("type").and_then(|t| t.as_str()) != Some("package") {
+            continue;
+        }
+
+        // "package" may hold a single definition or a list of versions
+        let definitions: Vec<&serde_json::Value> = match repo.get("package") {
+            Some(serde_json::Value::Array(list)) => list.iter().collect(),
+            Some(def) => vec![def],
+            None => continue,
+        };
+
+        for def in definitions {
+            let name = def.get("name").and_then(|v| v.as_str());
+            let version = def.get("version").and_then(|v| v.as_str());
+            if let (Some(name), Some(version)) = (name, version) {
+                packages
+                    .entry(normalize_package_name(name))
+                    .or_default()
+                    .push(version.to_string());
+            }
+        }
+    }
+
+    let mut result: Vec<InlinePackage> = packages
+        .into_iter()
+        .map(|(name, versions)| InlinePackage { name, versions })
+        .collect();
+    result.sort_by(|a, b| a.name.cmp(&b.name));
+    result
+}
+
+/// Namespace PSR-4 expects for a file, given the mapping's namespace prefix and the file's
+/// path relative to the mapped directory. Accepts `\` separators, `./` prefixes and doubled
+/// slashes, so `src` and `src/` mappings and Windows paths give the same result.
+pub fn calculate_expected_namespace(base_namespace: &str, relative_file_path: &str) -> String {
+    let normalized = relative_file_path.replace('\\', "/");
+
+    // Directory segments only; the last segment is the file name
+    let mut parts: Vec<&str> = normalized
+        .split('/')
+        .filter(|p| !p.is_empty() && *p != ".")
+        .collect();
+    parts.pop();
+
+    let namespace = base_namespace.trim_matches('\\');
+    match (namespace.is_empty(), parts.is_empty()) {
+        (_, true) => namespace.to_string(),
+        (true, false) => parts.join("\\"),
+        (false, false) => format!("{}\\{}", namespace, parts.join("\\")),
     }
 }
 
+/// Whether a require key names a platform package (php, extensions, system libs)
+/// rather than an installable Composer package
+pub fn is_platform_package(name: &str) -> bool {
+    // Real packages are always vendor/name; this keeps php-http/* and phpunit/* out
+    if name.contains('/') {
+        return false;
+    }
+    name == "php"
+        || name.starts_with("php-")
+        || name.starts_with("ext-")
+        || name.starts_with("lib-")
+        || name == "composer-plugin-api"
+        || name == "composer-runtime-api"
+}
+
+/// Whether `name` has Composer's `vendor/package` shape, so joining it onto the vendor
+/// directory stays inside it (a lock could otherwise name `../../etc`)
+pub fn is_valid_package_name(name: &str) -> bool {
+    let segment = |s: &str| {
+        s.starts_with(|c: char| c.is_ascii_alphanumeric())
+            && s.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+    };
+    matches!(name.split_once('/'), Some((vendor, package)) if segment(vendor) && segment(package))
+}
+
 pub fn filter_php_dependencies(deps: &HashMap<String, String>) -> HashMap<String, String> {
     deps.iter()
-        .filter(|(name, _)| !name.starts_with("php") && !name.starts_with("ext-"))
+        .filter(|(name, _)| !is_platform_package(name))
         .map(|(k, v)| (k.clone(), v.clone()))
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expected_namespace_normalizes_paths() {
+        for path in [
+            "Http/Controller/Home.php",
+            "Http\\Controller\\Home.php",
+            "./Http/Controller/Home.php",
+            ".\\Http\\Controller\\Home.php",
+            "/Http//Controller/Home.php",
+        ] {
+            assert_eq!(calculate_expected_namespace("App\\", path), "App\\Http\\Controller", "{}", path);
+        }
+
+        assert_eq!(calculate_expected_namespace("App\\", "Kernel.php"), "App");
+        assert_eq!(calculate_expected_namespace("App", ".\\Kernel.php"), "App");
+        assert_eq!(calculate_expected_namespace("", "Legacy\\Util.php"), "Legacy");
+    }
+
+    #[test]
+    fn test_valid_package_names() {
+        for name in ["monolog/monolog", "symfony/polyfill-php80", "acme/lib.core", "Acme/Lib_2"] {
+            assert!(is_valid_package_name(name), "{}", name);
+        }
+        for name in ["../etc", "acme/..", "acme/../../x", "acme/lib/extra", "/etc", "acme/", "php", "acme/.hidden"] {
+            assert!(!is_valid_package_name(name), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_io_retry_backoff_is_capped() {
+        assert_eq!(retry_delay(0), IO_RETRY_BASE_DELAY);
+        assert_eq!(retry_delay(2), IO_RETRY_BASE_DELAY * 4);
+        assert_eq!(retry_delay(40), IO_RETRY_MAX_DELAY);
+        assert_eq!(retry_delay(u32::MAX), IO_RETRY_MAX_DELAY);
+    }
+
+    #[test]
+    fn test_io_retry_repeats_only_transient_errors() {
+        let mut calls = 0;
+        let result = with_io_retry(|| {
+            calls += 1;
+            if calls < 3 { Err(io::Error::from(io::ErrorKind::Interrupted)) } else { Ok(calls) }
+        });
+        assert_eq!(result.unwrap(), 3);
+
+        let mut calls = 0;
+        let result: io::Result<()> = with_io_retry(|| {
+            calls += 1;
+            Err(io::Error::from(io::ErrorKind::InvalidData))
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_decode_text_handles_bom_and_utf16() {
+        let json = "{\"name\": \"acme/café\"}";
+        let with_bom = [&[0xEF, 0xBB, 0xBF][..], json.as_bytes()].concat();
+        let utf16le: Vec<u8> = [0xFF, 0xFE].into_iter().chain(json.encode_utf16().flat_map(u16::to_le_bytes)).collect();
+        let utf16be: Vec<u8> = [0xFE, 0xFF].into_iter().chain(json.encode_utf16().flat_map(u16::to_be_bytes)).collect();
+
+        for bytes in [json.as_bytes().to_vec(), with_bom, utf16le, utf16be] {
+            assert_eq!(decode_text(bytes).unwrap(), json);
+        }
+        assert!(decode_text(json.encode_utf16().flat_map(u16::to_le_bytes).collect()).is_err());
+        assert!(decode_text(vec![b'{', 0xC3, b'}']).is_err());
+    }
+
+    #[test]
+    fn test_empty_list_extra_parses_as_none() {
+        let composer = parse_composer_json(r#"{"name": "acme/app", "extra": []}"#).unwrap();
+        assert!(composer.extra.is_none());
+
+        let composer = parse_composer_json(r#"{"extra": {"patches-file": "patches.json"}}"#).unwrap();
+        assert_eq!(composer.extra.unwrap()["patches-file"], "patches.json");
+    }
+}
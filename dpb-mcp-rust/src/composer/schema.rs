@@ -0,0 +1,126 @@
+//! composer.json validation
+//! Checks the raw JSON against a bundled copy of Composer's schema rules, then
+//! the version constraints that the schema only types as strings
+
+use anyhow::{Context, Result};
+use jsonschema::JSONSchema;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+use super::constraint::Constraint;
+use super::{decode_text, with_io_retry};
+
+lazy_static! {
+    static ref COMPOSER_SCHEMA: JSONSchema = {
+        let schema: Value = serde_json::from_str(include_str!("composer-schema.json"))
+            .expect("bundled composer schema is valid JSON");
+        JSONSchema::compile(&schema).expect("bundled composer schema compiles")
+    };
+}
+
+/// Sections whose values are version constraints
+const LINK_SECTIONS: [&str; 5] = ["require", "require-dev", "conflict", "replace", "provide"];
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SchemaValidationResult {
+    pub valid: bool,
+    pub errors: Vec<SchemaViolation>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SchemaViolation {
+    /// JSON pointer to the offending value, e.g. `/autoload/psr-4`
+    pub path: String,
+    pub message: String,
+}
+
+/// Validate composer.json in `repo_path`, like `composer validate`
+pub fn validate_composer_json<P: AsRef<Path>>(repo_path: P) -> Result<SchemaValidationResult> {
+    let composer_path = repo_path.as_ref().join("composer.json");
+    let bytes = with_io_retry(|| fs::read(&composer_path))
+        .with_context(|| format!("Failed to read composer.json at {:?}", composer_path))?;
+
+    // Encoding and syntax errors are a validation result, not a tool failure
+    let invalid = |message: String| SchemaValidationResult {
+        valid: false,
+        errors: vec![SchemaViolation { path: String::new(), message }],
+    };
+    let contents = match decode_text(bytes) {
+        Ok(c) => c,
+        Err(e) => return Ok(invalid(format!("Unsupported encoding: {}; re-save it as UTF-8", e))),
+    };
+    let value: Value = match serde_json::from_str(&contents) {
+        Ok(v) => v,
+        Err(e) => return Ok(invalid(format!("Invalid JSON: {}", e))),
+    };
+
+    let errors = validate_composer_value(&value);
+    Ok(SchemaValidationResult { valid: errors.is_empty(), errors })
+}
+
+fn validate_composer_value(value: &Value) -> Vec<SchemaViolation> {
+    let mut errors: Vec<SchemaViolation> = match COMPOSER_SCHEMA.validate(value) {
+        Ok(()) => Vec::new(),
+        Err(schema_errors) => schema_errors
+            .map(|e| SchemaViolation {
+                path: e.instance_path.to_string(),
+                message: e.to_string(),
+            })
+            .collect(),
+    };
+
+    for section in LINK_SECTIONS {
+        let Some(links) = value.get(section).and_then(|v| v.as_object()) else {
+            continue;
+        };
+        for (package, constraint) in links {
+            let Some(constraint) = constraint.as_str() else {
+                continue; // already reported by the schema
+            };
+            if constraint.trim() == "self.version" || Constraint::parse(constraint).is_some() {
+                continue;
+            }
+            errors.push(SchemaViolation {
+                path: format!("/{}/{}", section, package.replace('~', "~0").replace('/', "~1")),
+                message: format!("Invalid version constraint \"{}\" for {}", constraint, package),
+            });
+        }
+    }
+
+    errors.sort_by(|a, b| a.path.cmp(&b.path));
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_schema_and_constraint_errors_are_reported_by_path() {
+        let errors = validate_composer_value(&json!({
+            "name": "Acme/App",
+            "require": {"php": "^8.1", "acme/lib": "not a constraint", "acme/self": "self.version"},
+            "unknown-key": true
+        }));
+        let paths: Vec<&str> = errors.iter().map(|e| e.path.as_str()).collect();
+
+        assert_eq!(paths, ["", "/name", "/require/acme~1lib"]);
+        assert!(errors[2].message.contains("not a constraint"));
+        assert!(validate_composer_value(&json!({"name": "acme/app", "require": {"php": ">=8.1 <9"}})).is_empty());
+    }
+
+    #[test]
+    fn test_unreadable_json_is_an_invalid_result() {
+        let temp = crate::test_support::temp_dir();
+        std::fs::write(temp.path().join("composer.json"), "{\"name\": ").unwrap();
+
+        let result = validate_composer_json(temp.path()).unwrap();
+
+        assert!(!result.valid);
+        assert!(result.errors[0].message.starts_with("Invalid JSON"));
+    }
+}
@@ -0,0 +1,240 @@
+//! Repository-level configuration loaded from `.dpb.json`
+//! Every section is optional; missing keys fall back to the built-in defaults.
+
+use anyhow::{anyhow, Context, Result};
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::analyzer::security::{severity_rank, SecurityConfig};
+
+const CONFIG_FILE: &str = ".dpb.json";
+
+lazy_static! {
+    static ref ENV_VAR_RE: Regex = Regex::new(r"\$(?:\{([A-Za-z_][A-Za-z0-9_]*)\}|([A-Za-z_][A-Za-z0-9_]*))").unwrap();
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DpbConfig {
+    pub security: SecurityConfig,
+    pub analysis: AnalysisConfig,
+    pub redaction: RedactionConfig,
+    pub overlaps: OverlapConfig,
+    pub health: HealthConfig,
+    pub reporting: ReportingConfig,
+}
+
+/// Output settings shared by every tool that reports findings with a severity
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReportingConfig {
+    /// Lowest severity reported: critical, high, medium, low or info (default: everything)
+    #[serde(rename = "minReportedSeverity")]
+    pub min_reported_severity: Option<String>,
+}
+
+/// Packages hidden from reports generated with `redact` (e.g. docs shared with vendors)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RedactionConfig {
+    /// Package names or vendor wildcards, e.g. `["mycompany/*"]`
+    pub patterns: Vec<String>,
+    /// Key for the placeholder hashes, so placeholders match across machines sharing it;
+    /// without one a random key kept in `~/.dpb/redaction-key` is used. Never shared in reports.
+    pub secret: Option<String>,
+}
+
+/// Extra "same role" package families for `find_overlapping_dependencies`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OverlapConfig {
+    /// Added to the built-in families; a family with a built-in role extends that family
+    pub families: Vec<PackageFamily>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageFamily {
+    /// What the packages are for, e.g. "HTTP client"
+    pub role: String,
+    /// Package names or vendor wildcards
+    pub packages: Vec<String>,
+}
+
+/// Settings for `overall_health`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HealthConfig {
+    pub weights: HealthWeights,
+}
+
+/// Category weights; they are relative, so they need not sum to 100. A zero weight drops the category
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HealthWeights {
+    pub security: f64,
+    pub licenses: f64,
+    pub psr4: f64,
+    pub staleness: f64,
+}
+
+impl Default for HealthWeights {
+    fn default() -> Self {
+        Self {
+            security: 40.0,
+            licenses: 20.0,
+            psr4: 20.0,
+            staleness: 20.0,
+        }
+    }
+}
+
+/// Settings for the source-code analyzers (namespaces, PSR-4)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnalysisConfig {
+    /// Extensions of files scanned as PHP source, e.g. `["php", "inc", "php5"]`
+    #[serde(rename = "phpExtensions")]
+    pub php_extensions: Vec<String>,
+    /// Most files parsed at once; unset parallelizes across all files. Lower it to
+    /// bound peak memory on large repositories at the cost of throughput
+    #[serde(rename = "maxParallelFiles")]
+    pub max_parallel_files: Option<usize>,
+}
+
+impl Default for AnalysisConfig {
+    fn default() -> Self {
+        Self {
+            php_extensions: vec!["php".to_string()],
+            max_parallel_files: None,
+        }
+    }
+}
+
+/// Load `.dpb.json` from the repository root, or defaults when it is absent
+pub fn load_config<P: AsRef<Path>>(repo_path: P) -> Result<DpbConfig> {
+    let config_path = repo_path.as_ref().join(CONFIG_FILE);
+    if !config_path.exists() {
+        return Ok(DpbConfig::default());
+    }
+
+    let contents = fs::read_to_string(&config_path)
+        .with_context(|| format!("Failed to read {:?}", config_path))?;
+    let config: DpbConfig = serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to parse {:?}", config_path))?;
+
+    Ok(config)
+}
+
+/// The severity floor as a rank for [`crate::analyzer::security::within_severity_floor`]:
+/// the per-call `override_severity`, else `reporting.minReportedSeverity`. "info" reports everything.
+pub fn severity_floor<P: AsRef<Path>>(repo_path: P, override_severity: Option<&str>) -> Result<Option<u8>> {
+    match override_severity {
+        Some(severity) => parse_severity_floor(severity),
+        None => match load_config(repo_path)?.reporting.min_reported_severity {
+            Some(severity) => parse_severity_floor(&severity),
+            None => Ok(None),
+        },
+    }
+}
+
+/// A severity floor given without a repository to configure it
+pub fn parse_severity_floor(severity: &str) -> Result<Option<u8>> {
+    let severity = severity.to_lowercase();
+    match (severity.as_str(), severity_rank(&severity)) {
+        ("info", _) => Ok(None),
+        (_, rank) if rank < 4 => Ok(Some(rank)),
+        _ => Err(anyhow!(
+            "Invalid minimum severity: {} (expected critical, high, medium, low or info)",
+            severity
+        )),
+    }
+}
+
+/// Expand a leading `~` and `$VAR` / `${VAR}` references so path configs work across machines
+pub fn expand_path(path: &str) -> Result<PathBuf> {
+    let home = || std::env::var("HOME").map_err(|_| anyhow!("Cannot expand ~ in {:?}: HOME is not set", path));
+    let path_with_home = if path == "~" {
+        home()?
+    } else if let Some(rest) = path.strip_prefix("~/") {
+        format!("{}/{}", home()?, rest)
+    } else {
+        path.to_string()
+    };
+
+    let mut missing = None;
+    let expanded = ENV_VAR_RE.replace_all(&path_with_home, |caps: &Captures| {
+        let name = caps.get(1).or_else(|| caps.get(2)).unwrap().as_str();
+        std::env::var(name).unwrap_or_else(|_| {
+            missing.get_or_insert_with(|| name.to_string());
+            String::new()
+        })
+    });
+
+    if let Some(name) = missing {
+        return Err(anyhow!("Cannot expand {:?}: environment variable {} is not set", path, name));
+    }
+
+    Ok(PathBuf::from(expanded.into_owned()))
+}
+
+/// Expand a configured path and require that it exists
+pub fn expand_existing_path(path: &str) -> Result<PathBuf> {
+    let expanded = expand_path(path)?;
+    if !expanded.exists() {
+        if expanded.as_os_str() == path {
+            return Err(anyhow!("Path {} not found", path));
+        }
+        return Err(anyhow!("Path {} (expanded from {}) not found", expanded.display(), path));
+    }
+    Ok(expanded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::EnvGuard;
+
+    #[test]
+    fn test_expand_path_home_and_variables() {
+        let mut env = EnvGuard::lock();
+        env.set("HOME", "/home/dev");
+        env.set("DPB_TEST_ROOT", "/srv/repos");
+
+        assert_eq!(expand_path("~").unwrap(), PathBuf::from("/home/dev"));
+        assert_eq!(expand_path("~/work/app").unwrap(), PathBuf::from("/home/dev/work/app"));
+        assert_eq!(expand_path("$DPB_TEST_ROOT/app").unwrap(), PathBuf::from("/srv/repos/app"));
+        assert_eq!(expand_path("${DPB_TEST_ROOT}_old/app").unwrap(), PathBuf::from("/srv/repos_old/app"));
+        // Only a leading ~ refers to the home directory
+        assert_eq!(expand_path("backup/~user").unwrap(), PathBuf::from("backup/~user"));
+    }
+
+    #[test]
+    fn test_expand_path_rejects_unset_variables() {
+        let mut env = EnvGuard::lock();
+        env.remove("DPB_TEST_UNSET");
+        env.remove("HOME");
+
+        let error = expand_path("$DPB_TEST_UNSET/app").unwrap_err().to_string();
+        assert_eq!(error, r#"Cannot expand "$DPB_TEST_UNSET/app": environment variable DPB_TEST_UNSET is not set"#);
+        assert!(expand_path("~/app").unwrap_err().to_string().contains("HOME is not set"));
+    }
+
+    #[test]
+    fn test_expand_existing_path_reports_the_expansion() {
+        let temp = crate::test_support::temp_dir();
+        let mut env = EnvGuard::lock();
+        env.set("DPB_TEST_ROOT", temp.path());
+
+        assert_eq!(expand_existing_path("$DPB_TEST_ROOT").unwrap(), temp.path());
+        let missing = expand_existing_path("${DPB_TEST_ROOT}/missing").unwrap_err().to_string();
+        assert_eq!(
+            missing,
+            format!("Path {}/missing (expanded from ${{DPB_TEST_ROOT}}/missing) not found", temp.path().display())
+        );
+        let plain = temp.path().join("missing").display().to_string();
+        assert_eq!(expand_existing_path(&plain).unwrap_err().to_string(), format!("Path {} not found", plain));
+    }
+}
@@ -0,0 +1,455 @@
+//! Message catalogs for human-facing report text
+//! Report generators take their headings, labels and sentences from the catalog of the
+//! requested locale. JSON field names and values are never translated.
+
+use anyhow::{anyhow, Result};
+use std::fmt::Display;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    En,
+    De,
+}
+
+impl Locale {
+    /// Parse a language tag such as `en`, `de`, `de-DE` or `de_AT`
+    pub fn parse(tag: &str) -> Result<Self> {
+        let language = tag.split(['-', '_']).next().unwrap_or_default().to_lowercase();
+        match language.as_str() {
+            "en" => Ok(Locale::En),
+            "de" => Ok(Locale::De),
+            _ => Err(anyhow!("Invalid locale: {} (expected en or de)", tag)),
+        }
+    }
+
+    /// ISO 639-1 code, e.g. for `<html lang>` and the MkDocs theme language
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::De => "de",
+        }
+    }
+
+    pub fn messages(self) -> &'static Messages {
+        match self {
+            Locale::En => &EN,
+            Locale::De => &DE,
+        }
+    }
+}
+
+/// Substitute each `{}` in a catalog template with the next argument
+pub fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut parts = template.split("{}");
+    let mut out = parts.next().unwrap_or_default().to_string();
+    for (index, part) in parts.enumerate() {
+        if let Some(arg) = args.get(index) {
+            out.push_str(&arg.to_string());
+        }
+        out.push_str(part);
+    }
+    out
+}
+
+/// One locale's strings. Fields holding `{}` are templates for [`fill`].
+pub struct Messages {
+    // Shared labels
+    pub generated: &'static str,
+    pub summary: &'static str,
+    pub package: &'static str,
+    pub version: &'static str,
+    pub count: &'static str,
+    pub percentage: &'static str,
+    pub license: &'static str,
+    pub not_specified: &'static str,
+    pub production_dependencies: &'static str,
+    pub development_dependencies: &'static str,
+    pub license_distribution: &'static str,
+    pub critical: &'static str,
+    pub high: &'static str,
+    pub medium: &'static str,
+    pub low: &'static str,
+
+    // Comprehensive docs
+    pub docs_title: &'static str,
+    pub project_information: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub project_type_short: &'static str,
+    pub unknown: &'static str,
+    pub not_available: &'static str,
+    pub dependency_summary: &'static str,
+    pub individual_tools_hint: &'static str,
+
+    // Multi-repo report
+    pub multi_repo_title: &'static str,
+    pub repositories_analyzed: &'static str,
+    pub team: &'static str,
+    pub total_unique_packages: &'static str,
+    pub shared_dependencies_count: &'static str,
+    pub version_conflicts_count: &'static str,
+    pub failed_repositories_count: &'static str,
+    pub cached_repositories_count: &'static str,
+    pub failed_repositories: &'static str,
+    pub failed_repositories_note: &'static str,
+    pub repository: &'static str,
+    pub path: &'static str,
+    pub error: &'static str,
+    pub shared_dependencies: &'static str,
+    pub used_by: &'static str,
+    pub version_conflicts: &'static str,
+    pub installed: &'static str,
+    pub php_platform_versions: &'static str,
+    pub php_constraint: &'static str,
+    pub lowest_version: &'static str,
+    pub repos: &'static str,
+    pub php_upgrade_blockers: &'static str,
+
+    // MkDocs site
+    pub default_site_description: &'static str,
+    pub home: &'static str,
+    pub quick_overview: &'static str,
+    pub project_type: &'static str,
+    pub documentation_sections: &'static str,
+    pub dependencies: &'static str,
+    pub dependencies_section: &'static str,
+    pub security: &'static str,
+    pub security_section: &'static str,
+    pub licenses: &'static str,
+    pub licenses_section: &'static str,
+    pub architecture: &'static str,
+    pub architecture_section: &'static str,
+    pub changelog: &'static str,
+    pub changelog_section: &'static str,
+    pub getting_started: &'static str,
+    pub generated_by: &'static str,
+    pub view_with_mkdocs: &'static str,
+    pub production: &'static str,
+    pub development: &'static str,
+    pub total: &'static str,
+    pub packages_count: &'static str,
+    pub and_more: &'static str,
+    pub dependency_graph: &'static str,
+    pub dependencies_tool_hint: &'static str,
+    pub security_audit: &'static str,
+    pub risk_level: &'static str,
+    pub total_issues: &'static str,
+    pub vulnerabilities: &'static str,
+    pub severity: &'static str,
+    pub status: &'static str,
+    pub no_vulnerabilities: &'static str,
+    pub and_more_vulnerabilities: &'static str,
+    pub security_tool_hint: &'static str,
+    pub license_compliance: &'static str,
+    pub total_packages: &'static str,
+    pub unique_licenses: &'static str,
+    pub no_license_declared: &'static str,
+    pub unrecognized_licenses: &'static str,
+    pub risk_rollup: &'static str,
+    pub risk_safe: &'static str,
+    pub risk_caution: &'static str,
+    pub risk_review_required: &'static str,
+    pub compatibility_issues: &'static str,
+    pub packages_without_license: &'static str,
+    pub packages_without_license_note: &'static str,
+    pub licenses_tool_hint: &'static str,
+    pub psr4_autoloading: &'static str,
+    pub total_mappings: &'static str,
+    pub files_analyzed: &'static str,
+    pub psr4_compliant: &'static str,
+    pub violations: &'static str,
+    pub mappings: &'static str,
+    pub namespace_prefix: &'static str,
+    pub directory: &'static str,
+    pub and_more_mappings: &'static str,
+    pub psr4_tool_hint: &'static str,
+    pub namespaces: &'static str,
+    pub namespaces_found: &'static str,
+    pub namespace_files: &'static str,
+    pub and_more_namespaces: &'static str,
+    pub no_namespaces: &'static str,
+    pub namespaces_tool_hint: &'static str,
+
+    // Agent suggestions
+    pub issue_title: &'static str,
+    pub merged_issues_title: &'static str,
+    pub abandoned_package_title: &'static str,
+    pub psr4_violation_title: &'static str,
+    pub stale_dependency_title: &'static str,
+    pub analysis_summary_title: &'static str,
+    pub no_issues_found: &'static str,
+    pub found_issue: &'static str,
+    pub found_issues: &'static str,
+    pub quick_commands: &'static str,
+    pub run_security_audit: &'static str,
+    pub update_all_dependencies: &'static str,
+}
+
+impl Messages {
+    /// Label for a severity key ("critical", "high", "medium", "low")
+    pub fn severity_label(&self, severity: &str) -> &'static str {
+        match severity {
+            "critical" => self.critical,
+            "high" => self.high,
+            "medium" => self.medium,
+            _ => self.low,
+        }
+    }
+}
+
+pub static EN: Messages = Messages {
+    generated: "Generated",
+    summary: "Summary",
+    package: "Package",
+    version: "Version",
+    count: "Count",
+    percentage: "Percentage",
+    license: "License",
+    not_specified: "Not specified",
+    production_dependencies: "Production Dependencies",
+    development_dependencies: "Development Dependencies",
+    license_distribution: "License Distribution",
+    critical: "Critical",
+    high: "High",
+    medium: "Medium",
+    low: "Low",
+
+    docs_title: "PHP Dependency Documentation",
+    project_information: "Project Information",
+    name: "Name",
+    description: "Description",
+    project_type_short: "Type",
+    unknown: "Unknown",
+    not_available: "N/A",
+    dependency_summary: "Dependency Summary",
+    individual_tools_hint: "For detailed analysis, use the individual tools:",
+
+    multi_repo_title: "Multi-Repository Dependency Analysis",
+    repositories_analyzed: "Repositories Analyzed",
+    team: "Team",
+    total_unique_packages: "Total unique packages",
+    shared_dependencies_count: "Shared dependencies",
+    version_conflicts_count: "Version conflicts",
+    failed_repositories_count: "Failed repositories",
+    cached_repositories_count: "Unchanged since last scan (cached)",
+    failed_repositories: "Failed Repositories",
+    failed_repositories_note: "These repositories were skipped and are not included in the analysis below.",
+    repository: "Repository",
+    path: "Path",
+    error: "Error",
+    shared_dependencies: "Shared Dependencies",
+    used_by: "Used By",
+    version_conflicts: "Version Conflicts",
+    installed: "installed",
+    php_platform_versions: "PHP Platform Versions",
+    php_constraint: "PHP Constraint",
+    lowest_version: "Lowest Version",
+    repos: "Repos",
+    php_upgrade_blockers: "Holding back a PHP upgrade",
+
+    default_site_description: "Dependency Analysis Documentation",
+    home: "Home",
+    quick_overview: "Quick Overview",
+    project_type: "Project Type",
+    documentation_sections: "Documentation Sections",
+    dependencies: "Dependencies",
+    dependencies_section: "Complete dependency analysis and tree",
+    security: "Security",
+    security_section: "Security audit and vulnerability report",
+    licenses: "Licenses",
+    licenses_section: "License compliance and distribution",
+    architecture: "Architecture",
+    architecture_section: "Namespace structure and PSR-4 compliance",
+    changelog: "Changelog",
+    changelog_section: "Dependency change history",
+    getting_started: "Getting Started",
+    generated_by: "This documentation was automatically generated by dependency-buster MCP.",
+    view_with_mkdocs: "To view with MkDocs:",
+    production: "Production",
+    development: "Development",
+    total: "Total",
+    packages_count: "{} packages",
+    and_more: "... and {} more",
+    dependency_graph: "Dependency Graph",
+    dependencies_tool_hint: "For detailed dependency information, use the `analyze_dependencies` tool.",
+    security_audit: "Security Audit",
+    risk_level: "Risk Level",
+    total_issues: "Total Issues",
+    vulnerabilities: "Vulnerabilities",
+    severity: "Severity",
+    status: "Status",
+    no_vulnerabilities: "No known vulnerabilities found.",
+    and_more_vulnerabilities: "... and {} more vulnerabilities",
+    security_tool_hint: "For detailed security information, use the `audit_security` tool.",
+    license_compliance: "License Compliance",
+    total_packages: "Total Packages",
+    unique_licenses: "Unique Licenses",
+    no_license_declared: "No License Declared",
+    unrecognized_licenses: "Unrecognized Licenses",
+    risk_rollup: "Risk Rollup",
+    risk_safe: "Safe",
+    risk_caution: "Caution",
+    risk_review_required: "Review Required",
+    compatibility_issues: "Compatibility Issues",
+    packages_without_license: "Packages Without a License",
+    packages_without_license_note: "These packages declare no license and are \"all rights reserved\" by default.",
+    licenses_tool_hint: "For detailed license information, use the `analyze_licenses` tool.",
+    psr4_autoloading: "PSR-4 Autoloading",
+    total_mappings: "Total Mappings",
+    files_analyzed: "Files Analyzed",
+    psr4_compliant: "PSR-4 Compliant",
+    violations: "Violations",
+    mappings: "Mappings",
+    namespace_prefix: "Namespace Prefix",
+    directory: "Directory",
+    and_more_mappings: "... and {} more mappings",
+    psr4_tool_hint: "For detailed PSR-4 information, use the `analyze_psr4` tool.",
+    namespaces: "Namespaces",
+    namespaces_found: "Found **{}** namespaces:",
+    namespace_files: "{} files",
+    and_more_namespaces: "... and {} more namespaces",
+    no_namespaces: "No namespaces detected.",
+    namespaces_tool_hint: "For detailed namespace information, use the `detect_namespaces` tool.",
+
+    issue_title: "{} Issue: {}",
+    merged_issues_title: "{} Issues: {}",
+    abandoned_package_title: "Abandoned Package: {}",
+    psr4_violation_title: "PSR-4 Violation: {}",
+    stale_dependency_title: "Stale Dependency: {}",
+    analysis_summary_title: "Dependency Analysis Summary",
+    no_issues_found: "No issues found",
+    found_issue: "Found {} issue: {}",
+    found_issues: "Found {} issues: {}",
+    quick_commands: "Quick commands:",
+    run_security_audit: "Run security audit",
+    update_all_dependencies: "Update all dependencies",
+};
+
+pub static DE: Messages = Messages {
+    generated: "Erstellt",
+    summary: "Zusammenfassung",
+    package: "Paket",
+    version: "Version",
+    count: "Anzahl",
+    percentage: "Anteil",
+    license: "Lizenz",
+    not_specified: "Nicht angegeben",
+    production_dependencies: "Produktionsabhängigkeiten",
+    development_dependencies: "Entwicklungsabhängigkeiten",
+    license_distribution: "Lizenzverteilung",
+    critical: "Kritisch",
+    high: "Hoch",
+    medium: "Mittel",
+    low: "Niedrig",
+
+    docs_title: "PHP-Abhängigkeitsdokumentation",
+    project_information: "Projektinformationen",
+    name: "Name",
+    description: "Beschreibung",
+    project_type_short: "Typ",
+    unknown: "Unbekannt",
+    not_available: "k. A.",
+    dependency_summary: "Abhängigkeitsübersicht",
+    individual_tools_hint: "Für eine detaillierte Analyse die einzelnen Tools verwenden:",
+
+    multi_repo_title: "Abhängigkeitsanalyse über mehrere Repositories",
+    repositories_analyzed: "Analysierte Repositories",
+    team: "Team",
+    total_unique_packages: "Verschiedene Pakete insgesamt",
+    shared_dependencies_count: "Gemeinsame Abhängigkeiten",
+    version_conflicts_count: "Versionskonflikte",
+    failed_repositories_count: "Fehlgeschlagene Repositories",
+    cached_repositories_count: "Seit dem letzten Scan unverändert (Cache)",
+    failed_repositories: "Fehlgeschlagene Repositories",
+    failed_repositories_note: "Diese Repositories wurden übersprungen und fehlen in der folgenden Analyse.",
+    repository: "Repository",
+    path: "Pfad",
+    error: "Fehler",
+    shared_dependencies: "Gemeinsame Abhängigkeiten",
+    used_by: "Verwendet von",
+    version_conflicts: "Versionskonflikte",
+    installed: "installiert",
+    php_platform_versions: "PHP-Plattformversionen",
+    php_constraint: "PHP-Constraint",
+    lowest_version: "Niedrigste Version",
+    repos: "Repos",
+    php_upgrade_blockers: "Blockieren ein PHP-Upgrade",
+
+    default_site_description: "Dokumentation der Abhängigkeitsanalyse",
+    home: "Start",
+    quick_overview: "Überblick",
+    project_type: "Projekttyp",
+    documentation_sections: "Inhalt der Dokumentation",
+    dependencies: "Abhängigkeiten",
+    dependencies_section: "Vollständige Abhängigkeitsanalyse mit Baum",
+    security: "Sicherheit",
+    security_section: "Sicherheitsaudit und Schwachstellenbericht",
+    licenses: "Lizenzen",
+    licenses_section: "Lizenzkonformität und -verteilung",
+    architecture: "Architektur",
+    architecture_section: "Namespace-Struktur und PSR-4-Konformität",
+    changelog: "Änderungsprotokoll",
+    changelog_section: "Verlauf der Abhängigkeitsänderungen",
+    getting_started: "Erste Schritte",
+    generated_by: "Diese Dokumentation wurde automatisch von dependency-buster MCP erstellt.",
+    view_with_mkdocs: "Anzeige mit MkDocs:",
+    production: "Produktion",
+    development: "Entwicklung",
+    total: "Gesamt",
+    packages_count: "{} Pakete",
+    and_more: "... und {} weitere",
+    dependency_graph: "Abhängigkeitsgraph",
+    dependencies_tool_hint: "Detaillierte Informationen zu Abhängigkeiten liefert das Tool `analyze_dependencies`.",
+    security_audit: "Sicherheitsaudit",
+    risk_level: "Risikostufe",
+    total_issues: "Probleme insgesamt",
+    vulnerabilities: "Schwachstellen",
+    severity: "Schweregrad",
+    status: "Status",
+    no_vulnerabilities: "Keine bekannten Schwachstellen gefunden.",
+    and_more_vulnerabilities: "... und {} weitere Schwachstellen",
+    security_tool_hint: "Detaillierte Sicherheitsinformationen liefert das Tool `audit_security`.",
+    license_compliance: "Lizenzkonformität",
+    total_packages: "Pakete insgesamt",
+    unique_licenses: "Verschiedene Lizenzen",
+    no_license_declared: "Ohne Lizenzangabe",
+    unrecognized_licenses: "Unbekannte Lizenzen",
+    risk_rollup: "Risikoübersicht",
+    risk_safe: "Unbedenklich",
+    risk_caution: "Vorsicht",
+    risk_review_required: "Prüfung erforderlich",
+    compatibility_issues: "Kompatibilitätsprobleme",
+    packages_without_license: "Pakete ohne Lizenz",
+    packages_without_license_note: "Diese Pakete geben keine Lizenz an; ohne Lizenz gilt \"alle Rechte vorbehalten\".",
+    licenses_tool_hint: "Detaillierte Lizenzinformationen liefert das Tool `analyze_licenses`.",
+    psr4_autoloading: "PSR-4-Autoloading",
+    total_mappings: "Mappings insgesamt",
+    files_analyzed: "Analysierte Dateien",
+    psr4_compliant: "PSR-4-konform",
+    violations: "Verstöße",
+    mappings: "Mappings",
+    namespace_prefix: "Namespace-Präfix",
+    directory: "Verzeichnis",
+    and_more_mappings: "... und {} weitere Mappings",
+    psr4_tool_hint: "Detaillierte PSR-4-Informationen liefert das Tool `analyze_psr4`.",
+    namespaces: "Namespaces",
+    namespaces_found: "**{}** Namespaces gefunden:",
+    namespace_files: "{} Dateien",
+    and_more_namespaces: "... und {} weitere Namespaces",
+    no_namespaces: "Keine Namespaces gefunden.",
+    namespaces_tool_hint: "Detaillierte Namespace-Informationen liefert das Tool `detect_namespaces`.",
+
+    issue_title: "Problem ({}): {}",
+    merged_issues_title: "{} Probleme: {}",
+    abandoned_package_title: "Aufgegebenes Paket: {}",
+    psr4_violation_title: "PSR-4-Verstoß: {}",
+    stale_dependency_title: "Veraltete Abhängigkeit: {}",
+    analysis_summary_title: "Zusammenfassung der Abhängigkeitsanalyse",
+    no_issues_found: "Keine Probleme gefunden",
+    found_issue: "{} Problem gefunden: {}",
+    found_issues: "{} Probleme gefunden: {}",
+    quick_commands: "Schnellbefehle:",
+    run_security_audit: "Sicherheitsaudit ausführen",
+    update_all_dependencies: "Alle Abhängigkeiten aktualisieren",
+};
@@ -1,12 +1,21 @@
 mod analyzer;
+mod archive;
 mod composer;
+mod config;
+mod i18n;
 mod mcp;
+mod packagist;
+#[cfg(test)]
+mod test_support;
 mod types;
 
 use anyhow::Result;
 use std::collections::HashMap;
 
-use analyzer::{dependency, namespace, psr4, security, suggestions, tracker};
+use analyzer::{autoload, autoload_config, binaries, compatibility, constraint_diff, contents, dependency, extra, fixes, footprint, graph_html, health, incremental, lag, maintainers, majors, metadata, namespace, overlaps, platform, policy, psr4, reproducibility, security, suggestions, tracker, updates, vendor_integrity};
+use analyzer::dependency::{DependencyFilter, DependencyScope};
+use analyzer::redaction::Redactor;
+use i18n::Locale;
 use mcp::{InputSchema, Property, Server, Tool};
 
 #[tokio::main]
@@ -14,6 +23,7 @@ async fn main() -> Result<()> {
     let server = Server::new("php-dependency-analyzer", "2.0.0");
 
     register_tools(&server).await;
+    server.register_capabilities_tool().await;
 
     server.run().await
 }
@@ -37,19 +47,130 @@ fn repo_path_tool(name: &str, description: &str) -> Tool {
     }
 }
 
+/// Advertise the optional `fields` projection handled by the server for heavy tools
+fn with_fields(mut tool: Tool) -> Tool {
+    tool.input_schema.properties.insert("fields".to_string(), Property {
+        property_type: "array".to_string(),
+        description: "Only return these fields for each record in the result (e.g. [\"name\", \"version\"])".to_string(),
+    });
+    tool
+}
+
+/// Advertise the optional `strict` flag that turns findings into a failed tool call, for CI gates
+fn with_strict(mut tool: Tool) -> Tool {
+    tool.input_schema.properties.insert("strict".to_string(), Property {
+        property_type: "boolean".to_string(),
+        description: "Fail the call with a StrictModeError (carrying the full result) when any problem is found (default: false)".to_string(),
+    });
+    tool
+}
+
+/// Advertise `format: "ndjson"`, which streams the tool's main list one record per line
+fn with_ndjson(mut tool: Tool) -> Tool {
+    tool.input_schema.properties.insert("format".to_string(), Property {
+        property_type: "string".to_string(),
+        description: "Output format: json (default) or ndjson (one compact record per line, with the summary as the last line)".to_string(),
+    });
+    tool
+}
+
+/// Advertise the per-call override of `reporting.minReportedSeverity` from `.dpb.json`
+fn with_severity_floor(mut tool: Tool) -> Tool {
+    tool.input_schema.properties.insert("min_reported_severity".to_string(), Property {
+        property_type: "string".to_string(),
+        description: "Drop findings below this severity from the output and summary counts: critical, high, medium, low or info (default: .dpb.json reporting.minReportedSeverity, else everything)".to_string(),
+    });
+    tool
+}
+
+/// The severity floor for a call: its `min_reported_severity` argument, else the repository's config
+fn severity_floor_from_args(args: &serde_json::Value, repo_path: &str) -> Result<Option<u8>> {
+    config::severity_floor(repo_path, args.get("min_reported_severity").and_then(|v| v.as_str()))
+}
+
+/// Serialize a result, failing with a StrictModeError when `strict` is set and `problems` isn't zero
+fn strict_result<T: serde::Serialize>(args: &serde_json::Value, result: &T, problems: usize, what: &str) -> Result<String> {
+    let strict = args.get("strict").and_then(|v| v.as_bool()).unwrap_or(false);
+    if strict && problems > 0 {
+        let message = format!("Strict mode: {} {}", problems, what);
+        return Err(mcp::strict_mode_error(&message, serde_json::to_value(result)?).into());
+    }
+    Ok(serde_json::to_string_pretty(result)?)
+}
+
+/// Apply `.dpb.json` redaction patterns to a generated report when the `redact` argument is set
+fn redacted_output(args: &serde_json::Value, repo_path: &str, output: String) -> Result<String> {
+    if args.get("redact").and_then(|v| v.as_bool()).unwrap_or(false) {
+        return Ok(Redactor::for_repo(repo_path)?.apply(&output));
+    }
+    Ok(output)
+}
+
+/// Report language from the `locale` tool argument (default: English)
+fn locale_from_args(args: &serde_json::Value) -> Result<Locale> {
+    args.get("locale")
+        .and_then(|v| v.as_str())
+        .map(Locale::parse)
+        .unwrap_or(Ok(Locale::En))
+}
+
+/// Build a dependency filter from the `scope` and `exclude` tool arguments
+fn dependency_filter_from_args(args: &serde_json::Value, default_scope: DependencyScope) -> DependencyFilter {
+    let scope = args.get("scope")
+        .and_then(|v| v.as_str())
+        .and_then(DependencyScope::parse)
+        .unwrap_or(default_scope);
+    let exclude = args.get("exclude")
+        .and_then(|v| v.as_array())
+        .map(|list| list.iter().filter_map(|v| v.as_str()).map(|s| s.to_string()).collect())
+        .unwrap_or_default();
+    DependencyFilter { scope, exclude }
+}
+
 async fn register_tools(server: &Server) {
     // Tool 1: Analyze Dependencies
     server
         .register_tool(
-            repo_path_tool(
-                "analyze_dependencies",
-                "Comprehensive dependency analysis including production, dev, and dependency tree"
-            ),
+            with_ndjson(with_strict(with_fields(Tool {
+                name: "analyze_dependencies".to_string(),
+                description: "Comprehensive dependency analysis including production, dev, and dependency tree".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("repo_path".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Absolute path to PHP repository".to_string(),
+                        }),
+                        ("nested".to_string(), Property {
+                            property_type: "boolean".to_string(),
+                            description: "Include a nested install tree rooted at the direct requires (default: false)".to_string(),
+                        }),
+                        ("scope".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Dependency scope: production, development, or all (default: all)".to_string(),
+                        }),
+                        ("exclude".to_string(), Property {
+                            property_type: "array".to_string(),
+                            description: "Package names or vendor wildcards to hide (e.g. symfony/*)".to_string(),
+                        }),
+                    ]),
+                    required: vec!["repo_path".to_string()],
+                },
+                annotations: None,
+            }))),
             |args| {
                 let repo_path = args.get("repo_path")
                     .and_then(|v| v.as_str())
                     .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
-                dependency::analyze_dependencies(repo_path)
+                let nested = args.get("nested")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+                let filter = dependency_filter_from_args(&args, DependencyScope::All);
+                let mut result = dependency::analyze_dependencies_filtered(repo_path, &filter)?;
+                if nested {
+                    result.nested_tree = Some(dependency::build_nested_tree(&result));
+                }
+                strict_result(&args, &result, result.warnings.len(), "dependency warnings (e.g. composer.lock out of date)")
             },
         )
         .await;
@@ -57,15 +178,16 @@ async fn register_tools(server: &Server) {
     // Tool 2: Analyze PSR-4
     server
         .register_tool(
-            repo_path_tool(
+            with_ndjson(with_strict(with_fields(repo_path_tool(
                 "analyze_psr4",
                 "Analyze PSR-4 autoloading configuration and validate namespace compliance"
-            ),
+            )))),
             |args| {
                 let repo_path = args.get("repo_path")
                     .and_then(|v| v.as_str())
                     .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
-                psr4::analyze_psr4_autoloading(repo_path)
+                let result = psr4::analyze_psr4_autoloading_raw(repo_path)?;
+                strict_result(&args, &result, result.stats.violation_count, "PSR-4 violations")
             },
         )
         .await;
@@ -73,15 +195,52 @@ async fn register_tools(server: &Server) {
     // Tool 3: Detect Namespaces
     server
         .register_tool(
-            repo_path_tool(
-                "detect_namespaces",
-                "Detect all namespaces used in the codebase"
-            ),
+            with_ndjson(with_fields(Tool {
+                name: "detect_namespaces".to_string(),
+                description: "Detect all namespaces used in the codebase".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("repo_path".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Absolute path to PHP repository".to_string(),
+                        }),
+                        ("list_files_without_namespace".to_string(), Property {
+                            property_type: "boolean".to_string(),
+                            description: "Also return the flat list of files without a namespace, not just the per-directory counts (default: false)".to_string(),
+                        }),
+                        ("include_markers".to_string(), Property {
+                            property_type: "boolean".to_string(),
+                            description: "Also collect TODO/FIXME comments and @deprecated tags with file and line, grouped by namespace, with files without one under \\ (default: false)".to_string(),
+                        }),
+                        ("limit".to_string(), Property {
+                            property_type: "number".to_string(),
+                            description: "Return at most this many namespaces, sorted by name, plus a nextCursor for the rest (default: all)".to_string(),
+                        }),
+                        ("cursor".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "nextCursor from the previous page".to_string(),
+                        }),
+                    ]),
+                    required: vec!["repo_path".to_string()],
+                },
+                annotations: None,
+            })),
             |args| {
                 let repo_path = args.get("repo_path")
                     .and_then(|v| v.as_str())
                     .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
-                namespace::detect_namespaces(repo_path)
+                let list_files = args.get("list_files_without_namespace")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+                let include_markers = args.get("include_markers")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+                let limit = args.get("limit")
+                    .and_then(|v| v.as_f64())
+                    .map(|v| v as usize);
+                let cursor = args.get("cursor").and_then(|v| v.as_str());
+                namespace::detect_namespaces(repo_path, list_files, include_markers, limit, cursor)
             },
         )
         .await;
@@ -89,7 +248,7 @@ async fn register_tools(server: &Server) {
     // Tool 4: Analyze Namespace Usage
     server
         .register_tool(
-            Tool {
+            with_fields(Tool {
                 name: "analyze_namespace_usage".to_string(),
                 description: "Analyze usage of a specific namespace across the codebase".to_string(),
                 input_schema: InputSchema {
@@ -107,7 +266,7 @@ async fn register_tools(server: &Server) {
                     required: vec!["repo_path".to_string(), "namespace".to_string()],
                 },
                 annotations: None,
-            },
+            }),
             |args| {
                 let repo_path = args.get("repo_path")
                     .and_then(|v| v.as_str())
@@ -125,7 +284,7 @@ async fn register_tools(server: &Server) {
         .register_tool(
             Tool {
                 name: "generate_dependency_graph".to_string(),
-                description: "Generate Mermaid diagram of dependency relationships".to_string(),
+                description: "Generate Mermaid diagram of dependency relationships, or an interactive HTML page".to_string(),
                 input_schema: InputSchema {
                     schema_type: "object".to_string(),
                     properties: HashMap::from([
@@ -141,10 +300,26 @@ async fn register_tools(server: &Server) {
                             property_type: "boolean".to_string(),
                             description: "Include development dependencies".to_string(),
                         }),
+                        ("scope".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Dependency scope: production, development, or all (overrides include_dev)".to_string(),
+                        }),
+                        ("exclude".to_string(), Property {
+                            property_type: "array".to_string(),
+                            description: "Package names or vendor wildcards to hide (e.g. symfony/*)".to_string(),
+                        }),
                         ("focus_package".to_string(), Property {
                             property_type: "string".to_string(),
                             description: "Focus on specific package and its dependencies".to_string(),
                         }),
+                        ("format".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Output format: mermaid (default) or html (self-contained interactive page showing the full graph; loads nothing from the network)".to_string(),
+                        }),
+                        ("redact".to_string(), Property {
+                            property_type: "boolean".to_string(),
+                            description: "Replace packages matching redaction.patterns in .dpb.json with hashed placeholders, for sharing outside the company (default: false)".to_string(),
+                        }),
                     ]),
                     required: vec!["repo_path".to_string()],
                 },
@@ -164,7 +339,18 @@ async fn register_tools(server: &Server) {
                 let focus_package = args.get("focus_package")
                     .and_then(|v| v.as_str())
                     .map(|s| s.to_string());
-                analyzer::generate_dependency_graph(repo_path, max_depth, include_dev, focus_package)
+                let default_scope = if include_dev {
+                    DependencyScope::All
+                } else {
+                    DependencyScope::Production
+                };
+                let filter = dependency_filter_from_args(&args, default_scope);
+                let graph = match args.get("format").and_then(|v| v.as_str()).unwrap_or("mermaid") {
+                    "mermaid" => analyzer::generate_dependency_graph(repo_path, max_depth, &filter, focus_package),
+                    "html" => graph_html::generate_dependency_graph_html(repo_path, max_depth, &filter, focus_package),
+                    other => Err(anyhow::anyhow!("invalid format {:?}: expected mermaid or html", other)),
+                }?;
+                redacted_output(&args, repo_path, graph)
             },
         )
         .await;
@@ -172,15 +358,65 @@ async fn register_tools(server: &Server) {
     // Tool 6: Audit Security
     server
         .register_tool(
-            repo_path_tool(
-                "audit_security",
-                "Audit dependencies for security vulnerabilities and outdated packages"
-            ),
+            with_severity_floor(with_ndjson(with_strict(with_fields(Tool {
+                name: "audit_security".to_string(),
+                description: "Audit dependencies for security vulnerabilities and outdated packages (sorted by severity)".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("repo_path".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Absolute path to PHP repository".to_string(),
+                        }),
+                        ("max_per_severity".to_string(), Property {
+                            property_type: "number".to_string(),
+                            description: "Maximum findings returned per severity level (summary still counts all)".to_string(),
+                        }),
+                        ("security_config".to_string(), Property {
+                            property_type: "object".to_string(),
+                            description: "Heuristic severities, e.g. {\"stale\": \"high\", \"preRelease\": null}; null disables a check (default: .dpb.json)".to_string(),
+                        }),
+                        ("include_dev".to_string(), Property {
+                            property_type: "boolean".to_string(),
+                            description: "Include packages-dev from composer.lock (default: true)".to_string(),
+                        }),
+                        ("fail_on".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Lowest severity that fails the call in strict mode: critical, high, medium or low (default: high)".to_string(),
+                        }),
+                    ]),
+                    required: vec!["repo_path".to_string()],
+                },
+                annotations: None,
+            })))),
             |args| {
                 let repo_path = args.get("repo_path")
                     .and_then(|v| v.as_str())
                     .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
-                security::audit_security(repo_path)
+                let fail_on = args.get("fail_on")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("high")
+                    .to_lowercase();
+                let fail_rank = match security::severity_rank(&fail_on) {
+                    rank if rank < 4 => rank,
+                    _ => return Err(anyhow::anyhow!("Invalid fail_on: {} (expected critical, high, medium or low)", fail_on)),
+                };
+                let max_per_severity = args.get("max_per_severity")
+                    .and_then(|v| v.as_f64())
+                    .map(|v| v as usize);
+                let security_config = match args.get("security_config") {
+                    Some(v) => serde_json::from_value(v.clone())?,
+                    None => config::load_config(repo_path)?.security,
+                };
+                let include_dev = args.get("include_dev")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(true);
+                let severity_floor = severity_floor_from_args(&args, repo_path)?;
+                let mut result = security::audit_security_raw(repo_path, max_per_severity, None, &security_config, include_dev)?;
+                // fail_on gates on everything found; the floor only trims what is reported
+                let failing = security::findings_at_or_above(&result.summary, fail_rank);
+                security::apply_severity_floor(&mut result, severity_floor);
+                strict_result(&args, &result, failing, &format!("security findings at {} severity or above", fail_on))
             },
         )
         .await;
@@ -188,15 +424,35 @@ async fn register_tools(server: &Server) {
     // Tool 7: Analyze Licenses
     server
         .register_tool(
-            repo_path_tool(
-                "analyze_licenses",
-                "Analyze license distribution and compatibility across dependencies"
-            ),
+            with_strict(with_fields(Tool {
+                name: "analyze_licenses".to_string(),
+                description: "Analyze license distribution and compatibility across dependencies".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("repo_path".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Absolute path to PHP repository".to_string(),
+                        }),
+                        ("include_dev".to_string(), Property {
+                            property_type: "boolean".to_string(),
+                            description: "Include packages-dev from composer.lock (default: true)".to_string(),
+                        }),
+                    ]),
+                    required: vec!["repo_path".to_string()],
+                },
+                annotations: None,
+            })),
             |args| {
                 let repo_path = args.get("repo_path")
                     .and_then(|v| v.as_str())
                     .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
-                security::analyze_licenses(repo_path)
+                let include_dev = args.get("include_dev")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(true);
+                let result = security::analyze_licenses_raw(repo_path, include_dev)?;
+                let problems = result.compatibility_issues.len() + result.summary.unknown_licenses;
+                strict_result(&args, &result, problems, "license issues (incompatible, missing or unrecognized)")
             },
         )
         .await;
@@ -220,7 +476,7 @@ async fn register_tools(server: &Server) {
     // Tool 9: Analyze Multi Repo
     server
         .register_tool(
-            Tool {
+            with_fields(Tool {
                 name: "analyze_multi_repo".to_string(),
                 description: "Analyze dependencies across multiple repositories (Dependency Buster platform)".to_string(),
                 input_schema: InputSchema {
@@ -230,16 +486,27 @@ async fn register_tools(server: &Server) {
                             property_type: "string".to_string(),
                             description: "Path to repository configuration JSON file".to_string(),
                         }),
+                        ("use_cache".to_string(), Property {
+                            property_type: "boolean".to_string(),
+                            description: "Reuse per-repo results from .dpb/multirepo-cache/ next to the config for repos whose composer.lock is unchanged, and save new ones (default: false)".to_string(),
+                        }),
+                        ("locale".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Language of the report text: en or de (default: en)".to_string(),
+                        }),
                     ]),
                     required: vec!["config_path".to_string()],
                 },
                 annotations: None,
-            },
+            }),
             |args| {
                 let config_path = args.get("config_path")
                     .and_then(|v| v.as_str())
                     .ok_or_else(|| anyhow::anyhow!("config_path required"))?;
-                analyzer::analyze_multiple_repositories(config_path)
+                let use_cache = args.get("use_cache")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+                analyzer::analyze_multiple_repositories(config_path, use_cache, locale_from_args(&args)?)
             },
         )
         .await;
@@ -261,6 +528,10 @@ async fn register_tools(server: &Server) {
                             property_type: "string".to_string(),
                             description: "Where to save the documentation file".to_string(),
                         }),
+                        ("locale".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Language of the report text: en or de (default: en)".to_string(),
+                        }),
                     ]),
                     required: vec!["repo_path".to_string()],
                 },
@@ -272,7 +543,7 @@ async fn register_tools(server: &Server) {
                     .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
                 let output_path = args.get("output_path")
                     .and_then(|v| v.as_str());
-                analyzer::generate_comprehensive_docs(repo_path, output_path)
+                analyzer::generate_comprehensive_docs(repo_path, output_path, locale_from_args(&args)?)
             },
         )
         .await;
@@ -310,6 +581,18 @@ async fn register_tools(server: &Server) {
                             property_type: "string".to_string(),
                             description: "Site description for mkdocs.yml (optional)".to_string(),
                         }),
+                        ("redact".to_string(), Property {
+                            property_type: "boolean".to_string(),
+                            description: "Replace packages matching redaction.patterns in .dpb.json with hashed placeholders, for sharing outside the company (default: false)".to_string(),
+                        }),
+                        ("dry_run".to_string(), Property {
+                            property_type: "boolean".to_string(),
+                            description: "List the files that would be written and their sizes without writing anything (default: false)".to_string(),
+                        }),
+                        ("locale".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Language of the report text: en or de (default: en)".to_string(),
+                        }),
                     ]),
                     required: vec!["repo_path".to_string()],
                 },
@@ -343,6 +626,9 @@ async fn register_tools(server: &Server) {
                     format,
                     site_name,
                     site_description,
+                    locale: locale_from_args(&args)?,
+                    redact: args.get("redact").and_then(|v| v.as_bool()).unwrap_or(false),
+                    dry_run: args.get("dry_run").and_then(|v| v.as_bool()).unwrap_or(false),
                 })
             },
         )
@@ -365,6 +651,10 @@ async fn register_tools(server: &Server) {
                             property_type: "boolean".to_string(),
                             description: "Save snapshot to disk for future comparison (default: true)".to_string(),
                         }),
+                        ("label".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Reason or label stored with the snapshot, e.g. \"pre-upgrade baseline\" or a ticket id".to_string(),
+                        }),
                     ]),
                     required: vec!["repo_path".to_string()],
                 },
@@ -377,7 +667,8 @@ async fn register_tools(server: &Server) {
                 let save = args.get("save")
                     .and_then(|v| v.as_bool())
                     .unwrap_or(true);
-                let snapshot = tracker::create_dependency_snapshot(repo_path)?;
+                let label = args.get("label").and_then(|v| v.as_str());
+                let snapshot = tracker::create_dependency_snapshot(repo_path, label)?;
                 if save {
                     tracker::save_snapshot(repo_path, &snapshot)?;
                 }
@@ -406,33 +697,977 @@ async fn register_tools(server: &Server) {
     // Tool 13: Check Compliance
     server
         .register_tool(
-            repo_path_tool(
+            with_severity_floor(repo_path_tool(
                 "check_compliance",
                 "Check dependencies for compliance issues (licenses, outdated, deprecated)"
-            ),
+            )),
             |args| {
                 let repo_path = args.get("repo_path")
                     .and_then(|v| v.as_str())
                     .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
-                let issues = tracker::check_compliance(repo_path)?;
+                let severity_floor = severity_floor_from_args(&args, repo_path)?;
+                let mut issues = tracker::check_compliance(repo_path)?;
+                issues.retain(|issue| security::within_severity_floor(&issue.severity, severity_floor));
                 Ok(serde_json::to_string_pretty(&issues)?)
             },
         )
         .await;
 
     // Tool 14: Get Agent Suggestions
+    server
+        .register_tool(
+            with_severity_floor(Tool {
+                name: "get_agent_suggestions".to_string(),
+                description: "Get structured suggestions for AI agents (Cursor, Cline, Claude Code) about dependency issues".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("repo_path".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Absolute path to PHP repository".to_string(),
+                        }),
+                        ("min_severity".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Only return suggestions at or above this severity: critical, high, medium, low or info; same as min_reported_severity (default: .dpb.json reporting.minReportedSeverity, else all)".to_string(),
+                        }),
+                        ("locale".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Language of the report text: en or de (default: en)".to_string(),
+                        }),
+                    ]),
+                    required: vec!["repo_path".to_string()],
+                },
+                annotations: None,
+            }),
+            |args| {
+                let repo_path = args.get("repo_path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
+                let min_severity = args.get("min_severity")
+                    .or_else(|| args.get("min_reported_severity"))
+                    .and_then(|v| v.as_str());
+                let response = suggestions::generate_agent_suggestions(repo_path, min_severity, locale_from_args(&args)?)?;
+                Ok(serde_json::to_string_pretty(&response)?)
+            },
+        )
+        .await;
+
+    // Tool 16: Import Composer Audit
+    server
+        .register_tool(
+            with_severity_floor(Tool {
+                name: "import_composer_audit".to_string(),
+                description: "Merge `composer audit --format=json` output with heuristic security findings into one report".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("repo_path".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Absolute path to PHP repository".to_string(),
+                        }),
+                        ("audit_json_path".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Path to the saved output of `composer audit --format=json`".to_string(),
+                        }),
+                    ]),
+                    required: vec!["repo_path".to_string(), "audit_json_path".to_string()],
+                },
+                annotations: None,
+            }),
+            |args| {
+                let repo_path = args.get("repo_path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
+                let audit_json_path = args.get("audit_json_path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("audit_json_path required"))?;
+                let security_config = config::load_config(repo_path)?.security;
+                let severity_floor = severity_floor_from_args(&args, repo_path)?;
+                security::import_composer_audit(repo_path, audit_json_path, severity_floor, &security_config)
+            },
+        )
+        .await;
+
+    // Tool 17: Generate Revert Script
+    server
+        .register_tool(
+            Tool {
+                name: "generate_revert_script".to_string(),
+                description: "Generate a shell script that rolls dependencies back from one snapshot to an earlier one".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("repo_path".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Absolute path to repository".to_string(),
+                        }),
+                        ("from_snapshot".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Snapshot file to roll back to (default: the saved tracker snapshot)".to_string(),
+                        }),
+                        ("to_snapshot".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Snapshot file to roll back from (default: current dependencies)".to_string(),
+                        }),
+                    ]),
+                    required: vec!["repo_path".to_string()],
+                },
+                annotations: None,
+            },
+            |args| {
+                let repo_path = args.get("repo_path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
+                let from = args.get("from_snapshot").and_then(|v| v.as_str());
+                let to = args.get("to_snapshot").and_then(|v| v.as_str());
+                let plan = tracker::generate_revert_plan(repo_path, from, to)?;
+                Ok(serde_json::to_string_pretty(&plan)?)
+            },
+        )
+        .await;
+
+    // Tool 18: Dependency Count Trend
     server
         .register_tool(
             repo_path_tool(
-                "get_agent_suggestions",
-                "Get structured suggestions for AI agents (Cursor, Cline, Claude Code) about dependency issues"
+                "get_dependency_count_trend",
+                "Get total dependency count for each saved snapshot over time, for charting dependency growth"
             ),
             |args| {
                 let repo_path = args.get("repo_path")
                     .and_then(|v| v.as_str())
                     .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
-                let response = suggestions::generate_agent_suggestions(repo_path)?;
-                Ok(serde_json::to_string_pretty(&response)?)
+                let trend: Vec<serde_json::Value> = tracker::dependency_count_trend(repo_path)?
+                    .into_iter()
+                    .map(|(timestamp, count)| serde_json::json!({ "timestamp": timestamp, "count": count }))
+                    .collect();
+                Ok(serde_json::to_string_pretty(&trend)?)
+            },
+        )
+        .await;
+
+    // Tool 19: Simulate Update
+    server
+        .register_tool(
+            repo_path_tool(
+                "simulate_update",
+                "Query Packagist for the highest version each dependency's constraint allows, grouped into updatable within constraints and needing a constraint change"
+            ),
+            |args| {
+                let repo_path = args.get("repo_path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
+                updates::simulate_update(repo_path)
+            },
+        )
+        .await;
+
+    // Tool 20: Packages By License
+    server
+        .register_tool(
+            Tool {
+                name: "packages_by_license".to_string(),
+                description: "List packages under a given license (case-insensitive SPDX id or family such as GPL; license expressions are split into ids)".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("repo_path".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Absolute path to PHP repository".to_string(),
+                        }),
+                        ("license".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "SPDX id (e.g. GPL-3.0-only) or family (e.g. GPL)".to_string(),
+                        }),
+                        ("include_dev".to_string(), Property {
+                            property_type: "boolean".to_string(),
+                            description: "Include packages-dev from composer.lock (default: true)".to_string(),
+                        }),
+                    ]),
+                    required: vec!["repo_path".to_string(), "license".to_string()],
+                },
+                annotations: None,
+            },
+            |args| {
+                let repo_path = args.get("repo_path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
+                let license = args.get("license")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("license required"))?;
+                let include_dev = args.get("include_dev")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(true);
+                security::packages_by_license(repo_path, license, include_dev)
+            },
+        )
+        .await;
+
+    // Tool 21: Analyze Composer Contents
+    server
+        .register_tool(
+            with_fields(Tool {
+                name: "analyze_composer_contents".to_string(),
+                description: "Run dependency, security and license analysis on composer.json / composer.lock content passed inline, for servers without access to the repository".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("composer_json".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Contents of composer.json".to_string(),
+                        }),
+                        ("composer_lock".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Contents of composer.lock (required for security and license analysis)".to_string(),
+                        }),
+                        ("include_dev".to_string(), Property {
+                            property_type: "boolean".to_string(),
+                            description: "Include development dependencies (default: true)".to_string(),
+                        }),
+                        ("security_config".to_string(), Property {
+                            property_type: "object".to_string(),
+                            description: "Heuristic severities, as for audit_security (default: built-in severities)".to_string(),
+                        }),
+                        ("min_reported_severity".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Drop security findings below this severity from the output and summary counts: critical, high, medium, low or info (default: everything)".to_string(),
+                        }),
+                    ]),
+                    required: vec![],
+                },
+                annotations: None,
+            }),
+            |args| {
+                let composer_json = args.get("composer_json").and_then(|v| v.as_str());
+                let composer_lock = args.get("composer_lock").and_then(|v| v.as_str());
+                let include_dev = args.get("include_dev")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(true);
+                let security_config = match args.get("security_config") {
+                    Some(v) => serde_json::from_value(v.clone())?,
+                    None => security::SecurityConfig::default(),
+                };
+                let severity_floor = args.get("min_reported_severity")
+                    .and_then(|v| v.as_str())
+                    .map(config::parse_severity_floor)
+                    .transpose()?
+                    .flatten();
+                contents::analyze_composer_contents(composer_json, composer_lock, include_dev, severity_floor, &security_config)
+            },
+        )
+        .await;
+
+    // Tool 22: Compare Repos
+    server
+        .register_tool(
+            Tool {
+                name: "compare_repos".to_string(),
+                description: "Compare two repositories' dependencies: packages unique to each, shared at the same version, and shared with differing versions".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("repo_a".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Absolute path to the first PHP repository".to_string(),
+                        }),
+                        ("repo_b".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Absolute path to the second PHP repository".to_string(),
+                        }),
+                    ]),
+                    required: vec!["repo_a".to_string(), "repo_b".to_string()],
+                },
+                annotations: None,
+            },
+            |args| {
+                let repo_a = args.get("repo_a")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("repo_a required"))?;
+                let repo_b = args.get("repo_b")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("repo_b required"))?;
+                analyzer::compare_repos(repo_a, repo_b)
+            },
+        )
+        .await;
+
+    // Tool 23: Package Info
+    server
+        .register_tool(
+            Tool {
+                name: "package_info".to_string(),
+                description: "Fetch Packagist popularity metadata for a package (downloads, favers, GitHub stars) with adoption and abandonment risk signals".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("package".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Package name, e.g. monolog/monolog".to_string(),
+                        }),
+                    ]),
+                    required: vec!["package".to_string()],
+                },
+                annotations: None,
+            },
+            |args| {
+                let package = args.get("package")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("package required"))?;
+                let info = packagist::package_info(package)?;
+                Ok(serde_json::to_string_pretty(&info)?)
+            },
+        )
+        .await;
+
+    // Tool 24: Validate composer.json
+    server
+        .register_tool(
+            with_strict(repo_path_tool(
+                "validate_composer_json",
+                "Validate composer.json against Composer's schema: unknown keys, malformed autoload entries and invalid version constraints"
+            )),
+            |args| {
+                let repo_path = args.get("repo_path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
+                let result = composer::schema::validate_composer_json(repo_path)?;
+                strict_result(&args, &result, result.errors.len(), "composer.json schema errors")
+            },
+        )
+        .await;
+
+    // Tool 25: Check License Compatibility
+    server
+        .register_tool(
+            Tool {
+                name: "check_license_compatibility".to_string(),
+                description: "Check dependency licenses against the project's own composer.json license and report only genuine incompatibilities".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("repo_path".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Absolute path to PHP repository".to_string(),
+                        }),
+                        ("include_dev".to_string(), Property {
+                            property_type: "boolean".to_string(),
+                            description: "Also check packages-dev, which are normally not distributed (default: false)".to_string(),
+                        }),
+                    ]),
+                    required: vec!["repo_path".to_string()],
+                },
+                annotations: None,
+            },
+            |args| {
+                let repo_path = args.get("repo_path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
+                let include_dev = args.get("include_dev")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+                compatibility::check_license_compatibility(repo_path, include_dev)
+            },
+        )
+        .await;
+
+    // Tool 26: Check Package Policy
+    server
+        .register_tool(
+            with_severity_floor(Tool {
+                name: "check_package_policy".to_string(),
+                description: "Check installed packages against an allow/deny/require policy file (supports vendor wildcards like evil-corp/*) and report pass/fail".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("repo_path".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Absolute path to PHP repository".to_string(),
+                        }),
+                        ("policy_path".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Path to the JSON policy file, absolute or relative to the repository".to_string(),
+                        }),
+                    ]),
+                    required: vec!["repo_path".to_string(), "policy_path".to_string()],
+                },
+                annotations: None,
+            }),
+            |args| {
+                let repo_path = args.get("repo_path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
+                let policy_path = args.get("policy_path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("policy_path required"))?;
+                let severity_floor = severity_floor_from_args(&args, repo_path)?;
+                let result = policy::check_package_policy(repo_path, policy_path, severity_floor)?;
+                Ok(serde_json::to_string_pretty(&result)?)
+            },
+        )
+        .await;
+
+    // Tool 27: Find Major Version Conflicts
+    server
+        .register_tool(
+            repo_path_tool(
+                "find_major_version_conflicts",
+                "Flag packages locked at several major versions or whose requirers expect incompatible majors (runtime-conflict risks)"
+            ),
+            |args| {
+                let repo_path = args.get("repo_path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
+                majors::find_major_version_conflicts(repo_path)
+            },
+        )
+        .await;
+
+    // Tool 28: Generate Release Changelog
+    server
+        .register_tool(
+            Tool {
+                name: "generate_release_changelog".to_string(),
+                description: "Markdown dependency changelog of the current composer.lock against the one at a git ref (default: the latest tag), for release notes".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("repo_path".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Absolute path to PHP repository (must be a git checkout)".to_string(),
+                        }),
+                        ("from_ref".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Git tag, branch or commit to compare against (default: most recent tag)".to_string(),
+                        }),
+                    ]),
+                    required: vec!["repo_path".to_string()],
+                },
+                annotations: None,
+            },
+            |args| {
+                let repo_path = args.get("repo_path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
+                let from_ref = args.get("from_ref").and_then(|v| v.as_str());
+                analyzer::mkdocs::generate_release_changelog(repo_path, from_ref)
+            },
+        )
+        .await;
+
+    // Tool 29: Analyze Autoload Optimization
+    server
+        .register_tool(
+            repo_path_tool(
+                "analyze_autoload_optimization",
+                "Recommend production autoloader settings (--optimize-autoloader vs --classmap-authoritative) from classmap size and files autoloads"
+            ),
+            |args| {
+                let repo_path = args.get("repo_path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
+                autoload::analyze_autoload_optimization(repo_path)
+            },
+        )
+        .await;
+
+    // Tool 30: Reanalyze Changed Files
+    server
+        .register_tool(
+            Tool {
+                name: "reanalyze_changed_files".to_string(),
+                description: "Update namespace and PSR-4 results for just the files that changed since the last call (the first call per repository runs a full scan)".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("repo_path".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Absolute path to PHP repository".to_string(),
+                        }),
+                        ("changed_files".to_string(), Property {
+                            property_type: "array".to_string(),
+                            description: "Added, modified or deleted files, absolute or relative to the repository".to_string(),
+                        }),
+                    ]),
+                    required: vec!["repo_path".to_string(), "changed_files".to_string()],
+                },
+                annotations: None,
+            },
+            |args| {
+                let repo_path = args.get("repo_path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
+                let changed_files: Vec<String> = args.get("changed_files")
+                    .and_then(|v| v.as_array())
+                    .ok_or_else(|| anyhow::anyhow!("changed_files required"))?
+                    .iter()
+                    .filter_map(|v| v.as_str())
+                    .map(|s| s.to_string())
+                    .collect();
+                incremental::reanalyze_changed_files(repo_path, &changed_files)
+            },
+        )
+        .await;
+
+    // Tool 31: Find Dev Autoload Leaks
+    server
+        .register_tool(
+            repo_path_tool(
+                "find_dev_autoload_leaks",
+                "Find production files importing classes from autoload-dev PSR-4 namespaces, which fatal in production where dev autoloads aren't loaded"
+            ),
+            |args| {
+                let repo_path = args.get("repo_path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
+                namespace::find_dev_autoload_leaks(repo_path)
+            },
+        )
+        .await;
+
+    // Tool 32: Namespace Metrics
+    server
+        .register_tool(
+            repo_path_tool(
+                "namespace_metrics",
+                "Architecture metrics: average and deepest namespace nesting, namespaces with the most classes, and namespaces imported by the most files"
+            ),
+            |args| {
+                let repo_path = args.get("repo_path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
+                namespace::namespace_metrics(repo_path)
+            },
+        )
+        .await;
+
+    // Tool 33: Find Unresolved Imports
+    server
+        .register_tool(
+            repo_path_tool(
+                "find_unresolved_imports",
+                "Find use imports whose namespace is neither declared in the repository nor provided by any locked package (typos, removed dependencies)"
+            ),
+            |args| {
+                let repo_path = args.get("repo_path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
+                namespace::find_unresolved_imports(repo_path)
+            },
+        )
+        .await;
+
+    // Tool 34: Analyze Footprint
+    server
+        .register_tool(
+            Tool {
+                name: "analyze_footprint".to_string(),
+                description: "Estimate the disk footprint of installed dependencies by measuring vendor/ and list the largest packages".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("repo_path".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Absolute path to PHP repository".to_string(),
+                        }),
+                        ("limit".to_string(), Property {
+                            property_type: "number".to_string(),
+                            description: "Number of largest packages to return (default: 10)".to_string(),
+                        }),
+                    ]),
+                    required: vec!["repo_path".to_string()],
+                },
+                annotations: None,
+            },
+            |args| {
+                let repo_path = args.get("repo_path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
+                let limit = args.get("limit")
+                    .and_then(|v| v.as_f64())
+                    .map(|v| v as usize)
+                    .unwrap_or(10);
+                footprint::analyze_footprint(repo_path, limit)
+            },
+        )
+        .await;
+
+    // Tool 35: Check Reproducibility
+    server
+        .register_tool(
+            with_strict(repo_path_tool(
+                "check_reproducibility",
+                "Verify every package in composer.lock is pinned to a commit reference (no branches, missing references or path repositories) so installs are reproducible"
+            )),
+            |args| {
+                let repo_path = args.get("repo_path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
+                let result = reproducibility::check_reproducibility_raw(repo_path)?;
+                strict_result(&args, &result, result.issues.len(), "unpinned packages")
+            },
+        )
+        .await;
+
+    // Tool 36: Suggest Fix Version
+    server
+        .register_tool(
+            Tool {
+                name: "suggest_fix_version".to_string(),
+                description: "Find the lowest release of a vulnerable package that clears its Packagist security advisories within the project's constraints, and the Composer command to install it".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("repo_path".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Absolute path to PHP repository".to_string(),
+                        }),
+                        ("package".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Package flagged by audit_security, e.g. guzzlehttp/guzzle".to_string(),
+                        }),
+                    ]),
+                    required: vec!["repo_path".to_string(), "package".to_string()],
+                },
+                annotations: None,
+            },
+            |args| {
+                let repo_path = args.get("repo_path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
+                let package = args.get("package")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("package required"))?;
+                fixes::suggest_fix_version(repo_path, package)
+            },
+        )
+        .await;
+
+    // Tool 37: List Installed Binaries
+    server
+        .register_tool(
+            Tool {
+                name: "list_installed_binaries".to_string(),
+                description: "List the executables composer install links into vendor/bin and the package that ships each one, including name collisions".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("repo_path".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Absolute path to PHP repository".to_string(),
+                        }),
+                        ("include_dev".to_string(), Property {
+                            property_type: "boolean".to_string(),
+                            description: "Include packages-dev from composer.lock (default: true)".to_string(),
+                        }),
+                    ]),
+                    required: vec!["repo_path".to_string()],
+                },
+                annotations: None,
+            },
+            |args| {
+                let repo_path = args.get("repo_path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
+                let include_dev = args.get("include_dev")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(true);
+                binaries::list_installed_binaries(repo_path, include_dev)
+            },
+        )
+        .await;
+
+    // Tool 38: Find Overlapping Dependencies
+    server
+        .register_tool(
+            Tool {
+                name: "find_overlapping_dependencies".to_string(),
+                description: "Flag direct dependencies that serve the same role (e.g. several HTTP clients or date libraries) and suggest consolidating; families are extendable via overlaps.families in .dpb.json".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("repo_path".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Absolute path to PHP repository".to_string(),
+                        }),
+                        ("include_dev".to_string(), Property {
+                            property_type: "boolean".to_string(),
+                            description: "Include require-dev from composer.json (default: true)".to_string(),
+                        }),
+                    ]),
+                    required: vec!["repo_path".to_string()],
+                },
+                annotations: None,
+            },
+            |args| {
+                let repo_path = args.get("repo_path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
+                let include_dev = args.get("include_dev")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(true);
+                overlaps::find_overlapping_dependencies(repo_path, include_dev)
+            },
+        )
+        .await;
+
+    // Tool 39: Get Changes Since Last Snapshot
+    server
+        .register_tool(
+            repo_path_tool(
+                "get_changes_since_last",
+                "List dependencies added, removed or updated since the last saved snapshot, with revert commands, without saving a new snapshot"
+            ),
+            |args| {
+                let repo_path = args.get("repo_path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
+                let changes = tracker::get_changes_since_last(repo_path)?;
+                Ok(serde_json::to_string_pretty(&changes)?)
+            },
+        )
+        .await;
+
+    // Tool 40: Explain Suggestion
+    server
+        .register_tool(
+            Tool {
+                name: "explain_suggestion".to_string(),
+                description: "Explain one suggestion from get_agent_suggestions in full: why it was flagged, the impact of leaving it, links, and the tradeoffs of each action".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("repo_path".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Absolute path to PHP repository".to_string(),
+                        }),
+                        ("id".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Suggestion id as returned by get_agent_suggestions".to_string(),
+                        }),
+                    ]),
+                    required: vec!["repo_path".to_string(), "id".to_string()],
+                },
+                annotations: None,
+            },
+            |args| {
+                let repo_path = args.get("repo_path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
+                let id = args.get("id")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("id required"))?;
+                let explanation = suggestions::explain_suggestion(repo_path, id)?;
+                Ok(serde_json::to_string_pretty(&explanation)?)
+            },
+        )
+        .await;
+
+    // Tool 41: Analyze Version Lag
+    server
+        .register_tool(
+            Tool {
+                name: "analyze_version_lag".to_string(),
+                description: "Query Packagist for the latest stable major of each locked package and group packages as 0, 1 or 2+ major versions behind".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("repo_path".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Absolute path to PHP repository".to_string(),
+                        }),
+                        ("include_dev".to_string(), Property {
+                            property_type: "boolean".to_string(),
+                            description: "Include packages-dev from composer.lock (default: true)".to_string(),
+                        }),
+                    ]),
+                    required: vec!["repo_path".to_string()],
+                },
+                annotations: None,
+            },
+            |args| {
+                let repo_path = args.get("repo_path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
+                let include_dev = args.get("include_dev")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(true);
+                lag::analyze_version_lag(repo_path, include_dev)
+            },
+        )
+        .await;
+
+    // Tool 42: Overall Health
+    server
+        .register_tool(
+            repo_path_tool(
+                "overall_health",
+                "Run the security, license, PSR-4 and staleness analyses and combine them into one weighted 0-100 health score with per-category breakdowns; weights are set in health.weights in .dpb.json"
+            ),
+            |args| {
+                let repo_path = args.get("repo_path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
+                health::overall_health(repo_path)
+            },
+        )
+        .await;
+
+    // Tool 43: Check Publishing Metadata
+    server
+        .register_tool(
+            repo_path_tool(
+                "check_publishing_metadata",
+                "Report whether composer.json declares support (issues/source URLs), funding and authors, and whether a SECURITY.md exists, before publishing a package"
+            ),
+            |args| {
+                let repo_path = args.get("repo_path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
+                metadata::check_publishing_metadata(repo_path)
+            },
+        )
+        .await;
+
+    // Tool 44: Compare Constraints
+    server
+        .register_tool(
+            Tool {
+                name: "compare_constraints".to_string(),
+                description: "Compare the constraints declared in two composer.json files (two repos, or two git refs of one repo) and classify each change as loosened, tightened, shifted, added or removed".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("repo_a".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Absolute path to the first PHP repository".to_string(),
+                        }),
+                        ("repo_b".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Absolute path to the second PHP repository (may be the same as repo_a when comparing refs)".to_string(),
+                        }),
+                        ("ref_a".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Git tag, branch or commit to read repo_a's composer.json from (default: working tree)".to_string(),
+                        }),
+                        ("ref_b".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Git tag, branch or commit to read repo_b's composer.json from (default: working tree)".to_string(),
+                        }),
+                    ]),
+                    required: vec!["repo_a".to_string(), "repo_b".to_string()],
+                },
+                annotations: None,
+            },
+            |args| {
+                let repo_a = args.get("repo_a")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("repo_a required"))?;
+                let repo_b = args.get("repo_b")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("repo_b required"))?;
+                let ref_a = args.get("ref_a").and_then(|v| v.as_str());
+                let ref_b = args.get("ref_b").and_then(|v| v.as_str());
+                constraint_diff::compare_constraints(repo_a, repo_b, ref_a, ref_b)
+            },
+        )
+        .await;
+
+    // Tool 45: Analyze Bus Factor
+    server
+        .register_tool(
+            repo_path_tool(
+                "analyze_bus_factor",
+                "Flag production dependencies with a single listed author or no author data, marking stale ones as high abandonment risk"
+            ),
+            |args| {
+                let repo_path = args.get("repo_path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
+                maintainers::analyze_bus_factor(repo_path)
+            },
+        )
+        .await;
+
+    // Tool 46: Suggest Autoload Config
+    server
+        .register_tool(
+            repo_path_tool(
+                "suggest_autoload_config",
+                "Infer PSR-4 mappings from where each namespace's files live and propose autoload / autoload-dev blocks to paste into composer.json"
+            ),
+            |args| {
+                let repo_path = args.get("repo_path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
+                autoload_config::suggest_autoload_config(repo_path)
+            },
+        )
+        .await;
+
+    // Tool 47: Check Vendor Integrity
+    server
+        .register_tool(
+            repo_path_tool(
+                "check_vendor_integrity",
+                "Find installed packages whose vendor/ files were edited in place after install (git changes for source installs, files newer than the last install for dist installs), separating those patched via composer-patches"
+            ),
+            |args| {
+                let repo_path = args.get("repo_path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
+                vendor_integrity::check_vendor_integrity(repo_path)
+            },
+        )
+        .await;
+
+    // Tool 48: Analyze Extra
+    server
+        .register_tool(
+            repo_path_tool(
+                "analyze_extra",
+                "Surface the composer.json `extra` settings of the project and each locked package: Laravel providers and aliases, Symfony Flex, composer-patches and branch aliases, with a summary per package"
+            ),
+            |args| {
+                let repo_path = args.get("repo_path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
+                extra::analyze_extra(repo_path)
+            },
+        )
+        .await;
+
+    // Tool 49: Analyze Platform Requirements
+    server
+        .register_tool(
+            Tool {
+                name: "analyze_platform_requirements".to_string(),
+                description: "Check php/ext-*/lib-* requirements of the project and its locked packages against the effective platform (host versions with composer.json config.platform overrides applied), flagging overrides that mask a requirement the host doesn't meet".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::from([
+                        ("repo_path".to_string(), Property {
+                            property_type: "string".to_string(),
+                            description: "Absolute path to PHP repository".to_string(),
+                        }),
+                        ("host_platform".to_string(), Property {
+                            property_type: "object".to_string(),
+                            description: "Versions on the target host, e.g. {\"php\": \"7.4.33\", \"ext-intl\": \"7.4.33\"} (default: detected from the php binary on PATH)".to_string(),
+                        }),
+                    ]),
+                    required: vec!["repo_path".to_string()],
+                },
+                annotations: None,
+            },
+            |args| {
+                let repo_path = args.get("repo_path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("repo_path required"))?;
+                let host_platform = match args.get("host_platform") {
+                    Some(v) => Some(serde_json::from_value(v.clone())
+                        .map_err(|e| anyhow::anyhow!("Invalid host_platform: {}", e))?),
+                    None => None,
+                };
+                platform::analyze_platform_requirements(repo_path, host_platform)
             },
         )
         .await;
@@ -85,6 +85,19 @@ impl ToolAnnotations {
         }
     }
 
+    /// Create annotation for tools that query the Packagist registry
+    pub fn registry() -> Self {
+        Self {
+            title: None,
+            read_only_hint: Some(true),
+            idempotent_hint: Some(true),
+            destructive_hint: Some(false),
+            open_world_hint: Some(true),
+            cache_ttl_seconds: Some(3600),
+            tags: Some(vec!["packagist".to_string(), "network".to_string()]),
+        }
+    }
+
     /// Set title
     pub fn with_title(mut self, title: &str) -> Self {
         self.title = Some(title.to_string());
@@ -96,15 +109,53 @@ impl ToolAnnotations {
 pub fn get_tool_annotation(tool_name: &str) -> ToolAnnotations {
     match tool_name {
         "analyze_dependencies" => ToolAnnotations::analysis().with_title("Analyze Dependencies"),
+        "validate_composer_json" => ToolAnnotations::analysis().with_title("Validate composer.json"),
         "analyze_psr4" => ToolAnnotations::analysis().with_title("Analyze PSR-4"),
         "detect_namespaces" => ToolAnnotations::analysis().with_title("Detect Namespaces"),
         "analyze_namespace_usage" => ToolAnnotations::analysis().with_title("Analyze Namespace Usage"),
         "audit_security" => ToolAnnotations::security().with_title("Audit Security"),
         "analyze_licenses" => ToolAnnotations::security().with_title("Analyze Licenses"),
+        "analyze_platform_requirements" => ToolAnnotations::analysis().with_title("Analyze Platform Requirements"),
+        "analyze_extra" => ToolAnnotations::analysis().with_title("Analyze Extra"),
+        "check_vendor_integrity" => ToolAnnotations::analysis().with_title("Check Vendor Integrity"),
+        "suggest_autoload_config" => ToolAnnotations::analysis().with_title("Suggest Autoload Config"),
+        "analyze_bus_factor" => ToolAnnotations::analysis().with_title("Analyze Bus Factor"),
+        "compare_constraints" => ToolAnnotations::multi_repo().with_title("Compare Constraints"),
+        "check_publishing_metadata" => ToolAnnotations::analysis().with_title("Check Publishing Metadata"),
+        "overall_health" => ToolAnnotations::analysis().with_title("Overall Health"),
+        "analyze_version_lag" => ToolAnnotations::registry().with_title("Analyze Version Lag"),
+        "explain_suggestion" => ToolAnnotations::analysis().with_title("Explain Suggestion"),
+        "get_changes_since_last" => ToolAnnotations::analysis().with_title("Changes Since Last Snapshot"),
+        "find_overlapping_dependencies" => ToolAnnotations::analysis().with_title("Find Overlapping Dependencies"),
+        "list_installed_binaries" => ToolAnnotations::security().with_title("List Installed Binaries"),
+        "suggest_fix_version" => ToolAnnotations::security().with_title("Suggest Fix Version"),
+        "check_reproducibility" => ToolAnnotations::security().with_title("Check Reproducibility"),
+        "analyze_footprint" => ToolAnnotations::analysis().with_title("Analyze Footprint"),
+        "find_unresolved_imports" => ToolAnnotations::analysis().with_title("Find Unresolved Imports"),
+        "namespace_metrics" => ToolAnnotations::analysis().with_title("Namespace Metrics"),
+        "find_dev_autoload_leaks" => ToolAnnotations::analysis().with_title("Find Dev Autoload Leaks"),
+        "describe_capabilities" => ToolAnnotations::analysis().with_title("Describe Capabilities"),
+        "reanalyze_changed_files" => ToolAnnotations::analysis().with_title("Reanalyze Changed Files"),
+        "analyze_autoload_optimization" => ToolAnnotations::analysis().with_title("Analyze Autoload Optimization"),
+        "generate_release_changelog" => ToolAnnotations::documentation().with_title("Generate Release Changelog"),
+        "find_major_version_conflicts" => ToolAnnotations::analysis().with_title("Find Major Version Conflicts"),
+        "check_package_policy" => ToolAnnotations::security().with_title("Check Package Policy"),
+        "check_license_compatibility" => ToolAnnotations::security().with_title("Check License Compatibility"),
+        "packages_by_license" => ToolAnnotations::security().with_title("Packages By License"),
+        "import_composer_audit" => ToolAnnotations::security().with_title("Import Composer Audit"),
         "generate_dependency_graph" => ToolAnnotations::visualization().with_title("Generate Dependency Graph"),
         "find_circular_dependencies" => ToolAnnotations::visualization().with_title("Find Circular Dependencies"),
+        "generate_revert_script" => ToolAnnotations::analysis().with_title("Generate Revert Script"),
+        "get_dependency_count_trend" => ToolAnnotations::analysis().with_title("Dependency Count Trend"),
+        "analyze_composer_contents" => ToolAnnotations::analysis().with_title("Analyze Composer Contents"),
+        "simulate_update" => ToolAnnotations::registry().with_title("Simulate Update"),
+        "package_info" => ToolAnnotations::registry().with_title("Package Info"),
         "analyze_multi_repo" => ToolAnnotations::multi_repo().with_title("Analyze Multi-Repo"),
+        "compare_repos" => ToolAnnotations::multi_repo().with_title("Compare Repos"),
         "generate_comprehensive_docs" => ToolAnnotations::documentation().with_title("Generate Documentation"),
+        "get_dependency_history" => ToolAnnotations::analysis().with_title("Dependency History"),
+        "check_compliance" => ToolAnnotations::analysis().with_title("Check Compliance"),
+        "get_agent_suggestions" => ToolAnnotations::analysis().with_title("Agent Suggestions"),
         _ => ToolAnnotations::default(),
     }
 }
@@ -30,6 +30,40 @@ impl Default for AuthConfig {
     }
 }
 
+impl AuthConfig {
+    /// Credentials for a call to `method`, or an authentication error
+    pub fn validate(&self, method: &str, headers: &HashMap<String, String>) -> Result<Credentials, McpError> {
+        // If auth disabled, return anonymous
+        if !self.enabled {
+            return Ok(Credentials::anonymous());
+        }
+
+        // Check public methods
+        if self.public_methods.contains(&method.to_string()) {
+            return Ok(Credentials::anonymous());
+        }
+
+        // Get authorization header
+        let auth_header = headers.get("Authorization")
+            .or_else(|| headers.get("authorization"));
+
+        let auth_header = match auth_header {
+            Some(h) => h,
+            None => return Err(authentication_error("Authentication required: No authorization header")),
+        };
+
+        // Extract token
+        let token = auth_header.strip_prefix("Bearer ").unwrap_or(auth_header);
+
+        // Validate token
+        if self.static_tokens.contains(&token.to_string()) {
+            return Ok(Credentials::static_token("mcp-client", &hash_token(token)));
+        }
+
+        Err(authentication_error("Invalid token"))
+    }
+}
+
 /// Credentials representing authenticated context
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Credentials {
@@ -63,6 +97,15 @@ impl Credentials {
     }
 }
 
+/// Generate a unique request id, e.g. `req_1718000000000_3fa9c2`
+pub fn generate_request_id() -> String {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis();
+    format!("req_{}_{:x}", timestamp, rand::random::<u32>())
+}
+
 /// Request context with credentials and metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RequestContext {
@@ -76,18 +119,19 @@ pub struct RequestContext {
 
 impl RequestContext {
     pub fn new(credentials: Credentials) -> Self {
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_millis();
-        
         Self {
             credentials,
-            request_id: format!("req_{}_{:x}", timestamp, rand::random::<u32>()),
+            request_id: generate_request_id(),
             timestamp: chrono::Utc::now().to_rfc3339(),
             client_info: None,
         }
     }
+
+    /// Use a request id supplied by the transport, e.g. from an `X-Request-Id` header
+    pub fn with_request_id(mut self, request_id: String) -> Self {
+        self.request_id = request_id;
+        self
+    }
 }
 
 lazy_static::lazy_static! {
@@ -120,42 +164,9 @@ fn hash_token(token: &str) -> String {
     hex::encode(&result[..8])
 }
 
-/// Validate authentication
+/// Validate authentication against the server-wide configuration
 pub fn validate_auth(method: &str, headers: &HashMap<String, String>) -> Result<Credentials, McpError> {
-    let config = AUTH_CONFIG.read().unwrap();
-
-    // If auth disabled, return anonymous
-    if !config.enabled {
-        return Ok(Credentials::anonymous());
-    }
-
-    // Check public methods
-    if config.public_methods.contains(&method.to_string()) {
-        return Ok(Credentials::anonymous());
-    }
-
-    // Get authorization header
-    let auth_header = headers.get("Authorization")
-        .or_else(|| headers.get("authorization"));
-
-    let auth_header = match auth_header {
-        Some(h) => h,
-        None => return Err(authentication_error("Authentication required: No authorization header")),
-    };
-
-    // Extract token
-    let token = if auth_header.starts_with("Bearer ") {
-        &auth_header[7..]
-    } else {
-        auth_header
-    };
-
-    // Validate token
-    if config.static_tokens.contains(&token.to_string()) {
-        return Ok(Credentials::static_token("mcp-client", &hash_token(token)));
-    }
-
-    Err(authentication_error("Invalid token"))
+    AUTH_CONFIG.read().unwrap().validate(method, headers)
 }
 
 /// Get auth info for reporting
@@ -172,5 +183,5 @@ pub fn generate_token() -> String {
     use rand::Rng;
     let mut rng = rand::thread_rng();
     let bytes: [u8; 24] = rng.gen();
-    BASE64.encode(&bytes)
+    BASE64.encode(bytes)
 }
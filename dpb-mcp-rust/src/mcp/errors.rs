@@ -17,6 +17,7 @@ pub mod error_codes {
     pub const AUTHENTICATION: i32 = -32003;
     pub const RATE_LIMITED: i32 = -32004;
     pub const TIMEOUT: i32 = -32005;
+    pub const STRICT_MODE: i32 = -32006;
 }
 
 /// Typed MCP error
@@ -86,6 +87,26 @@ pub fn timeout_error(message: &str) -> McpError {
     McpError::new("TimeoutError", error_codes::TIMEOUT, message)
 }
 
+/// Analysis succeeded but found problems while the caller asked for strict mode.
+/// `data` carries the full result so the caller needn't re-run the tool.
+pub fn strict_mode_error(message: &str, data: serde_json::Value) -> McpError {
+    McpError::new("StrictModeError", error_codes::STRICT_MODE, message).with_data(data)
+}
+
+/// The typed error name for a JSON-RPC error code, e.g. when re-raising an error read
+/// back from a response; "RpcError" for codes without one
+pub fn error_type_for_code(code: i32) -> &'static str {
+    match code {
+        error_codes::NOT_FOUND => "NotFoundError",
+        error_codes::NOT_ALLOWED => "NotAllowedError",
+        error_codes::VALIDATION => "ValidationError",
+        error_codes::AUTHENTICATION => "AuthenticationError",
+        error_codes::TIMEOUT => "TimeoutError",
+        error_codes::STRICT_MODE => "StrictModeError",
+        _ => "RpcError",
+    }
+}
+
 /// Convert any error to MCP error
 pub fn to_mcp_error(err: &dyn std::error::Error) -> McpError {
     let msg = err.to_string();
@@ -112,6 +133,11 @@ pub fn to_mcp_error(err: &dyn std::error::Error) -> McpError {
 
 /// Convert anyhow::Error to McpError
 pub fn anyhow_to_mcp_error(err: &anyhow::Error) -> McpError {
+    // Handlers that already produced a typed error keep it
+    if let Some(mcp_err) = err.downcast_ref::<McpError>() {
+        return mcp_err.clone();
+    }
+
     let msg = err.to_string();
     let msg_lower = msg.to_lowercase();
 
@@ -0,0 +1,157 @@
+//! Message framing for stream transports
+//! Supports newline-delimited JSON and LSP-style Content-Length headers
+
+use anyhow::Result;
+use std::fmt;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Largest Content-Length accepted; the body buffer is sized from the header, so anything
+/// bigger is refused before allocating
+const MAX_MESSAGE_BYTES: usize = 16 * 1024 * 1024;
+
+/// A malformed message (bad headers or body). Unlike an I/O error the stream is still
+/// usable, so the server answers with a parse error and reads on.
+#[derive(Debug)]
+pub struct FramingError(pub String);
+
+impl fmt::Display for FramingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for FramingError {}
+
+/// How JSON-RPC messages are delimited on the wire
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Framing {
+    /// One JSON message per line
+    Line,
+    /// `Content-Length: N\r\n\r\n` header followed by N bytes of JSON
+    Lsp,
+    /// Decide from the first message received
+    Auto,
+}
+
+impl Framing {
+    /// Read framing from `MCP_FRAMING` (`line`, `lsp` or `auto`); defaults to auto-detection
+    pub fn from_env() -> Self {
+        match std::env::var("MCP_FRAMING").map(|v| v.to_lowercase()).as_deref() {
+            Ok("line") | Ok("ndjson") => Framing::Line,
+            Ok("lsp") | Ok("content-length") => Framing::Lsp,
+            _ => Framing::Auto,
+        }
+    }
+}
+
+fn parse_content_length(header: &str) -> Option<Result<usize>> {
+    let (name, value) = header.split_once(':')?;
+    if !name.trim().eq_ignore_ascii_case("content-length") {
+        return None;
+    }
+    Some(
+        value
+            .trim()
+            .parse::<usize>()
+            .map_err(|_| FramingError(format!("Invalid Content-Length header: {}", header.trim())).into()),
+    )
+}
+
+/// Read the next message body. Returns `None` at EOF, and a [`FramingError`] for a
+/// malformed message.
+/// In auto mode the first message fixes `framing` to the detected variant.
+pub async fn read_message<R>(reader: &mut R, framing: &mut Framing) -> Result<Option<String>>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let mut line = String::new();
+
+    if *framing != Framing::Lsp {
+        if reader.read_line(&mut line).await? == 0 {
+            return Ok(None);
+        }
+
+        if *framing == Framing::Line {
+            return Ok(Some(line));
+        }
+
+        match parse_content_length(&line) {
+            Some(_) => *framing = Framing::Lsp,
+            None => {
+                *framing = Framing::Line;
+                return Ok(Some(line));
+            }
+        }
+    } else {
+        // Skip any blank lines left between messages
+        loop {
+            line.clear();
+            if reader.read_line(&mut line).await? == 0 {
+                return Ok(None);
+            }
+            if !line.trim().is_empty() {
+                break;
+            }
+        }
+    }
+
+    // Header block: `line` holds the first header, read the rest up to the blank separator
+    let mut content_length = None;
+    loop {
+        if let Some(len) = parse_content_length(&line) {
+            content_length = Some(len?);
+        }
+
+        line.clear();
+        if reader.read_line(&mut line).await? == 0 {
+            return Err(FramingError("Unexpected EOF in message headers".to_string()).into());
+        }
+        if line.trim().is_empty() {
+            break;
+        }
+    }
+
+    let len = content_length.ok_or_else(|| FramingError("Missing Content-Length header".to_string()))?;
+    if len > MAX_MESSAGE_BYTES {
+        // Skip the body without buffering it so the next message is read from its header
+        tokio::io::copy(&mut (&mut *reader).take(len as u64), &mut tokio::io::sink()).await?;
+        return Err(FramingError(format!(
+            "Content-Length {} exceeds the {} byte message limit",
+            len, MAX_MESSAGE_BYTES
+        ))
+        .into());
+    }
+    let mut body = vec![0u8; len];
+    reader.read_exact(&mut body).await?;
+    let body = String::from_utf8(body).map_err(|e| FramingError(format!("Message body is not UTF-8: {}", e)))?;
+    Ok(Some(body))
+}
+
+/// Write side of a framed transport
+pub struct MessageWriter<W> {
+    inner: W,
+    pub framing: Framing,
+}
+
+impl<W: AsyncWrite + Unpin> MessageWriter<W> {
+    pub fn new(inner: W, framing: Framing) -> Self {
+        Self { inner, framing }
+    }
+
+    pub async fn write_message(&mut self, json: &str) -> Result<()> {
+        match self.framing {
+            Framing::Lsp => {
+                let header = format!("Content-Length: {}\r\n\r\n", json.len());
+                self.inner.write_all(header.as_bytes()).await?;
+                self.inner.write_all(json.as_bytes()).await?;
+            }
+            // Nothing detected yet (e.g. a parse error on the first read) - fall back to lines
+            Framing::Line | Framing::Auto => {
+                self.inner.write_all(json.as_bytes()).await?;
+                self.inner.write_all(b"\n").await?;
+            }
+        }
+        self.inner.flush().await?;
+        Ok(())
+    }
+}
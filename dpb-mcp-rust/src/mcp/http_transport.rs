@@ -1,19 +1,32 @@
 //! HTTP/SSE Transport for MCP
 //! Provides HTTP endpoints alongside stdio transport
 
-#![allow(dead_code)]
-
 use anyhow::Result;
-use hyper::{body::Bytes, Request, Response, StatusCode, Method};
-use http_body_util::{BodyExt, Full};
-use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
+use hyper::{body::{Body, Bytes, Frame}, Request, Response, StatusCode, Method};
+use http_body_util::{combinators::BoxBody, BodyExt, Full, LengthLimitError, Limited};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
+use std::convert::Infallible;
 use std::net::SocketAddr;
+use std::path::Path;
+use std::pin::Pin;
 use std::sync::Arc;
-use tokio::sync::{broadcast, RwLock};
+use std::task::{Context, Poll};
+use std::time::{Instant, SystemTime};
+use tokio::net::TcpListener;
+use tokio::sync::{mpsc, Semaphore};
+
+use super::auth::{generate_request_id, validate_auth, AuthConfig, Credentials, RequestContext};
+use super::errors::{error_codes, McpError};
 
-use super::auth::{validate_auth, Credentials, RequestContext};
+/// Correlation header, read from the request and echoed on every response
+const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Files besides the lock (see [`crate::composer::lock_path`]) whose contents determine the
+/// output of a cacheable tool
+const CACHE_KEY_FILES: [&str; 2] = ["composer.json", ".dpb.json"];
 
 /// HTTP/SSE configuration
 #[derive(Debug, Clone)]
@@ -22,6 +35,21 @@ pub struct HttpConfig {
     pub host: String,
     pub base_path: String,
     pub cors_origins: Vec<String>,
+    /// Connections served at once; further clients wait in the accept backlog
+    pub max_connections: usize,
+    /// Largest JSON-RPC request body accepted, in bytes
+    pub max_body_bytes: usize,
+    /// Log method, path, status and duration of every request (`MCP_LOG_LEVEL=debug`)
+    pub log_requests: bool,
+    /// Tools whose `tools/call` results are streamed as SSE segments instead of one JSON body
+    pub streamed_tools: Vec<String>,
+    /// Largest segment of a streamed result, in bytes
+    pub stream_chunk_bytes: usize,
+    /// Tools served at `GET {base_path}/v1/tools/{name}` with an ETag derived from the
+    /// repository's composer files; only tools whose output depends on nothing else belong here
+    pub cacheable_tools: Vec<String>,
+    /// Authentication for HTTP clients; `None` uses the server-wide configuration
+    pub auth: Option<AuthConfig>,
 }
 
 impl Default for HttpConfig {
@@ -31,35 +59,111 @@ impl Default for HttpConfig {
             host: "127.0.0.1".to_string(),
             base_path: "/api/mcp".to_string(),
             cors_origins: vec!["*".to_string()],
+            max_connections: 64,
+            max_body_bytes: 1024 * 1024,
+            log_requests: std::env::var("MCP_LOG_LEVEL").is_ok_and(|v| v.eq_ignore_ascii_case("debug")),
+            streamed_tools: [
+                "detect_namespaces",
+                "analyze_namespace_usage",
+                "generate_dependency_graph",
+                "generate_comprehensive_docs",
+                "reanalyze_changed_files",
+            ]
+            .iter()
+            .map(|t| t.to_string())
+            .collect(),
+            stream_chunk_bytes: 64 * 1024,
+            cacheable_tools: [
+                "analyze_dependencies",
+                "audit_security",
+                "analyze_licenses",
+                "check_license_compatibility",
+                "find_circular_dependencies",
+                "find_major_version_conflicts",
+                "check_reproducibility",
+                "list_installed_binaries",
+                "validate_composer_json",
+            ]
+            .iter()
+            .map(|t| t.to_string())
+            .collect(),
+            auth: None,
+        }
+    }
+}
+
+impl HttpConfig {
+    /// Defaults overridden by `MCP_HTTP_PORT`, `MCP_HTTP_MAX_CONNECTIONS`, `MCP_HTTP_MAX_BODY_BYTES`,
+    /// `MCP_HTTP_STREAMED_TOOLS` (comma-separated) and `MCP_HTTP_STREAM_CHUNK_BYTES`
+    pub fn from_env() -> Result<Self> {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        // Zero would stall every connection or loop forever on a streamed result
+        let positive = |name: &str| -> Result<Option<usize>> {
+            var(name)
+                .map(|value| match value.trim().parse::<usize>() {
+                    Ok(n) if n > 0 => Ok(n),
+                    _ => Err(anyhow::anyhow!("Invalid {}: {}", name, value)),
+                })
+                .transpose()
+        };
+
+        let mut config = Self::default();
+        if let Some(port) = var("MCP_HTTP_PORT") {
+            config.port = port.parse().map_err(|_| anyhow::anyhow!("Invalid MCP_HTTP_PORT: {}", port))?;
         }
+        if let Some(n) = positive("MCP_HTTP_MAX_CONNECTIONS")? {
+            config.max_connections = n;
+        }
+        if let Some(n) = positive("MCP_HTTP_MAX_BODY_BYTES")? {
+            config.max_body_bytes = n;
+        }
+        if let Some(n) = positive("MCP_HTTP_STREAM_CHUNK_BYTES")? {
+            config.stream_chunk_bytes = n;
+        }
+        if let Some(tools) = var("MCP_HTTP_STREAMED_TOOLS") {
+            config.streamed_tools =
+                tools.split(',').map(str::trim).filter(|t| !t.is_empty()).map(str::to_string).collect();
+        }
+        Ok(config)
     }
 }
 
 /// JSON-RPC request
 #[derive(Debug, Deserialize)]
 struct JsonRpcRequest {
+    #[allow(dead_code)]
     jsonrpc: String,
     id: Option<Value>,
     method: String,
     params: Option<Value>,
 }
 
-/// JSON-RPC response
-#[derive(Debug, Serialize)]
-struct JsonRpcResponse {
-    jsonrpc: String,
-    id: Option<Value>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    result: Option<Value>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    error: Option<Value>,
+/// Response body: a buffered JSON document or a streamed result
+type HttpBody = BoxBody<Bytes, Infallible>;
+
+/// Outcome of a JSON-RPC call
+enum RpcReply {
+    Json((StatusCode, Value)),
+    /// SSE events (`chunk`, then `complete`) produced by a background task
+    Stream(mpsc::Receiver<Bytes>),
 }
 
-/// SSE event
-#[derive(Debug, Clone, Serialize)]
-pub struct SseEvent {
-    pub event: String,
-    pub data: Value,
+/// Body that forwards SSE events as they are produced, so the client never
+/// waits for (or buffers) the whole result
+struct EventStreamBody {
+    rx: mpsc::Receiver<Bytes>,
+}
+
+impl Body for EventStreamBody {
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Frame<Bytes>, Infallible>>> {
+        self.get_mut().rx.poll_recv(cx).map(|event| event.map(|bytes| Ok(Frame::data(bytes))))
+    }
 }
 
 /// Request handler type
@@ -73,8 +177,6 @@ pub type RequestHandler = Arc<
 pub struct HttpTransport {
     config: HttpConfig,
     handler: RequestHandler,
-    sse_tx: broadcast::Sender<SseEvent>,
-    client_count: Arc<RwLock<u64>>,
 }
 
 impl HttpTransport {
@@ -84,8 +186,6 @@ impl HttpTransport {
         F: Fn(String, Value, RequestContext) -> Fut + Send + Sync + 'static,
         Fut: std::future::Future<Output = Result<Value>> + Send + 'static,
     {
-        let (sse_tx, _) = broadcast::channel(100);
-        
         let handler: RequestHandler = Arc::new(move |method, params, ctx| {
             let fut = handler(method, params, ctx);
             Box::pin(fut)
@@ -94,29 +194,14 @@ impl HttpTransport {
         Self {
             config: config.unwrap_or_default(),
             handler,
-            sse_tx,
-            client_count: Arc::new(RwLock::new(0)),
         }
     }
 
-    /// Broadcast event to all SSE clients
-    pub fn broadcast(&self, event: &str, data: Value) {
-        let _ = self.sse_tx.send(SseEvent {
-            event: event.to_string(),
-            data,
-        });
-    }
-
     /// Start HTTP server (simplified version using hyper directly)
     pub async fn start(self: Arc<Self>) -> Result<()> {
-        use hyper::server::conn::http1;
-        use hyper::service::service_fn;
-        use hyper_util::rt::TokioIo;
-        use tokio::net::TcpListener;
-
         let addr: SocketAddr = format!("{}:{}", self.config.host, self.config.port)
             .parse()
-            .unwrap();
+            .map_err(|e| anyhow::anyhow!("Invalid HTTP address {}:{}: {}", self.config.host, self.config.port, e))?;
 
         let listener = TcpListener::bind(addr).await?;
         
@@ -124,17 +209,27 @@ impl HttpTransport {
             "HTTP/SSE transport listening on http://{}{}",
             addr, self.config.base_path
         );
-        eprintln!(
-            "SSE endpoint: http://{}{}/sse",
-            addr, self.config.base_path
-        );
+
+        self.serve(listener).await
+    }
+
+    /// Serve connections accepted on `listener`, at most `max_connections` at a time
+    async fn serve(self: Arc<Self>, listener: TcpListener) -> Result<()> {
+        use hyper::server::conn::http1;
+        use hyper::service::service_fn;
+        use hyper_util::rt::TokioIo;
+
+        let connection_slots = Arc::new(Semaphore::new(self.config.max_connections.max(1)));
 
         loop {
+            // Stop accepting while all slots are busy, so excess clients queue in the kernel backlog
+            let permit = Arc::clone(&connection_slots).acquire_owned().await?;
             let (stream, _) = listener.accept().await?;
             let io = TokioIo::new(stream);
             let transport = Arc::clone(&self);
 
             tokio::spawn(async move {
+                let _permit = permit;
                 let service = service_fn(move |req| {
                     let transport = Arc::clone(&transport);
                     async move { transport.handle_request(req).await }
@@ -150,30 +245,57 @@ impl HttpTransport {
         }
     }
 
-    async fn handle_request(
-        &self,
-        req: Request<hyper::body::Incoming>,
-    ) -> Result<Response<Full<Bytes>>, hyper::Error> {
+    async fn handle_request<B>(&self, req: Request<B>) -> Result<Response<HttpBody>, hyper::Error>
+    where
+        B: Body<Data = Bytes>,
+        B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        let started = Instant::now();
         let path = req.uri().path().to_string();
         let method = req.method().clone();
+        let origin = req
+            .headers()
+            .get(hyper::header::ORIGIN)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string());
 
-        // CORS headers
-        let response_headers = vec![
-            ("Access-Control-Allow-Origin", "*"),
-            ("Access-Control-Allow-Methods", "GET, POST, OPTIONS"),
-            ("Access-Control-Allow-Headers", "Content-Type, Authorization"),
-        ];
+        // Echo the client's request id so failures can be correlated with server logs
+        let request_id = req
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty() && v.len() <= 128)
+            .map(|v| v.to_string())
+            .unwrap_or_else(generate_request_id);
+
+        let if_none_match = req
+            .headers()
+            .get(hyper::header::IF_NONE_MATCH)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string());
 
         // Handle preflight
         if method == Method::OPTIONS {
-            let mut response = Response::new(Full::new(Bytes::new()));
+            let mut response = Response::new(Full::new(Bytes::new()).boxed());
             *response.status_mut() = StatusCode::NO_CONTENT;
-            for (key, value) in response_headers {
-                response.headers_mut().insert(
-                    hyper::header::HeaderName::from_static(key),
-                    hyper::header::HeaderValue::from_static(value),
-                );
-            }
+            self.finish_response(&mut response, &request_id, origin.as_deref(), &method, &path, started);
+            return Ok(response);
+        }
+
+        // Read-only analysis over GET, revalidated against the repository's composer files
+        let tools_prefix = format!("{}/v1/tools/", self.config.base_path);
+        if let Some(tool) = path.strip_prefix(&tools_prefix).filter(|_| method == Method::GET) {
+            let headers: HashMap<String, String> = req
+                .headers()
+                .iter()
+                .filter_map(|(k, v)| v.to_str().ok().map(|v| (k.to_string(), v.to_string())))
+                .collect();
+            let query = req.uri().query().unwrap_or_default();
+            let mut response = self
+                .handle_tool_get(tool, query, &headers, if_none_match.as_deref(), &request_id)
+                .await;
+            self.finish_response(&mut response, &request_id, origin.as_deref(), &method, &path, started);
             return Ok(response);
         }
 
@@ -182,7 +304,23 @@ impl HttpTransport {
             || path == format!("{}/v1/", self.config.base_path)
         {
             if method == Method::POST {
-                self.handle_json_rpc(req).await
+                match self.handle_json_rpc(req, &request_id).await {
+                    RpcReply::Json(reply) => reply,
+                    RpcReply::Stream(rx) => {
+                        let mut response = Response::new(EventStreamBody { rx }.boxed());
+                        let headers = response.headers_mut();
+                        headers.insert(
+                            hyper::header::CONTENT_TYPE,
+                            hyper::header::HeaderValue::from_static("text/event-stream"),
+                        );
+                        headers.insert(
+                            hyper::header::CACHE_CONTROL,
+                            hyper::header::HeaderValue::from_static("no-cache"),
+                        );
+                        self.finish_response(&mut response, &request_id, origin.as_deref(), &method, &path, started);
+                        return Ok(response);
+                    }
+                }
             } else {
                 (StatusCode::METHOD_NOT_ALLOWED, json!({"error": "Method not allowed"}))
             }
@@ -195,26 +333,101 @@ impl HttpTransport {
         };
 
         let body_str = serde_json::to_string(&body).unwrap_or_default();
-        let mut response = Response::new(Full::new(Bytes::from(body_str)));
-        *response.status_mut() = status;
-        response.headers_mut().insert(
-            hyper::header::CONTENT_TYPE,
-            hyper::header::HeaderValue::from_static("application/json"),
-        );
-        for (key, value) in response_headers {
+
+        // /info only changes with the configuration, so clients can revalidate it; /health never caches
+        let info_etag = (path == format!("{}/info", self.config.base_path))
+            .then(|| format!("\"{}\"", short_hash(body_str.as_bytes())));
+        let not_modified = info_etag
+            .as_deref()
+            .is_some_and(|etag| etag_matches(if_none_match.as_deref(), etag));
+
+        let mut response = if not_modified {
+            let mut response = Response::new(Full::new(Bytes::new()).boxed());
+            *response.status_mut() = StatusCode::NOT_MODIFIED;
+            response
+        } else {
+            let mut response = Response::new(Full::new(Bytes::from(body_str)).boxed());
+            *response.status_mut() = status;
             response.headers_mut().insert(
-                hyper::header::HeaderName::from_static(key),
-                hyper::header::HeaderValue::from_static(value),
+                hyper::header::CONTENT_TYPE,
+                hyper::header::HeaderValue::from_static("application/json"),
             );
+            response
+        };
+        let cache_control = if info_etag.is_some() { "no-cache" } else { "no-store" };
+        response.headers_mut().insert(
+            hyper::header::CACHE_CONTROL,
+            hyper::header::HeaderValue::from_static(cache_control),
+        );
+        if let Some(value) = info_etag.and_then(|etag| hyper::header::HeaderValue::from_str(&etag).ok()) {
+            response.headers_mut().insert(hyper::header::ETAG, value);
         }
+        self.finish_response(&mut response, &request_id, origin.as_deref(), &method, &path, started);
 
         Ok(response)
     }
 
-    async fn handle_json_rpc(
+    /// Attach the request id and CORS headers, and log the request when enabled
+    fn finish_response(
         &self,
-        req: Request<hyper::body::Incoming>,
-    ) -> (StatusCode, Value) {
+        response: &mut Response<HttpBody>,
+        request_id: &str,
+        origin: Option<&str>,
+        method: &Method,
+        path: &str,
+        started: Instant,
+    ) {
+        let headers = response.headers_mut();
+        if let Ok(value) = hyper::header::HeaderValue::from_str(request_id) {
+            headers.insert(REQUEST_ID_HEADER, value);
+        }
+        if let Some(value) = self.allowed_origin(origin).and_then(|o| hyper::header::HeaderValue::from_str(o).ok()) {
+            for (key, value) in [
+                ("access-control-allow-methods", "GET, POST, OPTIONS"),
+                ("access-control-allow-headers", "Content-Type, Authorization, X-Request-Id, If-None-Match"),
+                ("access-control-expose-headers", "X-Request-Id, ETag, Last-Modified"),
+            ] {
+                headers.insert(key, hyper::header::HeaderValue::from_static(value));
+            }
+            headers.insert(hyper::header::ACCESS_CONTROL_ALLOW_ORIGIN, value);
+        }
+        // The answer depends on the origin unless every origin is allowed
+        if !self.config.cors_origins.iter().any(|o| o == "*") {
+            headers.append(hyper::header::VARY, hyper::header::HeaderValue::from_static("Origin"));
+        }
+        if self.config.log_requests {
+            eprintln!(
+                "[HTTP] {} {} -> {} in {}ms (request id {})",
+                method,
+                path,
+                response.status().as_u16(),
+                started.elapsed().as_millis(),
+                request_id
+            );
+        }
+    }
+
+    /// `Access-Control-Allow-Origin` for a request from `origin`: `*` when every origin is
+    /// allowed, the origin itself when it is listed in `cors_origins`, otherwise none
+    fn allowed_origin<'a>(&self, origin: Option<&'a str>) -> Option<&'a str> {
+        if self.config.cors_origins.iter().any(|o| o == "*") {
+            return Some("*");
+        }
+        origin.filter(|origin| self.config.cors_origins.iter().any(|o| o == origin))
+    }
+
+    fn authenticate(&self, method: &str, headers: &HashMap<String, String>) -> Result<Credentials, McpError> {
+        match &self.config.auth {
+            Some(auth) => auth.validate(method, headers),
+            None => validate_auth(method, headers),
+        }
+    }
+
+    async fn handle_json_rpc<B>(&self, req: Request<B>, request_id: &str) -> RpcReply
+    where
+        B: Body<Data = Bytes>,
+        B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
         // Extract headers before consuming body
         let headers: HashMap<String, String> = req
             .headers()
@@ -224,28 +437,70 @@ impl HttpTransport {
             })
             .collect();
 
-        // Read body
-        let body_bytes = match req.collect().await {
+        // Reject oversized bodies up front when the client declares the length
+        let max_body_bytes = self.config.max_body_bytes;
+        let declared_length = headers
+            .get("content-length")
+            .and_then(|v| v.parse::<usize>().ok());
+        if declared_length.is_some_and(|len| len > max_body_bytes) {
+            return RpcReply::Json(payload_too_large(max_body_bytes));
+        }
+
+        // Read body, never buffering more than the limit
+        let body_bytes = match Limited::new(req.into_body(), max_body_bytes).collect().await {
             Ok(collected) => collected.to_bytes(),
-            Err(_) => return (StatusCode::BAD_REQUEST, json!({"error": "Failed to read body"})),
+            Err(e) if e.is::<LengthLimitError>() => return RpcReply::Json(payload_too_large(max_body_bytes)),
+            Err(_) => {
+                return RpcReply::Json((
+                    StatusCode::BAD_REQUEST,
+                    json_rpc_error(None, error_codes::INVALID_REQUEST, "Failed to read body", None),
+                ))
+            }
         };
 
-        // Parse JSON-RPC request
-        let request: JsonRpcRequest = match serde_json::from_slice(&body_bytes) {
+        // Parse JSON-RPC request; the id is echoed whenever the body is valid JSON
+        let body: Value = match serde_json::from_slice(&body_bytes) {
+            Ok(v) => v,
+            Err(e) => {
+                return RpcReply::Json((
+                    StatusCode::BAD_REQUEST,
+                    json_rpc_error(None, error_codes::PARSE_ERROR, &format!("Parse error: {}", e), None),
+                ))
+            }
+        };
+        let id = body.get("id").cloned();
+        let request: JsonRpcRequest = match serde_json::from_value(body) {
             Ok(r) => r,
-            Err(_) => return (StatusCode::BAD_REQUEST, json!({"error": "Invalid JSON"})),
+            Err(e) => {
+                return RpcReply::Json((
+                    StatusCode::BAD_REQUEST,
+                    json_rpc_error(id, error_codes::INVALID_REQUEST, &format!("Invalid request: {}", e), None),
+                ))
+            }
         };
 
         // Validate auth
-        let credentials = validate_auth(&request.method, &headers)
-            .unwrap_or_else(|_| Credentials::anonymous());
-        let ctx = RequestContext::new(credentials);
+        let credentials = match self.authenticate(&request.method, &headers) {
+            Ok(credentials) => credentials,
+            Err(e) => {
+                return RpcReply::Json((StatusCode::UNAUTHORIZED, json_rpc_error(request.id, e.code, &e.message, None)))
+            }
+        };
+        let ctx = RequestContext::new(credentials).with_request_id(request_id.to_string());
 
-        // Call handler
+        // Call handler; designated heavy tools run in the background and stream their result
         let params = request.params.unwrap_or(json!({}));
-        let result = (self.handler)(request.method, params, ctx).await;
+        let streamed = request.method == "tools/call"
+            && params
+                .get("name")
+                .and_then(|n| n.as_str())
+                .is_some_and(|name| self.config.streamed_tools.iter().any(|t| t == name));
+        let call = (self.handler)(request.method, params, ctx);
+        if streamed {
+            return RpcReply::Stream(stream_result(call, request.id, self.config.stream_chunk_bytes));
+        }
 
-        match result {
+        RpcReply::Json(match call.await {
             Ok(value) => (
                 StatusCode::OK,
                 json!({
@@ -254,17 +509,99 @@ impl HttpTransport {
                     "result": value
                 }),
             ),
-            Err(e) => (
-                StatusCode::OK,
-                json!({
-                    "jsonrpc": "2.0",
-                    "id": request.id,
-                    "error": {
-                        "code": -32603,
-                        "message": e.to_string()
-                    }
-                }),
-            ),
+            Err(e) => (StatusCode::OK, handler_error(request.id, &e)),
+        })
+    }
+
+    /// Run a cacheable tool with its arguments taken from the query string. The ETag hashes
+    /// the repository's composer files and the arguments, so a matching `If-None-Match`
+    /// is answered with 304 before any analysis runs.
+    async fn handle_tool_get(
+        &self,
+        tool: &str,
+        query: &str,
+        headers: &HashMap<String, String>,
+        if_none_match: Option<&str>,
+        request_id: &str,
+    ) -> Response<HttpBody> {
+        let json_response = |status: StatusCode, body: Value| {
+            let mut response = Response::new(Full::new(Bytes::from(body.to_string())).boxed());
+            *response.status_mut() = status;
+            response.headers_mut().insert(
+                hyper::header::CONTENT_TYPE,
+                hyper::header::HeaderValue::from_static("application/json"),
+            );
+            response
+        };
+
+        if !self.config.cacheable_tools.iter().any(|t| t == tool) {
+            return json_response(
+                StatusCode::NOT_FOUND,
+                json!({"error": format!("Tool {} is not available over GET", tool)}),
+            );
+        }
+
+        let credentials = match self.authenticate("tools/call", headers) {
+            Ok(credentials) => credentials,
+            Err(e) => return json_response(StatusCode::UNAUTHORIZED, json!({"error": e.message})),
+        };
+
+        let mut pairs: Vec<(String, String)> = form_urlencoded::parse(query.as_bytes()).into_owned().collect();
+        pairs.sort();
+        let Some(repo_path) = pairs.iter().find(|(k, _)| k == "repo_path").map(|(_, v)| v.clone()) else {
+            return json_response(StatusCode::BAD_REQUEST, json!({"error": "repo_path required"}));
+        };
+
+        let validator = cache_validator(Path::new(&repo_path), tool, &pairs);
+        let with_validator = |mut response: Response<HttpBody>| {
+            let headers = response.headers_mut();
+            headers.insert(
+                hyper::header::CACHE_CONTROL,
+                hyper::header::HeaderValue::from_static("no-cache"),
+            );
+            if let Some((etag, modified)) = &validator {
+                if let Ok(value) = hyper::header::HeaderValue::from_str(etag) {
+                    headers.insert(hyper::header::ETAG, value);
+                }
+                if let Ok(value) = hyper::header::HeaderValue::from_str(&http_date(*modified)) {
+                    headers.insert(hyper::header::LAST_MODIFIED, value);
+                }
+            }
+            response
+        };
+
+        if validator.as_ref().is_some_and(|(etag, _)| etag_matches(if_none_match, etag)) {
+            let mut response = Response::new(Full::new(Bytes::new()).boxed());
+            *response.status_mut() = StatusCode::NOT_MODIFIED;
+            return with_validator(response);
+        }
+
+        // Query values are strings; booleans and numbers are restored so tools read them as usual
+        let arguments: Map<String, Value> = pairs
+            .into_iter()
+            .map(|(key, value)| {
+                let value = match value.as_str() {
+                    "true" => Value::Bool(true),
+                    "false" => Value::Bool(false),
+                    _ => match value.parse::<f64>().ok().and_then(serde_json::Number::from_f64) {
+                        Some(n) => Value::Number(n),
+                        None => Value::String(value),
+                    },
+                };
+                (key, value)
+            })
+            .collect();
+
+        let ctx = RequestContext::new(credentials).with_request_id(request_id.to_string());
+        let params = json!({"name": tool, "arguments": arguments});
+
+        match (self.handler)("tools/call".to_string(), params, ctx).await {
+            // Failed analyses aren't worth revalidating
+            Ok(result) if result.get("isError").and_then(|v| v.as_bool()) == Some(true) => {
+                json_response(StatusCode::UNPROCESSABLE_ENTITY, result)
+            }
+            Ok(result) => with_validator(json_response(StatusCode::OK, result)),
+            Err(e) => json_response(StatusCode::INTERNAL_SERVER_ERROR, json!({"error": e.to_string()})),
         }
     }
 
@@ -287,10 +624,399 @@ impl HttpTransport {
                 "protocols": ["stdio", "http", "sse"],
                 "endpoints": {
                     "http": format!("{}/v1", self.config.base_path),
-                    "sse": format!("{}/v1/sse", self.config.base_path),
-                    "health": format!("{}/health", self.config.base_path)
-                }
+                    "health": format!("{}/health", self.config.base_path),
+                    "tools": format!("{}/v1/tools/{{name}}", self.config.base_path)
+                },
+                "streamedTools": self.config.streamed_tools,
+                "cacheableTools": self.config.cacheable_tools
+            }),
+        )
+    }
+}
+
+fn payload_too_large(limit: usize) -> (StatusCode, Value) {
+    (
+        StatusCode::PAYLOAD_TOO_LARGE,
+        json_rpc_error(
+            None,
+            error_codes::INVALID_REQUEST,
+            &format!("Request body exceeds {} bytes", limit),
+            None,
+        ),
+    )
+}
+
+/// Strong ETag and Last-Modified time for a cacheable tool call: a hash of the composer files,
+/// the current UTC date, the tool and its (sorted) arguments, and the newest modification time
+/// among those files (no earlier than midnight). `None` when the repository has none of the files.
+/// Some results depend on the clock (e.g. the staleness check of `audit_security`), so a
+/// validator only holds for the day it was issued.
+fn cache_validator(repo_path: &Path, tool: &str, arguments: &[(String, String)]) -> Option<(String, SystemTime)> {
+    let mut hasher = Sha256::new();
+    let mut modified: Option<SystemTime> = None;
+
+    let lock = crate::composer::lock_path(repo_path);
+    let files = CACHE_KEY_FILES.iter().map(|name| repo_path.join(name)).chain(std::iter::once(lock));
+    for path in files {
+        if let Some(name) = path.file_name() {
+            hasher.update(name.as_encoded_bytes());
+        }
+        if let Ok(contents) = std::fs::read(&path) {
+            hasher.update(Sha256::digest(&contents));
+            let mtime = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+            modified = modified.max(mtime);
+        }
+    }
+    let today = chrono::Utc::now().date_naive();
+    let midnight = SystemTime::from(today.and_time(chrono::NaiveTime::MIN).and_utc());
+    let modified = modified?.max(midnight);
+
+    hasher.update(today.to_string().as_bytes());
+    hasher.update(tool.as_bytes());
+    for (key, value) in arguments {
+        hasher.update(format!("\0{}={}", key, value).as_bytes());
+    }
+    Some((format!("\"{}\"", hex::encode(&hasher.finalize()[..16])), modified))
+}
+
+fn short_hash(bytes: &[u8]) -> String {
+    hex::encode(&Sha256::digest(bytes)[..16])
+}
+
+/// Whether an `If-None-Match` header value lists `etag` (weak comparison, as RFC 9110 requires)
+fn etag_matches(if_none_match: Option<&str>, etag: &str) -> bool {
+    let Some(header) = if_none_match else {
+        return false;
+    };
+    let etag = etag.trim_start_matches("W/");
+    header
+        .split(',')
+        .map(str::trim)
+        .any(|candidate| candidate == "*" || candidate.trim_start_matches("W/") == etag)
+}
+
+/// IMF-fixdate, the format of `Last-Modified`
+fn http_date(time: SystemTime) -> String {
+    chrono::DateTime::<chrono::Utc>::from(time)
+        .format("%a, %d %b %Y %H:%M:%S GMT")
+        .to_string()
+}
+
+/// JSON-RPC error envelope; `id` is null when the request's id couldn't be read
+fn json_rpc_error(id: Option<Value>, code: i32, message: &str, data: Option<&Value>) -> Value {
+    let mut error = json!({
+        "code": code,
+        "message": message
+    });
+    if let Some(data) = data {
+        error["data"] = data.clone();
+    }
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": error
+    })
+}
+
+/// JSON-RPC error for a failed handler call, keeping the code and data of a typed [`McpError`]
+/// (a StrictModeError's data is the full result)
+fn handler_error(id: Option<Value>, e: &anyhow::Error) -> Value {
+    match e.downcast_ref::<McpError>() {
+        Some(err) => json_rpc_error(id, err.code, &err.message, err.data.as_ref()),
+        None => json_rpc_error(id, error_codes::INTERNAL_ERROR, &e.to_string(), None),
+    }
+}
+
+/// Run a handler call in the background and emit its JSON-RPC response as SSE
+/// `chunk` events of at most `chunk_bytes`, followed by a `complete` event.
+/// Clients concatenate the `data` strings of the chunks in `index` order and parse the result.
+fn stream_result(
+    call: Pin<Box<dyn std::future::Future<Output = Result<Value>> + Send>>,
+    id: Option<Value>,
+    chunk_bytes: usize,
+) -> mpsc::Receiver<Bytes> {
+    // A small buffer gives backpressure: segments are serialized only as fast as the client reads
+    let (tx, rx) = mpsc::channel(4);
+
+    tokio::spawn(async move {
+        let response = match call.await {
+            Ok(value) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "result": value
             }),
+            Err(e) => handler_error(id.clone(), &e),
+        };
+        let text = serde_json::to_string(&response).unwrap_or_default();
+
+        let chunks = split_chunks(&text, chunk_bytes.max(1));
+        for (index, chunk) in chunks.iter().enumerate() {
+            let event = sse_event("chunk", &json!({"index": index, "data": chunk}));
+            if tx.send(event).await.is_err() {
+                return; // client went away
+            }
+        }
+        let done = json!({"id": id, "chunks": chunks.len(), "bytes": text.len()});
+        let _ = tx.send(sse_event("complete", &done)).await;
+    });
+
+    rx
+}
+
+/// Split `text` into pieces of at most `max` bytes without cutting through a UTF-8 character
+fn split_chunks(text: &str, max: usize) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let mut end = max.min(rest.len());
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        if end == 0 {
+            // A single character wider than `max`
+            end = rest.chars().next().map_or(rest.len(), |c| c.len_utf8());
+        }
+        let (chunk, tail) = rest.split_at(end);
+        chunks.push(chunk);
+        rest = tail;
+    }
+    chunks
+}
+
+fn sse_event(event: &str, data: &Value) -> Bytes {
+    Bytes::from(format!("event: {}\ndata: {}\n\n", event, data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::header::HeaderValue;
+
+    /// Transport whose handler answers with the method called and the caller's subject
+    fn echo_transport(config: HttpConfig) -> HttpTransport {
+        HttpTransport::new(
+            |method, _params, ctx: RequestContext| async move {
+                Ok(json!({"method": method, "subject": ctx.credentials.subject}))
+            },
+            Some(config),
         )
     }
+
+    async fn send(transport: &HttpTransport, request: Request<Full<Bytes>>) -> (StatusCode, hyper::HeaderMap, Value) {
+        let (parts, body) = transport.handle_request(request).await.unwrap().into_parts();
+        let body = body.collect().await.unwrap().to_bytes();
+        (parts.status, parts.headers, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    fn rpc(method: &str) -> Request<Full<Bytes>> {
+        let body = json!({"jsonrpc": "2.0", "id": 1, "method": method}).to_string();
+        Request::post("/api/mcp/v1").body(Full::new(Bytes::from(body))).unwrap()
+    }
+
+    fn get(path: &str) -> Request<Full<Bytes>> {
+        Request::get(path).body(Full::new(Bytes::new())).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_cors_allows_only_configured_origins() {
+        let transport = echo_transport(HttpConfig {
+            cors_origins: vec!["https://app.example".to_string()],
+            ..Default::default()
+        });
+        let preflight = |origin: &str| {
+            Request::options("/api/mcp/v1")
+                .header("origin", origin)
+                .body(Full::new(Bytes::new()))
+                .unwrap()
+        };
+
+        let (status, headers, _) = send(&transport, preflight("https://app.example")).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        assert_eq!(headers["access-control-allow-origin"], "https://app.example");
+        assert_eq!(headers["vary"], "Origin");
+
+        let (_, headers, _) = send(&transport, preflight("https://other.example")).await;
+        assert!(!headers.contains_key("access-control-allow-origin"));
+    }
+
+    #[test]
+    fn test_config_reads_limits_from_env() {
+        let vars: HashMap<&str, &str> = [
+            ("MCP_HTTP_MAX_CONNECTIONS", "8"),
+            ("MCP_HTTP_MAX_BODY_BYTES", "2048"),
+            ("MCP_HTTP_STREAMED_TOOLS", "detect_namespaces, audit_security,"),
+            ("MCP_HTTP_STREAM_CHUNK_BYTES", "512"),
+        ]
+        .into();
+        let config = HttpConfig::from_vars(|name| vars.get(name).map(|v| v.to_string())).unwrap();
+
+        assert_eq!(config.port, 3000);
+        assert_eq!(config.max_connections, 8);
+        assert_eq!(config.max_body_bytes, 2048);
+        assert_eq!(config.streamed_tools, ["detect_namespaces", "audit_security"]);
+        assert_eq!(config.stream_chunk_bytes, 512);
+
+        assert!(HttpConfig::from_vars(|name| (name == "MCP_HTTP_MAX_CONNECTIONS").then(|| "0".to_string())).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_strict_mode_error_keeps_its_result() {
+        let transport = HttpTransport::new(
+            |_method, _params, _ctx: RequestContext| async move {
+                Err(crate::mcp::strict_mode_error("Strict mode: 1 finding", json!({"findings": [1]})).into())
+            },
+            Some(HttpConfig::default()),
+        );
+
+        let (status, _, body) = send(&transport, rpc("tools/call")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["error"]["code"], error_codes::STRICT_MODE);
+        assert_eq!(body["error"]["data"], json!({"findings": [1]}));
+    }
+
+    #[tokio::test]
+    async fn test_auth_rejects_calls_without_a_valid_token() {
+        let auth = AuthConfig {
+            enabled: true,
+            static_tokens: vec!["secret".to_string()],
+            ..Default::default()
+        };
+        let transport = echo_transport(HttpConfig { auth: Some(auth), ..Default::default() });
+
+        let (status, _, body) = send(&transport, rpc("tools/call")).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["error"]["code"], error_codes::AUTHENTICATION);
+        assert_eq!(body["id"], 1);
+
+        let (status, _, _) = send(&transport, get("/api/mcp/v1/tools/audit_security?repo_path=.")).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let mut request = rpc("tools/call");
+        request.headers_mut().insert("authorization", HeaderValue::from_static("Bearer secret"));
+        let (status, _, body) = send(&transport, request).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["result"]["subject"], "mcp-client");
+
+        // Public methods need no token
+        let (status, _, body) = send(&transport, rpc("tools/list")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["result"]["method"], "tools/list");
+    }
+
+    #[tokio::test]
+    async fn test_health_echoes_request_id_and_is_never_cached() {
+        let transport = echo_transport(HttpConfig::default());
+        let mut request = get("/api/mcp/health");
+        request.headers_mut().insert(REQUEST_ID_HEADER, HeaderValue::from_static("client-42"));
+
+        let (status, headers, body) = send(&transport, request).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "healthy");
+        assert_eq!(headers["cache-control"], "no-store");
+        assert_eq!(headers[REQUEST_ID_HEADER], "client-42");
+        assert_eq!(headers["access-control-allow-origin"], "*");
+    }
+
+    #[tokio::test]
+    async fn test_oversized_body_is_rejected() {
+        let transport = echo_transport(HttpConfig { max_body_bytes: 16, ..Default::default() });
+
+        let (status, _, body) = send(&transport, rpc("tools/list")).await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(body["error"]["code"], error_codes::INVALID_REQUEST);
+
+        // A declared length over the limit is refused before the body is read
+        let mut request = rpc("tools/list");
+        request.headers_mut().insert("content-length", HeaderValue::from_static("1048576"));
+        let (status, _, _) = send(&transport, request).await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn test_connection_limit_queues_extra_clients() {
+        use hyper::client::conn::http1;
+        use hyper_util::rt::TokioIo;
+        use std::time::Duration;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let transport = Arc::new(echo_transport(HttpConfig { max_connections: 1, ..Default::default() }));
+        tokio::spawn(transport.serve(listener));
+
+        let connect = || async move {
+            let stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+            let (sender, connection) = http1::handshake(TokioIo::new(stream)).await.unwrap();
+            tokio::spawn(connection);
+            sender
+        };
+
+        let mut first = connect().await;
+        let response = first.send_request(get("/api/mcp/health")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // The only slot is held by the first connection while it stays open
+        let mut second = connect().await;
+        let queued = tokio::spawn(async move { second.send_request(get("/api/mcp/health")).await.unwrap().status() });
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(!queued.is_finished());
+
+        drop(first);
+        let status = tokio::time::timeout(Duration::from_secs(5), queued).await.unwrap().unwrap();
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_streamed_result_reassembles() {
+        let value = json!({"namespaces": vec!["Ünïcode\\Name"; 20]});
+        let expected = value.clone();
+        let call: Pin<Box<dyn std::future::Future<Output = Result<Value>> + Send>> =
+            Box::pin(async move { Ok(value) });
+        let mut rx = stream_result(call, Some(json!(7)), 5);
+
+        let mut text = String::new();
+        let mut completed = None;
+        while let Some(event) = rx.recv().await {
+            let event = String::from_utf8(event.to_vec()).unwrap();
+            let (name, data) = event.trim_end().split_once('\n').unwrap();
+            let data: Value = serde_json::from_str(data.trim_start_matches("data: ")).unwrap();
+            match name {
+                "event: chunk" => text.push_str(data["data"].as_str().unwrap()),
+                _ => completed = Some(data),
+            }
+        }
+
+        let response: Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(response["id"], 7);
+        assert_eq!(response["result"], expected);
+        assert_eq!(completed.unwrap()["bytes"], text.len());
+    }
+
+    #[test]
+    fn test_cache_validator_follows_lock_contents() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        assert!(cache_validator(dir, "audit_security", &[]).is_none());
+
+        std::fs::write(dir.join("composer.lock"), r#"{"packages": []}"#).unwrap();
+        let (etag, _) = cache_validator(dir, "audit_security", &[]).unwrap();
+        assert!(etag_matches(Some(&format!("W/{}, \"other\"", etag)), &etag));
+        assert_ne!(cache_validator(dir, "analyze_licenses", &[]).unwrap().0, etag);
+
+        std::fs::write(dir.join("composer.lock"), r#"{"packages": [{"name": "a/b"}]}"#).unwrap();
+        let (changed, _) = cache_validator(dir, "audit_security", &[]).unwrap();
+
+        assert_ne!(changed, etag);
+        assert!(!etag_matches(Some(&etag), &changed));
+    }
+
+    #[test]
+    fn test_cache_validator_reads_the_gzipped_lock() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        std::fs::write(dir.join("composer.lock.gz"), b"first").unwrap();
+        let (etag, _) = cache_validator(dir, "audit_security", &[]).unwrap();
+
+        std::fs::write(dir.join("composer.lock.gz"), b"second").unwrap();
+        assert_ne!(cache_validator(dir, "audit_security", &[]).unwrap().0, etag);
+    }
 }
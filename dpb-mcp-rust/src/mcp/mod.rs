@@ -5,7 +5,11 @@
 //! - Authentication (static tokens)
 //! - Tool Annotations (readOnlyHint, idempotentHint, etc.)
 //! - HTTP/SSE Transport
+//! - Unix Domain Socket Transport
+//! - Line-delimited or Content-Length (LSP-style) framing
 //! - Typed Errors (NotFound, NotAllowed, ValidationError)
+//! - Field projection via the `fields` tool argument
+//! - Zip/tarball archives accepted as `repo_path` for read-only tools
 //! - Credentials Context
 
 pub mod errors;
@@ -13,20 +17,24 @@ pub mod annotations;
 pub mod auth;
 pub mod registry;
 pub mod http_transport;
+pub mod framing;
+pub mod projection;
+pub mod ndjson;
 
 pub use errors::*;
 pub use annotations::*;
 pub use auth::*;
-// Registry and HTTP transport are available but not yet integrated into main server
+// Registry is available but not yet integrated into main server
 // pub use registry::*;
-// pub use http_transport::*;
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use framing::{read_message, Framing, FramingError, MessageWriter};
+use http_transport::{HttpConfig, HttpTransport};
+use tokio::io::{AsyncBufRead, AsyncWrite, BufReader};
 use tokio::sync::RwLock;
 
 pub type ToolHandler = Arc<dyn Fn(Value) -> Result<String> + Send + Sync>;
@@ -93,6 +101,7 @@ struct ToolContent {
     text: String,
 }
 
+#[derive(Clone)]
 pub struct Server {
     name: String,
     version: String,
@@ -139,6 +148,60 @@ impl Server {
         handlers.insert(tool.name.clone(), Arc::new(handler));
     }
 
+    /// Register `describe_capabilities`, a self-describing manifest of every registered
+    /// tool grouped by its primary annotation tag, with full input schemas and hints.
+    /// Call after all other tools are registered.
+    pub async fn register_capabilities_tool(&self) {
+        let tools = self.tools.clone();
+        let server_info = json!({ "name": self.name, "version": self.version });
+
+        self.register_tool(
+            Tool {
+                name: "describe_capabilities".to_string(),
+                description: "List every tool grouped by category, with input schemas and annotations (read-only, idempotent and cache hints)".to_string(),
+                input_schema: InputSchema {
+                    schema_type: "object".to_string(),
+                    properties: HashMap::new(),
+                    required: Vec::new(),
+                },
+                annotations: None,
+            },
+            move |_args| {
+                // Tools are only written during startup registration
+                let tools = tools
+                    .try_read()
+                    .map_err(|_| anyhow::anyhow!("Tool list is being updated, retry"))?;
+
+                let mut categories: BTreeMap<String, Vec<&Tool>> = BTreeMap::new();
+                for tool in tools.iter() {
+                    let category = tool
+                        .annotations
+                        .as_ref()
+                        .and_then(|a| a.tags.as_ref())
+                        .and_then(|tags| tags.first())
+                        .cloned()
+                        .unwrap_or_else(|| "other".to_string());
+                    categories.entry(category).or_default().push(tool);
+                }
+
+                let categories: Vec<Value> = categories
+                    .into_iter()
+                    .map(|(category, mut tools)| {
+                        tools.sort_by(|a, b| a.name.cmp(&b.name));
+                        json!({ "category": category, "tools": tools })
+                    })
+                    .collect();
+
+                Ok(serde_json::to_string_pretty(&json!({
+                    "server": server_info,
+                    "toolCount": tools.len(),
+                    "categories": categories,
+                }))?)
+            },
+        )
+        .await;
+    }
+
     pub async fn run(&self) -> Result<()> {
         let transport = std::env::var("MCP_TRANSPORT").unwrap_or_else(|_| "stdio".to_string());
         
@@ -147,25 +210,41 @@ impl Server {
         eprintln!("Auth: {}", is_auth_enabled());
         eprintln!("Features: Tool Annotations, Typed Errors, Credentials Context");
 
-        if transport == "http" {
-            self.run_http().await
-        } else {
-            self.run_stdio().await
+        match transport.as_str() {
+            "http" => self.run_http().await,
+            "unix" => self.run_unix().await,
+            _ => self.run_stdio().await,
         }
     }
 
     async fn run_stdio(&self) -> Result<()> {
-        let stdin = tokio::io::stdin();
-        let mut reader = BufReader::new(stdin);
-        let mut stdout = tokio::io::stdout();
+        self.run_on(BufReader::new(tokio::io::stdin()), tokio::io::stdout()).await
+    }
+
+    /// Serve JSON-RPC over any stream pair (stdio, sockets, pipes, test buffers).
+    /// Framing follows `MCP_FRAMING`, auto-detected from the first message by default.
+    pub async fn run_on<R, W>(&self, mut reader: R, writer: W) -> Result<()>
+    where
+        R: AsyncBufRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let mut framing = Framing::from_env();
+        let mut stdout = MessageWriter::new(writer, framing);
 
-        let mut line = String::new();
         loop {
-            line.clear();
-            let bytes_read = reader.read_line(&mut line).await?;
-            if bytes_read == 0 {
-                break; // EOF
-            }
+            let message = read_message(&mut reader, &mut framing).await;
+            stdout.framing = framing;
+            let line = match message {
+                Ok(Some(message)) => message,
+                Ok(None) => break, // EOF
+                // One malformed message shouldn't end the session
+                Err(e) if e.is::<FramingError>() => {
+                    self.send_error(&mut stdout, None, error_codes::PARSE_ERROR, &e.to_string(), None)
+                        .await?;
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
 
             let request: JsonRpcRequest = match serde_json::from_str(&line) {
                 Ok(req) => req,
@@ -184,18 +263,84 @@ impl Server {
         Ok(())
     }
 
-    async fn run_http(&self) -> Result<()> {
-        let _port = std::env::var("MCP_HTTP_PORT").unwrap_or_else(|_| "3000".to_string());
-        eprintln!("HTTP transport not yet implemented in Rust. Use stdio mode.");
-        eprintln!("To use HTTP, set MCP_TRANSPORT=stdio (default)");
-        // For a full HTTP implementation, we would use axum, actix-web, or hyper
-        // For now, fall back to stdio
+    #[cfg(unix)]
+    async fn run_unix(&self) -> Result<()> {
+        use tokio::net::UnixListener;
+
+        let socket_path = std::env::var("MCP_SOCKET_PATH").unwrap_or_else(|_| "/tmp/dpb-mcp.sock".to_string());
+
+        remove_stale_socket(std::path::Path::new(&socket_path))?;
+
+        let listener = UnixListener::bind(&socket_path)?;
+        // Unlinks the socket however this returns, including on an accept error or a panic
+        let _socket = SocketFile(std::path::PathBuf::from(&socket_path));
+        eprintln!("Listening on unix socket {}", socket_path);
+
+        loop {
+            tokio::select! {
+                accepted = listener.accept() => {
+                    let (stream, _) = accepted?;
+                    let server = self.clone();
+                    tokio::spawn(async move {
+                        let (read_half, write_half) = stream.into_split();
+                        if let Err(e) = server.run_on(BufReader::new(read_half), write_half).await {
+                            eprintln!("Unix socket connection error: {}", e);
+                        }
+                    });
+                }
+                _ = tokio::signal::ctrl_c() => return Ok(()),
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    async fn run_unix(&self) -> Result<()> {
+        eprintln!("Unix socket transport is not supported on this platform. Falling back to stdio.");
         self.run_stdio().await
     }
 
-    async fn handle_request(
+    async fn run_http(&self) -> Result<()> {
+        let config = HttpConfig::from_env()?;
+
+        let server = self.clone();
+        let transport = HttpTransport::new(
+            move |method, params, ctx| {
+                let server = server.clone();
+                async move { server.call(method, params, ctx).await }
+            },
+            Some(config),
+        );
+        Arc::new(transport).start().await
+    }
+
+    /// Handle one request outside a stream transport: the JSON-RPC result, or its error as an [`McpError`]
+    async fn call(&self, method: String, params: Value, ctx: RequestContext) -> Result<Value> {
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: None,
+            method,
+            params: Some(params),
+        };
+        let mut output = Vec::new();
+        self.handle_request(&mut MessageWriter::new(&mut output, Framing::Line), request, ctx)
+            .await?;
+
+        let mut response: Value = serde_json::from_slice(&output)?;
+        if let Some(error) = response.get_mut("error") {
+            let code = error.get("code").and_then(|c| c.as_i64()).unwrap_or_default() as i32;
+            let message = error.get("message").and_then(|m| m.as_str()).unwrap_or_default();
+            let mut err = McpError::new(error_type_for_code(code), code, message);
+            if let Some(data) = error.get_mut("data").map(Value::take) {
+                err = err.with_data(data);
+            }
+            return Err(err.into());
+        }
+        Ok(response["result"].take())
+    }
+
+    async fn handle_request<W: AsyncWrite + Unpin>(
         &self,
-        stdout: &mut tokio::io::Stdout,
+        stdout: &mut MessageWriter<W>,
         request: JsonRpcRequest,
         ctx: RequestContext,
     ) -> Result<()> {
@@ -210,9 +355,9 @@ impl Server {
         }
     }
 
-    async fn handle_initialize(
+    async fn handle_initialize<W: AsyncWrite + Unpin>(
         &self,
-        stdout: &mut tokio::io::Stdout,
+        stdout: &mut MessageWriter<W>,
         id: Option<Value>,
     ) -> Result<()> {
         let result = json!({
@@ -234,9 +379,9 @@ impl Server {
         self.send_response(stdout, id, result).await
     }
 
-    async fn handle_list_tools(
+    async fn handle_list_tools<W: AsyncWrite + Unpin>(
         &self,
-        stdout: &mut tokio::io::Stdout,
+        stdout: &mut MessageWriter<W>,
         id: Option<Value>,
     ) -> Result<()> {
         let tools = self.tools.read().await;
@@ -244,9 +389,9 @@ impl Server {
         self.send_response(stdout, id, result).await
     }
 
-    async fn handle_call_tool(
+    async fn handle_call_tool<W: AsyncWrite + Unpin>(
         &self,
-        stdout: &mut tokio::io::Stdout,
+        stdout: &mut MessageWriter<W>,
         id: Option<Value>,
         params: Option<Value>,
         _ctx: RequestContext,
@@ -286,8 +431,24 @@ impl Server {
 
         drop(handlers); // Release lock before calling handler
 
-        match handler(args) {
+        let read_only = self
+            .tools
+            .read()
+            .await
+            .iter()
+            .find(|t| t.name == name)
+            .and_then(|t| t.annotations.as_ref()?.read_only_hint)
+            .unwrap_or(false);
+
+        let fields = projection::fields_from_args(&args);
+        let ndjson = ndjson::wants_ndjson(&args);
+        match crate::archive::call_with_archive(name, args, read_only, |args| handler(args)) {
             Ok(result_text) => {
+                let result_text = match &fields {
+                    Some(fields) => projection::project_result(result_text, fields),
+                    None => result_text,
+                };
+                let result_text = if ndjson { ndjson::to_ndjson(name, result_text) } else { result_text };
                 let content = vec![ToolContent {
                     content_type: "text".to_string(),
                     text: result_text,
@@ -314,9 +475,9 @@ impl Server {
         }
     }
 
-    async fn send_response(
+    async fn send_response<W: AsyncWrite + Unpin>(
         &self,
-        stdout: &mut tokio::io::Stdout,
+        stdout: &mut MessageWriter<W>,
         id: Option<Value>,
         result: Value,
     ) -> Result<()> {
@@ -328,15 +489,12 @@ impl Server {
         };
 
         let json = serde_json::to_string(&response)?;
-        stdout.write_all(json.as_bytes()).await?;
-        stdout.write_all(b"\n").await?;
-        stdout.flush().await?;
-        Ok(())
+        stdout.write_message(&json).await
     }
 
-    async fn send_error(
+    async fn send_error<W: AsyncWrite + Unpin>(
         &self,
-        stdout: &mut tokio::io::Stdout,
+        stdout: &mut MessageWriter<W>,
         id: Option<Value>,
         code: i32,
         message: &str,
@@ -354,9 +512,283 @@ impl Server {
         };
 
         let json = serde_json::to_string(&response)?;
-        stdout.write_all(json.as_bytes()).await?;
-        stdout.write_all(b"\n").await?;
-        stdout.flush().await?;
-        Ok(())
+        stdout.write_message(&json).await
+    }
+}
+
+/// The listening socket's path, removed when the listener goes away
+#[cfg(unix)]
+struct SocketFile(std::path::PathBuf);
+
+#[cfg(unix)]
+impl Drop for SocketFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Remove a socket left behind by a crashed server, which would make bind fail. A socket
+/// another server still accepts on is refused rather than taken over, and anything else at
+/// the path is left alone: MCP_SOCKET_PATH must never delete a regular file.
+#[cfg(unix)]
+fn remove_stale_socket(path: &std::path::Path) -> Result<()> {
+    use std::os::unix::fs::FileTypeExt;
+
+    match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => {
+            if std::os::unix::net::UnixStream::connect(path).is_ok() {
+                return Err(anyhow::anyhow!("MCP_SOCKET_PATH {:?} is in use by another server", path));
+            }
+            Ok(std::fs::remove_file(path)?)
+        }
+        Ok(_) => Err(anyhow::anyhow!("MCP_SOCKET_PATH {:?} exists and is not a socket", path)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feed newline-delimited requests through the server and collect the responses
+    async fn exchange(server: &Server, requests: &[Value]) -> Vec<Value> {
+        let input: String = requests.iter().map(|r| format!("{}\n", r)).collect();
+        let mut output = Vec::new();
+        server.run_on(input.as_bytes(), &mut output).await.unwrap();
+
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect()
+    }
+
+    async fn test_server() -> Server {
+        let server = Server::new("test-server", "0.0.0");
+        server
+            .register_tool(
+                Tool {
+                    name: "echo".to_string(),
+                    description: "Echo the message argument".to_string(),
+                    input_schema: InputSchema {
+                        schema_type: "object".to_string(),
+                        properties: HashMap::from([(
+                            "message".to_string(),
+                            Property {
+                                property_type: "string".to_string(),
+                                description: "Message to echo".to_string(),
+                            },
+                        )]),
+                        required: vec!["message".to_string()],
+                    },
+                    annotations: None,
+                },
+                |args| {
+                    Ok(args.get("message").and_then(|v| v.as_str()).unwrap_or_default().to_string())
+                },
+            )
+            .await;
+        server
+    }
+
+    #[tokio::test]
+    async fn test_content_length_framing() {
+        let server = test_server().await;
+        let body = json!({"jsonrpc": "2.0", "id": 6, "method": "initialize"}).to_string();
+        let input = format!("Content-Length: {}\r\n\r\n{}", body.len(), body);
+        let mut output = Vec::new();
+        server.run_on(input.as_bytes(), &mut output).await.unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        let (header, payload) = output.split_once("\r\n\r\n").unwrap();
+        assert_eq!(header, format!("Content-Length: {}", payload.len()));
+        let response: Value = serde_json::from_str(payload).unwrap();
+        assert_eq!(response["id"], 6);
+    }
+
+    #[tokio::test]
+    async fn test_malformed_content_length_keeps_serving() {
+        let server = test_server().await;
+        let body = json!({"jsonrpc": "2.0", "id": 7, "method": "initialize"}).to_string();
+        let input = format!(
+            "Content-Length: 10\r\n\r\n0123456789Content-Length: abc\r\n\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        let mut output = Vec::new();
+        server.run_on(input.as_bytes(), &mut output).await.unwrap();
+
+        let mut rest = String::from_utf8(output).unwrap();
+        let mut payloads: Vec<Value> = Vec::new();
+        while let Some((header, tail)) = rest.split_once("\r\n\r\n") {
+            let len: usize = header.trim_start_matches("Content-Length: ").parse().unwrap();
+            payloads.push(serde_json::from_str(&tail[..len]).unwrap());
+            rest = tail[len..].to_string();
+        }
+        assert_eq!(payloads.len(), 3);
+        assert_eq!(payloads[1]["error"]["code"], error_codes::PARSE_ERROR);
+        assert!(payloads[1]["error"]["message"].as_str().unwrap().contains("Invalid Content-Length"));
+        assert_eq!(payloads[2]["id"], 7);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_content_length_is_refused() {
+        let server = test_server().await;
+        let input = format!("Content-Length: {}\r\n\r\n{{}}", usize::MAX);
+        let mut output = Vec::new();
+        server.run_on(input.as_bytes(), &mut output).await.unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        let (_, payload) = output.split_once("\r\n\r\n").unwrap();
+        let response: Value = serde_json::from_str(payload).unwrap();
+        assert_eq!(response["error"]["code"], error_codes::PARSE_ERROR);
+        assert!(response["error"]["message"].as_str().unwrap().contains("exceeds"));
+    }
+
+    #[tokio::test]
+    async fn test_initialize() {
+        let server = test_server().await;
+        let responses = exchange(&server, &[json!({"jsonrpc": "2.0", "id": 1, "method": "initialize"})]).await;
+
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0]["id"], 1);
+        assert_eq!(responses[0]["result"]["serverInfo"]["name"], "test-server");
+        assert_eq!(responses[0]["result"]["protocolVersion"], "2024-11-05");
+    }
+
+    #[tokio::test]
+    async fn test_tools_list() {
+        let server = test_server().await;
+        let responses = exchange(&server, &[json!({"jsonrpc": "2.0", "id": 2, "method": "tools/list"})]).await;
+
+        let tools = responses[0]["result"]["tools"].as_array().unwrap();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0]["name"], "echo");
+        assert!(tools[0]["annotations"].is_object());
+    }
+
+    #[tokio::test]
+    async fn test_describe_capabilities() {
+        let server = test_server().await;
+        server.register_capabilities_tool().await;
+        let responses = exchange(
+            &server,
+            &[json!({"jsonrpc": "2.0", "id": 7, "method": "tools/call", "params": {"name": "describe_capabilities"}})],
+        )
+        .await;
+
+        let text = responses[0]["result"]["content"][0]["text"].as_str().unwrap();
+        let manifest: Value = serde_json::from_str(text).unwrap();
+        assert_eq!(manifest["toolCount"], 2);
+        let categories = manifest["categories"].as_array().unwrap();
+        let echo = categories
+            .iter()
+            .flat_map(|c| c["tools"].as_array().unwrap())
+            .find(|t| t["name"] == "echo")
+            .unwrap();
+        assert_eq!(echo["inputSchema"]["required"][0], "message");
+    }
+
+    #[tokio::test]
+    async fn test_tools_call() {
+        let server = test_server().await;
+        let responses = exchange(
+            &server,
+            &[json!({
+                "jsonrpc": "2.0",
+                "id": 3,
+                "method": "tools/call",
+                "params": {"name": "echo", "arguments": {"message": "hello"}}
+            })],
+        )
+        .await;
+
+        let result = &responses[0]["result"];
+        assert_eq!(result["content"][0]["text"], "hello");
+        assert!(result.get("isError").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_strict_mode_error_carries_the_result() {
+        let server = test_server().await;
+        server
+            .register_tool(
+                Tool {
+                    name: "strict".to_string(),
+                    description: "Always fails in strict mode".to_string(),
+                    input_schema: InputSchema {
+                        schema_type: "object".to_string(),
+                        properties: HashMap::new(),
+                        required: Vec::new(),
+                    },
+                    annotations: None,
+                },
+                |_args| Err(strict_mode_error("Strict mode: 2 findings", json!({"summary": {"total": 2}})).into()),
+            )
+            .await;
+
+        let responses = exchange(
+            &server,
+            &[json!({"jsonrpc": "2.0", "id": 8, "method": "tools/call", "params": {"name": "strict"}})],
+        )
+        .await;
+
+        assert_eq!(responses[0]["result"]["isError"], true);
+        let error: Value =
+            serde_json::from_str(responses[0]["result"]["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(error["type"], "StrictModeError");
+        assert_eq!(error["code"], error_codes::STRICT_MODE);
+        assert_eq!(error["data"], json!({"summary": {"total": 2}}));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_tool() {
+        let server = test_server().await;
+        let responses = exchange(
+            &server,
+            &[
+                json!({"jsonrpc": "2.0", "id": 4, "method": "tools/call", "params": {"name": "missing"}}),
+                json!({"jsonrpc": "2.0", "id": 5, "method": "bogus/method"}),
+            ],
+        )
+        .await;
+
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0]["result"]["isError"], true);
+        let text = responses[0]["result"]["content"][0]["text"].as_str().unwrap();
+        assert!(text.contains("missing"));
+        assert_eq!(responses[1]["error"]["code"], error_codes::METHOD_NOT_FOUND);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_remove_stale_socket_keeps_regular_files() {
+        let temp = crate::test_support::temp_dir();
+        let path = temp.path().join("dpb.sock");
+        std::fs::write(&path, "not a socket").unwrap();
+
+        assert!(remove_stale_socket(&path).is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "not a socket");
+        std::fs::remove_file(&path).unwrap();
+
+        let listener = std::os::unix::net::UnixListener::bind(&path).unwrap();
+        assert!(remove_stale_socket(&path).is_err());
+        assert!(path.exists());
+
+        drop(listener);
+        remove_stale_socket(&path).unwrap();
+        assert!(!path.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_socket_file_is_removed_on_drop() {
+        let temp = crate::test_support::temp_dir();
+        let path = temp.path().join("dpb.sock");
+        let _listener = std::os::unix::net::UnixListener::bind(&path).unwrap();
+
+        drop(SocketFile(path.clone()));
+        assert!(!path.exists());
     }
 }
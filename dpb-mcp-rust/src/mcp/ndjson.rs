@@ -0,0 +1,77 @@
+//! NDJSON output for streaming consumers
+//! With `format: "ndjson"`, a tool's main list is emitted one compact record per line,
+//! followed by the rest of the result (summaries, stats) as the last line
+
+use serde_json::Value;
+
+/// The list each tool streams; other tools stream their only top-level list, if any
+const RECORD_FIELDS: &[(&str, &str)] = &[
+    ("analyze_dependencies", "tree"),
+    ("analyze_psr4", "violations"),
+    ("audit_security", "vulnerabilities"),
+    ("detect_namespaces", "namespaces"),
+];
+
+/// Whether the tool arguments ask for NDJSON output
+pub fn wants_ndjson(args: &Value) -> bool {
+    args.get("format").and_then(|v| v.as_str()) == Some("ndjson")
+}
+
+/// Convert a tool's text result to NDJSON. Non-JSON output (e.g. Mermaid) is returned unchanged,
+/// and a result with no list to stream becomes a single line.
+pub fn to_ndjson(tool: &str, result_text: String) -> String {
+    let Ok(value) = serde_json::from_str::<Value>(&result_text) else {
+        return result_text;
+    };
+
+    let (records, envelope) = match value {
+        Value::Array(items) => (items, None),
+        Value::Object(mut map) => {
+            let field = RECORD_FIELDS
+                .iter()
+                .find(|(name, _)| *name == tool)
+                .map(|(_, field)| field.to_string())
+                .or_else(|| {
+                    let mut lists = map.iter().filter(|(_, v)| v.is_array()).map(|(k, _)| k);
+                    match (lists.next(), lists.next()) {
+                        (Some(only), None) => Some(only.clone()),
+                        _ => None,
+                    }
+                });
+            let records = match field.filter(|f| map.get(f).is_some_and(Value::is_array)).and_then(|f| map.remove(&f)) {
+                Some(Value::Array(items)) => items,
+                _ => Vec::new(),
+            };
+            // Nothing but the list, e.g. a bare page of records
+            let envelope = (!map.is_empty()).then_some(Value::Object(map));
+            (records, envelope)
+        }
+        other => (Vec::new(), Some(other)),
+    };
+
+    records
+        .iter()
+        .chain(envelope.iter())
+        .map(|record| format!("{}\n", record))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_to_ndjson_streams_records_then_envelope() {
+        let result = json!({
+            "vulnerabilities": [{ "package": "a/a" }, { "package": "b/b" }],
+            "summary": { "high": 2 }
+        });
+        let lines = to_ndjson("audit_security", result.to_string());
+        assert_eq!(
+            lines,
+            "{\"package\":\"a/a\"}\n{\"package\":\"b/b\"}\n{\"summary\":{\"high\":2}}\n"
+        );
+        assert_eq!(to_ndjson("generate_dependency_graph", "graph TD".to_string()), "graph TD");
+    }
+}
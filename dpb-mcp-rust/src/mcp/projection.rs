@@ -0,0 +1,77 @@
+//! Field projection for tool output
+//! Trims JSON results down to the fields a client asked for, to save tokens
+
+use serde_json::Value;
+
+/// Read the optional `fields` tool argument
+pub fn fields_from_args(args: &Value) -> Option<Vec<String>> {
+    let fields: Vec<String> = args
+        .get("fields")?
+        .as_array()?
+        .iter()
+        .filter_map(|f| f.as_str().map(|s| s.to_string()))
+        .collect();
+    if fields.is_empty() {
+        None
+    } else {
+        Some(fields)
+    }
+}
+
+/// Apply a projection to a tool's text result. Non-JSON output (e.g. Mermaid) is returned unchanged.
+pub fn project_result(result_text: String, fields: &[String]) -> String {
+    match serde_json::from_str::<Value>(&result_text) {
+        Ok(mut value) => {
+            project_fields(&mut value, fields);
+            serde_json::to_string_pretty(&value).unwrap_or(result_text)
+        }
+        Err(_) => result_text,
+    }
+}
+
+/// Keep only `fields` in every record, i.e. every object inside an array.
+/// Records with none of the requested fields are left intact, as is the
+/// top-level envelope (summaries, stats), so unrelated sections never come back empty.
+pub fn project_fields(value: &mut Value, fields: &[String]) {
+    match value {
+        Value::Object(map) => {
+            for child in map.values_mut() {
+                project_fields(child, fields);
+            }
+        }
+        Value::Array(items) => {
+            for item in items.iter_mut() {
+                project_fields(item, fields);
+                if let Value::Object(record) = item {
+                    if fields.iter().any(|f| record.contains_key(f)) {
+                        record.retain(|key, _| fields.iter().any(|f| f == key));
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_project_fields() {
+        let mut value = json!({
+            "tree": [
+                { "name": "a/a", "version": "1.0.0", "dependencies": ["b/b"] },
+                { "name": "b/b", "version": "2.0.0", "dependencies": [] }
+            ],
+            "warnings": [{ "message": "kept" }],
+            "stats": { "totalProduction": 2 }
+        });
+        project_fields(&mut value, &["name".to_string(), "version".to_string()]);
+
+        assert_eq!(value["tree"][0], json!({ "name": "a/a", "version": "1.0.0" }));
+        assert_eq!(value["warnings"][0], json!({ "message": "kept" }));
+        assert_eq!(value["stats"]["totalProduction"], 2);
+    }
+}
@@ -127,7 +127,7 @@ impl ActionRegistry {
         actions
             .values()
             .filter(|a| {
-                plugin_id.map_or(true, |pid| {
+                plugin_id.is_none_or(|pid| {
                     a.registered.definition.plugin_id.as_deref() == Some(pid)
                 })
             })
@@ -0,0 +1,312 @@
+//! Packagist registry client
+//! Fetches package metadata from the Composer v2 (`/p2/`) API, and popularity
+//! stats and security advisories from the packagist.org web API, all with an in-process cache
+
+use anyhow::{anyhow, Context, Result};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::composer::constraint::{Constraint, Stability, Version};
+use crate::types::AbandonedField;
+
+const DEFAULT_PACKAGIST_URL: &str = "https://repo.packagist.org";
+const DEFAULT_PACKAGIST_API_URL: &str = "https://packagist.org";
+
+/// Monthly downloads below which a dependency counts as barely adopted
+const LOW_MONTHLY_DOWNLOADS: u64 = 100;
+
+lazy_static! {
+    static ref CACHE: Mutex<HashMap<String, Arc<PackagistPackage>>> = Mutex::new(HashMap::new());
+    static ref STATS_CACHE: Mutex<HashMap<String, Arc<PackageStats>>> = Mutex::new(HashMap::new());
+    static ref ADVISORY_CACHE: Mutex<HashMap<String, Arc<Vec<SecurityAdvisory>>>> = Mutex::new(HashMap::new());
+    static ref AGENT: ureq::Agent = ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(15))
+        .user_agent(concat!("dpb-mcp/", env!("CARGO_PKG_VERSION")))
+        .build();
+}
+
+/// One published version of a package
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackagistVersion {
+    pub version: String,
+    #[serde(default)]
+    pub time: Option<String>,
+    #[serde(default)]
+    pub license: Option<Vec<String>>,
+    #[serde(default)]
+    pub abandoned: Option<AbandonedField>,
+    #[serde(default)]
+    pub require: Option<HashMap<String, String>>,
+}
+
+/// All tagged releases of a package, newest first as served by Packagist
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackagistPackage {
+    pub name: String,
+    pub versions: Vec<PackagistVersion>,
+}
+
+impl PackagistPackage {
+    /// Parsed versions, skipping any Packagist lists that Composer could not parse either
+    pub fn parsed_versions(&self) -> Vec<(Version, &PackagistVersion)> {
+        self.versions
+            .iter()
+            .filter_map(|v| Version::parse(&v.version).map(|parsed| (parsed, v)))
+            .collect()
+    }
+
+    /// Highest release accepted by `constraint` at the given minimum stability
+    pub fn highest_matching(&self, constraint: &Constraint, minimum_stability: Stability) -> Option<&PackagistVersion> {
+        self.parsed_versions()
+            .into_iter()
+            .filter(|(v, _)| !v.is_branch() && constraint.accepts(v, minimum_stability))
+            .max_by(|(a, _), (b, _)| a.cmp(b))
+            .map(|(_, v)| v)
+    }
+
+    /// Highest stable release
+    pub fn latest_stable(&self) -> Option<&PackagistVersion> {
+        self.parsed_versions()
+            .into_iter()
+            .filter(|(v, _)| v.is_stable())
+            .max_by(|(a, _), (b, _)| a.cmp(b))
+            .map(|(_, v)| v)
+    }
+}
+
+/// Download counts as reported by packagist.org
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DownloadStats {
+    #[serde(default)]
+    pub total: u64,
+    #[serde(default)]
+    pub monthly: u64,
+    #[serde(default)]
+    pub daily: u64,
+}
+
+/// Popularity metadata for a package; these numbers move slowly, so they are cached for the process lifetime
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageStats {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub repository: Option<String>,
+    #[serde(default)]
+    pub downloads: DownloadStats,
+    /// Packagist "favers" (stars on packagist.org)
+    #[serde(default)]
+    pub favers: u64,
+    #[serde(rename = "githubStars", alias = "github_stars", default)]
+    pub github_stars: Option<u64>,
+    #[serde(default)]
+    pub abandoned: Option<AbandonedField>,
+}
+
+/// A published security advisory, as listed by the packagist.org advisories API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecurityAdvisory {
+    #[serde(rename = "advisoryId", default)]
+    pub advisory_id: Option<String>,
+    pub title: String,
+    #[serde(default)]
+    pub cve: Option<String>,
+    #[serde(default)]
+    pub link: Option<String>,
+    #[serde(default)]
+    pub severity: Option<String>,
+    /// Composer constraint covering the vulnerable releases, e.g. `>=7.0,<7.4.5|>=6.0,<6.5.8`
+    #[serde(rename = "affectedVersions")]
+    pub affected_versions: String,
+}
+
+impl SecurityAdvisory {
+    /// Whether `version` falls in the affected range; unparseable ranges count as affecting everything
+    pub fn affects(&self, version: &Version) -> bool {
+        Constraint::parse(&self.affected_versions).is_none_or(|c| c.matches(version))
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PackageInfo {
+    #[serde(flatten)]
+    pub stats: PackageStats,
+    #[serde(rename = "latestStable")]
+    pub latest_stable: Option<String>,
+    /// Adoption and maintenance warnings worth surfacing before depending on the package
+    #[serde(rename = "riskSignals")]
+    pub risk_signals: Vec<String>,
+}
+
+/// Base URL of the Composer repository, overridable via `PACKAGIST_URL` for mirrors
+pub fn packagist_url() -> String {
+    std::env::var("PACKAGIST_URL")
+        .map(|u| u.trim_end_matches('/').to_string())
+        .unwrap_or_else(|_| DEFAULT_PACKAGIST_URL.to_string())
+}
+
+/// Base URL of the packagist.org web API (download stats), overridable via `PACKAGIST_API_URL`
+pub fn packagist_api_url() -> String {
+    std::env::var("PACKAGIST_API_URL")
+        .map(|u| u.trim_end_matches('/').to_string())
+        .unwrap_or_else(|_| DEFAULT_PACKAGIST_API_URL.to_string())
+}
+
+/// Fetch a package's release metadata, served from cache after the first lookup
+pub fn fetch_package(name: &str) -> Result<Arc<PackagistPackage>> {
+    let name = crate::composer::normalize_package_name(name);
+    if !name.contains('/') {
+        return Err(anyhow!("Invalid package name: {}", name));
+    }
+
+    if let Some(cached) = CACHE.lock().unwrap().get(&name) {
+        return Ok(cached.clone());
+    }
+
+    let url = format!("{}/p2/{}.json", packagist_url(), name);
+    let body: Value = match AGENT.get(&url).call() {
+        Ok(response) => response
+            .into_json()
+            .with_context(|| format!("Invalid Packagist response for {}", name))?,
+        Err(ureq::Error::Status(404, _)) => return Err(anyhow!("Package {} not found on Packagist", name)),
+        Err(e) => return Err(anyhow!("Packagist request for {} failed: {}", name, e)),
+    };
+
+    let entries = body
+        .get("packages")
+        .and_then(|p| p.get(&name))
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| anyhow!("Package {} not found in Packagist response", name))?;
+
+    let versions = expand_minified(entries)
+        .into_iter()
+        .filter_map(|entry| serde_json::from_value(Value::Object(entry)).ok())
+        .collect();
+
+    let package = Arc::new(PackagistPackage { name: name.clone(), versions });
+    CACHE.lock().unwrap().insert(name, package.clone());
+    Ok(package)
+}
+
+/// Fetch download and star counts, served from cache after the first lookup
+pub fn fetch_package_stats(name: &str) -> Result<Arc<PackageStats>> {
+    let name = crate::composer::normalize_package_name(name);
+    if !name.contains('/') {
+        return Err(anyhow!("Invalid package name: {}", name));
+    }
+
+    if let Some(cached) = STATS_CACHE.lock().unwrap().get(&name) {
+        return Ok(cached.clone());
+    }
+
+    let url = format!("{}/packages/{}.json", packagist_api_url(), name);
+    let body: Value = match AGENT.get(&url).call() {
+        Ok(response) => response
+            .into_json()
+            .with_context(|| format!("Invalid Packagist response for {}", name))?,
+        Err(ureq::Error::Status(404, _)) => return Err(anyhow!("Package {} not found on Packagist", name)),
+        Err(e) => return Err(anyhow!("Packagist request for {} failed: {}", name, e)),
+    };
+
+    let package = body
+        .get("package")
+        .cloned()
+        .ok_or_else(|| anyhow!("Package {} not found in Packagist response", name))?;
+    let stats: PackageStats = serde_json::from_value(package)
+        .with_context(|| format!("Invalid Packagist stats for {}", name))?;
+
+    let stats = Arc::new(stats);
+    STATS_CACHE.lock().unwrap().insert(name, stats.clone());
+    Ok(stats)
+}
+
+/// Fetch the known security advisories for a package, served from cache after the first lookup
+pub fn fetch_security_advisories(name: &str) -> Result<Arc<Vec<SecurityAdvisory>>> {
+    let name = crate::composer::normalize_package_name(name);
+    if !name.contains('/') {
+        return Err(anyhow!("Invalid package name: {}", name));
+    }
+
+    if let Some(cached) = ADVISORY_CACHE.lock().unwrap().get(&name) {
+        return Ok(cached.clone());
+    }
+
+    let url = format!("{}/api/security-advisories/", packagist_api_url());
+    let body: Value = match AGENT.get(&url).query("packages[]", &name).call() {
+        Ok(response) => response
+            .into_json()
+            .with_context(|| format!("Invalid Packagist advisories response for {}", name))?,
+        Err(e) => return Err(anyhow!("Packagist advisories request for {} failed: {}", name, e)),
+    };
+
+    // Packagist answers `"advisories": []` rather than an object when nothing is known
+    let advisories = match body.get("advisories").and_then(|a| a.get(&name)) {
+        Some(list) => serde_json::from_value(list.clone())
+            .with_context(|| format!("Invalid Packagist advisories for {}", name))?,
+        None => Vec::new(),
+    };
+
+    let advisories = Arc::new(advisories);
+    ADVISORY_CACHE.lock().unwrap().insert(name, advisories.clone());
+    Ok(advisories)
+}
+
+/// Popularity stats plus the latest stable release and any risk signals
+pub fn package_info(name: &str) -> Result<PackageInfo> {
+    let stats = fetch_package_stats(name)?;
+    let releases = fetch_package(name).ok();
+    let latest_stable = releases
+        .as_ref()
+        .and_then(|p| p.latest_stable().map(|v| v.version.clone()));
+
+    let mut risk_signals = Vec::new();
+    if stats.downloads.monthly < LOW_MONTHLY_DOWNLOADS {
+        risk_signals.push(format!(
+            "Low adoption: {} downloads in the last month ({} total)",
+            stats.downloads.monthly, stats.downloads.total
+        ));
+    }
+    match &stats.abandoned {
+        Some(AbandonedField::Replacement(r)) if !r.is_empty() => {
+            risk_signals.push(format!("Abandoned; the maintainers suggest {} instead", r))
+        }
+        Some(AbandonedField::Flag(true)) | Some(AbandonedField::Replacement(_)) => {
+            risk_signals.push("Abandoned with no suggested replacement".to_string())
+        }
+        _ => {}
+    }
+    if releases.is_some() && latest_stable.is_none() {
+        risk_signals.push("No stable release published".to_string());
+    }
+
+    Ok(PackageInfo {
+        stats: (*stats).clone(),
+        latest_stable,
+        risk_signals,
+    })
+}
+
+/// Undo Composer 2 metadata minification: each entry only lists keys that changed
+/// from the previous one, and `"__unset"` removes an inherited key
+fn expand_minified(entries: &[Value]) -> Vec<Map<String, Value>> {
+    let mut expanded = Vec::with_capacity(entries.len());
+    let mut current = Map::new();
+
+    for entry in entries.iter().filter_map(|e| e.as_object()) {
+        for (key, value) in entry {
+            if value.as_str() == Some("__unset") {
+                current.remove(key);
+            } else {
+                current.insert(key.clone(), value.clone());
+            }
+        }
+        expanded.push(current.clone());
+    }
+
+    expanded
+}
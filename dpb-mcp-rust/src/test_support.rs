@@ -0,0 +1,49 @@
+//! Fixtures shared by the unit tests
+
+use std::ffi::OsString;
+use std::sync::{Mutex, MutexGuard};
+use tempfile::TempDir;
+
+static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+/// A fresh directory removed when dropped, even if the test panics. The name has no
+/// leading dot, so scans that skip hidden directories still walk it.
+pub fn temp_dir() -> TempDir {
+    tempfile::Builder::new().prefix("dpb-test-").tempdir().unwrap()
+}
+
+/// Exclusive access to the process environment for one test; variables changed through
+/// the guard are restored when it is dropped
+pub struct EnvGuard {
+    saved: Vec<(String, Option<OsString>)>,
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl EnvGuard {
+    pub fn lock() -> Self {
+        // A test that panicked while holding the lock has still restored the environment
+        let lock = ENV_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        Self { saved: Vec::new(), _lock: lock }
+    }
+
+    pub fn set(&mut self, name: &str, value: impl AsRef<std::ffi::OsStr>) {
+        self.saved.push((name.to_string(), std::env::var_os(name)));
+        std::env::set_var(name, value);
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.saved.push((name.to_string(), std::env::var_os(name)));
+        std::env::remove_var(name);
+    }
+}
+
+impl Drop for EnvGuard {
+    fn drop(&mut self) {
+        for (name, value) in self.saved.drain(..).rev() {
+            match value {
+                Some(value) => std::env::set_var(&name, value),
+                None => std::env::remove_var(&name),
+            }
+        }
+    }
+}
@@ -11,11 +11,49 @@ pub struct ComposerJson {
     pub require: Option<HashMap<String, String>>,
     #[serde(rename = "require-dev")]
     pub require_dev: Option<HashMap<String, String>>,
+    /// Packages and version ranges this project cannot be installed alongside
+    pub conflict: Option<HashMap<String, String>>,
     pub autoload: Option<AutoloadConfig>,
     #[serde(rename = "autoload-dev")]
     pub autoload_dev: Option<AutoloadConfig>,
     pub scripts: Option<HashMap<String, serde_json::Value>>,
     pub config: Option<HashMap<String, serde_json::Value>>,
+    pub repositories: Option<RepositoriesField>,
+    #[serde(rename = "minimum-stability")]
+    pub minimum_stability: Option<String>,
+    /// Support channels keyed by kind: issues, source, docs, security, ...
+    pub support: Option<HashMap<String, serde_json::Value>>,
+    pub funding: Option<Vec<serde_json::Value>>,
+    pub authors: Option<Vec<serde_json::Value>>,
+    /// Settings for scripts and plugins, e.g. cweagans/composer-patches' `patches`
+    #[serde(default, deserialize_with = "object_or_none")]
+    pub extra: Option<HashMap<String, serde_json::Value>>,
+}
+
+/// An optional object field that Composer also accepts as an empty list (`"extra": []`,
+/// as PHP's json_encode writes an empty array); anything but an object reads as `None`
+fn object_or_none<'de, D>(deserializer: D) -> Result<Option<HashMap<String, serde_json::Value>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(match Option::<serde_json::Value>::deserialize(deserializer)? {
+        Some(serde_json::Value::Object(map)) => Some(map.into_iter().collect()),
+        _ => None,
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RepositoriesField {
+    List(Vec<serde_json::Value>),
+    Map(HashMap<String, serde_json::Value>),
+}
+
+/// A package defined inline by a `"type": "package"` repository in composer.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InlinePackage {
+    pub name: String,
+    pub versions: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,6 +71,9 @@ pub struct AutoloadConfig {
     pub psr0: Option<HashMap<String, Psr4Path>>,
     pub files: Option<Vec<String>>,
     pub classmap: Option<Vec<String>>,
+    /// Paths left out of the classmap and PSR-4/PSR-0 scanning; `*` and `**` wildcards allowed
+    #[serde(rename = "exclude-from-classmap")]
+    pub exclude_from_classmap: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -63,11 +104,27 @@ pub struct PackageInfo {
     pub require: Option<HashMap<String, String>>,
     #[serde(rename = "require-dev")]
     pub require_dev: Option<HashMap<String, String>>,
+    /// Packages and version ranges this package cannot be installed alongside
+    pub conflict: Option<HashMap<String, String>>,
     pub autoload: Option<AutoloadConfig>,
+    /// Executables Composer links into the bin dir (`vendor/bin` by default)
+    pub bin: Option<Vec<String>>,
     pub homepage: Option<String>,
     pub source: Option<SourceInfo>,
     pub dist: Option<DistInfo>,
     pub time: Option<String>,
+    pub abandoned: Option<AbandonedField>,
+    /// Integration settings for plugins and frameworks, e.g. `laravel.providers`
+    #[serde(default, deserialize_with = "object_or_none")]
+    pub extra: Option<HashMap<String, serde_json::Value>>,
+}
+
+/// Composer's abandonment marker: `true`, or the name of the suggested replacement
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum AbandonedField {
+    Flag(bool),
+    Replacement(String),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -110,6 +167,16 @@ pub struct Psr4Violation {
     #[serde(rename = "actualNamespace")]
     pub actual_namespace: Option<String>,
     pub issue: String,
+    /// 1-based line of the namespace declaration (1 when it is missing)
+    #[serde(default = "first_position")]
+    pub line: usize,
+    /// 1-based column where the declaration starts
+    #[serde(default = "first_position")]
+    pub column: usize,
+}
+
+fn first_position() -> usize {
+    1
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -132,6 +199,9 @@ pub struct SecurityVulnerability {
     pub cve: Option<String>,
     pub description: String,
     pub recommendation: String,
+    /// Where the finding came from: "heuristic" or "composer-audit"
+    #[serde(default)]
+    pub source: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -153,6 +223,14 @@ pub struct RepoConfig {
     pub description: Option<String>,
 }
 
+/// A configured repository that could not be analyzed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailedRepo {
+    pub name: String,
+    pub path: String,
+    pub error: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LicenseDistribution {
     pub license: String,
@@ -160,6 +238,9 @@ pub struct LicenseDistribution {
     pub packages: Vec<String>,
     #[serde(rename = "riskLevel")]
     pub risk_level: String,
+    /// Share of analyzed packages under this license
+    #[serde(default)]
+    pub percentage: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -172,4 +253,7 @@ pub struct VersionConflict {
 pub struct RepoVersion {
     pub repo: String,
     pub version: String,
+    /// Version locked in that repo's composer.lock, when it has one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub installed: Option<String>,
 }